@@ -0,0 +1,281 @@
+// A command-line front end over the same `lib` core as the wasm
+// component, keeping the list in a local JSON file between runs.
+use clap::{Parser, Subcommand, ValueEnum};
+use lib::{
+    app_error::AppResultExt,
+    core::{uuid_from, AppResult},
+    todos::{self, OptionalDeadlineInput, Snapshot, Title, TodoList},
+};
+use std::{
+    fs,
+    io::{self, ErrorKind, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "todo",
+    about = "Manage a todo list stored in a local JSON file"
+)]
+struct Cli {
+    /// Where the list is kept; created on the first change.
+    #[arg(long, env = "TODO_FILE", default_value = "todos.json")]
+    file: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add a todo and print it.
+    Add {
+        title: String,
+
+        /// Left out, the list's creation defaults decide.
+        #[arg(long, value_enum)]
+        priority: Option<Priority>,
+
+        /// Such as `2023-06-18 13`; see the accepted formats in `meta`.
+        #[arg(long)]
+        deadline: Option<String>,
+
+        /// An IANA zone or a fixed offset for the deadline.
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+
+    /// List todos, optionally narrowed by status or priority.
+    List {
+        #[arg(long, value_enum)]
+        status: Option<Status>,
+
+        #[arg(long, value_enum)]
+        priority: Option<Priority>,
+
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+
+    /// Mark a todo as done.
+    Done { id: String },
+
+    /// Delete a todo.
+    Delete { id: String },
+
+    /// Find todos whose title contains a keyword.
+    Search {
+        keyword: String,
+
+        #[arg(long, value_enum)]
+        sort: Option<QuerySort>,
+
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Status {
+    Backlog,
+    InProgress,
+    Done,
+}
+
+impl From<Status> for todos::Status {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Backlog => Self::Backlog,
+            Status::InProgress => Self::InProgress,
+            Status::Done => Self::Done,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<Priority> for todos::Priority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Low => Self::Low,
+            Priority::Medium => Self::Medium,
+            Priority::High => Self::High,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum QuerySort {
+    Priority,
+    Status,
+    Deadline,
+    StartDate,
+    CreatedAt,
+    UpdatedTimestamp,
+}
+
+impl From<QuerySort> for todos::QuerySort {
+    fn from(sort: QuerySort) -> Self {
+        match sort {
+            QuerySort::Priority => Self::Priority,
+            QuerySort::Status => Self::Status,
+            QuerySort::Deadline => Self::Deadline,
+            QuerySort::StartDate => Self::StartDate,
+            QuerySort::CreatedAt => Self::CreatedAt,
+            QuerySort::UpdatedTimestamp => Self::UpdatedTimestamp,
+        }
+    }
+}
+
+fn load(file: &PathBuf) -> AppResult<TodoList> {
+    let mut todos = TodoList::new();
+
+    match fs::read_to_string(file) {
+        Ok(json) => {
+            let snapshot: Snapshot = serde_json::from_str(&json)
+                .map_err(|e| format!("Cannot read '{}': {e}", file.display()))?;
+
+            todos.restore(snapshot).err_as_string()?;
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Cannot open '{}': {e}", file.display())),
+    }
+
+    Ok(todos)
+}
+
+// The list is written beside the file and renamed over it, so a run
+// that dies halfway leaves the old list whole rather than a truncated one.
+fn save(file: &PathBuf, todos: &TodoList) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(&todos.snapshot()).map_err(|e| e.to_string())?;
+
+    let mut temp = file.clone().into_os_string();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    let write = || -> io::Result<()> {
+        let mut out = fs::File::create(&temp)?;
+        out.write_all(json.as_bytes())?;
+        out.sync_all()?;
+
+        fs::rename(&temp, file)
+    };
+
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+
+        format!("Cannot write '{}': {e}", file.display())
+    })
+}
+
+fn print_todo(t: &todos::Todo) {
+    let deadline = t
+        .deadline_local()
+        .map(|d| format!("  due {d}"))
+        .unwrap_or_default();
+
+    println!(
+        "{}  {:<11} {:<6} {}{deadline}",
+        t.id(),
+        format!("{:?}", t.status()),
+        format!("{:?}", t.priority()),
+        t.title(),
+    );
+}
+
+fn run(cli: Cli) -> AppResult<()> {
+    let mut todos = load(&cli.file)?;
+
+    match cli.command {
+        Command::Add {
+            title,
+            priority,
+            deadline,
+            timezone,
+        } => {
+            let added = todos
+                .add(
+                    &todos::NewTodo::builder()
+                        .title(Title::new(&title))
+                        .priority(priority.map(todos::Priority::from))
+                        .deadline(OptionalDeadlineInput::new(deadline, timezone))
+                        .build(),
+                )
+                .err_as_string()?;
+
+            print_todo(&added);
+        }
+        Command::List {
+            status,
+            priority,
+            limit,
+        } => {
+            let query = todos::Query::builder()
+                .status(status.map(Into::into))
+                .priority(priority.map(Into::into))
+                .limit(limit.into())
+                .build();
+
+            todos
+                .search(&query)
+                .err_as_string()?
+                .iter()
+                .for_each(print_todo);
+
+            return Ok(());
+        }
+        Command::Done { id } => {
+            let done = todos
+                .update(
+                    uuid_from(&id)?,
+                    &todos::UpdateTodo::builder()
+                        .status(Some(todos::Status::Done))
+                        .build(),
+                )
+                .err_as_string()?;
+
+            print_todo(&done);
+        }
+        Command::Delete { id } => {
+            todos.delete(uuid_from(&id)?).err_as_string()?;
+        }
+        Command::Search {
+            keyword,
+            sort,
+            limit,
+        } => {
+            let query = todos::Query::builder()
+                .keyword(Some(keyword))
+                .sort(sort.map(Into::into))
+                .limit(limit.into())
+                .build();
+
+            todos
+                .search(&query)
+                .err_as_string()?
+                .iter()
+                .for_each(print_todo);
+
+            return Ok(());
+        }
+    }
+
+    save(&cli.file, &todos)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
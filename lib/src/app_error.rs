@@ -1,3 +1,4 @@
+use crate::todos::Role;
 use std::fmt::{
     self, Display, Formatter,
 };
@@ -30,8 +31,34 @@ impl<T> AppResultExt<T>
 
 #[derive(Debug, EnumDiscriminants)]
 pub enum AppError {
+    AmbiguousTitle {
+        title: String,
+        count: usize,
+    },
+
+    AttachmentNotFound {
+        id: Uuid,
+        url: String,
+    },
+
+    AuthNotConfigured,
+
+    AuthTokenInUse,
+
+    BatchCommandFailed {
+        index: usize,
+        reason: String,
+    },
+
     CollectionIsEmpty,
 
+    CommentNotFound {
+        id: Uuid,
+        comment_id: Uuid,
+    },
+
+    CompressionFailed(String),
+
     DataConversionU32ToUsize,
 
     DataConversionUsizeToU64(usize),
@@ -41,18 +68,179 @@ pub enum AppError {
         expected_format: String,
     },
 
+    DeadlineInPast(String),
+
+    DeadlineOutOfRange {
+        input: String,
+        earliest: String,
+        latest: String,
+    },
+
+    DuplicatePrincipal(String),
+
+    DurationOverflow(String),
+
+    EmptyComment,
+
+    EmptyExternalRef,
+
+    EmptySavedQueryName,
+
+    EmptyTemplateName,
+
     EmptyTodoTitle,
 
+    ExternalRefInUse {
+        system: String,
+        external_id: String,
+        holder: Uuid,
+    },
+
+    ExternalRefNotFound {
+        system: String,
+        external_id: String,
+    },
+
+    ImportChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+
+    ImportHeaderMissing {
+        format: String,
+        column: String,
+    },
+
+    ImportPayloadInvalid {
+        format: String,
+        reason: String,
+    },
+
+    ImportRowFailed {
+        row: usize,
+        reason: String,
+    },
+
+    /// A defect caught before it could abort the worker; the code names
+    /// the check that failed.
+    Internal(&'static str),
+
+    InvalidActor(String),
+
+    InvalidAttachmentUrl(String),
+
+    InvalidColor(String),
+
+    InvalidCommentAuthor(String),
+
+    InvalidCompressedPayload(String),
+
+    InvalidContinuation(String),
+
+    InvalidCursor(String),
+
+    InvalidDelta(String),
+
+    InvalidExtractExpression(String),
+
+    InvalidFilterExpr(String),
+
+    InvalidRegex {
+        pattern: String,
+        reason: String,
+    },
+
+    InvalidResultLimit {
+        given: u32,
+        max: u32,
+    },
+
+    InvalidResultSet {
+        set: usize,
+        reason: String,
+    },
+
+    InvalidSimilarityThreshold(u8),
+
+    InvalidSql(String),
+
+    InvalidTimeZone(String),
+
+    InvalidTrendRange(String),
+
     InvalidUuid(String),
 
+    InvalidWatcher(String),
+
+    ListFrozen,
+
+    NoTodoMatches(String),
+
+    OperationLogUnreadable(String),
+
+    OperationLogUnwritable(String),
+
+    PermissionDenied {
+        principal: String,
+        role: Role,
+        required: Role,
+    },
+
+    PinnedViewNotFound(u64),
+
+    PrincipalNotFound(String),
+
+    ReadOnlyReplica,
+
+    SavedQueryNotFound(String),
+
+    SettingsVersionMismatch {
+        expected: u32,
+        actual: u32,
+    },
+
+    ShortIdNotFound(u64),
+
+    SimilarTodoExists(Uuid),
+
+    StartDateAfterDeadline {
+        start_date: i64,
+        deadline: i64,
+    },
+
+    TemplateNotFound(String),
+
+    TimestampOutOfRange(i64),
+
+    TooLongComment {
+        len: usize,
+        max: usize,
+    },
+
     TooLongTodoTitle {
         input: String,
         expected_len: usize,
     },
 
+    TooManyAttachments {
+        id: Uuid,
+        max: usize,
+    },
+
     TodoNotFound(Uuid),
 
+    Unauthorized,
+
+    UnknownIcon {
+        input: String,
+        allowed: String,
+    },
+
     UpdateHasNoChanges,
+
+    WeakAuthToken {
+        min: usize,
+    },
 }
 impl AppError {
     fn kind(
@@ -70,6 +258,56 @@ impl Display for AppError {
         use AppError as E;
 
         match self {
+            e @ E::AmbiguousTitle {
+                title,
+                count,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] {} items are titled '{}'; use an ID instead.",
+                    e.kind(),
+                    count,
+                    title
+                )
+            },
+            e @ E::AttachmentNotFound {
+                id,
+                url,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Item with ID '{}' has no attachment '{}'.",
+                    e.kind(),
+                    id,
+                    url
+                )
+            },
+            e @ E::AuthNotConfigured => {
+                write!(
+                    f,
+                    "[{:?}] Set a shared secret with configure-auth first.",
+                    e.kind()
+                )
+            },
+            e @ E::AuthTokenInUse => {
+                write!(
+                    f,
+                    "[{:?}] That auth token is already in use.",
+                    e.kind()
+                )
+            },
+            e @ E::BatchCommandFailed {
+                index,
+                reason
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Batch command #{} failed, so none was applied: {}",
+                    e.kind(),
+                    index,
+                    reason
+                )
+            },
             e @ E::CollectionIsEmpty => {
                 write!(
                     f,
@@ -77,6 +315,26 @@ impl Display for AppError {
                     e.kind()
                 )
             },
+            e @ E::CommentNotFound {
+                id,
+                comment_id,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Item with ID '{}' has no comment '{}'.",
+                    e.kind(),
+                    id,
+                    comment_id
+                )
+            },
+            e @ E::CompressionFailed(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Cannot compress the payload: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
             e @ E::DataConversionU32ToUsize => {
                 write!(
                     f,
@@ -104,6 +362,72 @@ impl Display for AppError {
                     expected_format
                 )
             },
+            e @ E::DeadlineInPast(input) => {
+                write!(
+                    f,
+                    "[{:?}] Deadline '{}' is already in the past.",
+                    e.kind(),
+                    input
+                )
+            },
+            e @ E::DeadlineOutOfRange {
+                input,
+                earliest,
+                latest
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Deadline '{}' must fall between {} and {}.",
+                    e.kind(),
+                    input,
+                    earliest,
+                    latest
+                )
+            },
+            e @ E::DuplicatePrincipal(name) => {
+                write!(
+                    f,
+                    "[{:?}] A principal named '{}' already exists or the name is reserved.",
+                    e.kind(),
+                    name
+                )
+            },
+            e @ E::DurationOverflow(input) => {
+                write!(
+                    f,
+                    "[{:?}] {} is too long a span of time.",
+                    e.kind(),
+                    input
+                )
+            },
+            e @ E::EmptyComment => {
+                write!(
+                    f,
+                    "[{:?}] Comment cannot be empty.",
+                    e.kind()
+                )
+            },
+            e @ E::EmptyExternalRef => {
+                write!(
+                    f,
+                    "[{:?}] External system and id cannot be empty.",
+                    e.kind()
+                )
+            },
+            e @ E::EmptySavedQueryName => {
+                write!(
+                    f,
+                    "[{:?}] Saved query name cannot be empty.",
+                    e.kind()
+                )
+            },
+            e @ E::EmptyTemplateName => {
+                write!(
+                    f,
+                    "[{:?}] Template name cannot be empty.",
+                    e.kind()
+                )
+            },
             e @ E::EmptyTodoTitle => {
                 write!(
                     f,
@@ -111,6 +435,242 @@ impl Display for AppError {
                     e.kind()
                 )
             },
+            e @ E::ExternalRefInUse {
+                system,
+                external_id,
+                holder,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] '{}' id '{}' already belongs to item '{}'.",
+                    e.kind(),
+                    system,
+                    external_id,
+                    holder
+                )
+            },
+            e @ E::ExternalRefNotFound {
+                system,
+                external_id,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] No item has '{}' id '{}'.",
+                    e.kind(),
+                    system,
+                    external_id
+                )
+            },
+            e @ E::ImportChecksumMismatch {
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Payload checksum is '{}', not the expected '{}', so nothing was imported.",
+                    e.kind(),
+                    actual,
+                    expected
+                )
+            },
+            e @ E::ImportHeaderMissing {
+                format,
+                column,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] No header row with a '{}' column found in the {} payload.",
+                    e.kind(),
+                    column,
+                    format
+                )
+            },
+            e @ E::ImportPayloadInvalid {
+                format,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] The {} payload cannot be read: {}.",
+                    e.kind(),
+                    format,
+                    reason
+                )
+            },
+            e @ E::ImportRowFailed {
+                row,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Row {} failed, so none was imported: {}",
+                    e.kind(),
+                    row,
+                    reason
+                )
+            },
+            e @ E::Internal(code) => {
+                write!(
+                    f,
+                    "[{:?}] Internal error '{}'; please report it.",
+                    e.kind(),
+                    code
+                )
+            },
+            e @ E::InvalidActor(actor) => {
+                write!(
+                    f,
+                    "[{:?}] Actor '{}' must be 1 to 50 characters.",
+                    e.kind(),
+                    actor
+                )
+            },
+            e @ E::InvalidAttachmentUrl(url) => {
+                write!(
+                    f,
+                    "[{:?}] '{}' is not an http or https URL.",
+                    e.kind(),
+                    url
+                )
+            },
+            e @ E::InvalidColor(input) => {
+                write!(
+                    f,
+                    "[{:?}] '{}' is not a hex color like '#1e90ff' or '#fff'.",
+                    e.kind(),
+                    input
+                )
+            },
+            e @ E::InvalidCommentAuthor(author) => {
+                write!(
+                    f,
+                    "[{:?}] Comment author '{}' must be 1 to 50 characters.",
+                    e.kind(),
+                    author
+                )
+            },
+            e @ E::InvalidCompressedPayload(
+                reason,
+            ) => {
+                write!(
+                    f,
+                    "[{:?}] Payload is not validly compressed: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::InvalidContinuation(token) => {
+                write!(
+                    f,
+                    "[{:?}] Continuation '{}' is malformed or belongs to other items.",
+                    e.kind(),
+                    token
+                )
+            },
+            e @ E::InvalidCursor(cursor) => {
+                write!(
+                    f,
+                    "[{:?}] Cursor '{}' is malformed or belongs to a query with another sort.",
+                    e.kind(),
+                    cursor
+                )
+            },
+            e @ E::InvalidDelta(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Delta is not one export-delta produced: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::InvalidExtractExpression(
+                reason,
+            ) => {
+                write!(
+                    f,
+                    "[{:?}] Invalid extract expression: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::InvalidFilterExpr(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Filter expression is malformed: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::InvalidRegex {
+                pattern,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] '{}' is not a usable title pattern: {}.",
+                    e.kind(),
+                    pattern,
+                    reason
+                )
+            },
+            e @ E::InvalidResultLimit {
+                given,
+                max
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Result limit {} is outside the allowed range of 1 to {}.",
+                    e.kind(),
+                    given,
+                    max
+                )
+            },
+            e @ E::InvalidResultSet {
+                set,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Result set {} is not a serialized search page: {}.",
+                    e.kind(),
+                    set,
+                    reason
+                )
+            },
+            e @ E::InvalidSimilarityThreshold(
+                given,
+            ) => {
+                write!(
+                    f,
+                    "[{:?}] Similarity threshold {} is above 100 percent.",
+                    e.kind(),
+                    given
+                )
+            },
+            e @ E::InvalidSql(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Invalid SQL query: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::InvalidTimeZone(s) => {
+                write!(
+                    f,
+                    "[{:?}] '{}' is neither an IANA time zone nor an offset like '+05:30'.",
+                    e.kind(),
+                    s
+                )
+            },
+            e @ E::InvalidTrendRange(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Trend range is invalid: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
             e @ E::InvalidUuid(s) => {
                 write!(
                     f,
@@ -119,6 +679,158 @@ impl Display for AppError {
                     s
                 )
             },
+            e @ E::InvalidWatcher(watcher) => {
+                write!(
+                    f,
+                    "[{:?}] Watcher '{}' must be 1 to 50 characters.",
+                    e.kind(),
+                    watcher
+                )
+            },
+            e @ E::ListFrozen => {
+                write!(
+                    f,
+                    "[{:?}] The list is frozen and cannot be changed.",
+                    e.kind()
+                )
+            },
+            e @ E::NoTodoMatches(input) => {
+                write!(
+                    f,
+                    "[{:?}] No item has the ID, short ID or title '{}'.",
+                    e.kind(),
+                    input
+                )
+            },
+            e @ E::OperationLogUnreadable(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Cannot read the operation log: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::OperationLogUnwritable(reason) => {
+                write!(
+                    f,
+                    "[{:?}] Cannot write to the operation log: {}.",
+                    e.kind(),
+                    reason
+                )
+            },
+            e @ E::PermissionDenied {
+                principal,
+                role,
+                required,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] '{}' is a {:?}, but this takes a {:?}.",
+                    e.kind(),
+                    principal,
+                    role,
+                    required
+                )
+            },
+            e @ E::PinnedViewNotFound(pin) => {
+                write!(
+                    f,
+                    "[{:?}] Pinned view {} not found; it was released or has expired.",
+                    e.kind(),
+                    pin
+                )
+            },
+            e @ E::PrincipalNotFound(name) => {
+                write!(
+                    f,
+                    "[{:?}] No principal is named '{}'.",
+                    e.kind(),
+                    name
+                )
+            },
+            e @ E::ReadOnlyReplica => {
+                write!(
+                    f,
+                    "[{:?}] This list is a read replica; change the primary instead.",
+                    e.kind()
+                )
+            },
+            e @ E::SavedQueryNotFound(name) => {
+                write!(
+                    f,
+                    "[{:?}] Saved query '{}' not found.",
+                    e.kind(),
+                    name
+                )
+            },
+            e @ E::SettingsVersionMismatch {
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] The update is for settings version {}, but the list has version {}.",
+                    e.kind(),
+                    actual,
+                    expected
+                )
+            },
+            e @ E::ShortIdNotFound(short_id) => {
+                write!(
+                    f,
+                    "[{:?}] Item #{} not found.",
+                    e.kind(),
+                    short_id
+                )
+            },
+            e @ E::SimilarTodoExists(id) => {
+                write!(
+                    f,
+                    "[{:?}] Open item '{}' has a very similar title.",
+                    e.kind(),
+                    id
+                )
+            },
+            e @ E::TemplateNotFound(name) => {
+                write!(
+                    f,
+                    "[{:?}] Template '{}' not found.",
+                    e.kind(),
+                    name
+                )
+            },
+            e @ E::StartDateAfterDeadline {
+                start_date,
+                deadline,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Start date {} falls after the deadline {}.",
+                    e.kind(),
+                    start_date,
+                    deadline
+                )
+            },
+            e @ E::TimestampOutOfRange(t) => {
+                write!(
+                    f,
+                    "[{:?}] Unix time {} is outside the years 1 to 9999.",
+                    e.kind(),
+                    t
+                )
+            },
+            e @ E::TooLongComment {
+                len,
+                max,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Comment of {} characters exceeds max {} characters.",
+                    e.kind(),
+                    len,
+                    max
+                )
+            },
             e @ E::TooLongTodoTitle {
                 input,
                 expected_len
@@ -131,6 +843,18 @@ impl Display for AppError {
                     expected_len
                 )
             },
+            e @ E::TooManyAttachments {
+                id,
+                max,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Item with ID '{}' already has the maximum of {} attachments.",
+                    e.kind(),
+                    id,
+                    max
+                )
+            },
             e @ E::TodoNotFound(id) => {
                 write!(
                     f,
@@ -139,6 +863,25 @@ impl Display for AppError {
                     id
                 )
             },
+            e @ E::Unauthorized => {
+                write!(
+                    f,
+                    "[{:?}] A valid auth token is required.",
+                    e.kind()
+                )
+            },
+            e @ E::UnknownIcon {
+                input,
+                allowed,
+            } => {
+                write!(
+                    f,
+                    "[{:?}] Icon '{}' is not one of: {}.",
+                    e.kind(),
+                    input,
+                    allowed
+                )
+            },
             e @ E::UpdateHasNoChanges => {
                 write!(
                     f,
@@ -146,6 +889,14 @@ impl Display for AppError {
                     e.kind()
                 )
             },
+            e @ E::WeakAuthToken { min } => {
+                write!(
+                    f,
+                    "[{:?}] An auth token must be at least {} characters long.",
+                    e.kind(),
+                    min
+                )
+            },
         }
     }
 }
@@ -0,0 +1,136 @@
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use derive_more::From;
+use nutype::nutype;
+
+/// A hex color such as `#1e90ff` or `#fff`; empty means none.
+#[nutype(sanitize(trim, lowercase))]
+#[derive(Clone, From)]
+pub struct Color(String);
+
+impl Color {
+    pub(crate) fn validated(
+        &self,
+    ) -> AppResult<Option<String>> {
+        let color =
+            self.clone().into_inner();
+
+        let digits = color
+            .strip_prefix('#')
+            .filter(|d| {
+                matches!(d.len(), 3 | 6)
+                    && d.chars().all(|c| {
+                        c.is_ascii_hexdigit()
+                    })
+            });
+
+        if color.is_empty() {
+            Ok(None)
+        } else if digits.is_some() {
+            Ok(Some(color))
+        } else {
+            bail!(
+                AppError::InvalidColor(
+                    color
+                )
+            )
+        }
+    }
+}
+
+/// One of `Icon::SAFELIST`; empty means none.
+#[nutype(sanitize(trim, lowercase))]
+#[derive(Clone, From)]
+pub struct Icon(String);
+
+impl Icon {
+    /// Names every client is expected to have artwork for.
+    pub const SAFELIST:
+        &'static [&'static str] = &[
+        "bell",
+        "book",
+        "briefcase",
+        "calendar",
+        "cart",
+        "check",
+        "flag",
+        "heart",
+        "home",
+        "phone",
+        "star",
+        "tag",
+        "wrench",
+    ];
+
+    pub(crate) fn validated(
+        &self,
+    ) -> AppResult<Option<String>> {
+        let icon =
+            self.clone().into_inner();
+
+        if icon.is_empty() {
+            Ok(None)
+        } else if Self::SAFELIST
+            .contains(&icon.as_str())
+        {
+            Ok(Some(icon))
+        } else {
+            bail!(
+                AppError::UnknownIcon {
+                    input: icon,
+                    allowed:
+                        Self::SAFELIST
+                            .join(", "),
+                }
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("#1E90FF", Some("#1e90ff") ; "six digits")]
+    #[test_case(" #fff ", Some("#fff") ; "three digits")]
+    #[test_case("", None ; "empty")]
+    fn color_should_accept_hex(
+        input: &str,
+        expected: Option<&str>,
+    ) {
+        assert_eq!(
+            Color::new(input)
+                .validated()
+                .unwrap(),
+            expected.map(String::from)
+        )
+    }
+
+    #[test_case("1e90ff" ; "no hash")]
+    #[test_case("#12345" ; "five digits")]
+    #[test_case("#ggg" ; "not hex")]
+    fn color_should_reject_anything_else(
+        input: &str,
+    ) {
+        assert!(Color::new(input)
+            .validated()
+            .is_err())
+    }
+
+    #[test]
+    fn icon_should_only_accept_the_safelist(
+    ) {
+        assert_eq!(
+            Icon::new("Star")
+                .validated()
+                .unwrap(),
+            Some("star".into())
+        );
+        assert!(Icon::new("skull")
+            .validated()
+            .is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::app_error::{
+    report, AppError, AppResult, Report,
+};
+use base64::{
+    engine::general_purpose::STANDARD,
+    Engine,
+};
+use flate2::{
+    read::GzDecoder, write::GzEncoder,
+};
+use ruzstd::{
+    decoding::StreamingDecoder,
+    encoding::{
+        compress_to_vec,
+        CompressionLevel,
+    },
+};
+use std::io::{Read, Write};
+
+/// How a bulky payload travels. Anything but `None` is compressed and
+/// then base64-encoded, so it still fits a string parameter or result.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+)]
+pub enum Compression {
+    #[default]
+    None,
+
+    Gzip,
+
+    Zstd,
+}
+
+pub fn encode(
+    text: &str,
+    compression: Compression,
+) -> AppResult<String> {
+    let bytes = match compression {
+        Compression::None => {
+            return Ok(text.to_string())
+        }
+        Compression::Gzip => {
+            let mut encoder =
+                GzEncoder::new(
+                    Vec::new(),
+                    flate2::Compression::default(),
+                );
+
+            encoder
+                .write_all(
+                    text.as_bytes(),
+                )
+                .and_then(|_| {
+                    encoder.finish()
+                })
+                .map_err(|e| {
+                    report!(AppError::CompressionFailed(
+                        e.to_string()
+                    ))
+                })?
+        }
+        // The pure-Rust encoder only implements its fastest level,
+        // which suits a payload that is compressed once and sent.
+        Compression::Zstd => {
+            compress_to_vec(
+                text.as_bytes(),
+                CompressionLevel::Fastest,
+            )
+        }
+    };
+
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Undoes `encode`; the result must be UTF-8 text.
+pub fn decode(
+    payload: &str,
+    compression: Compression,
+) -> AppResult<String> {
+    if compression == Compression::None
+    {
+        return Ok(payload.to_string());
+    }
+
+    let bytes = STANDARD
+        .decode(payload.trim())
+        .map_err(invalid)?;
+
+    let mut text = String::new();
+
+    match compression {
+        Compression::None => {}
+        Compression::Gzip => {
+            GzDecoder::new(
+                bytes.as_slice(),
+            )
+            .read_to_string(&mut text)
+            .map_err(invalid)?;
+        }
+        Compression::Zstd => {
+            let mut source =
+                bytes.as_slice();
+
+            StreamingDecoder::new(
+                &mut source,
+            )
+            .map_err(invalid)?
+            .read_to_string(&mut text)
+            .map_err(invalid)?;
+        }
+    }
+
+    Ok(text)
+}
+
+fn invalid(
+    reason: impl ToString,
+) -> Report<AppError> {
+    report!(
+        AppError::InvalidCompressedPayload(
+            reason.to_string()
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(Compression::None ; "none")]
+    #[test_case(Compression::Gzip ; "gzip")]
+    #[test_case(Compression::Zstd ; "zstd")]
+    fn decode_should_undo_encode(
+        compression: Compression,
+    ) {
+        let text = "(A) Buy milk\n"
+            .repeat(100);
+
+        let encoded =
+            encode(&text, compression)
+                .unwrap();
+
+        assert_eq!(
+            decode(
+                &encoded,
+                compression
+            )
+            .unwrap(),
+            text
+        );
+    }
+
+    #[test_case(Compression::Gzip ; "gzip")]
+    #[test_case(Compression::Zstd ; "zstd")]
+    fn decode_should_reject_garbage(
+        compression: Compression,
+    ) {
+        assert!(decode(
+            "bm90IGNvbXByZXNzZWQ=",
+            compression
+        )
+        .is_err())
+    }
+}
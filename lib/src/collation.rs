@@ -0,0 +1,76 @@
+use unicode_normalization::{
+    char::is_combining_mark,
+    UnicodeNormalization,
+};
+
+/// How titles compare when items are sorted by them. Variants are in
+/// the same order as the WIT `title-collation` enum.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+)]
+pub enum TitleCollation {
+    /// Byte by byte, so uppercase sorts before lowercase and accented
+    /// letters after `z`.
+    #[default]
+    Binary,
+
+    /// Ignoring case and accents, so `Ångström` sorts with `angstrom`.
+    Natural,
+}
+
+impl TitleCollation {
+    /// What sorting compares in place of `title`.
+    pub(crate) fn key(
+        self,
+        title: &str,
+    ) -> String {
+        match self {
+            Self::Binary => {
+                title.into()
+            }
+            Self::Natural => title
+                .nfkd()
+                .filter(|c| {
+                    !is_combining_mark(
+                        *c,
+                    )
+                })
+                .collect::<String>()
+                .to_lowercase(),
+        }
+    }
+
+    pub(crate) fn tag(
+        self,
+    ) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Natural => "natural",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("Ångström", "angstrom" ; "accents")]
+    #[test_case("ﬁle ＡＢＣ", "file abc" ; "compatibility forms")]
+    fn natural_key_should_drop_case_and_accents(
+        title: &str,
+        expected: &str,
+    ) {
+        assert_eq!(
+            TitleCollation::Natural
+                .key(title),
+            expected
+        )
+    }
+}
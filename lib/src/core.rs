@@ -10,6 +10,12 @@ pub type AppResult<T> =
 
 pub(crate) type UnixTime = i64;
 
+pub(crate) const SECONDS_PER_HOUR:
+    UnixTime = 3_600;
+
+pub(crate) const SECONDS_PER_DAY:
+    UnixTime = 86_400;
+
 pub fn u64_from(
     n: usize,
 ) -> AppResult<u64> {
@@ -20,6 +26,16 @@ pub fn u64_from(
         .err_as_string()
 }
 
+pub fn usize_from(
+    n: u32,
+) -> AppResult<usize> {
+    usize::try_from(n)
+        .change_context(
+            AppError::DataConversionU32ToUsize,
+        )
+        .err_as_string()
+}
+
 pub fn uuid_from(
     s: &str,
 ) -> AppResult<Uuid> {
@@ -57,6 +73,31 @@ where
     }
 }
 
+/// Optional cargo features compiled into this build.
+pub fn enabled_features(
+) -> Vec<&'static str> {
+    [
+        (
+            "persistence",
+            cfg!(
+                feature = "persistence"
+            ),
+        ),
+        (
+            "serde",
+            cfg!(feature = "serde"),
+        ),
+        (
+            "tracing",
+            cfg!(feature = "tracing"),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name)
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,300 @@
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+        Report,
+    },
+    collation::TitleCollation,
+    query::{QuerySort, SortDirection},
+    sort_by::SortBy,
+};
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use uuid::Uuid;
+
+// Bumped whenever the payload layout changes, so older cursors are
+// refused rather than misread.
+const CURSOR_VERSION: &str = "3";
+
+const CHECKSUM_LEN: usize = 4;
+
+/// Where a page ended: the sort key and id of its last item. Encoded as
+/// base64 with a checksum, so an edited or truncated cursor is refused
+/// instead of silently paging from the wrong place.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct Cursor {
+    pub(crate) key: SortBy,
+
+    pub(crate) id: Uuid,
+}
+
+impl Cursor {
+    pub(crate) fn encode(
+        &self,
+        sort: &Option<QuerySort>,
+        direction: SortDirection,
+        collation: TitleCollation,
+    ) -> String {
+        let payload = [
+            CURSOR_VERSION,
+            &header(
+                sort, direction,
+                collation,
+            ),
+            &self.id.to_string(),
+            &self.key.value(),
+        ]
+        .join("\n");
+
+        let mut bytes =
+            payload.into_bytes();
+        let sum = checksum(&bytes);
+        bytes.extend(sum.to_be_bytes());
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Reads a cursor issued for a query with the same sort and
+    /// direction, while the list had the same title collation.
+    pub(crate) fn decode(
+        cursor: &str,
+        sort: &Option<QuerySort>,
+        direction: SortDirection,
+        collation: TitleCollation,
+    ) -> AppResult<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| {
+                invalid(cursor)
+            })?;
+
+        if bytes.len() < CHECKSUM_LEN {
+            return Err(invalid(
+                cursor,
+            ));
+        }
+
+        let (payload, sum) = bytes
+            .split_at(
+                bytes.len()
+                    - CHECKSUM_LEN,
+            );
+
+        if checksum(payload)
+            .to_be_bytes()
+            != sum
+        {
+            return Err(invalid(
+                cursor,
+            ));
+        }
+
+        let payload =
+            std::str::from_utf8(
+                payload,
+            )
+            .map_err(
+                |_| invalid(cursor),
+            )?;
+
+        // The value goes last, since a title may contain anything.
+        let mut parts =
+            payload.splitn(4, '\n');
+
+        let (
+            Some(CURSOR_VERSION),
+            Some(given_header),
+            Some(id),
+            Some(value),
+        ) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        )
+        else {
+            return Err(invalid(
+                cursor,
+            ));
+        };
+
+        if given_header
+            != header(
+                sort, direction,
+                collation,
+            )
+        {
+            return Err(invalid(
+                cursor,
+            ));
+        }
+
+        let id = Uuid::try_parse(id)
+            .map_err(|_| {
+                invalid(cursor)
+            })?;
+
+        let key = SortBy::parse(
+            sort, value, id,
+        )
+        .ok_or_else(|| {
+            invalid(cursor)
+        })?;
+
+        Ok(Self { key, id })
+    }
+}
+
+fn header(
+    sort: &Option<QuerySort>,
+    direction: SortDirection,
+    collation: TitleCollation,
+) -> String {
+    let direction = match direction {
+        SortDirection::Ascending => {
+            "asc"
+        }
+        SortDirection::Descending => {
+            "desc"
+        }
+    };
+
+    format!(
+        "{}:{}:{}",
+        SortBy::tag(sort),
+        direction,
+        collation.tag()
+    )
+}
+
+// FNV-1a: cheap, and enough to catch a cursor that was edited by hand.
+pub(crate) fn checksum(
+    bytes: &[u8],
+) -> u32 {
+    bytes.iter().fold(
+        0x811c_9dc5,
+        |hash, b| {
+            (hash ^ u32::from(*b))
+                .wrapping_mul(
+                    0x0100_0193,
+                )
+        },
+    )
+}
+
+fn invalid(
+    cursor: &str,
+) -> Report<AppError> {
+    report!(AppError::InvalidCursor(
+        cursor.into()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort_by::Tiebreak;
+    use pretty_assertions::assert_eq;
+
+    fn cursor() -> Cursor {
+        let id = Uuid::nil();
+
+        Cursor {
+            key: SortBy::CreatedAt(
+                42,
+                Tiebreak(
+                    "a".into(),
+                    id,
+                ),
+            ),
+            id,
+        }
+    }
+
+    #[test]
+    fn decode_should_read_back_an_encoded_cursor(
+    ) {
+        let sort =
+            Some(QuerySort::CreatedAt);
+
+        let encoded = cursor().encode(
+            &sort,
+            SortDirection::Descending,
+            TitleCollation::Binary,
+        );
+
+        assert_eq!(
+            Cursor::decode(
+                &encoded,
+                &sort,
+                SortDirection::Descending,
+                TitleCollation::Binary
+            )
+            .unwrap(),
+            cursor()
+        )
+    }
+
+    #[test]
+    fn decode_should_reject_a_cursor_for_another_sort(
+    ) {
+        let encoded = cursor().encode(
+            &Some(QuerySort::CreatedAt),
+            SortDirection::Ascending,
+            TitleCollation::Binary,
+        );
+
+        assert!(Cursor::decode(
+            &encoded,
+            &Some(QuerySort::CreatedAt),
+            SortDirection::Descending,
+            TitleCollation::Binary
+        )
+        .is_err());
+        assert!(Cursor::decode(
+            &encoded,
+            &Some(QuerySort::Priority),
+            SortDirection::Ascending,
+            TitleCollation::Binary
+        )
+        .is_err());
+        assert!(Cursor::decode(
+            &encoded,
+            &Some(QuerySort::CreatedAt),
+            SortDirection::Ascending,
+            TitleCollation::Natural
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn decode_should_reject_an_edited_cursor(
+    ) {
+        let sort =
+            Some(QuerySort::CreatedAt);
+
+        let mut encoded = cursor()
+            .encode(
+                &sort,
+                SortDirection::Ascending,
+                TitleCollation::Binary,
+            )
+            .into_bytes();
+        encoded[3] =
+            if encoded[3] == b'A' {
+                b'B'
+            } else {
+                b'A'
+            };
+
+        assert!(Cursor::decode(
+            &String::from_utf8(encoded)
+                .unwrap(),
+            &sort,
+            SortDirection::Ascending,
+            TitleCollation::Binary
+        )
+        .is_err());
+    }
+}
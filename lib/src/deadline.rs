@@ -1,16 +1,28 @@
 use crate::{
     app_error::{
-        AppError, AppResult, ResultExt,
+        report, AppError, AppResult,
     },
-    core::UnixTime,
+    core::{
+        UnixTime, SECONDS_PER_DAY,
+        SECONDS_PER_HOUR,
+    },
+    time,
+    time_zone::TimeZone,
+};
+use chrono::{
+    naive::{NaiveDate, NaiveDateTime},
+    DateTime, Utc,
 };
-use chrono::naive::NaiveDateTime;
-use derive_more::From;
 use once_cell::sync::Lazy;
 
 pub(crate) const USER_DATE_TIME_FORMAT: &str =
     "%Y-%m-%d %H";
 
+const USER_DATE_FORMAT: &str =
+    "%Y-%m-%d";
+
+const TOMORROW: &str = "tomorrow";
+
 static DATE_TIME_FORMAT: Lazy<String> =
     Lazy::new(|| {
         format!(
@@ -19,40 +31,318 @@ static DATE_TIME_FORMAT: Lazy<String> =
         )
     });
 
-#[derive(Clone, Default, From)]
-pub struct OptionalDeadlineInput(
-    Option<String>,
-);
+pub(crate) static ACCEPTED_DEADLINE_FORMATS: Lazy<String> =
+    Lazy::new(|| {
+        format!(
+            "{} | {} | RFC 3339 | +<n>d | +<n>h | {} %H",
+            USER_DATE_TIME_FORMAT,
+            USER_DATE_FORMAT,
+            TOMORROW
+        )
+    });
+
+#[derive(Clone, Debug, Default)]
+pub struct OptionalDeadlineInput {
+    input: Option<String>,
+
+    time_zone: Option<String>,
+
+    ignore_bounds: bool,
+}
+
+impl From<Option<String>>
+    for OptionalDeadlineInput
+{
+    fn from(
+        input: Option<String>,
+    ) -> Self {
+        Self::new(input, None)
+    }
+}
 
 impl OptionalDeadlineInput {
-    pub(crate) fn is_some(
+    pub fn new(
+        input: Option<String>,
+        time_zone: Option<String>,
+    ) -> Self {
+        Self {
+            input,
+            time_zone,
+            ignore_bounds: false,
+        }
+    }
+
+    /// Every input shape the parser understands.
+    pub fn accepted_formats() -> String
+    {
+        ACCEPTED_DEADLINE_FORMATS
+            .clone()
+    }
+
+    /// Exempts this input from the list's deadline bounds.
+    pub fn ignoring_bounds(
+        self,
+        ignore_bounds: bool,
+    ) -> Self {
+        Self {
+            ignore_bounds,
+            ..self
+        }
+    }
+
+    pub(crate) fn input(
+        &self,
+    ) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    pub(crate) fn names_time_zone(
         &self,
     ) -> bool {
-        self.0.is_some()
+        self.time_zone.is_some()
+    }
+
+    pub(crate) fn ignores_bounds(
+        &self,
+    ) -> bool {
+        self.ignore_bounds
+    }
+
+    // Falls back to `default` when the input does not name its own zone.
+    pub(crate) fn time_zone_or(
+        &self,
+        default: TimeZone,
+    ) -> AppResult<TimeZone> {
+        self.time_zone
+            .as_deref()
+            .map(TimeZone::parse)
+            .transpose()
+            .map(|tz| {
+                tz.unwrap_or(default)
+            })
     }
 
     pub(crate) fn unix_time(
         &self,
+        default_time_zone: TimeZone,
     ) -> AppResult<Option<UnixTime>>
     {
-        self.0.as_ref().map(|s| {
-            let unix_time =
-                NaiveDateTime::parse_from_str(
-                    &format!("{}:00:00", s.trim()),
-                    &DATE_TIME_FORMAT
+        self.unix_time_at(
+            Utc::now().timestamp(),
+            default_time_zone,
+        )
+    }
+
+    // Relative inputs such as `+3d` are resolved against `now`.
+    pub(crate) fn unix_time_at(
+        &self,
+        now: UnixTime,
+        default_time_zone: TimeZone,
+    ) -> AppResult<Option<UnixTime>>
+    {
+        let time_zone = self
+            .time_zone_or(
+                default_time_zone,
+            )?;
+
+        self.input
+            .as_ref()
+            .map(|s| {
+                parse(
+                    s.trim(),
+                    now,
+                    time_zone,
                 )
-                .change_context(
-                    AppError::DateTimeParseError {
-                        input: s.into(),
-                        expected_format: USER_DATE_TIME_FORMAT.into(),
-                    },
-                )?
-                .timestamp();
-
-            Ok(unix_time)
+                .ok_or_else(|| {
+                    report!(
+                        AppError::DateTimeParseError {
+                            input: s.into(),
+                            expected_format: ACCEPTED_DEADLINE_FORMATS.clone(),
+                        }
+                    )
+                })
+            })
+            .transpose()
+    }
+}
+
+/// What an update does with an item's deadline.
+#[derive(Clone, Default)]
+pub enum DeadlineChange {
+    #[default]
+    Keep,
+
+    Clear,
+
+    Set(OptionalDeadlineInput),
+}
+
+impl DeadlineChange {
+    pub(crate) fn is_keep(
+        &self,
+    ) -> bool {
+        matches!(self, Self::Keep)
+    }
+
+    // `None` leaves the deadline untouched while `Some(None)` removes it.
+    pub(crate) fn resolved(
+        &self,
+        default_time_zone: TimeZone,
+    ) -> AppResult<
+        Option<
+            Option<(
+                UnixTime,
+                TimeZone,
+            )>,
+        >,
+    > {
+        match self {
+            Self::Keep => Ok(None),
+            Self::Clear => {
+                Ok(Some(None))
+            }
+            Self::Set(input) => {
+                let time_zone = input
+                    .time_zone_or(
+                    default_time_zone,
+                )?;
+
+                let deadline = input
+                    .unix_time(
+                        time_zone,
+                    )?;
+
+                Ok(Some(deadline.map(
+                    |d| (d, time_zone),
+                )))
+            }
+        }
+    }
+}
+
+fn parse(
+    s: &str,
+    now: UnixTime,
+    time_zone: TimeZone,
+) -> Option<UnixTime> {
+    parse_date_hour(s, time_zone)
+        .or_else(|| parse_rfc3339(s))
+        .or_else(|| {
+            parse_date(s, time_zone)
+        })
+        .or_else(|| {
+            parse_relative(s, now)
+        })
+        .or_else(|| {
+            parse_tomorrow(
+                s, now, time_zone,
+            )
+        })
+}
+
+fn parse_date_hour(
+    s: &str,
+    time_zone: TimeZone,
+) -> Option<UnixTime> {
+    NaiveDateTime::parse_from_str(
+        &format!("{}:00:00", s),
+        &DATE_TIME_FORMAT,
+    )
+    .ok()
+    .and_then(|t| {
+        time_zone.to_unix_time(&t)
+    })
+}
+
+fn parse_rfc3339(
+    s: &str,
+) -> Option<UnixTime> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.timestamp())
+}
+
+// A bare date means "by the end of that day".
+fn parse_date(
+    s: &str,
+    time_zone: TimeZone,
+) -> Option<UnixTime> {
+    NaiveDate::parse_from_str(
+        s,
+        USER_DATE_FORMAT,
+    )
+    .ok()?
+    .and_hms_opt(23, 59, 59)
+    .and_then(|t| {
+        time_zone.to_unix_time(&t)
+    })
+}
+
+fn parse_relative(
+    s: &str,
+    now: UnixTime,
+) -> Option<UnixTime> {
+    let offset = s.strip_prefix('+')?;
+
+    let unit_seconds = if let Some(n) =
+        offset.strip_suffix('d')
+    {
+        Some((n, SECONDS_PER_DAY))
+    } else {
+        offset.strip_suffix('h').map(
+            |n| (n, SECONDS_PER_HOUR),
+        )
+    };
+
+    let (n, seconds) = unit_seconds?;
+
+    if n.is_empty()
+        || !n
+            .chars()
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    n.parse::<UnixTime>()
+        .ok()?
+        .checked_mul(seconds)?
+        .checked_add(now)
+        .and_then(|t| {
+            time::in_range(t).ok()
         })
-        .transpose()
+}
+
+fn parse_tomorrow(
+    s: &str,
+    now: UnixTime,
+    time_zone: TimeZone,
+) -> Option<UnixTime> {
+    let hour = s
+        .to_lowercase()
+        .strip_prefix(TOMORROW)?
+        .trim()
+        .to_string();
+
+    if hour.is_empty()
+        || hour.len() > 2
+        || !hour
+            .chars()
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
     }
+
+    let local = time_zone
+        .local_date(now)?
+        .succ_opt()?
+        .and_hms_opt(
+            hour.parse().ok()?,
+            0,
+            0,
+        )?;
+
+    time_zone.to_unix_time(&local)
 }
 
 #[cfg(test)]
@@ -66,11 +356,11 @@ mod tests {
         pub(crate) fn some(
             s: &str,
         ) -> Self {
-            Self(Some(s.into()))
+            Some(s.to_string()).into()
         }
 
         pub(crate) fn none() -> Self {
-            Self(None)
+            None.into()
         }
     }
 
@@ -89,6 +379,21 @@ mod tests {
         7_983_874_800 ;
         "epoch of 2222-12-31 23 hour should be 1641027600"
     )]
+    #[test_case(
+        "2022-01-01T09:00:00Z",
+        1_641_027_600 ;
+        "rfc 3339 in utc"
+    )]
+    #[test_case(
+        "2022-01-01T10:00:00+01:00",
+        1_641_027_600 ;
+        "rfc 3339 with offset"
+    )]
+    #[test_case(
+        "2022-01-01",
+        1_641_081_599 ;
+        "date only should default to end of day"
+    )]
     fn unix_time_should_succeed_with_expected_unix_time(
         input: &str,
         expected: i64,
@@ -98,7 +403,9 @@ mod tests {
                 input,
             );
         let actual = deadline
-            .unix_time()
+            .unix_time(
+                TimeZone::default(),
+            )
             .unwrap()
             .unwrap();
 
@@ -109,18 +416,27 @@ mod tests {
     fn unix_time_should_succeed_with_none_when_input_is_none(
     ) {
         let deadline =
-            OptionalDeadlineInput(None);
+            OptionalDeadlineInput::none(
+            );
 
         let actual = deadline
-            .unix_time()
+            .unix_time(
+                TimeZone::default(),
+            )
             .unwrap();
 
         assert_eq!(actual, None)
     }
 
-    #[test_case("2022-01-01")]
     #[test_case("abc")]
     #[test_case("2021-02-29 01")]
+    #[test_case("2021-02-29")]
+    #[test_case("+3")]
+    #[test_case("+d")]
+    #[test_case("+-3d")]
+    #[test_case("+3w")]
+    #[test_case("tomorrow")]
+    #[test_case("tomorrow 24")]
     fn unix_time_should_fail_when_input_does_not_match_expected_date_time_format_or_the_date_is_invalid(
         input: &str,
     ) {
@@ -128,16 +444,131 @@ mod tests {
             OptionalDeadlineInput::some(
                 input,
             );
-        let actual =
-            deadline.unix_time();
+        let actual = deadline
+            .unix_time(
+                TimeZone::default(),
+            );
 
         let expected = AppError::DateTimeParseError {
                 input: input.into(),
-                expected_format: USER_DATE_TIME_FORMAT.into()
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
             };
 
         assert_app_error!(
             actual, expected
         )
     }
+
+    // 2022-01-01 09:00:00 UTC
+    const NOW: UnixTime = 1_641_027_600;
+
+    #[test_case(
+        "+3d",
+        NOW + 3 * SECONDS_PER_DAY ;
+        "days from now"
+    )]
+    #[test_case(
+        " +2h ",
+        NOW + 2 * SECONDS_PER_HOUR ;
+        "hours from now"
+    )]
+    #[test_case(
+        "tomorrow 09",
+        NOW + SECONDS_PER_DAY ;
+        "tomorrow at the given hour"
+    )]
+    #[test_case(
+        "Tomorrow 0",
+        1_641_081_600 ;
+        "tomorrow at midnight"
+    )]
+    fn unix_time_at_should_resolve_relative_input_against_now(
+        input: &str,
+        expected: UnixTime,
+    ) {
+        let deadline =
+            OptionalDeadlineInput::some(
+                input,
+            );
+        let actual = deadline
+            .unix_time_at(
+                NOW,
+                TimeZone::default(),
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(actual, expected)
+    }
+
+    #[test_case(
+        "2022-01-01 10",
+        "Europe/Berlin",
+        NOW ;
+        "date hour in a named zone"
+    )]
+    #[test_case(
+        "2022-01-01 14",
+        "+05:00",
+        NOW ;
+        "date hour at a fixed offset"
+    )]
+    #[test_case(
+        "2022-01-01T09:00:00Z",
+        "+05:00",
+        NOW ;
+        "rfc 3339 ignores the zone"
+    )]
+    #[test_case(
+        "tomorrow 04",
+        "America/New_York",
+        NOW + SECONDS_PER_DAY ;
+        "tomorrow in a named zone"
+    )]
+    fn unix_time_at_should_interpret_local_input_in_the_given_time_zone(
+        input: &str,
+        time_zone: &str,
+        expected: UnixTime,
+    ) {
+        let deadline =
+            OptionalDeadlineInput::new(
+                Some(input.into()),
+                Some(time_zone.into()),
+            );
+        let actual = deadline
+            .unix_time_at(
+                NOW,
+                TimeZone::default(),
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn unix_time_should_fail_when_time_zone_is_invalid(
+    ) {
+        let deadline =
+            OptionalDeadlineInput::new(
+                Some(
+                    "2022-01-01 09"
+                        .into(),
+                ),
+                Some("Nowhere".into()),
+            );
+        let actual = deadline
+            .unix_time(
+                TimeZone::default(),
+            );
+
+        let expected =
+            AppError::InvalidTimeZone(
+                "Nowhere".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
 }
@@ -0,0 +1,315 @@
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+        Report,
+    },
+    core::UnixTime,
+    todos::{Priority, Status, Todo},
+};
+
+// Bounds the work a single query can ask for.
+const MAX_FILTER_NODES: usize = 256;
+
+/// A single test on one field of an item.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldPredicate {
+    Keyword(String),
+
+    Priority(Priority),
+
+    Status(Status),
+
+    /// Open, with a deadline that has already passed.
+    Overdue,
+
+    HasDeadline,
+}
+
+impl FieldPredicate {
+    fn matches(
+        &self,
+        now: UnixTime,
+        todo: &Todo,
+    ) -> bool {
+        match self {
+            Self::Keyword(keyword) => {
+                todo.title()
+                    .contains(keyword)
+            }
+            Self::Priority(p) => {
+                *p == todo.priority()
+            }
+            Self::Status(s) => {
+                *s == todo.status()
+            }
+            Self::Overdue => {
+                todo.is_open()
+                    && todo
+                        .is_due_before(
+                            now,
+                        )
+            }
+            Self::HasDeadline => todo
+                .deadline()
+                .is_some(),
+        }
+    }
+}
+
+/// A boolean combination of field predicates, such as
+/// `(High AND overdue) OR keyword:urgent`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterExpr {
+    /// True when every child is; an empty `And` matches everything.
+    And(Vec<FilterExpr>),
+
+    /// True when any child is; an empty `Or` matches nothing.
+    Or(Vec<FilterExpr>),
+
+    Not(Box<FilterExpr>),
+
+    Field(FieldPredicate),
+}
+
+/// One node of a `FilterExpr` laid out flat, as in the WIT
+/// `filter-expr` record, whose variants cannot nest.
+#[derive(Clone, Debug)]
+pub enum FilterNode {
+    And(Vec<u32>),
+
+    Or(Vec<u32>),
+
+    Not(u32),
+
+    Field(FieldPredicate),
+}
+
+impl FilterExpr {
+    /// Builds the tree rooted at the first node. Each child index must
+    /// come after its parent and be used only once, which keeps the
+    /// tree acyclic and no larger than the node list.
+    pub fn from_nodes(
+        nodes: Vec<FilterNode>,
+    ) -> AppResult<Self> {
+        if nodes.is_empty() {
+            return Err(invalid(
+                "no nodes were given"
+                    .into(),
+            ));
+        }
+
+        if nodes.len()
+            > MAX_FILTER_NODES
+        {
+            return Err(invalid(format!(
+                "{} nodes exceed the limit of {}",
+                nodes.len(),
+                MAX_FILTER_NODES
+            )));
+        }
+
+        let mut used =
+            vec![false; nodes.len()];
+
+        build(&nodes, 0, &mut used)
+    }
+
+    pub(crate) fn matches(
+        &self,
+        now: UnixTime,
+        todo: &Todo,
+    ) -> bool {
+        match self {
+            Self::And(children) => {
+                children.iter().all(
+                    |c| {
+                        c.matches(
+                            now, todo,
+                        )
+                    },
+                )
+            }
+            Self::Or(children) => {
+                children.iter().any(
+                    |c| {
+                        c.matches(
+                            now, todo,
+                        )
+                    },
+                )
+            }
+            Self::Not(child) => !child
+                .matches(now, todo),
+            Self::Field(predicate) => {
+                predicate
+                    .matches(now, todo)
+            }
+        }
+    }
+}
+
+fn invalid(
+    reason: String,
+) -> Report<AppError> {
+    report!(
+        AppError::InvalidFilterExpr(
+            reason
+        )
+    )
+}
+
+fn build(
+    nodes: &[FilterNode],
+    index: usize,
+    used: &mut [bool],
+) -> AppResult<FilterExpr> {
+    let mut child = |c: u32| -> AppResult<
+        FilterExpr,
+    > {
+        let c = c as usize;
+
+        if c <= index {
+            return Err(invalid(format!(
+                "node {} refers to node {}, which does not come after it",
+                index, c
+            )));
+        }
+
+        if c >= nodes.len() {
+            return Err(invalid(format!(
+                "node {} refers to missing node {}",
+                index, c
+            )));
+        }
+
+        if used[c] {
+            return Err(invalid(format!(
+                "node {} has more than one parent",
+                c
+            )));
+        }
+        used[c] = true;
+
+        build(nodes, c, used)
+    };
+
+    let expr = match &nodes[index] {
+        FilterNode::And(children) => {
+            FilterExpr::And(
+                children
+                    .iter()
+                    .map(|c| child(*c))
+                    .collect::<AppResult<_>>(
+                    )?,
+            )
+        }
+        FilterNode::Or(children) => {
+            FilterExpr::Or(
+                children
+                    .iter()
+                    .map(|c| child(*c))
+                    .collect::<AppResult<_>>(
+                    )?,
+            )
+        }
+        FilterNode::Not(c) => {
+            FilterExpr::Not(Box::new(
+                child(*c)?,
+            ))
+        }
+        FilterNode::Field(predicate) => {
+            FilterExpr::Field(
+                predicate.clone(),
+            )
+        }
+    };
+
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_nodes_should_build_the_tree_rooted_at_the_first_node(
+    ) {
+        let actual =
+            FilterExpr::from_nodes(vec![
+                FilterNode::Or(vec![1, 4]),
+                FilterNode::And(vec![2, 3]),
+                FilterNode::Field(
+                    FieldPredicate::Priority(
+                        Priority::High,
+                    ),
+                ),
+                FilterNode::Field(
+                    FieldPredicate::Overdue,
+                ),
+                FilterNode::Field(
+                    FieldPredicate::Keyword(
+                        "urgent".into(),
+                    ),
+                ),
+            ])
+            .unwrap();
+
+        let expected = FilterExpr::Or(vec![
+            FilterExpr::And(vec![
+                FilterExpr::Field(
+                    FieldPredicate::Priority(
+                        Priority::High,
+                    ),
+                ),
+                FilterExpr::Field(
+                    FieldPredicate::Overdue,
+                ),
+            ]),
+            FilterExpr::Field(
+                FieldPredicate::Keyword(
+                    "urgent".into(),
+                ),
+            ),
+        ]);
+
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn from_nodes_should_reject_a_shared_child(
+    ) {
+        let actual =
+            FilterExpr::from_nodes(vec![
+                FilterNode::And(vec![1, 1]),
+                FilterNode::Field(
+                    FieldPredicate::HasDeadline,
+                ),
+            ]);
+
+        assert_eq!(
+            actual
+                .unwrap_err()
+                .current_context()
+                .to_string(),
+            AppError::InvalidFilterExpr(
+                "node 1 has more than one parent"
+                    .into()
+            )
+            .to_string()
+        )
+    }
+
+    #[test]
+    fn from_nodes_should_reject_a_child_before_its_parent(
+    ) {
+        let actual =
+            FilterExpr::from_nodes(
+                vec![FilterNode::Not(
+                    0,
+                )],
+            );
+
+        assert!(actual.is_err())
+    }
+}
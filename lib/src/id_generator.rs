@@ -0,0 +1,268 @@
+use chrono::Utc;
+use uuid::{Builder, Uuid};
+
+/// Source of IDs for new items.
+pub trait IdGenerator: Send {
+    fn next_id(&mut self) -> Uuid;
+
+    /// A generator in the same state, so a batch that fails can hand
+    /// out the IDs it used again.
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn IdGenerator>;
+}
+
+impl Default for Box<dyn IdGenerator> {
+    fn default() -> Self {
+        Box::new(RandomIdGenerator)
+    }
+}
+
+/// Random v4 UUIDs.
+#[derive(
+    Clone, Copy, Debug, Default,
+)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn next_id(&mut self) -> Uuid {
+        Uuid::new_v4()
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn IdGenerator> {
+        Box::new(*self)
+    }
+}
+
+/// Well-formed v4 UUIDs from a seeded pseudo-random sequence, so the same
+/// seed always yields the same IDs.
+#[derive(Clone, Copy, Debug)]
+pub struct SeededIdGenerator {
+    state: u64,
+}
+
+impl SeededIdGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        self.state =
+            self.state.wrapping_add(
+                0x9E37_79B9_7F4A_7C15,
+            );
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30))
+            .wrapping_mul(
+                0xBF58_476D_1CE4_E5B9,
+            );
+        z = (z ^ (z >> 27))
+            .wrapping_mul(
+                0x94D0_49BB_1331_11EB,
+            );
+
+        z ^ (z >> 31)
+    }
+}
+
+impl IdGenerator for SeededIdGenerator {
+    fn next_id(&mut self) -> Uuid {
+        let mut bytes = [0; 16];
+
+        bytes[..8].copy_from_slice(
+            &self
+                .next_u64()
+                .to_be_bytes(),
+        );
+        bytes[8..].copy_from_slice(
+            &self
+                .next_u64()
+                .to_be_bytes(),
+        );
+
+        Builder::from_random_bytes(
+            bytes,
+        )
+        .into_uuid()
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn IdGenerator> {
+        Box::new(*self)
+    }
+}
+
+/// v7 UUIDs: a millisecond timestamp followed by random bits, so IDs
+/// sort roughly by creation time.
+#[derive(
+    Clone, Copy, Debug, Default,
+)]
+pub struct TimeOrderedIdGenerator;
+
+impl IdGenerator
+    for TimeOrderedIdGenerator
+{
+    fn next_id(&mut self) -> Uuid {
+        let millis = Utc::now()
+            .timestamp_millis()
+            .to_be_bytes();
+
+        let mut bytes =
+            *Uuid::new_v4().as_bytes();
+
+        bytes[..6].copy_from_slice(
+            &millis[2..],
+        );
+        bytes[6] =
+            (bytes[6] & 0x0F) | 0x70;
+        bytes[8] =
+            (bytes[8] & 0x3F) | 0x80;
+
+        Uuid::from_bytes(bytes)
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn IdGenerator> {
+        Box::new(*self)
+    }
+}
+
+/// `00000000-0000-0000-0000-000000000001`, `...0002` and so on.
+#[derive(Clone, Copy, Debug)]
+pub struct SequentialIdGenerator {
+    next: u128,
+}
+
+impl Default for SequentialIdGenerator {
+    fn default() -> Self {
+        Self { next: 1 }
+    }
+}
+
+impl IdGenerator
+    for SequentialIdGenerator
+{
+    fn next_id(&mut self) -> Uuid {
+        let id =
+            Uuid::from_u128(self.next);
+
+        self.next += 1;
+
+        id
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn IdGenerator> {
+        Box::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use uuid::{uuid, Version};
+
+    fn take(
+        generator: &mut impl IdGenerator,
+        n: usize,
+    ) -> Vec<Uuid> {
+        (0..n)
+            .map(|_| {
+                generator.next_id()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_id_generator_should_generate_v4_uuids(
+    ) {
+        let id =
+            RandomIdGenerator.next_id();
+
+        assert_eq!(
+            id.get_version(),
+            Some(Version::Random)
+        );
+    }
+
+    #[test]
+    fn seeded_id_generator_should_repeat_the_sequence_for_the_same_seed(
+    ) {
+        let first = take(
+            &mut SeededIdGenerator::new(
+                42,
+            ),
+            3,
+        );
+        let second = take(
+            &mut SeededIdGenerator::new(
+                42,
+            ),
+            3,
+        );
+        let other = take(
+            &mut SeededIdGenerator::new(
+                43,
+            ),
+            3,
+        );
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert!(first.iter().all(
+            |id| {
+                id.get_version()
+                    == Some(
+                        Version::Random,
+                    )
+            }
+        ));
+    }
+
+    #[test]
+    fn time_ordered_id_generator_should_generate_v7_uuids_in_creation_order(
+    ) {
+        let first =
+            TimeOrderedIdGenerator
+                .next_id();
+
+        std::thread::sleep(
+            std::time::Duration::from_millis(2),
+        );
+
+        let second =
+            TimeOrderedIdGenerator
+                .next_id();
+
+        assert_eq!(
+            first.get_version_num(),
+            7
+        );
+        assert!(first < second);
+    }
+
+    #[test]
+    fn sequential_id_generator_should_count_up_from_1(
+    ) {
+        let actual = take(
+            &mut SequentialIdGenerator::default(),
+            2,
+        );
+
+        assert_eq!(
+            actual,
+            vec![
+                uuid!("00000000-0000-0000-0000-000000000001"),
+                uuid!("00000000-0000-0000-0000-000000000002"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Just enough RFC 4180 for app exports: quoted fields, doubled quotes,
+// line breaks inside quotes, CRLF endings and a leading BOM.
+pub(super) fn records(
+    payload: &str,
+) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    let mut chars = payload
+        .trim_start_matches('\u{feff}')
+        .chars()
+        .peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) => {
+                if chars.peek()
+                    == Some(&'"')
+                {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            ('"', false)
+                if field.is_empty() =>
+            {
+                in_quotes = true
+            }
+            (',', false) => record
+                .push(std::mem::take(
+                    &mut field,
+                )),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(
+                    std::mem::take(
+                        &mut field,
+                    ),
+                );
+                records.push(
+                    std::mem::take(
+                        &mut record,
+                    ),
+                );
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty()
+        || !record.is_empty()
+    {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+        .into_iter()
+        .filter(|r| {
+            r.iter().any(|f| {
+                !f.trim().is_empty()
+            })
+        })
+        .collect()
+}
+
+/// Looks fields up by header name, so column order does not matter.
+pub(super) struct Header(Vec<String>);
+
+impl Header {
+    pub(super) fn new(
+        record: &[String],
+    ) -> Self {
+        Self(
+            record
+                .iter()
+                .map(|f| {
+                    f.trim()
+                        .to_lowercase()
+                })
+                .collect(),
+        )
+    }
+
+    pub(super) fn contains(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.0.iter().any(|h| h == name)
+    }
+
+    // Missing and blank fields both come back as `None`.
+    pub(super) fn get<'a>(
+        &self,
+        record: &'a [String],
+        name: &str,
+    ) -> Option<&'a str> {
+        self.0
+            .iter()
+            .position(|h| h == name)
+            .and_then(|i| record.get(i))
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn records_should_handle_quotes_and_embedded_line_breaks(
+    ) {
+        let actual = records(
+            "\u{feff}a,\"b, \"\"c\"\"\"\r\n\"multi\nline\",d\n\n",
+        );
+
+        let expected = vec![
+            vec![
+                "a".to_string(),
+                "b, \"c\"".to_string(),
+            ],
+            vec![
+                "multi\nline"
+                    .to_string(),
+                "d".to_string(),
+            ],
+        ];
+
+        assert_eq!(actual, expected)
+    }
+}
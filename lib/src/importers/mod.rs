@@ -0,0 +1,249 @@
+//! Adapters between other apps' files and this app's todos.
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    core::UnixTime,
+    todos::NewTodo,
+};
+
+mod csv;
+#[cfg(feature = "json-import")]
+mod things;
+mod ticktick;
+mod todoist;
+pub(crate) mod todotxt;
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum ImportFormat {
+    /// Todoist's "Export as CSV" template.
+    TodoistCsv,
+
+    /// TickTick's backup CSV.
+    TickTickCsv,
+
+    /// One todo.txt task per line.
+    TodoTxt,
+
+    /// Tasks as Todoist's REST API returns them.
+    #[cfg(feature = "json-import")]
+    TodoistJson,
+
+    /// Tasks as TickTick's Open API returns them, on their own or with
+    /// their project.
+    #[cfg(feature = "json-import")]
+    TickTickJson,
+
+    /// Things' JSON, with to-dos on their own or inside projects.
+    #[cfg(feature = "json-import")]
+    ThingsJson,
+}
+
+impl ImportFormat {
+    /// The system an item's external ref names for ids from this
+    /// format.
+    pub(crate) fn system(
+        self,
+    ) -> &'static str {
+        match self {
+            Self::TodoistCsv => {
+                "todoist"
+            }
+            Self::TickTickCsv => {
+                "ticktick"
+            }
+            Self::TodoTxt => "todotxt",
+            #[cfg(
+                feature = "json-import"
+            )]
+            Self::TodoistJson => {
+                "todoist"
+            }
+            #[cfg(
+                feature = "json-import"
+            )]
+            Self::TickTickJson => {
+                "ticktick"
+            }
+            #[cfg(
+                feature = "json-import"
+            )]
+            Self::ThingsJson => {
+                "things"
+            }
+        }
+    }
+}
+
+/// One task from an export, mapped onto this app's fields.
+pub(crate) struct ExternalTodo {
+    pub(crate) item: NewTodo,
+
+    pub(crate) done: bool,
+
+    /// The task's id in the exporting app, when the format has one.
+    pub(crate) external_id:
+        Option<String>,
+
+    /// When the task last changed, when the format says.
+    pub(crate) updated:
+        Option<UnixTime>,
+}
+
+/// The tasks in `payload`, each with its 1-based row after any header,
+/// or its 1-based place among the tasks of a JSON payload, so failures
+/// can be traced back to the file.
+pub(crate) fn parse(
+    format: ImportFormat,
+    payload: &str,
+) -> AppResult<Vec<(usize, ExternalTodo)>>
+{
+    match format {
+        ImportFormat::TodoistCsv => {
+            todoist::parse(
+                &csv::records(payload),
+            )
+        }
+        ImportFormat::TickTickCsv => {
+            ticktick::parse(
+                &csv::records(payload),
+            )
+        }
+        ImportFormat::TodoTxt => {
+            Ok(todotxt::parse(payload))
+        }
+        #[cfg(feature = "json-import")]
+        ImportFormat::TodoistJson => {
+            Ok(todoist::parse_json(
+                &tasks(
+                    payload, None,
+                    format,
+                )?,
+            ))
+        }
+        #[cfg(feature = "json-import")]
+        ImportFormat::TickTickJson => {
+            Ok(ticktick::parse_json(
+                &tasks(
+                    payload,
+                    Some("tasks"),
+                    format,
+                )?,
+            ))
+        }
+        #[cfg(feature = "json-import")]
+        ImportFormat::ThingsJson => {
+            Ok(things::parse(&tasks(
+                payload, None, format,
+            )?))
+        }
+    }
+}
+
+// The array `payload` holds, or holds under `key`.
+#[cfg(feature = "json-import")]
+fn tasks(
+    payload: &str,
+    key: Option<&str>,
+    format: ImportFormat,
+) -> AppResult<Vec<serde_json::Value>> {
+    let invalid = |reason: String| {
+        report!(
+            AppError::ImportPayloadInvalid {
+                format: format!(
+                    "{format:?}"
+                ),
+                reason,
+            }
+        )
+    };
+
+    let value: serde_json::Value =
+        serde_json::from_str(payload)
+            .map_err(|e| {
+            invalid(e.to_string())
+        })?;
+
+    let tasks = match (value, key) {
+        (
+            serde_json::Value::Array(
+                tasks,
+            ),
+            _,
+        ) => Some(tasks),
+        (
+            serde_json::Value::Object(
+                mut object,
+            ),
+            Some(key),
+        ) => match object.remove(key) {
+            Some(
+                serde_json::Value::Array(
+                    tasks,
+                ),
+            ) => Some(tasks),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    tasks.ok_or_else(|| {
+        invalid(match key {
+            Some(key) => format!(
+                "expected an array, or an object with a '{key}' array"
+            ),
+            None => {
+                "expected an array".into()
+            }
+        })
+    })
+}
+
+// The string at `key` of a JSON task, when there is one.
+#[cfg(feature = "json-import")]
+fn json_str<'a>(
+    task: &'a serde_json::Value,
+    key: &str,
+) -> Option<&'a str> {
+    task.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+// Exports may open with a preamble, so the header is the first record
+// naming `column`.
+fn split_at_header<'a>(
+    records: &'a [Vec<String>],
+    column: &str,
+    format: ImportFormat,
+) -> AppResult<(
+    csv::Header,
+    &'a [Vec<String>],
+)> {
+    records
+        .iter()
+        .position(|r| {
+            csv::Header::new(r)
+                .contains(column)
+        })
+        .map(|i| {
+            (
+                csv::Header::new(
+                    &records[i],
+                ),
+                &records[i + 1..],
+            )
+        })
+        .ok_or_else(|| {
+            report!(
+                AppError::ImportHeaderMissing {
+                    format: format!(
+                        "{format:?}"
+                    ),
+                    column: column.into(),
+                }
+            )
+        })
+}
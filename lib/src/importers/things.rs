@@ -0,0 +1,248 @@
+use super::{json_str, ExternalTodo};
+use crate::todos::{
+    NewTodo, OptionalDeadlineInput,
+    Priority, Title,
+};
+use chrono::{DateTime, NaiveDate};
+use serde_json::Value;
+
+// Things has no priorities, so a `High`, `Medium` or `Low` tag stands in
+// for one; without it the list's creation default applies.
+const PRIORITY_TAGS: [(
+    &str,
+    Priority,
+); 3] = [
+    ("high", Priority::High),
+    ("medium", Priority::Medium),
+    ("low", Priority::Low),
+];
+
+/// Every to-do, including those inside projects, numbered from 1 in the
+/// order they appear. Projects and headings only group them.
+pub(super) fn parse(
+    items: &[Value],
+) -> Vec<(usize, ExternalTodo)> {
+    let mut to_dos = vec![];
+    collect(items, &mut to_dos);
+
+    to_dos
+        .into_iter()
+        .enumerate()
+        .map(|(i, to_do)| {
+            (i + 1, external(to_do))
+        })
+        .collect()
+}
+
+fn collect<'a>(
+    items: &'a [Value],
+    to_dos: &mut Vec<&'a Value>,
+) {
+    for item in items {
+        if json_str(item, "type")
+            == Some("to-do")
+        {
+            to_dos.push(item);
+        }
+
+        if let Some(children) = item
+            .get("attributes")
+            .and_then(|a| {
+                a.get("items")
+            })
+            .and_then(Value::as_array)
+        {
+            collect(children, to_dos);
+        }
+    }
+}
+
+fn external(
+    to_do: &Value,
+) -> ExternalTodo {
+    let attributes = to_do
+        .get("attributes")
+        .unwrap_or(&Value::Null);
+
+    let flag = |key| {
+        attributes
+            .get(key)
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+
+    let tags: Vec<_> = attributes
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(
+                    Value::as_str,
+                )
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let item = NewTodo::builder()
+        .title(Title::new(
+            json_str(
+                attributes, "title",
+            )
+            .unwrap_or_default(),
+        ))
+        .priority(tag_priority(&tags))
+        .deadline(
+            OptionalDeadlineInput::from(
+                json_str(
+                    attributes,
+                    "deadline",
+                )
+                .map(String::from),
+            ),
+        )
+        .start_date(
+            OptionalDeadlineInput::from(
+                json_str(
+                    attributes, "when",
+                )
+                .and_then(start_date),
+            ),
+        )
+        .allow_past_deadline(true)
+        .build();
+
+    let updated = [
+        "completion-date",
+        "creation-date",
+    ]
+    .into_iter()
+    .filter_map(|key| {
+        json_str(attributes, key)
+    })
+    .filter_map(|date| {
+        DateTime::parse_from_rfc3339(
+            date,
+        )
+        .ok()
+    })
+    .map(|date| date.timestamp())
+    .max();
+
+    ExternalTodo {
+        item,
+        done: flag("completed")
+            || flag("canceled"),
+        external_id: json_str(
+            to_do, "id",
+        )
+        .map(String::from),
+        updated,
+    }
+}
+
+// The priority named by the first of `PRIORITY_TAGS` among `tags`.
+fn tag_priority(
+    tags: &[&str],
+) -> Option<Priority> {
+    PRIORITY_TAGS
+        .iter()
+        .find(|(tag, _)| {
+            tags.iter().any(|t| {
+                t.eq_ignore_ascii_case(
+                    tag,
+                )
+            })
+        })
+        .map(|(_, priority)| *priority)
+}
+
+// `when` may also be a word such as `today` or `someday`, which names
+// no date to start on.
+fn start_date(
+    when: &str,
+) -> Option<String> {
+    NaiveDate::parse_from_str(
+        when, "%Y-%m-%d",
+    )
+    .ok()
+    .map(|_| when.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn parsed(
+        json: &str,
+    ) -> Vec<(usize, ExternalTodo)>
+    {
+        parse(
+            serde_json::from_str::<Value>(
+                json,
+            )
+            .unwrap()
+            .as_array()
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn parse_should_number_to_dos_inside_and_outside_projects(
+    ) {
+        let actual: Vec<_> = parsed(
+            r#"[
+                {"type": "project", "attributes": {"title": "Go Shopping", "items": [
+                    {"type": "heading", "attributes": {"title": "Dairy"}},
+                    {"type": "to-do", "attributes": {"title": "Milk", "completed": true}}
+                ]}},
+                {"type": "to-do", "id": "A1", "attributes": {"title": "Dry cleaning", "canceled": true}},
+                {"type": "to-do", "attributes": {"title": "Call mom"}}
+            ]"#,
+        )
+        .into_iter()
+        .map(|(row, t)| {
+            (row, t.done, t.external_id)
+        })
+        .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (1, true, None),
+                (
+                    2,
+                    true,
+                    Some("A1".into())
+                ),
+                (3, false, None),
+            ]
+        )
+    }
+
+    #[test_case(&["High"], Some(Priority::High) ; "high")]
+    #[test_case(&["Errand", "low"], Some(Priority::Low) ; "low")]
+    #[test_case(&["Errand"], None ; "none")]
+    fn tag_priority_should_read_a_priority_tag(
+        tags: &[&str],
+        expected: Option<Priority>,
+    ) {
+        assert_eq!(
+            tag_priority(tags),
+            expected
+        )
+    }
+
+    #[test_case("2023-06-18", Some("2023-06-18") ; "date")]
+    #[test_case("someday", None ; "word")]
+    fn start_date_should_take_only_a_date(
+        when: &str,
+        expected: Option<&str>,
+    ) {
+        assert_eq!(
+            start_date(when).as_deref(),
+            expected
+        )
+    }
+}
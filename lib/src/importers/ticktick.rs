@@ -0,0 +1,334 @@
+#[cfg(feature = "json-import")]
+use super::json_str;
+use super::{
+    split_at_header, ExternalTodo,
+    ImportFormat,
+};
+use crate::{
+    app_error::AppResult,
+    todos::{
+        NewTodo, OptionalDeadlineInput,
+        Priority, Title,
+    },
+};
+use chrono::DateTime;
+
+pub(super) fn parse(
+    records: &[Vec<String>],
+) -> AppResult<Vec<(usize, ExternalTodo)>>
+{
+    let (header, rows) =
+        split_at_header(
+            records,
+            "title",
+            ImportFormat::TickTickCsv,
+        )?;
+
+    let date =
+        |row: &[String], name: &str| {
+            OptionalDeadlineInput::new(
+                header
+                    .get(row, name)
+                    .map(rfc3339),
+                header
+                    .get(
+                        row, "timezone",
+                    )
+                    .map(String::from),
+            )
+        };
+
+    let tasks = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let item = NewTodo::builder()
+                .title(Title::new(
+                    header
+                        .get(row, "title")
+                        .unwrap_or_default(),
+                ))
+                .priority(priority(
+                    header.get(
+                        row, "priority",
+                    ),
+                ))
+                .deadline(date(
+                    row, "due date",
+                ))
+                .start_date(date(
+                    row,
+                    "start date",
+                ))
+                .allow_past_deadline(true)
+                .build();
+
+            // 0 is open; 1 and 2 are both flavours of finished.
+            let done = header
+                .get(row, "status")
+                .map(|s| s != "0")
+                .unwrap_or(false);
+
+            // Completing a task is the last change the backup dates.
+            let updated = [
+                "completed time",
+                "created time",
+            ]
+            .into_iter()
+            .filter_map(|name| {
+                header.get(row, name)
+            })
+            .filter_map(|date| {
+                DateTime::parse_from_rfc3339(
+                    &rfc3339(date),
+                )
+                .ok()
+            })
+            .map(|date| date.timestamp())
+            .max();
+
+            (
+                i + 1,
+                ExternalTodo {
+                    item,
+                    done,
+                    external_id: header
+                        .get(row, "taskid")
+                        .map(String::from),
+                    updated,
+                },
+            )
+        })
+        .collect();
+
+    Ok(tasks)
+}
+
+// Each task of an Open API response, numbered from 1. The API gives
+// the same fields as the backup, named in camel case.
+#[cfg(feature = "json-import")]
+pub(super) fn parse_json(
+    tasks: &[serde_json::Value],
+) -> Vec<(usize, ExternalTodo)> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let date = |key| {
+                OptionalDeadlineInput::new(
+                    json_str(task, key)
+                        .map(rfc3339),
+                    json_str(
+                        task, "timeZone",
+                    )
+                    .map(String::from),
+                )
+            };
+
+            let item = NewTodo::builder()
+                .title(Title::new(
+                    json_str(task, "title")
+                        .unwrap_or_default(),
+                ))
+                .priority(priority(
+                    task.get("priority")
+                        .and_then(|p| {
+                            p.as_u64()
+                        })
+                        .map(|p| {
+                            p.to_string()
+                        })
+                        .as_deref(),
+                ))
+                .deadline(date("dueDate"))
+                .start_date(date(
+                    "startDate",
+                ))
+                .allow_past_deadline(true)
+                .build();
+
+            // 0 is open and 2 completed.
+            let done = task
+                .get("status")
+                .and_then(|s| s.as_u64())
+                .map(|s| s != 0)
+                .unwrap_or(false);
+
+            let updated = [
+                "modifiedTime",
+                "completedTime",
+            ]
+            .into_iter()
+            .filter_map(|key| {
+                json_str(task, key)
+            })
+            .filter_map(|date| {
+                DateTime::parse_from_rfc3339(
+                    &rfc3339(date),
+                )
+                .ok()
+            })
+            .map(|date| date.timestamp())
+            .max();
+
+            (
+                i + 1,
+                ExternalTodo {
+                    item,
+                    done,
+                    external_id: json_str(
+                        task, "id",
+                    )
+                    .map(String::from),
+                    updated,
+                },
+            )
+        })
+        .collect()
+}
+
+fn priority(
+    value: Option<&str>,
+) -> Priority {
+    match value {
+        Some("5") => Priority::High,
+        Some("3") => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+// TickTick writes offsets as `+0000`; RFC 3339 wants `+00:00`.
+fn rfc3339(date: &str) -> String {
+    let bytes = date.as_bytes();
+    let n = bytes.len();
+
+    let compact_offset = date
+        .contains('T')
+        && n > 5
+        && matches!(
+            bytes[n - 5],
+            b'+' | b'-'
+        )
+        && bytes[n - 4..]
+            .iter()
+            .all(u8::is_ascii_digit);
+
+    if compact_offset {
+        format!(
+            "{}:{}",
+            &date[..n - 2],
+            &date[n - 2..]
+        )
+    } else {
+        date.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::csv;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(
+        "2023-06-18T09:00:00+0000",
+        "2023-06-18T09:00:00+00:00"
+    )]
+    #[test_case(
+        "2023-06-18T09:00:00-0530",
+        "2023-06-18T09:00:00-05:30"
+    )]
+    #[test_case(
+        "2023-06-18",
+        "2023-06-18"
+    )]
+    fn rfc3339_should_add_a_colon_to_compact_offsets(
+        input: &str,
+        expected: &str,
+    ) {
+        assert_eq!(
+            rfc3339(input),
+            expected
+        )
+    }
+
+    #[test]
+    fn parse_should_skip_the_preamble_and_read_status(
+    ) {
+        let records = csv::records(
+            "\"Date: 2023-06-18+0000\"\n\
+             \"Version: 7.1\"\n\
+             \"Folder Name\",\"List Name\",\"Title\",\"Priority\",\"Status\",\"Due Date\"\n\
+             \"\",\"Inbox\",\"Pay rent\",\"5\",\"0\",\"2023-07-01T09:00:00+0000\"\n\
+             \"\",\"Inbox\",\"File taxes\",\"3\",\"2\",\"\"\n",
+        );
+
+        let actual: Vec<_> =
+            parse(&records)
+                .unwrap()
+                .into_iter()
+                .map(|(row, t)| {
+                    (row, t.done)
+                })
+                .collect();
+
+        assert_eq!(
+            actual,
+            vec![(1, false), (2, true)]
+        )
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test]
+    fn parse_json_should_read_status_ids_and_the_latest_change(
+    ) {
+        let tasks: Vec<
+            serde_json::Value,
+        > = serde_json::from_str(
+            r#"[
+                {"id": "63b7bebb91c0a5474805fcd4", "title": "Pay rent", "priority": 5, "status": 0,
+                 "dueDate": "2023-07-01T09:00:00+0000", "timeZone": "Europe/Berlin",
+                 "modifiedTime": "2023-06-18T09:00:00+0000"},
+                {"title": "File taxes", "status": 2,
+                 "modifiedTime": "2023-06-18T09:00:00+0000",
+                 "completedTime": "2023-06-19T09:00:00+0000"}
+            ]"#,
+        )
+        .unwrap();
+
+        let actual: Vec<_> =
+            parse_json(&tasks)
+                .into_iter()
+                .map(|(row, t)| {
+                    (
+                        row,
+                        t.done,
+                        t.external_id,
+                        t.updated,
+                    )
+                })
+                .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    1,
+                    false,
+                    Some(
+                        "63b7bebb91c0a5474805fcd4"
+                            .into()
+                    ),
+                    Some(1_687_078_800)
+                ),
+                (
+                    2,
+                    true,
+                    None,
+                    Some(1_687_165_200)
+                ),
+            ]
+        )
+    }
+}
@@ -0,0 +1,364 @@
+#[cfg(feature = "json-import")]
+use super::json_str;
+use super::{
+    split_at_header, ExternalTodo,
+    ImportFormat,
+};
+use crate::{
+    app_error::AppResult,
+    todos::{
+        NewTodo, OptionalDeadlineInput,
+        Priority, Title,
+    },
+};
+#[cfg(feature = "json-import")]
+use chrono::DateTime;
+
+// Sections and notes share the file with tasks; only tasks are kept.
+pub(super) fn parse(
+    records: &[Vec<String>],
+) -> AppResult<Vec<(usize, ExternalTodo)>>
+{
+    let (header, rows) =
+        split_at_header(
+            records,
+            "content",
+            ImportFormat::TodoistCsv,
+        )?;
+
+    let field =
+        |row: &[String], name: &str| {
+            header
+                .get(row, name)
+                .map(String::from)
+        };
+
+    let tasks = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            header
+                .get(row, "type")
+                .map(|t| {
+                    t.eq_ignore_ascii_case(
+                        "task",
+                    )
+                })
+                .unwrap_or(false)
+        })
+        .map(|(i, row)| {
+            let item = NewTodo::builder()
+                .title(Title::new(
+                    field(row, "content")
+                        .unwrap_or_default(),
+                ))
+                .priority(priority(
+                    header.get(
+                        row, "priority",
+                    ),
+                ))
+                .deadline(
+                    OptionalDeadlineInput::new(
+                        field(row, "date"),
+                        field(row, "timezone"),
+                    ),
+                )
+                .allow_past_deadline(true)
+                .build();
+
+            (
+                i + 1,
+                ExternalTodo {
+                    item,
+                    done: false,
+                    external_id: None,
+                    updated: None,
+                },
+            )
+        })
+        .collect();
+
+    Ok(tasks)
+}
+
+// Each task of a REST API response, numbered from 1.
+#[cfg(feature = "json-import")]
+pub(super) fn parse_json(
+    tasks: &[serde_json::Value],
+) -> Vec<(usize, ExternalTodo)> {
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let due = task
+                .get("due")
+                .unwrap_or(
+                    &serde_json::Value::Null,
+                );
+
+            let deadline = json_str(
+                due, "datetime",
+            )
+            .map(due_time)
+            .or_else(|| {
+                json_str(due, "date")
+                    .map(String::from)
+            });
+
+            let item = NewTodo::builder()
+                .title(Title::new(
+                    json_str(
+                        task, "content",
+                    )
+                    .unwrap_or_default(),
+                ))
+                .priority(api_priority(
+                    task.get("priority")
+                        .and_then(|p| {
+                            p.as_u64()
+                        }),
+                ))
+                .deadline(
+                    OptionalDeadlineInput::new(
+                        deadline,
+                        json_str(
+                            due, "timezone",
+                        )
+                        .map(String::from),
+                    ),
+                )
+                .allow_past_deadline(true)
+                .build();
+
+            let done = ["is_completed", "checked"]
+                .into_iter()
+                .any(|key| {
+                    task.get(key)
+                        .and_then(|v| {
+                            v.as_bool()
+                        })
+                        .unwrap_or(false)
+                });
+
+            (
+                i + 1,
+                ExternalTodo {
+                    item,
+                    done,
+                    external_id: task
+                        .get("id")
+                        .and_then(|id| {
+                            match id {
+                                serde_json::Value::String(s) => {
+                                    Some(s.clone())
+                                }
+                                serde_json::Value::Number(n) => {
+                                    Some(n.to_string())
+                                }
+                                _ => None,
+                            }
+                        }),
+                    updated: json_str(
+                        task,
+                        "updated_at",
+                    )
+                    .and_then(|date| {
+                        DateTime::parse_from_rfc3339(date)
+                            .ok()
+                    })
+                    .map(|date| {
+                        date.timestamp()
+                    }),
+                },
+            )
+        })
+        .collect()
+}
+
+// A floating time, without an offset, is read to the hour in the
+// task's time zone; one with an offset is kept as it is.
+#[cfg(feature = "json-import")]
+fn due_time(datetime: &str) -> String {
+    if DateTime::parse_from_rfc3339(
+        datetime,
+    )
+    .is_ok()
+    {
+        return datetime.into();
+    }
+
+    match datetime.split_once('T') {
+        Some((date, time)) => format!(
+            "{date} {}",
+            time.get(..2)
+                .unwrap_or("00")
+        ),
+        None => datetime.into(),
+    }
+}
+
+// The API numbers priorities the other way round from the CSV: 4 is
+// p1, the most urgent.
+#[cfg(feature = "json-import")]
+fn api_priority(
+    value: Option<u64>,
+) -> Priority {
+    match value {
+        Some(4) => Priority::High,
+        Some(3) => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+// Todoist numbers priorities from p1, the most urgent, to p4.
+fn priority(
+    value: Option<&str>,
+) -> Priority {
+    match value {
+        Some("1") => Priority::High,
+        Some("2") => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::csv;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn parse_should_keep_only_tasks_numbered_by_row(
+    ) {
+        let records = csv::records(
+            "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE\n\
+             section,Errands,,,,,,,,\n\
+             task,Buy milk,,1,1,,,2023-06-18,en,Europe/Berlin\n\
+             note,remember the oat one,,,,,,,,\n\
+             task,Call mom,,4,1,,,,en,\n",
+        );
+
+        let actual: Vec<_> =
+            parse(&records)
+                .unwrap()
+                .into_iter()
+                .map(|(row, _)| row)
+                .collect();
+
+        assert_eq!(actual, vec![2, 4])
+    }
+
+    #[test_case(
+        Some("1"),
+        Priority::High
+    )]
+    #[test_case(
+        Some("2"),
+        Priority::Medium
+    )]
+    #[test_case(
+        Some("4"),
+        Priority::Low
+    )]
+    #[test_case(None, Priority::Low)]
+    fn priority_should_map_p1_to_high(
+        value: Option<&str>,
+        expected: Priority,
+    ) {
+        assert_eq!(
+            priority(value),
+            expected
+        )
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test]
+    fn parse_json_should_read_done_and_ids(
+    ) {
+        let tasks: Vec<
+            serde_json::Value,
+        > = serde_json::from_str(
+            r#"[
+                {"id": "2995104339", "content": "Buy milk", "priority": 4, "is_completed": false,
+                 "due": {"date": "2023-06-18", "timezone": "Europe/Berlin"}},
+                {"id": 2995104340, "content": "Call mom", "checked": true}
+            ]"#,
+        )
+        .unwrap();
+
+        let actual: Vec<_> =
+            parse_json(&tasks)
+                .into_iter()
+                .map(|(row, t)| {
+                    (
+                        row,
+                        t.done,
+                        t.external_id,
+                    )
+                })
+                .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    1,
+                    false,
+                    Some(
+                        "2995104339"
+                            .into()
+                    )
+                ),
+                (
+                    2,
+                    true,
+                    Some(
+                        "2995104340"
+                            .into()
+                    )
+                ),
+            ]
+        )
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test_case(
+        "2023-06-18T09:30:00Z",
+        "2023-06-18T09:30:00Z"
+    )]
+    #[test_case(
+        "2023-06-18T09:30:00",
+        "2023-06-18 09"
+    )]
+    fn due_time_should_read_a_floating_time_to_the_hour(
+        input: &str,
+        expected: &str,
+    ) {
+        assert_eq!(
+            due_time(input),
+            expected
+        )
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test_case(
+        Some(4),
+        Priority::High
+    )]
+    #[test_case(
+        Some(3),
+        Priority::Medium
+    )]
+    #[test_case(Some(1), Priority::Low)]
+    #[test_case(None, Priority::Low)]
+    fn api_priority_should_map_4_to_high(
+        value: Option<u64>,
+        expected: Priority,
+    ) {
+        assert_eq!(
+            api_priority(value),
+            expected
+        )
+    }
+}
@@ -0,0 +1,233 @@
+// See https://github.com/todotxt/todo.txt for the convention.
+use super::ExternalTodo;
+use crate::todos::{
+    NewTodo, OptionalDeadlineInput,
+    Priority, Status, Title, Todo,
+};
+use chrono::NaiveDate;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Blank lines are skipped but still counted, so rows match line numbers.
+pub(super) fn parse(
+    payload: &str,
+) -> Vec<(usize, ExternalTodo)> {
+    payload
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            !line.trim().is_empty()
+        })
+        .map(|(i, line)| {
+            (i + 1, task(line))
+        })
+        .collect()
+}
+
+fn task(line: &str) -> ExternalTodo {
+    let mut words = line
+        .split_whitespace()
+        .peekable();
+
+    let done = words
+        .next_if_eq(&"x")
+        .is_some();
+
+    let mut priority = None;
+    let mut due = None;
+    let mut external_id = None;
+    let mut title = Vec::new();
+
+    // Completion and creation dates may surround the priority.
+    while let Some(word) = words
+        .next_if(|w| {
+            is_date(w)
+                || letter(w).is_some()
+        })
+    {
+        priority =
+            letter(word).or(priority);
+    }
+
+    for word in words {
+        if let Some(date) =
+            word.strip_prefix("due:")
+        {
+            due =
+                Some(date.to_string());
+        } else if let Some(id) =
+            word.strip_prefix("id:")
+        {
+            external_id =
+                Some(id.to_string());
+        } else if let Some(p) = word
+            .strip_prefix("pri:")
+            .and_then(|p| {
+                p.chars().next()
+            })
+        {
+            priority = Some(p);
+        } else {
+            title.push(word);
+        }
+    }
+
+    let item = NewTodo::builder()
+        .title(Title::new(
+            title.join(" "),
+        ))
+        .priority(priority_from(
+            priority,
+        ))
+        .deadline(
+            OptionalDeadlineInput::new(
+                due, None,
+            ),
+        )
+        .allow_past_deadline(true)
+        .build();
+
+    ExternalTodo {
+        item,
+        done,
+        external_id,
+        updated: None,
+    }
+}
+
+/// `todo` as one todo.txt line. A finished task keeps its priority as a
+/// `pri:` tag, since the convention drops the leading letter on
+/// completion.
+pub(crate) fn line(
+    todo: &Todo,
+) -> String {
+    let priority =
+        letter_for(todo.priority());
+
+    let mut words = Vec::new();
+
+    if todo.status() == Status::Done {
+        words.push("x".to_string());
+    } else {
+        words.push(format!(
+            "({priority})"
+        ));
+    }
+
+    words.extend(
+        todo.title()
+            .split_whitespace()
+            .map(String::from),
+    );
+
+    if todo.status() == Status::Done {
+        words.push(format!(
+            "pri:{priority}"
+        ));
+    }
+
+    if let Some(date) =
+        todo.deadline().and_then(|d| {
+            todo.time_zone()
+                .local_date(d)
+        })
+    {
+        words.push(format!(
+            "due:{}",
+            date.format(DATE_FORMAT)
+        ));
+    }
+
+    words.join(" ")
+}
+
+fn is_date(word: &str) -> bool {
+    NaiveDate::parse_from_str(
+        word,
+        DATE_FORMAT,
+    )
+    .is_ok()
+}
+
+fn letter(word: &str) -> Option<char> {
+    match word.as_bytes() {
+        [b'(', c, b')']
+            if c.is_ascii_uppercase() =>
+        {
+            Some(*c as char)
+        }
+        _ => None,
+    }
+}
+
+// Letters run from A, the most urgent; everything past B is low.
+fn priority_from(
+    letter: Option<char>,
+) -> Priority {
+    match letter {
+        Some('A') => Priority::High,
+        Some('B') => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+fn letter_for(
+    priority: Priority,
+) -> char {
+    match priority {
+        Priority::High => 'A',
+        Priority::Medium => 'B',
+        Priority::Low => 'C',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::TodoList;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("(A) Call mom", Priority::High, false ; "a leading letter")]
+    #[test_case("x 2023-06-20 2023-06-01 Call mom pri:B", Priority::Medium, true ; "a completed task")]
+    #[test_case("2023-06-01 (D) Call mom", Priority::Low, false ; "a creation date first")]
+    #[test_case("Call mom +family @phone", Priority::Low, false ; "no priority")]
+    fn task_should_read_priority_and_completion(
+        line: &str,
+        priority: Priority,
+        done: bool,
+    ) {
+        let actual = task(line);
+
+        let added = TodoList::new()
+            .add(&actual.item)
+            .unwrap();
+
+        assert_eq!(
+            (
+                added.priority(),
+                actual.done
+            ),
+            (priority, done)
+        )
+    }
+
+    #[test]
+    fn parse_should_number_rows_by_line(
+    ) {
+        let actual: Vec<_> = parse(
+            "(A) Pay rent due:2023-07-01\n\
+             \n\
+             x File taxes\n",
+        )
+        .into_iter()
+        .map(|(row, t)| (row, t.done))
+        .collect();
+
+        assert_eq!(
+            actual,
+            vec![(1, false), (3, true)]
+        )
+    }
+}
@@ -0,0 +1,1119 @@
+//! A subset of JMESPath (https://jmespath.org/specification.html):
+//! identifiers (quote those with dashes, as in `"created-timestamp"`),
+//! `@`, sub-expressions, indexes, `[*]` and `*` projections, `[]`
+//! flattening, `[?...]` filters with comparisons, `&&`, `||` and `!`,
+//! multi-select lists and hashes, pipes, and raw-string and JSON
+//! literals. Slices and functions are not supported.
+use crate::app_error::{
+    report, AppError, AppResult, Report,
+};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+// Keeps the parser's recursion shallow for any expression it accepts.
+const MAX_EXPRESSION_LEN: usize = 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(i64),
+    Literal(Value),
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    LeftParen,
+    RightParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    At,
+    Compare(Comparator),
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq,
+)]
+enum Comparator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Current,
+    Field(String),
+    Index(i64),
+    Literal(Value),
+    Sub(Box<Expr>, Box<Expr>),
+    ListProjection(
+        Box<Expr>,
+        Box<Expr>,
+    ),
+    ObjectProjection(
+        Box<Expr>,
+        Box<Expr>,
+    ),
+    Flatten(Box<Expr>, Box<Expr>),
+    Filter {
+        base: Box<Expr>,
+        condition: Box<Expr>,
+        then: Box<Expr>,
+    },
+    MultiList(Vec<Expr>),
+    MultiHash(Vec<(String, Expr)>),
+    Compare(
+        Comparator,
+        Box<Expr>,
+        Box<Expr>,
+    ),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+/// Evaluates `expression` against `data`.
+pub(crate) fn search(
+    expression: &str,
+    data: &Value,
+) -> AppResult<Value> {
+    if expression.len()
+        > MAX_EXPRESSION_LEN
+    {
+        return Err(invalid(format!(
+            "longer than {} bytes",
+            MAX_EXPRESSION_LEN
+        )));
+    }
+
+    let mut parser = Parser {
+        tokens: tokens(expression)?,
+        pos: 0,
+    };
+
+    let expr = parser.expression(0)?;
+
+    if let Some(token) = parser.peek() {
+        return Err(invalid(format!(
+            "unexpected {token:?}"
+        )));
+    }
+
+    Ok(evaluate(&expr, data))
+}
+
+fn invalid(
+    reason: String,
+) -> Report<AppError> {
+    report!(
+        AppError::InvalidExtractExpression(
+            reason
+        )
+    )
+}
+
+fn tokens(
+    text: &str,
+) -> AppResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars =
+        text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                continue
+            }
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            ']' => Token::RightBracket,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::At,
+            '[' => {
+                if chars
+                    .next_if_eq(&']')
+                    .is_some()
+                {
+                    Token::Flatten
+                } else if chars
+                    .next_if_eq(&'?')
+                    .is_some()
+                {
+                    Token::Filter
+                } else {
+                    Token::LeftBracket
+                }
+            }
+            '|' => {
+                if chars
+                    .next_if_eq(&'|')
+                    .is_some()
+                {
+                    Token::Or
+                } else {
+                    Token::Pipe
+                }
+            }
+            '&' => {
+                if chars
+                    .next_if_eq(&'&')
+                    .is_some()
+                {
+                    Token::And
+                } else {
+                    return Err(invalid(
+                        "expected '&&'"
+                            .into(),
+                    ));
+                }
+            }
+            '!' => {
+                if chars
+                    .next_if_eq(&'=')
+                    .is_some()
+                {
+                    Token::Compare(
+                        Comparator::NotEqual,
+                    )
+                } else {
+                    Token::Not
+                }
+            }
+            '=' => {
+                if chars
+                    .next_if_eq(&'=')
+                    .is_some()
+                {
+                    Token::Compare(
+                        Comparator::Equal,
+                    )
+                } else {
+                    return Err(invalid(
+                        "expected '=='"
+                            .into(),
+                    ));
+                }
+            }
+            '<' => Token::Compare(
+                if chars
+                    .next_if_eq(&'=')
+                    .is_some()
+                {
+                    Comparator::LessOrEqual
+                } else {
+                    Comparator::Less
+                },
+            ),
+            '>' => Token::Compare(
+                if chars
+                    .next_if_eq(&'=')
+                    .is_some()
+                {
+                    Comparator::GreaterOrEqual
+                } else {
+                    Comparator::Greater
+                },
+            ),
+            '\'' => {
+                let text = delimited(
+                    &mut chars, '\'',
+                )?;
+
+                Token::Literal(
+                    Value::String(text),
+                )
+            }
+            '`' => {
+                let text = delimited(
+                    &mut chars, '`',
+                )?;
+
+                Token::Literal(
+                    serde_json::from_str(
+                        &text,
+                    )
+                    .map_err(|e| {
+                        invalid(format!(
+                            "literal `{text}` is not JSON: {e}"
+                        ))
+                    })?,
+                )
+            }
+            '"' => {
+                let text = delimited(
+                    &mut chars, '"',
+                )?;
+
+                Token::Identifier(
+                    serde_json::from_str(
+                        &format!(
+                            "\"{text}\""
+                        ),
+                    )
+                    .map_err(|e| {
+                        invalid(format!(
+                            "identifier \"{text}\" is malformed: {e}"
+                        ))
+                    })?,
+                )
+            }
+            c if c == '-'
+                || c.is_ascii_digit() =>
+            {
+                let mut digits =
+                    c.to_string();
+
+                while let Some(d) = chars
+                    .next_if(
+                        char::is_ascii_digit,
+                    )
+                {
+                    digits.push(d);
+                }
+
+                Token::Number(
+                    digits.parse().map_err(
+                        |_| {
+                            invalid(format!(
+                                "'{digits}' is not an index"
+                            ))
+                        },
+                    )?,
+                )
+            }
+            c if c.is_ascii_alphabetic()
+                || c == '_' =>
+            {
+                let mut name =
+                    c.to_string();
+
+                while let Some(c) = chars
+                    .next_if(|c| {
+                        c.is_ascii_alphanumeric()
+                            || *c == '_'
+                    })
+                {
+                    name.push(c);
+                }
+
+                Token::Identifier(name)
+            }
+            c => {
+                return Err(invalid(
+                    format!(
+                        "unexpected '{c}'"
+                    ),
+                ))
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+// The text up to an unescaped `end`; a backslash before `end` stands
+// for `end` itself and is otherwise kept.
+fn delimited(
+    chars: &mut std::iter::Peekable<
+        std::str::Chars,
+    >,
+    end: char,
+) -> AppResult<String> {
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            Some('\\') => {
+                match chars.next() {
+                    Some(c)
+                        if c == end =>
+                    {
+                        if end == '"' {
+                            text.push(
+                                '\\',
+                            );
+                        }
+                        text.push(c)
+                    }
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => break,
+                }
+            }
+            Some(c) if c == end => {
+                return Ok(text)
+            }
+            Some(c) => text.push(c),
+            None => break,
+        }
+    }
+
+    Err(invalid(format!(
+        "missing closing {end}"
+    )))
+}
+
+// Binding powers, as in the specification's reference parser.
+fn binding_power(token: &Token) -> u8 {
+    match token {
+        Token::Pipe => 1,
+        Token::Or => 2,
+        Token::And => 3,
+        Token::Compare(_) => 5,
+        Token::Flatten => 9,
+        Token::Star => 20,
+        Token::Filter => 21,
+        Token::Dot => 40,
+        Token::Not => 45,
+        Token::LeftBrace => 50,
+        Token::LeftBracket => 55,
+        Token::LeftParen => 60,
+        _ => 0,
+    }
+}
+
+// Below this, a token ends a projection's right-hand side.
+const PROJECTION_STOP: u8 = 10;
+
+struct Parser {
+    tokens: Vec<Token>,
+
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(
+        &mut self,
+    ) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned();
+
+        self.pos += 1;
+
+        token
+    }
+
+    fn expect(
+        &mut self,
+        expected: Token,
+    ) -> AppResult<()> {
+        match self.next() {
+            Some(token)
+                if token == expected =>
+            {
+                Ok(())
+            }
+            Some(token) => {
+                Err(invalid(format!(
+                    "expected {expected:?}, not {token:?}"
+                )))
+            }
+            None => Err(invalid(format!(
+                "expected {expected:?} at the end"
+            ))),
+        }
+    }
+
+    fn expression(
+        &mut self,
+        min_power: u8,
+    ) -> AppResult<Expr> {
+        let mut left = self.prefix()?;
+
+        while let Some(token) =
+            self.peek().cloned()
+        {
+            let power =
+                binding_power(&token);
+
+            if power <= min_power {
+                break;
+            }
+
+            self.pos += 1;
+            left = self.infix(
+                token, power, left,
+            )?;
+        }
+
+        Ok(left)
+    }
+
+    fn prefix(
+        &mut self,
+    ) -> AppResult<Expr> {
+        match self.next() {
+            Some(Token::Identifier(
+                name,
+            )) => Ok(Expr::Field(name)),
+            Some(Token::Literal(
+                value,
+            )) => Ok(Expr::Literal(value)),
+            Some(Token::At) => {
+                Ok(Expr::Current)
+            }
+            Some(Token::Star) => {
+                let then = self
+                    .projection_rhs(
+                        binding_power(
+                            &Token::Star,
+                        ),
+                    )?;
+
+                Ok(Expr::ObjectProjection(
+                    Box::new(Expr::Current),
+                    Box::new(then),
+                ))
+            }
+            Some(Token::Flatten) => {
+                self.infix(
+                    Token::Flatten,
+                    binding_power(
+                        &Token::Flatten,
+                    ),
+                    Expr::Current,
+                )
+            }
+            Some(Token::Filter) => self
+                .infix(
+                    Token::Filter,
+                    binding_power(
+                        &Token::Filter,
+                    ),
+                    Expr::Current,
+                ),
+            Some(Token::LeftBracket) => {
+                self.bracket(Expr::Current)
+            }
+            Some(Token::LeftBrace) => {
+                self.multi_hash()
+            }
+            Some(Token::LeftParen) => {
+                let expr =
+                    self.expression(0)?;
+
+                self.expect(
+                    Token::RightParen,
+                )?;
+
+                Ok(expr)
+            }
+            Some(Token::Not) => {
+                let expr = self.expression(
+                    binding_power(
+                        &Token::Not,
+                    ),
+                )?;
+
+                Ok(Expr::Not(Box::new(
+                    expr,
+                )))
+            }
+            Some(token) => {
+                Err(invalid(format!(
+                    "unexpected {token:?}"
+                )))
+            }
+            None => Err(invalid(
+                "unexpected end".into(),
+            )),
+        }
+    }
+
+    fn infix(
+        &mut self,
+        token: Token,
+        power: u8,
+        left: Expr,
+    ) -> AppResult<Expr> {
+        let left = Box::new(left);
+
+        match token {
+            Token::Dot => {
+                if self.peek()
+                    == Some(&Token::Star)
+                {
+                    self.pos += 1;
+
+                    let then = self
+                        .projection_rhs(
+                            binding_power(
+                                &Token::Star,
+                            ),
+                        )?;
+
+                    Ok(Expr::ObjectProjection(
+                        left,
+                        Box::new(then),
+                    ))
+                } else {
+                    let right =
+                        self.dot_rhs(power)?;
+
+                    Ok(Expr::Sub(
+                        left,
+                        Box::new(right),
+                    ))
+                }
+            }
+            Token::Pipe => {
+                let right =
+                    self.expression(power)?;
+
+                Ok(Expr::Sub(
+                    left,
+                    Box::new(right),
+                ))
+            }
+            Token::Or => Ok(Expr::Or(
+                left,
+                Box::new(
+                    self.expression(power)?,
+                ),
+            )),
+            Token::And => Ok(Expr::And(
+                left,
+                Box::new(
+                    self.expression(power)?,
+                ),
+            )),
+            Token::Compare(comparator) => {
+                Ok(Expr::Compare(
+                    comparator,
+                    left,
+                    Box::new(
+                        self.expression(
+                            power,
+                        )?,
+                    ),
+                ))
+            }
+            Token::Flatten => {
+                let then = self
+                    .projection_rhs(power)?;
+
+                Ok(Expr::Flatten(
+                    left,
+                    Box::new(then),
+                ))
+            }
+            Token::Filter => {
+                let condition =
+                    self.expression(0)?;
+
+                self.expect(
+                    Token::RightBracket,
+                )?;
+
+                let then = self
+                    .projection_rhs(power)?;
+
+                Ok(Expr::Filter {
+                    base: left,
+                    condition: Box::new(
+                        condition,
+                    ),
+                    then: Box::new(then),
+                })
+            }
+            Token::LeftBracket => {
+                self.bracket(*left)
+            }
+            token => Err(invalid(format!(
+                "unexpected {token:?}"
+            ))),
+        }
+    }
+
+    // After `[`: an index, `*]`, or a multi-select list.
+    fn bracket(
+        &mut self,
+        left: Expr,
+    ) -> AppResult<Expr> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let index = *n;
+                self.pos += 1;
+
+                self.expect(
+                    Token::RightBracket,
+                )?;
+
+                Ok(Expr::Sub(
+                    Box::new(left),
+                    Box::new(Expr::Index(
+                        index,
+                    )),
+                ))
+            }
+            Some(Token::Star)
+                if self
+                    .tokens
+                    .get(self.pos + 1)
+                    == Some(
+                        &Token::RightBracket,
+                    ) =>
+            {
+                self.pos += 2;
+
+                let then = self
+                    .projection_rhs(
+                        binding_power(
+                            &Token::Star,
+                        ),
+                    )?;
+
+                Ok(Expr::ListProjection(
+                    Box::new(left),
+                    Box::new(then),
+                ))
+            }
+            _ => {
+                let list =
+                    self.multi_list()?;
+
+                Ok(Expr::Sub(
+                    Box::new(left),
+                    Box::new(list),
+                ))
+            }
+        }
+    }
+
+    // The part of a projection applied to each element.
+    fn projection_rhs(
+        &mut self,
+        power: u8,
+    ) -> AppResult<Expr> {
+        match self.peek() {
+            None => Ok(Expr::Current),
+            Some(token)
+                if binding_power(token)
+                    < PROJECTION_STOP =>
+            {
+                Ok(Expr::Current)
+            }
+            Some(Token::Dot) => {
+                self.pos += 1;
+                self.dot_rhs(power)
+            }
+            Some(
+                Token::LeftBracket
+                | Token::Filter
+                | Token::Flatten,
+            ) => self.expression(power),
+            Some(token) => {
+                Err(invalid(format!(
+                    "unexpected {token:?} after a projection"
+                )))
+            }
+        }
+    }
+
+    fn dot_rhs(
+        &mut self,
+        power: u8,
+    ) -> AppResult<Expr> {
+        match self.peek() {
+            Some(
+                Token::LeftBracket,
+            ) => {
+                self.pos += 1;
+                self.multi_list()
+            }
+            Some(Token::LeftBrace) => {
+                self.pos += 1;
+                self.multi_hash()
+            }
+            _ => self.expression(power),
+        }
+    }
+
+    // After `[`, up to and including the closing `]`.
+    fn multi_list(
+        &mut self,
+    ) -> AppResult<Expr> {
+        let mut items = vec![];
+
+        loop {
+            items.push(
+                self.expression(0)?,
+            );
+
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(
+                    Token::RightBracket,
+                ) => break,
+                _ => {
+                    return Err(invalid(
+                        "expected ',' or ']' in a list"
+                            .into(),
+                    ))
+                }
+            }
+        }
+
+        Ok(Expr::MultiList(items))
+    }
+
+    // After `{`, up to and including the closing `}`.
+    fn multi_hash(
+        &mut self,
+    ) -> AppResult<Expr> {
+        let mut pairs = vec![];
+
+        loop {
+            let key = match self.next() {
+                Some(Token::Identifier(
+                    key,
+                )) => key,
+                _ => {
+                    return Err(invalid(
+                        "expected a key in a hash"
+                            .into(),
+                    ))
+                }
+            };
+
+            self.expect(Token::Colon)?;
+
+            pairs.push((
+                key,
+                self.expression(0)?,
+            ));
+
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RightBrace) => {
+                    break
+                }
+                _ => {
+                    return Err(invalid(
+                        "expected ',' or '}' in a hash"
+                            .into(),
+                    ))
+                }
+            }
+        }
+
+        Ok(Expr::MultiHash(pairs))
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => {
+            !s.is_empty()
+        }
+        Value::Array(a) => {
+            !a.is_empty()
+        }
+        Value::Object(o) => {
+            !o.is_empty()
+        }
+        Value::Number(_) => true,
+    }
+}
+
+// Applies `then` to each value, dropping nulls.
+fn project<'a>(
+    values: impl Iterator<Item = &'a Value>,
+    then: &Expr,
+) -> Value {
+    Value::Array(
+        values
+            .map(|v| evaluate(then, v))
+            .filter(|v| !v.is_null())
+            .collect(),
+    )
+}
+
+fn compare(
+    comparator: Comparator,
+    left: &Value,
+    right: &Value,
+) -> Value {
+    let ordering = || {
+        left.as_f64()
+            .zip(right.as_f64())
+            .and_then(|(l, r)| {
+                l.partial_cmp(&r)
+            })
+    };
+
+    let result = match comparator {
+        Comparator::Equal => {
+            Some(left == right)
+        }
+        Comparator::NotEqual => {
+            Some(left != right)
+        }
+        Comparator::Less => ordering()
+            .map(Ordering::is_lt),
+        Comparator::LessOrEqual => {
+            ordering()
+                .map(Ordering::is_le)
+        }
+        Comparator::Greater => {
+            ordering()
+                .map(Ordering::is_gt)
+        }
+        Comparator::GreaterOrEqual => {
+            ordering()
+                .map(Ordering::is_ge)
+        }
+    };
+
+    result
+        .map(Value::Bool)
+        .unwrap_or(Value::Null)
+}
+
+fn evaluate(
+    expr: &Expr,
+    data: &Value,
+) -> Value {
+    match expr {
+        Expr::Current => data.clone(),
+        Expr::Field(name) => data
+            .get(name)
+            .cloned()
+            .unwrap_or(Value::Null),
+        Expr::Index(i) => data
+            .as_array()
+            .and_then(|a| {
+                let i = if *i < 0 {
+                    a.len()
+                        .checked_sub(
+                        i.unsigned_abs()
+                            as usize,
+                    )?
+                } else {
+                    *i as usize
+                };
+
+                a.get(i).cloned()
+            })
+            .unwrap_or(Value::Null),
+        Expr::Literal(value) => {
+            value.clone()
+        }
+        Expr::Sub(left, right) => {
+            evaluate(
+                right,
+                &evaluate(left, data),
+            )
+        }
+        Expr::ListProjection(
+            base,
+            then,
+        ) => match evaluate(base, data)
+        {
+            Value::Array(a) => {
+                project(a.iter(), then)
+            }
+            _ => Value::Null,
+        },
+        Expr::ObjectProjection(
+            base,
+            then,
+        ) => match evaluate(base, data)
+        {
+            Value::Object(o) => {
+                project(
+                    o.values(),
+                    then,
+                )
+            }
+            _ => Value::Null,
+        },
+        Expr::Flatten(base, then) => {
+            match evaluate(base, data) {
+                Value::Array(a) => {
+                    let flat: Vec<_> = a
+                        .into_iter()
+                        .flat_map(|v| match v {
+                            Value::Array(
+                                inner,
+                            ) => inner,
+                            v => vec![v],
+                        })
+                        .collect();
+
+                    project(
+                        flat.iter(),
+                        then,
+                    )
+                }
+                _ => Value::Null,
+            }
+        }
+        Expr::Filter {
+            base,
+            condition,
+            then,
+        } => match evaluate(base, data)
+        {
+            Value::Array(a) => project(
+                a.iter().filter(|v| {
+                    is_truthy(
+                        &evaluate(
+                            condition,
+                            v,
+                        ),
+                    )
+                }),
+                then,
+            ),
+            _ => Value::Null,
+        },
+        Expr::MultiList(items) => {
+            if data.is_null() {
+                return Value::Null;
+            }
+
+            Value::Array(
+                items
+                    .iter()
+                    .map(|e| {
+                        evaluate(
+                            e, data,
+                        )
+                    })
+                    .collect(),
+            )
+        }
+        Expr::MultiHash(pairs) => {
+            if data.is_null() {
+                return Value::Null;
+            }
+
+            Value::Object(
+                pairs
+                    .iter()
+                    .map(|(k, e)| {
+                        (
+                            k.clone(),
+                            evaluate(e, data),
+                        )
+                    })
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Expr::Compare(
+            comparator,
+            left,
+            right,
+        ) => compare(
+            *comparator,
+            &evaluate(left, data),
+            &evaluate(right, data),
+        ),
+        Expr::And(left, right) => {
+            let left =
+                evaluate(left, data);
+
+            if is_truthy(&left) {
+                evaluate(right, data)
+            } else {
+                left
+            }
+        }
+        Expr::Or(left, right) => {
+            let left =
+                evaluate(left, data);
+
+            if is_truthy(&left) {
+                left
+            } else {
+                evaluate(right, data)
+            }
+        }
+        Expr::Not(expr) => {
+            Value::Bool(!is_truthy(
+                &evaluate(expr, data),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn data() -> Value {
+        json!({
+            "items": [
+                {"title": "a", "priority": "high", "tags": [1, 2]},
+                {"title": "b", "priority": "low", "tags": [3]},
+                {"title": "c", "priority": "high"},
+            ],
+            "default-time-zone": "UTC",
+        })
+    }
+
+    #[test_case("items[0].title", json!("a") ; "an index")]
+    #[test_case("items[-1].title", json!("c") ; "a negative index")]
+    #[test_case("items[*].title", json!(["a", "b", "c"]) ; "a list projection")]
+    #[test_case("items[?priority == 'high'].title", json!(["a", "c"]) ; "a filter")]
+    #[test_case("items[*].tags[]", json!([1, 2, 3]) ; "a flatten")]
+    #[test_case("items[*].title | [0]", json!("a") ; "a pipe")]
+    #[test_case("\"default-time-zone\"", json!("UTC") ; "a quoted identifier")]
+    #[test_case("items[?!tags].{t: title, p: priority}", json!([{"t": "c", "p": "high"}]) ; "a multi-select hash")]
+    #[test_case("items[?priority != 'high' || title == `\"a\"`].title", json!(["a", "b"]) ; "or and literals")]
+    fn search_should_evaluate(
+        expression: &str,
+        expected: Value,
+    ) {
+        assert_eq!(
+            search(expression, &data())
+                .unwrap(),
+            expected
+        )
+    }
+
+    #[test_case("items[" ; "an unclosed bracket")]
+    #[test_case("items[?title = 'a']" ; "a single equals sign")]
+    #[test_case("items[1:2]" ; "a slice")]
+    fn search_should_reject_malformed_expressions(
+        expression: &str,
+    ) {
+        assert!(search(
+            expression,
+            &data()
+        )
+        .is_err())
+    }
+}
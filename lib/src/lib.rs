@@ -1,10 +1,24 @@
 #![forbid(unsafe_code)]
 
 pub mod app_error;
+mod appearance;
+#[cfg(feature = "compression")]
+pub mod codec;
+mod collation;
 pub mod core;
+mod cursor;
 mod deadline;
+mod filter_expr;
+mod id_generator;
+mod importers;
+#[cfg(feature = "extract")]
+mod jmespath;
+pub mod metrics;
 mod query;
 mod result_limit;
 mod sort_by;
+mod sql;
+mod time;
+mod time_zone;
 mod title;
 pub mod todos;
@@ -0,0 +1,343 @@
+use crate::todos::QueryCacheStats;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _, time::Duration,
+};
+
+const LATENCY_BUCKETS: &[f64] = &[
+    0.0001, 0.0005, 0.001, 0.005, 0.01,
+    0.05, 0.1, 0.5, 1.0,
+];
+
+const RESULT_SIZE_BUCKETS: &[f64] = &[
+    0.0, 1.0, 5.0, 10.0, 25.0, 50.0,
+    100.0, 500.0, 1000.0,
+];
+
+struct Histogram {
+    bounds: &'static [f64],
+
+    // Per bucket rather than cumulative; the last one is `+Inf`.
+    counts: Vec<u64>,
+
+    sum: f64,
+
+    count: u64,
+}
+
+impl Histogram {
+    fn new(
+        bounds: &'static [f64],
+    ) -> Self {
+        Self {
+            bounds,
+            counts: vec![
+                0;
+                bounds.len()
+                    + 1
+            ],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|b| value <= *b)
+            .unwrap_or(
+                self.bounds.len(),
+            );
+
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(
+        &self,
+        out: &mut String,
+        name: &str,
+        operation: &str,
+    ) {
+        let mut cumulative = 0;
+
+        let les = self
+            .bounds
+            .iter()
+            .map(|b| b.to_string())
+            .chain(Some("+Inf".into()));
+
+        for (le, n) in
+            les.zip(&self.counts)
+        {
+            cumulative += n;
+
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{operation=\"{operation}\",le=\"{le}\"}} {cumulative}"
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "{name}_sum{{operation=\"{operation}\"}} {}",
+            self.sum
+        );
+        let _ = writeln!(
+            out,
+            "{name}_count{{operation=\"{operation}\"}} {}",
+            self.count
+        );
+    }
+}
+
+/// Per-operation latency, result sizes and failures, kept by the host
+/// around each call and rendered in the Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    failures: BTreeMap<String, u64>,
+
+    latencies:
+        BTreeMap<String, Histogram>,
+
+    result_sizes:
+        BTreeMap<String, Histogram>,
+
+    query_cache:
+        Option<QueryCacheStats>,
+}
+
+impl Metrics {
+    /// `result_size` is `None` for operations that return no items.
+    pub fn observe(
+        &mut self,
+        operation: &str,
+        latency: Duration,
+        result_size: Option<usize>,
+        failed: bool,
+    ) {
+        self.latencies
+            .entry(operation.into())
+            .or_insert_with(|| {
+                Histogram::new(
+                    LATENCY_BUCKETS,
+                )
+            })
+            .observe(
+                latency.as_secs_f64(),
+            );
+
+        if let Some(size) = result_size
+        {
+            self.result_sizes
+                .entry(operation.into())
+                .or_insert_with(|| {
+                    Histogram::new(
+                        RESULT_SIZE_BUCKETS,
+                    )
+                })
+                .observe(size as f64);
+        }
+
+        let failures = self
+            .failures
+            .entry(operation.into())
+            .or_insert(0);
+
+        if failed {
+            *failures += 1;
+        }
+    }
+
+    /// The latest counts from `TodoList::query_cache_stats`, summed
+    /// over the host's lists; rendered as they are, not added up.
+    pub fn observe_query_cache(
+        &mut self,
+        stats: QueryCacheStats,
+    ) {
+        self.query_cache = Some(stats);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(
+            "# TYPE todos_operation_failures_total counter\n",
+        );
+        for (operation, n) in
+            &self.failures
+        {
+            let _ = writeln!(
+                out,
+                "todos_operation_failures_total{{operation=\"{operation}\"}} {n}"
+            );
+        }
+
+        for (name, histograms) in [
+            (
+                "todos_operation_duration_seconds",
+                &self.latencies,
+            ),
+            (
+                "todos_operation_result_size",
+                &self.result_sizes,
+            ),
+        ] {
+            let _ = writeln!(
+                out,
+                "# TYPE {name} histogram"
+            );
+
+            for (operation, h) in
+                histograms
+            {
+                h.render(
+                    &mut out, name,
+                    operation,
+                );
+            }
+        }
+
+        if let Some(stats) =
+            self.query_cache
+        {
+            for (name, kind, value) in [
+                (
+                    "todos_query_cache_hits_total",
+                    "counter",
+                    stats.hits(),
+                ),
+                (
+                    "todos_query_cache_misses_total",
+                    "counter",
+                    stats.misses(),
+                ),
+                (
+                    "todos_query_cache_entries",
+                    "gauge",
+                    stats.entries() as u64,
+                ),
+            ] {
+                let _ = writeln!(
+                    out,
+                    "# TYPE {name} {kind}\n{name} {value}"
+                );
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        Query, TodoList,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_should_report_cumulative_buckets_per_operation(
+    ) {
+        let mut metrics =
+            Metrics::default();
+
+        metrics.observe(
+            "search",
+            Duration::from_millis(2),
+            Some(3),
+            false,
+        );
+        metrics.observe(
+            "search",
+            Duration::from_millis(20),
+            Some(30),
+            true,
+        );
+
+        let actual = metrics.render();
+
+        for expected in [
+            "todos_operation_failures_total{operation=\"search\"} 1",
+            "todos_operation_duration_seconds_bucket{operation=\"search\",le=\"0.001\"} 0",
+            "todos_operation_duration_seconds_bucket{operation=\"search\",le=\"0.005\"} 1",
+            "todos_operation_duration_seconds_bucket{operation=\"search\",le=\"+Inf\"} 2",
+            "todos_operation_duration_seconds_count{operation=\"search\"} 2",
+            "todos_operation_result_size_bucket{operation=\"search\",le=\"5\"} 1",
+            "todos_operation_result_size_bucket{operation=\"search\",le=\"50\"} 2",
+            "todos_operation_result_size_sum{operation=\"search\"} 33",
+        ] {
+            assert!(
+                actual.lines().any(|l| l == expected),
+                "missing `{expected}` in:\n{actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn observe_should_skip_result_sizes_when_none_is_given(
+    ) {
+        let mut metrics =
+            Metrics::default();
+
+        metrics.observe(
+            "delete",
+            Duration::ZERO,
+            None,
+            false,
+        );
+
+        let actual = metrics
+            .render()
+            .lines()
+            .filter(|l| {
+                l.starts_with("todos_operation_result_size")
+            })
+            .count();
+
+        assert_eq!(actual, 0)
+    }
+
+    #[test]
+    fn render_should_report_query_cache_stats_once_observed(
+    ) {
+        let mut todos = TodoList::new();
+
+        for _ in 0..3 {
+            todos
+                .search_cached(
+                    &Query::default(),
+                )
+                .unwrap();
+        }
+
+        let mut metrics =
+            Metrics::default();
+
+        assert!(!metrics
+            .render()
+            .contains(
+                "todos_query_cache"
+            ));
+
+        metrics.observe_query_cache(
+            todos.query_cache_stats(),
+        );
+
+        let actual = metrics.render();
+
+        for expected in [
+            "todos_query_cache_hits_total 2",
+            "todos_query_cache_misses_total 1",
+            "todos_query_cache_entries 1",
+        ] {
+            assert!(
+                actual.lines().any(|l| l == expected),
+                "missing `{expected}` in:\n{actual}"
+            );
+        }
+    }
+}
@@ -1,41 +1,207 @@
 use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
     core::UnixTime,
     deadline::OptionalDeadlineInput,
+    filter_expr::FilterExpr,
     result_limit::OptionalResultLimit,
-    todos::{Priority, Status, Todo},
+    todos::{
+        Priority, SearchField, Status,
+        Todo, TodoField,
+    },
 };
 use getset::Getters;
+use regex::{Regex, RegexBuilder};
+use strum::IntoEnumIterator;
 use typed_builder::TypedBuilder;
 
-#[derive(Clone)]
+// Long enough for any pattern typed by hand.
+const MAX_TITLE_REGEX_LEN: usize = 256;
+
+// Caps the compiled program, so a pattern such as `\w{1000}` is
+// rejected up front rather than slowing every search.
+const MAX_TITLE_REGEX_SIZE: usize =
+    1 << 16;
+
+// Variants are in the same order as the WIT `query-sort` enum.
+#[derive(Clone, Debug)]
 pub enum QuerySort {
-    Deadline,
     Priority,
     Status,
+    Deadline,
+    StartDate,
+    CreatedAt,
+    UpdatedTimestamp,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Availability {
+    /// No start date, or one that has already passed.
+    AvailableNow,
+
+    /// A start date still in the future.
+    ScheduledLater,
 }
 
 #[derive(
-    Default, Getters, TypedBuilder,
+    Clone,
+    Debug,
+    Default,
+    Getters,
+    TypedBuilder,
 )]
 #[builder(field_defaults(default))]
 pub struct Query {
     keyword: Option<String>,
 
+    title_regex: Option<String>,
+
+    /// Leaves out items whose title contains this.
+    not_keyword: Option<String>,
+
+    /// Matches both keywords as plain text: markdown markers and emoji
+    /// removed, and case ignored.
+    plain_text_match: bool,
+
+    /// Where both keywords look; empty looks in the title alone.
+    #[getset(get = "pub")]
+    search_fields: Vec<SearchField>,
+
     priority: Option<Priority>,
 
     status: Option<Status>,
 
+    exclude_priorities: Vec<Priority>,
+
+    exclude_statuses: Vec<Status>,
+
+    /// Combined with the other predicates by AND.
+    expr: Option<FilterExpr>,
+
     #[getset(get = "pub")]
     deadline: OptionalDeadlineInput,
 
+    availability: Option<Availability>,
+
+    include_snoozed: bool,
+
     #[getset(get = "pub")]
     sort: Option<QuerySort>,
 
+    #[getset(get = "pub")]
+    direction: SortDirection,
+
     #[getset(get = "pub")]
     limit: OptionalResultLimit,
+
+    /// A `SearchResult::next_cursor`; the search resumes after the item it
+    /// points at.
+    #[getset(get = "pub")]
+    after: Option<String>,
+
+    /// Fields kept by `TodoList::search_projected`; empty keeps them all.
+    #[getset(get = "pub")]
+    projection: Vec<TodoField>,
+}
+
+/// `text` lowercased, without emoji or the characters markdown uses
+/// for emphasis, code, headings, quotes and links, and with its words
+/// one space apart.
+fn plain_text(text: &str) -> String {
+    plain_lowercase_text(
+        &text.to_lowercase(),
+    )
+}
+
+/// `plain_text` for text already lowercased. None of the characters it
+/// drops has a case, so lowercasing first changes nothing else.
+fn plain_lowercase_text(
+    text: &str,
+) -> String {
+    text.chars()
+        .filter(|c| {
+            !is_markdown_marker(*c)
+                && !is_emoji(*c)
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_markdown_marker(c: char) -> bool {
+    matches!(
+        c,
+        '*' | '_'
+            | '~'
+            | '`'
+            | '#'
+            | '>'
+            | '['
+            | ']'
+    )
+}
+
+// Pictographs and symbols, plus the joiners, variation selectors and
+// tags that combine them into one emoji.
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200d}'
+            | '\u{2190}'..='\u{21ff}'
+            | '\u{2300}'..='\u{23ff}'
+            | '\u{2600}'..='\u{27bf}'
+            | '\u{2b00}'..='\u{2bff}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{1f000}'..='\u{1faff}'
+            | '\u{e0020}'..='\u{e007f}'
+    )
 }
 
 impl Query {
+    // How many predicates are set, for trace fields.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn predicate_count(
+        &self,
+    ) -> usize {
+        [
+            self.keyword.is_some(),
+            self.title_regex.is_some(),
+            self.not_keyword.is_some(),
+            self.priority.is_some(),
+            self.status.is_some(),
+            !self
+                .exclude_priorities
+                .is_empty(),
+            !self
+                .exclude_statuses
+                .is_empty(),
+            self.expr.is_some(),
+            self.deadline
+                .input()
+                .is_some(),
+            self.availability.is_some(),
+        ]
+        .into_iter()
+        .filter(|set| *set)
+        .count()
+    }
+
     pub(crate) fn match_keyword(
         &self,
         todo: &Todo,
@@ -43,8 +209,152 @@ impl Query {
         self.keyword
             .as_ref()
             .map(|keyword| {
-                todo.title()
-                    .contains(keyword)
+                self.fields_containing(
+                    todo, keyword,
+                )
+                .next()
+                .is_some()
+            })
+            .unwrap_or(true)
+    }
+
+    pub(crate) fn match_not_keyword(
+        &self,
+        todo: &Todo,
+    ) -> bool {
+        self.not_keyword
+            .as_ref()
+            .map(|keyword| {
+                self.fields_containing(
+                    todo, keyword,
+                )
+                .next()
+                .is_none()
+            })
+            .unwrap_or(true)
+    }
+
+    /// The searched fields `keyword` matched in `todo`; none without a
+    /// keyword.
+    pub(crate) fn keyword_fields(
+        &self,
+        todo: &Todo,
+    ) -> Vec<SearchField> {
+        self.keyword
+            .as_ref()
+            .map(|keyword| {
+                self.fields_containing(
+                    todo, keyword,
+                )
+                .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn fields_containing<'a>(
+        &'a self,
+        todo: &'a Todo,
+        keyword: &'a str,
+    ) -> impl Iterator<Item = SearchField> + 'a
+    {
+        SearchField::iter().filter(
+            move |field| {
+                (if self
+                    .search_fields
+                    .is_empty()
+                {
+                    *field
+                        == SearchField::Title
+                } else {
+                    self.search_fields
+                        .contains(field)
+                }) && self
+                    .field_contains(
+                        todo, *field,
+                        keyword,
+                    )
+            },
+        )
+    }
+
+    fn field_contains(
+        &self,
+        todo: &Todo,
+        field: SearchField,
+        keyword: &str,
+    ) -> bool {
+        if self.plain_text_match {
+            let keyword =
+                plain_text(keyword);
+
+            field
+                .lowercase_texts(todo)
+                .any(|text| {
+                    plain_lowercase_text(
+                        &text,
+                    )
+                    .contains(&keyword)
+                })
+        } else {
+            field.texts(todo).any(
+                |text| {
+                    text.contains(
+                        keyword,
+                    )
+                },
+            )
+        }
+    }
+
+    /// Compiles `title_regex`; done once per search, not per item.
+    pub(crate) fn compiled_title_regex(
+        &self,
+    ) -> AppResult<Option<Regex>> {
+        self.title_regex
+            .as_ref()
+            .map(|pattern| {
+                let invalid = |reason: String| {
+                    report!(
+                        AppError::InvalidRegex {
+                            pattern: pattern.clone(),
+                            reason,
+                        }
+                    )
+                };
+
+                if pattern.len()
+                    > MAX_TITLE_REGEX_LEN
+                {
+                    return Err(invalid(
+                        format!(
+                            "longer than {} bytes",
+                            MAX_TITLE_REGEX_LEN
+                        ),
+                    ));
+                }
+
+                RegexBuilder::new(pattern)
+                    .size_limit(
+                        MAX_TITLE_REGEX_SIZE,
+                    )
+                    .build()
+                    .map_err(|e| {
+                        invalid(e.to_string())
+                    })
+            })
+            .transpose()
+    }
+
+    pub(crate) fn match_title_regex(
+        title_regex: &Option<Regex>,
+        todo: &Todo,
+    ) -> bool {
+        title_regex
+            .as_ref()
+            .map(|re| {
+                re.is_match(
+                    todo.title(),
+                )
             })
             .unwrap_or(true)
     }
@@ -69,6 +379,33 @@ impl Query {
             .unwrap_or(true)
     }
 
+    pub(crate) fn match_exclusions(
+        &self,
+        todo: &Todo,
+    ) -> bool {
+        !self
+            .exclude_priorities
+            .contains(&todo.priority())
+            && !self
+                .exclude_statuses
+                .contains(
+                    &todo.status(),
+                )
+    }
+
+    pub(crate) fn match_expr(
+        &self,
+        now: UnixTime,
+        todo: &Todo,
+    ) -> bool {
+        self.expr
+            .as_ref()
+            .map(|e| {
+                e.matches(now, todo)
+            })
+            .unwrap_or(true)
+    }
+
     pub(crate) fn match_deadline(
         deadline: &Option<UnixTime>,
         todo: &Todo,
@@ -85,6 +422,35 @@ impl Query {
             })
             .unwrap_or(true)
     }
+
+    pub(crate) fn match_availability(
+        &self,
+        now: UnixTime,
+        todo: &Todo,
+    ) -> bool {
+        self.availability
+            .map(|a| {
+                let available = todo
+                    .start_date()
+                    .map(|s| s <= now)
+                    .unwrap_or(true);
+
+                match a {
+                    Availability::AvailableNow => available,
+                    Availability::ScheduledLater => !available,
+                }
+            })
+            .unwrap_or(true)
+    }
+
+    pub(crate) fn match_snoozed(
+        &self,
+        now: UnixTime,
+        todo: &Todo,
+    ) -> bool {
+        self.include_snoozed
+            || !todo.is_snoozed_at(now)
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use crate::app_error::{
-    AppError, AppResult, Report,
+    bail, AppError, AppResult, Report,
     ResultExt,
 };
 use derive_more::From;
@@ -10,12 +10,43 @@ const QUERY_DEFAULT_LIMIT: Limit = 10;
 
 const QUERY_MAX_LIMIT: Limit = 100;
 
-#[derive(Default, From)]
+#[derive(
+    Clone, Debug, Default, From,
+)]
 pub struct OptionalResultLimit(
     Option<Limit>,
 );
 
 impl OptionalResultLimit {
+    pub const MAX: Limit =
+        QUERY_MAX_LIMIT;
+
+    pub(crate) fn given(
+        &self,
+    ) -> Option<Limit> {
+        self.0
+    }
+
+    // Rejects what `validated` would quietly clamp.
+    pub(crate) fn strictly_validated(
+        &self,
+    ) -> AppResult<usize> {
+        if let Some(n) = self.0 {
+            if !(1..=QUERY_MAX_LIMIT)
+                .contains(&n)
+            {
+                bail!(
+                    AppError::InvalidResultLimit {
+                        given: n,
+                        max: QUERY_MAX_LIMIT,
+                    }
+                )
+            }
+        }
+
+        self.validated()
+    }
+
     pub(crate) fn validated(
         &self,
     ) -> AppResult<usize> {
@@ -43,6 +74,7 @@ impl OptionalResultLimit {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assert_app_error;
 
     impl OptionalResultLimit {
         pub(crate) fn some(
@@ -98,4 +130,45 @@ mod tests {
             QUERY_MAX_LIMIT,
         )
     }
+
+    #[test]
+    fn strictly_validated_should_fail_when_value_is_out_of_range(
+    ) {
+        for n in
+            [0, QUERY_MAX_LIMIT + 1]
+        {
+            let actual =
+                OptionalResultLimit::some(n)
+                    .strictly_validated();
+
+            let expected =
+                AppError::InvalidResultLimit {
+                    given: n,
+                    max: QUERY_MAX_LIMIT,
+                };
+
+            assert_app_error!(
+                actual, expected
+            );
+        }
+    }
+
+    #[test]
+    fn strictly_validated_should_accept_values_within_range(
+    ) {
+        assert_eq!(
+            OptionalResultLimit::some(
+                QUERY_MAX_LIMIT
+            )
+            .strictly_validated()
+            .unwrap(),
+            QUERY_MAX_LIMIT as usize
+        );
+        assert_eq!(
+            OptionalResultLimit::default()
+                .strictly_validated()
+                .unwrap(),
+            QUERY_DEFAULT_LIMIT as usize
+        );
+    }
 }
@@ -1,29 +1,131 @@
 use crate::{
+    collation::TitleCollation,
     core::UnixTime,
-    query::QuerySort,
+    query::{QuerySort, SortDirection},
     todos::{Priority, Status, Todo},
 };
 use std::cmp;
+use strum::IntoEnumIterator;
+use uuid::Uuid;
 
 #[derive(
-    Eq, PartialEq, Ord, PartialOrd,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
 )]
 pub(crate) enum SortBy {
-    Deadline(Option<UnixTime>),
+    CreatedAt(UnixTime, Tiebreak),
+
+    Deadline(
+        Option<UnixTime>,
+        Tiebreak,
+    ),
+
+    Priority(
+        cmp::Reverse<Priority>,
+        Tiebreak,
+    ),
+
+    StartDate(
+        Option<UnixTime>,
+        Tiebreak,
+    ),
+
+    Status(Status, Tiebreak),
+
+    Title(Tiebreak),
+
+    UpdatedTimestamp(
+        UnixTime,
+        Tiebreak,
+    ),
+}
+
+/// Orders items whose sort field is equal by title, then by ID, so that
+/// repeated searches and the pages of one search agree. The title is
+/// held as its collation key.
+#[derive(
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+)]
+pub(crate) struct Tiebreak(
+    pub(crate) String,
+    pub(crate) Uuid,
+);
+
+/// A `SortBy` with the query's direction applied.
+#[derive(
+    Eq, PartialEq, Ord, PartialOrd,
+)]
+pub(crate) enum SortKey {
+    Ascending(SortBy),
 
-    Priority(cmp::Reverse<Priority>),
+    Descending(cmp::Reverse<SortBy>),
+}
+
+impl SortKey {
+    pub(crate) fn from<'a>(
+        query_sort: &'a Option<
+            QuerySort,
+        >,
+        direction: SortDirection,
+        collation: TitleCollation,
+    ) -> impl Fn(&Todo) -> Self + 'a
+    {
+        let sort_by = SortBy::from(
+            query_sort, collation,
+        );
 
-    Status(Status),
+        move |t: &Todo| {
+            Self::directed(
+                sort_by(t),
+                direction,
+            )
+        }
+    }
 
-    Title(String),
+    pub(crate) fn directed(
+        sort_by: SortBy,
+        direction: SortDirection,
+    ) -> Self {
+        match direction {
+            SortDirection::Ascending => {
+                SortKey::Ascending(
+                    sort_by,
+                )
+            }
+            SortDirection::Descending => {
+                SortKey::Descending(
+                    cmp::Reverse(sort_by),
+                )
+            }
+        }
+    }
 }
 
 impl SortBy {
     pub(crate) fn from(
         query_sort: &Option<QuerySort>,
+        collation: TitleCollation,
     ) -> impl Fn(&Todo) -> Self + '_
     {
-        move |t: &Todo| match query_sort
+        move |t: &Todo| {
+            let tiebreak = || {
+                Tiebreak(
+                    t.title_key(
+                        collation,
+                    )
+                    .to_string(),
+                    *t.id(),
+                )
+            };
+
+            match query_sort
         {
             Some(
                 QuerySort::Priority,
@@ -31,20 +133,224 @@ impl SortBy {
                 cmp::Reverse(
                     t.priority(),
                 ),
+                tiebreak(),
             ),
             Some(QuerySort::Status) => {
                 SortBy::Status(
                     t.status(),
+                    tiebreak(),
                 )
             }
             Some(
                 QuerySort::Deadline,
             ) => SortBy::Deadline(
                 t.deadline(),
+                tiebreak(),
+            ),
+            Some(
+                QuerySort::StartDate,
+            ) => SortBy::StartDate(
+                t.start_date(),
+                tiebreak(),
+            ),
+            Some(
+                QuerySort::CreatedAt,
+            ) => SortBy::CreatedAt(
+                t.created_timestamp(),
+                tiebreak(),
+            ),
+            Some(
+                QuerySort::UpdatedTimestamp,
+            ) => SortBy::UpdatedTimestamp(
+                t.updated_timestamp(),
+                tiebreak(),
             ),
             None => SortBy::Title(
-                t.title().into(),
+                tiebreak(),
+            ),
+        }
+        }
+    }
+}
+
+// The text form of a key, for cursors. Enums are stored by position and
+// a missing date as an empty string.
+impl SortBy {
+    pub(crate) fn tag(
+        query_sort: &Option<QuerySort>,
+    ) -> &'static str {
+        match query_sort {
+            Some(QuerySort::Priority) => {
+                "priority"
+            }
+            Some(QuerySort::Status) => {
+                "status"
+            }
+            Some(QuerySort::Deadline) => {
+                "deadline"
+            }
+            Some(
+                QuerySort::StartDate,
+            ) => "start-date",
+            Some(
+                QuerySort::CreatedAt,
+            ) => "created-at",
+            Some(
+                QuerySort::UpdatedTimestamp,
+            ) => "updated-timestamp",
+            None => "title",
+        }
+    }
+
+    pub(crate) fn value(
+        &self,
+    ) -> String {
+        let time =
+            |t: &Option<UnixTime>| {
+                t.map(|t| t.to_string())
+                    .unwrap_or_default()
+            };
+
+        // The title goes last, since it may contain anything.
+        let with_title = |value: String,
+                          tiebreak: &Tiebreak| {
+            format!(
+                "{}\n{}",
+                value, tiebreak.0
+            )
+        };
+
+        match self {
+            SortBy::CreatedAt(t, tb)
+            | SortBy::UpdatedTimestamp(
+                t,
+                tb,
+            ) => with_title(
+                t.to_string(),
+                tb,
+            ),
+            SortBy::Deadline(t, tb)
+            | SortBy::StartDate(t, tb) => {
+                with_title(time(t), tb)
+            }
+            SortBy::Priority(
+                cmp::Reverse(p),
+                tb,
+            ) => with_title(
+                Priority::iter()
+                    .position(|q| q == *p)
+                    .unwrap_or_default()
+                    .to_string(),
+                tb,
+            ),
+            SortBy::Status(s, tb) => {
+                with_title(
+                    Status::iter()
+                        .position(|q| {
+                            q == *s
+                        })
+                        .unwrap_or_default()
+                        .to_string(),
+                    tb,
+                )
+            }
+            SortBy::Title(tb) => {
+                tb.0.clone()
+            }
+        }
+    }
+
+    /// The key `value` stands for under `query_sort`, if it is well
+    /// formed.
+    pub(crate) fn parse(
+        query_sort: &Option<QuerySort>,
+        value: &str,
+        id: Uuid,
+    ) -> Option<Self> {
+        let (field, tiebreak) = value
+            .split_once('\n')
+            .map(|(field, title)| {
+                (
+                    field,
+                    Some(Tiebreak(
+                        title.into(),
+                        id,
+                    )),
+                )
+            })
+            .unwrap_or((value, None));
+
+        let time = || {
+            field
+                .parse::<UnixTime>()
+                .ok()
+        };
+
+        let optional_time = || {
+            if field.is_empty() {
+                Some(None)
+            } else {
+                time().map(Some)
+            }
+        };
+
+        let position = || {
+            field.parse::<usize>().ok()
+        };
+
+        match query_sort {
+            Some(QuerySort::Priority) => {
+                let p = position()
+                    .and_then(|i| {
+                        Priority::iter()
+                            .nth(i)
+                    })?;
+
+                Some(SortBy::Priority(
+                    cmp::Reverse(p),
+                    tiebreak?,
+                ))
+            }
+            Some(QuerySort::Status) => {
+                let s = position()
+                    .and_then(|i| {
+                        Status::iter()
+                            .nth(i)
+                    })?;
+
+                Some(SortBy::Status(
+                    s, tiebreak?,
+                ))
+            }
+            Some(QuerySort::Deadline) => {
+                Some(SortBy::Deadline(
+                    optional_time()?,
+                    tiebreak?,
+                ))
+            }
+            Some(
+                QuerySort::StartDate,
+            ) => Some(SortBy::StartDate(
+                optional_time()?,
+                tiebreak?,
+            )),
+            Some(
+                QuerySort::CreatedAt,
+            ) => Some(SortBy::CreatedAt(
+                time()?,
+                tiebreak?,
+            )),
+            Some(
+                QuerySort::UpdatedTimestamp,
+            ) => Some(
+                SortBy::UpdatedTimestamp(
+                    time()?,
+                    tiebreak?,
+                ),
             ),
+            None => Some(SortBy::Title(
+                Tiebreak(value.into(), id),
+            )),
         }
     }
 }
@@ -0,0 +1,769 @@
+//! A small read-only SELECT dialect, translated into a `Query`:
+//!
+//! ```text
+//! SELECT title, deadline FROM todos
+//! WHERE (priority = 'high' AND overdue) OR title LIKE '%urgent%'
+//! ORDER BY deadline DESC LIMIT 20
+//! ```
+//!
+//! Every row carries the id, so `SELECT` must name at least one other
+//! field, or `*`.
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+        Report,
+    },
+    filter_expr::{
+        FieldPredicate, FilterExpr,
+    },
+    query::{
+        Query, QuerySort, SortDirection,
+    },
+    todos::{
+        Priority, Status, TodoField,
+    },
+};
+
+// Keeps the parser's recursion shallow for any text it accepts.
+const MAX_SQL_LEN: usize = 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+
+    Text(String),
+
+    Number(u32),
+
+    Symbol(&'static str),
+}
+
+pub(crate) fn parse(
+    text: &str,
+) -> AppResult<Query> {
+    if text.len() > MAX_SQL_LEN {
+        return Err(invalid(format!(
+            "longer than {} bytes",
+            MAX_SQL_LEN
+        )));
+    }
+
+    let mut parser = Parser {
+        tokens: tokens(text)?,
+        pos: 0,
+    };
+
+    let query = parser.select()?;
+
+    parser.symbol(";");
+
+    match parser.next() {
+        None => Ok(query),
+        Some(token) => {
+            Err(unexpected(&token))
+        }
+    }
+}
+
+fn invalid(
+    reason: String,
+) -> Report<AppError> {
+    report!(AppError::InvalidSql(
+        reason
+    ))
+}
+
+fn unexpected(
+    token: &Token,
+) -> Report<AppError> {
+    invalid(format!(
+        "unexpected {}",
+        describe(token)
+    ))
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(w) => {
+            format!("'{w}'")
+        }
+        Token::Text(t) => {
+            format!("'{t}'")
+        }
+        Token::Number(n) => {
+            n.to_string()
+        }
+        Token::Symbol(s) => {
+            format!("'{s}'")
+        }
+    }
+}
+
+fn tokens(
+    text: &str,
+) -> AppResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars =
+        text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' {
+            chars.next();
+
+            let mut text =
+                String::new();
+
+            // A doubled quote stands for one.
+            loop {
+                match chars.next() {
+                    Some('\'') => {
+                        if chars
+                            .next_if_eq(&'\'')
+                            .is_some()
+                        {
+                            text.push('\'');
+                        } else {
+                            break;
+                        }
+                    }
+                    Some(c) => text.push(c),
+                    None => {
+                        return Err(invalid(
+                            "unterminated string"
+                                .into(),
+                        ))
+                    }
+                }
+            }
+
+            tokens.push(Token::Text(
+                text,
+            ));
+        } else if c.is_ascii_digit() {
+            let mut digits =
+                String::new();
+
+            while let Some(d) = chars
+                .next_if(
+                char::is_ascii_digit,
+            ) {
+                digits.push(d);
+            }
+
+            let n = digits
+                .parse()
+                .map_err(|_| {
+                    invalid(format!(
+                        "{digits} is too large"
+                    ))
+                })?;
+
+            tokens
+                .push(Token::Number(n));
+        } else if c.is_alphabetic()
+            || c == '_'
+        {
+            let mut word =
+                String::new();
+
+            while let Some(c) = chars
+                .next_if(|c| {
+                    c.is_alphanumeric()
+                        || *c == '_'
+                })
+            {
+                word.push(c);
+            }
+
+            tokens.push(Token::Word(
+                word,
+            ));
+        } else {
+            chars.next();
+
+            let symbol = match c {
+                '(' => "(",
+                ')' => ")",
+                ',' => ",",
+                '*' => "*",
+                ';' => ";",
+                '=' => "=",
+                '!' if chars
+                    .next_if_eq(&'=')
+                    .is_some() =>
+                {
+                    "!="
+                }
+                '<' if chars
+                    .next_if_eq(&'>')
+                    .is_some() =>
+                {
+                    "!="
+                }
+                _ => {
+                    return Err(invalid(
+                        format!(
+                            "unexpected '{c}'"
+                        ),
+                    ))
+                }
+            };
+
+            tokens.push(Token::Symbol(
+                symbol,
+            ));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+
+    pos: usize,
+}
+
+impl Parser {
+    fn next(
+        &mut self,
+    ) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned();
+
+        self.pos += 1;
+
+        token
+    }
+
+    // Consumes the next token when it is the keyword `keyword`, in any
+    // case.
+    fn keyword(
+        &mut self,
+        keyword: &str,
+    ) -> bool {
+        let found = matches!(
+            self.tokens.get(self.pos),
+            Some(Token::Word(w))
+                if w.eq_ignore_ascii_case(keyword)
+        );
+
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn symbol(
+        &mut self,
+        symbol: &str,
+    ) -> bool {
+        let found = matches!(
+            self.tokens.get(self.pos),
+            Some(Token::Symbol(s))
+                if *s == symbol
+        );
+
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn expect_keyword(
+        &mut self,
+        keyword: &str,
+    ) -> AppResult<()> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.expected(
+                &keyword.to_uppercase(),
+            ))
+        }
+    }
+
+    fn expect_symbol(
+        &mut self,
+        symbol: &str,
+    ) -> AppResult<()> {
+        if self.symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.expected(
+                &format!("'{symbol}'"),
+            ))
+        }
+    }
+
+    fn expected(
+        &self,
+        what: &str,
+    ) -> Report<AppError> {
+        match self.tokens.get(self.pos)
+        {
+            Some(token) => invalid(
+                format!(
+                    "expected {what}, not {}",
+                    describe(token)
+                ),
+            ),
+            None => invalid(format!(
+                "expected {what} at the end"
+            )),
+        }
+    }
+
+    fn word(
+        &mut self,
+        what: &str,
+    ) -> AppResult<String> {
+        match self.next() {
+            Some(Token::Word(w)) => {
+                Ok(w.to_lowercase())
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.expected(what))
+            }
+        }
+    }
+
+    fn text(
+        &mut self,
+        what: &str,
+    ) -> AppResult<String> {
+        match self.next() {
+            Some(Token::Text(t)) => {
+                Ok(t)
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.expected(what))
+            }
+        }
+    }
+
+    fn select(
+        &mut self,
+    ) -> AppResult<Query> {
+        self.expect_keyword("select")?;
+
+        let projection =
+            self.projection()?;
+
+        if self.keyword("from") {
+            let table =
+                self.word("a table")?;
+
+            if table != "todos" {
+                return Err(invalid(
+                    format!(
+                        "unknown table '{table}'; only todos can be queried"
+                    ),
+                ));
+            }
+        }
+
+        let expr =
+            if self.keyword("where") {
+                Some(self.or()?)
+            } else {
+                None
+            };
+
+        let (sort, direction) = if self
+            .keyword("order")
+        {
+            self.expect_keyword("by")?;
+
+            let sort = self.sort()?;
+
+            let direction = if self
+                .keyword("desc")
+            {
+                SortDirection::Descending
+            } else {
+                self.keyword("asc");
+                SortDirection::Ascending
+            };
+
+            (Some(sort), direction)
+        } else {
+            (None, SortDirection::Ascending)
+        };
+
+        let limit = if self
+            .keyword("limit")
+        {
+            match self.next() {
+                Some(
+                    Token::Number(n),
+                ) => Some(n),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self
+                        .expected(
+                            "a number",
+                        ));
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Query::builder()
+            .projection(projection)
+            .expr(expr)
+            .sort(sort)
+            .direction(direction)
+            .limit(limit.into())
+            .build())
+    }
+
+    fn projection(
+        &mut self,
+    ) -> AppResult<Vec<TodoField>> {
+        if self.symbol("*") {
+            return Ok(vec![]);
+        }
+
+        let mut fields = Vec::new();
+
+        loop {
+            let field = match self
+                .word("a field")?
+                .as_str()
+            {
+                "id" => None,
+                "title" => {
+                    Some(TodoField::Title)
+                }
+                "priority" => Some(
+                    TodoField::Priority,
+                ),
+                "status" => {
+                    Some(TodoField::Status)
+                }
+                "deadline" => Some(
+                    TodoField::Deadline,
+                ),
+                other => {
+                    return Err(invalid(
+                        format!(
+                            "unknown field '{other}'"
+                        ),
+                    ))
+                }
+            };
+
+            fields.extend(field);
+
+            if !self.symbol(",") {
+                break;
+            }
+        }
+
+        // An empty projection would keep every field.
+        if fields.is_empty() {
+            return Err(invalid(
+                "select a field besides id, or *"
+                    .into(),
+            ));
+        }
+
+        Ok(fields)
+    }
+
+    fn sort(
+        &mut self,
+    ) -> AppResult<QuerySort> {
+        match self
+            .word("a field")?
+            .as_str()
+        {
+            "priority" => {
+                Ok(QuerySort::Priority)
+            }
+            "status" => {
+                Ok(QuerySort::Status)
+            }
+            "deadline" => {
+                Ok(QuerySort::Deadline)
+            }
+            "start_date" => {
+                Ok(QuerySort::StartDate)
+            }
+            "created_timestamp" => {
+                Ok(QuerySort::CreatedAt)
+            }
+            "updated_timestamp" => Ok(
+                QuerySort::UpdatedTimestamp,
+            ),
+            other => Err(invalid(format!(
+                "cannot order by '{other}'"
+            ))),
+        }
+    }
+
+    fn or(
+        &mut self,
+    ) -> AppResult<FilterExpr> {
+        let mut children =
+            vec![self.and()?];
+
+        while self.keyword("or") {
+            children.push(self.and()?);
+        }
+
+        Ok(single_or(
+            children,
+            FilterExpr::Or,
+        ))
+    }
+
+    fn and(
+        &mut self,
+    ) -> AppResult<FilterExpr> {
+        let mut children =
+            vec![self.not()?];
+
+        while self.keyword("and") {
+            children.push(self.not()?);
+        }
+
+        Ok(single_or(
+            children,
+            FilterExpr::And,
+        ))
+    }
+
+    fn not(
+        &mut self,
+    ) -> AppResult<FilterExpr> {
+        if self.keyword("not") {
+            Ok(FilterExpr::Not(
+                Box::new(self.not()?),
+            ))
+        } else if self.symbol("(") {
+            let expr = self.or()?;
+
+            self.expect_symbol(")")?;
+
+            Ok(expr)
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(
+        &mut self,
+    ) -> AppResult<FilterExpr> {
+        let field =
+            self.word("a condition")?;
+
+        match field.as_str() {
+            "overdue" => Ok(field_expr(
+                FieldPredicate::Overdue,
+            )),
+            "deadline" => {
+                self.expect_keyword("is")?;
+
+                let has_deadline =
+                    self.keyword("not");
+
+                self.expect_keyword("null")?;
+
+                Ok(negated_if(
+                    !has_deadline,
+                    field_expr(
+                        FieldPredicate::HasDeadline,
+                    ),
+                ))
+            }
+            "title" => {
+                let negated =
+                    self.keyword("not");
+
+                self.expect_keyword("like")?;
+
+                let keyword = contained(
+                    &self.text("a pattern")?,
+                )?;
+
+                Ok(negated_if(
+                    negated,
+                    field_expr(
+                        FieldPredicate::Keyword(
+                            keyword,
+                        ),
+                    ),
+                ))
+            }
+            "priority" => {
+                let negated =
+                    self.equality()?;
+
+                let priority = priority(
+                    &self.text("a priority")?,
+                )?;
+
+                Ok(negated_if(
+                    negated,
+                    field_expr(
+                        FieldPredicate::Priority(
+                            priority,
+                        ),
+                    ),
+                ))
+            }
+            "status" => {
+                let negated =
+                    self.equality()?;
+
+                let status = status(
+                    &self.text("a status")?,
+                )?;
+
+                Ok(negated_if(
+                    negated,
+                    field_expr(
+                        FieldPredicate::Status(
+                            status,
+                        ),
+                    ),
+                ))
+            }
+            other => Err(invalid(format!(
+                "cannot filter on '{other}'"
+            ))),
+        }
+    }
+
+    // Whether the comparison is `!=` rather than `=`.
+    fn equality(
+        &mut self,
+    ) -> AppResult<bool> {
+        if self.symbol("=") {
+            Ok(false)
+        } else if self.symbol("!=") {
+            Ok(true)
+        } else {
+            Err(self.expected(
+                "'=' or '!='",
+            ))
+        }
+    }
+}
+
+fn single_or(
+    mut children: Vec<FilterExpr>,
+    combine: fn(
+        Vec<FilterExpr>,
+    ) -> FilterExpr,
+) -> FilterExpr {
+    if children.len() == 1 {
+        children.remove(0)
+    } else {
+        combine(children)
+    }
+}
+
+fn field_expr(
+    predicate: FieldPredicate,
+) -> FilterExpr {
+    FilterExpr::Field(predicate)
+}
+
+fn negated_if(
+    negated: bool,
+    expr: FilterExpr,
+) -> FilterExpr {
+    if negated {
+        FilterExpr::Not(Box::new(expr))
+    } else {
+        expr
+    }
+}
+
+// Titles are matched by substring, so only `%text%` can be expressed.
+fn contained(
+    pattern: &str,
+) -> AppResult<String> {
+    pattern
+        .strip_prefix('%')
+        .and_then(|p| {
+            p.strip_suffix('%')
+        })
+        .filter(|p| {
+            !p.is_empty()
+                && !p.contains(['%', '_'])
+        })
+        .map(String::from)
+        .ok_or_else(|| {
+            invalid(format!(
+                "only LIKE '%text%' is supported, not '{pattern}'"
+            ))
+        })
+}
+
+fn priority(
+    value: &str,
+) -> AppResult<Priority> {
+    match value.to_lowercase().as_str()
+    {
+        "low" => Ok(Priority::Low),
+        "medium" => {
+            Ok(Priority::Medium)
+        }
+        "high" => Ok(Priority::High),
+        _ => Err(invalid(format!(
+            "unknown priority '{value}'"
+        ))),
+    }
+}
+
+fn status(
+    value: &str,
+) -> AppResult<Status> {
+    match value.to_lowercase().as_str()
+    {
+        "backlog" => {
+            Ok(Status::Backlog)
+        }
+        "in-progress" => {
+            Ok(Status::InProgress)
+        }
+        "done" => Ok(Status::Done),
+        _ => Err(invalid(format!(
+            "unknown status '{value}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_reject_unknown_fields_and_trailing_text(
+    ) {
+        for text in [
+            "SELECT color FROM todos",
+            "SELECT id FROM todos",
+            "SELECT * FROM todos LIMIT 5 5",
+            "SELECT * WHERE title LIKE 'a%b'",
+        ] {
+            assert!(
+                parse(text).is_err(),
+                "{text}"
+            );
+        }
+    }
+}
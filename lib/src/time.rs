@@ -0,0 +1,201 @@
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    core::UnixTime,
+};
+
+/// The first second of the year 1 (UTC), the earliest time the list
+/// keeps.
+pub(crate) const EARLIEST: UnixTime =
+    -62_135_596_800;
+
+/// The last second of the year 9999 (UTC), the latest time a date can
+/// be written as and so the latest the list keeps.
+pub(crate) const LATEST: UnixTime =
+    253_402_300_799;
+
+/// `t`, or an error when it falls outside `EARLIEST..=LATEST`.
+pub(crate) fn in_range(
+    t: UnixTime,
+) -> AppResult<UnixTime> {
+    (EARLIEST..=LATEST)
+        .contains(&t)
+        .then_some(t)
+        .ok_or_else(|| {
+            report!(
+                AppError::TimestampOutOfRange(
+                    t
+                )
+            )
+        })
+}
+
+/// `t` moved into `EARLIEST..=LATEST`.
+pub(crate) fn clamped(
+    t: UnixTime,
+) -> UnixTime {
+    t.clamp(EARLIEST, LATEST)
+}
+
+/// `amount` units of `unit_seconds` each, or an error when that does
+/// not fit a timestamp.
+pub(crate) fn seconds(
+    amount: i64,
+    unit_seconds: UnixTime,
+    unit: &str,
+) -> AppResult<UnixTime> {
+    amount
+        .checked_mul(unit_seconds)
+        .ok_or_else(|| {
+            report!(
+                AppError::DurationOverflow(
+                    format!(
+                        "{amount} {unit}"
+                    )
+                )
+            )
+        })
+}
+
+/// `t` moved by `seconds`, held within `EARLIEST..=LATEST` however far
+/// it goes.
+pub(crate) fn offset_clamped(
+    t: UnixTime,
+    seconds: UnixTime,
+) -> UnixTime {
+    clamped(t.saturating_add(seconds))
+}
+
+/// How long after `since` `until` is; negative when it is before, and
+/// never overflowing.
+pub(crate) fn elapsed(
+    since: UnixTime,
+    until: UnixTime,
+) -> UnixTime {
+    until.saturating_sub(since)
+}
+
+/// The `updated_timestamp` to give an item changed at `now`, which
+/// never goes back past `previous` even when the clock does.
+pub(crate) fn advanced(
+    previous: UnixTime,
+    now: UnixTime,
+) -> UnixTime {
+    now.max(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_app_error;
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bounds_should_be_the_first_and_last_second_of_four_digit_years(
+    ) {
+        let timestamp =
+            |y, m, d, h, min, s| {
+                NaiveDate::from_ymd_opt(
+                    y, m, d,
+                )
+                .unwrap()
+                .and_hms_opt(h, min, s)
+                .unwrap()
+                .and_utc()
+                .timestamp()
+            };
+
+        assert_eq!(
+            EARLIEST,
+            timestamp(1, 1, 1, 0, 0, 0)
+        );
+        assert_eq!(
+            LATEST,
+            timestamp(
+                9999, 12, 31, 23, 59,
+                59
+            )
+        );
+    }
+
+    #[test]
+    fn in_range_should_reject_times_past_the_bounds(
+    ) {
+        assert_eq!(
+            in_range(LATEST).unwrap(),
+            LATEST
+        );
+
+        let actual =
+            in_range(LATEST + 1);
+        let expected =
+            AppError::TimestampOutOfRange(
+                LATEST + 1,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn seconds_should_report_a_duration_that_overflows(
+    ) {
+        assert_eq!(
+            seconds(-2, 3_600, "hours")
+                .unwrap(),
+            -7_200
+        );
+
+        let actual = seconds(
+            i64::MAX,
+            3_600,
+            "hours",
+        );
+        let expected =
+            AppError::DurationOverflow(
+                format!(
+                    "{} hours",
+                    i64::MAX
+                ),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn offsets_and_spans_should_saturate_instead_of_overflowing(
+    ) {
+        assert_eq!(
+            offset_clamped(
+                i64::MAX - 1,
+                10
+            ),
+            LATEST
+        );
+        assert_eq!(
+            offset_clamped(0, i64::MIN),
+            EARLIEST
+        );
+        assert_eq!(
+            elapsed(i64::MIN, 0),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn advanced_should_never_go_back() {
+        assert_eq!(
+            advanced(100, 90),
+            100
+        );
+        assert_eq!(
+            advanced(100, 110),
+            110
+        );
+    }
+}
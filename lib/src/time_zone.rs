@@ -0,0 +1,309 @@
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    core::UnixTime,
+};
+use chrono::{
+    naive::{NaiveDate, NaiveDateTime},
+    FixedOffset, TimeZone as _,
+};
+use chrono_tz::Tz;
+use std::fmt::{
+    self, Display, Formatter,
+};
+
+const LOCAL_DATE_TIME_FORMAT: &str =
+    "%Y-%m-%d %H:%M %Z";
+
+const SECONDS_PER_MINUTE: i32 = 60;
+
+const MINUTES_PER_HOUR: i32 = 60;
+
+/// Either an IANA zone such as `Europe/Berlin` or a fixed offset such
+/// as `+05:30`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+)]
+pub enum TimeZone {
+    Named(Tz),
+
+    Fixed(FixedOffset),
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::Named(Tz::UTC)
+    }
+}
+
+impl TimeZone {
+    pub(crate) fn parse(
+        s: &str,
+    ) -> AppResult<Self> {
+        let s = s.trim();
+
+        s.parse::<Tz>()
+            .map(Self::Named)
+            .ok()
+            .or_else(|| {
+                parse_offset(s)
+                    .map(Self::Fixed)
+            })
+            .ok_or_else(|| {
+                report!(
+                    AppError::InvalidTimeZone(
+                        s.into()
+                    )
+                )
+            })
+    }
+
+    pub(crate) fn to_unix_time(
+        self,
+        local: &NaiveDateTime,
+    ) -> Option<UnixTime> {
+        match self {
+            Self::Named(tz) => tz
+                .from_local_datetime(
+                    local,
+                )
+                .earliest()
+                .map(|t| t.timestamp()),
+            Self::Fixed(offset) => {
+                offset
+                    .from_local_datetime(
+                        local,
+                    )
+                    .earliest()
+                    .map(|t| {
+                        t.timestamp()
+                    })
+            }
+        }
+    }
+
+    pub(crate) fn local_date(
+        self,
+        unix_time: UnixTime,
+    ) -> Option<NaiveDate> {
+        match self {
+            Self::Named(tz) => {
+                tz.timestamp_opt(
+                    unix_time, 0,
+                )
+                .single()
+                .map(|t| t.date_naive())
+            }
+            Self::Fixed(offset) => {
+                offset
+                    .timestamp_opt(
+                        unix_time, 0,
+                    )
+                    .single()
+                    .map(|t| {
+                        t.date_naive()
+                    })
+            }
+        }
+    }
+
+    pub(crate) fn format(
+        self,
+        unix_time: UnixTime,
+    ) -> Option<String> {
+        match self {
+            Self::Named(tz) => tz
+                .timestamp_opt(
+                    unix_time, 0,
+                )
+                .single()
+                .map(|t| {
+                    t.format(LOCAL_DATE_TIME_FORMAT)
+                        .to_string()
+                }),
+            Self::Fixed(offset) => {
+                offset
+                    .timestamp_opt(
+                        unix_time, 0,
+                    )
+                    .single()
+                    .map(|t| {
+                        t.format(LOCAL_DATE_TIME_FORMAT)
+                            .to_string()
+                    })
+            }
+        }
+    }
+}
+
+impl Display for TimeZone {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::Named(tz) => {
+                write!(
+                    f,
+                    "{}",
+                    tz.name()
+                )
+            }
+            Self::Fixed(offset) => {
+                write!(f, "{}", offset)
+            }
+        }
+    }
+}
+
+// Stored in its display form, which `parse` reads back.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TimeZone {
+    fn serialize<
+        S: serde::Serializer,
+    >(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de>
+    for TimeZone
+{
+    fn deserialize<
+        D: serde::Deserializer<'de>,
+    >(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let s = String::deserialize(
+            deserializer,
+        )?;
+
+        Self::parse(&s).map_err(|_| {
+            serde::de::Error::custom(
+                format!(
+                    "invalid time zone '{s}'"
+                ),
+            )
+        })
+    }
+}
+
+// Accepts `+HH`, `+HHMM` and `+HH:MM` (or the `-` equivalents).
+fn parse_offset(
+    s: &str,
+) -> Option<FixedOffset> {
+    let (sign, rest) =
+        if let Some(rest) =
+            s.strip_prefix('+')
+        {
+            (1, rest)
+        } else {
+            (-1, s.strip_prefix('-')?)
+        };
+
+    let digits =
+        rest.replacen(':', "", 1);
+
+    if !digits
+        .chars()
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let (hours, minutes) =
+        match digits.len() {
+            2 => (digits.as_str(), "0"),
+            4 => digits.split_at(2),
+            _ => return None,
+        };
+
+    let minutes =
+        hours.parse::<i32>().ok()?
+            * MINUTES_PER_HOUR
+            + minutes
+                .parse::<i32>()
+                .ok()?;
+
+    FixedOffset::east_opt(
+        sign * minutes
+            * SECONDS_PER_MINUTE,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_app_error;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("UTC", "UTC")]
+    #[test_case(
+        " Europe/Berlin ",
+        "Europe/Berlin"
+    )]
+    #[test_case("+05:30", "+05:30")]
+    #[test_case("-0800", "-08:00")]
+    #[test_case("+02", "+02:00")]
+    fn parse_should_accept_iana_names_and_fixed_offsets(
+        input: &str,
+        expected: &str,
+    ) {
+        let actual =
+            TimeZone::parse(input)
+                .unwrap()
+                .to_string();
+
+        assert_eq!(actual, expected)
+    }
+
+    #[test_case("Mars/Olympus")]
+    #[test_case("+5")]
+    #[test_case("+25:00")]
+    #[test_case("05:00")]
+    fn parse_should_fail_when_input_is_neither_an_iana_name_nor_an_offset(
+        input: &str,
+    ) {
+        let actual =
+            TimeZone::parse(input);
+
+        let expected =
+            AppError::InvalidTimeZone(
+                input.into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    #[test]
+    fn format_should_render_unix_time_in_local_time(
+    ) {
+        let tz = TimeZone::parse(
+            "America/New_York",
+        )
+        .unwrap();
+
+        // 2022-01-01 09:00:00 UTC
+        let actual = tz
+            .format(1_641_027_600)
+            .unwrap();
+
+        assert_eq!(
+            actual,
+            "2022-01-01 04:00 EST"
+        )
+    }
+}
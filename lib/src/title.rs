@@ -1,22 +1,44 @@
-use crate::app_error::{
-    bail, AppError, AppResult,
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    collation::TitleCollation,
 };
 use derive_more::From;
-use nutype::nutype;
+use once_cell::sync::OnceCell;
+use std::hash::{Hash, Hasher};
 
-#[nutype(sanitize(trim))]
+/// A title as given; surrounding whitespace is trimmed once it is
+/// validated, and `was_trimmed` tells whether that changed anything.
 #[derive(Clone, From)]
 pub struct Title(String);
 
 impl Title {
-    pub(crate) const MAX_LEN: usize =
-        30;
+    pub const MAX_LEN: usize = 30;
+
+    pub fn new(
+        title: impl Into<String>,
+    ) -> Self {
+        Self(title.into())
+    }
+
+    pub(crate) fn as_str(
+        &self,
+    ) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn was_trimmed(
+        &self,
+    ) -> bool {
+        self.0.trim() != self.0
+    }
 
     pub(crate) fn validated(
         &self,
     ) -> AppResult<String> {
         let title =
-            self.clone().into_inner();
+            self.0.trim().to_string();
 
         let size = title.len();
 
@@ -36,3 +58,53 @@ impl Title {
         }
     }
 }
+
+/// The forms of an item's title that case-insensitive matching and
+/// natural collation compare, each worked out the first time a search
+/// needs it and kept until the title changes. Items saved or sent
+/// elsewhere carry none, so they never go stale on the way back.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NormalizedTitle {
+    lowercase: OnceCell<String>,
+
+    natural: OnceCell<String>,
+}
+
+impl NormalizedTitle {
+    pub(crate) fn lowercase(
+        &self,
+        title: &str,
+    ) -> &str {
+        self.lowercase.get_or_init(
+            || title.to_lowercase(),
+        )
+    }
+
+    pub(crate) fn natural(
+        &self,
+        title: &str,
+    ) -> &str {
+        self.natural.get_or_init(|| {
+            TitleCollation::Natural
+                .key(title)
+        })
+    }
+}
+
+// A cache says nothing the title does not, so items compare and hash
+// the same whether or not theirs is filled.
+impl PartialEq for NormalizedTitle {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for NormalizedTitle {}
+
+impl Hash for NormalizedTitle {
+    fn hash<H: Hasher>(
+        &self,
+        _: &mut H,
+    ) {
+    }
+}
@@ -0,0 +1,183 @@
+use super::{
+    OptionalResultLimit, Status, Todo,
+    TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime,
+};
+use chrono::Utc;
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+use uuid::Uuid;
+
+// The feed is for catching up, not an audit trail, so the oldest events
+// are dropped past this many.
+const MAX_ACTIVITY: usize = 1000;
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum ActivityKind {
+    Created,
+
+    StatusChanged {
+        before: Status,
+        after: Status,
+    },
+
+    CommentAdded {
+        comment_id: Uuid,
+    },
+
+    /// `None` on either side means the item had no deadline.
+    DeadlineMoved {
+        before: Option<UnixTime>,
+        after: Option<UnixTime>,
+    },
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct Activity {
+    #[getset(get_copy = "pub")]
+    todo_id: Uuid,
+
+    #[getset(get_copy = "pub")]
+    timestamp: UnixTime,
+
+    /// Who made the change, when known.
+    #[getset(get = "pub")]
+    actor: Option<String>,
+
+    #[getset(get_copy = "pub")]
+    kind: ActivityKind,
+}
+
+/// Narrows the feed; an empty filter keeps every event.
+#[derive(
+    Clone, Debug, Default, TypedBuilder,
+)]
+#[builder(field_defaults(default))]
+pub struct ActivityFilter {
+    todo_id: Option<Uuid>,
+
+    /// Keeps events at or after this time.
+    since: Option<UnixTime>,
+}
+
+impl ActivityFilter {
+    fn matches(
+        &self,
+        activity: &Activity,
+    ) -> bool {
+        self.todo_id
+            .map(|id| {
+                id == activity.todo_id
+            })
+            .unwrap_or(true)
+            && self
+                .since
+                .map(|since| {
+                    activity.timestamp
+                        >= since
+                })
+                .unwrap_or(true)
+    }
+}
+
+impl TodoList {
+    /// Recorded events, newest first. Only the latest events are kept,
+    /// and changes taken in through `apply_remote` are not recorded.
+    pub fn activity(
+        &self,
+        limit: &OptionalResultLimit,
+        filter: &ActivityFilter,
+    ) -> AppResult<Vec<Activity>> {
+        let limit =
+            self.result_limit(limit)?;
+
+        Ok(self
+            .activity
+            .iter()
+            .rev()
+            .filter(|a| {
+                filter.matches(a)
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    /// Records what changed between `before`, `None` for a new item,
+    /// and `after`.
+    pub(super) fn record_changes(
+        &mut self,
+        before: Option<&Todo>,
+        after: &Todo,
+    ) {
+        let kinds = match before {
+            None => vec![
+                ActivityKind::Created,
+            ],
+            Some(before) => [
+                (before.status
+                    != after.status)
+                    .then_some(
+                    ActivityKind::StatusChanged {
+                        before: before.status,
+                        after: after.status,
+                    },
+                ),
+                (before.deadline
+                    != after.deadline)
+                    .then_some(
+                    ActivityKind::DeadlineMoved {
+                        before: before.deadline,
+                        after: after.deadline,
+                    },
+                ),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        };
+
+        for kind in kinds {
+            self.record_activity(
+                after.id,
+                self.actor.clone(),
+                kind,
+            );
+        }
+    }
+
+    pub(super) fn record_activity(
+        &mut self,
+        todo_id: Uuid,
+        actor: Option<String>,
+        kind: ActivityKind,
+    ) {
+        self.activity.push_back(
+            Activity {
+                todo_id,
+                timestamp:
+                    unix_time_now!(),
+                actor,
+                kind,
+            },
+        );
+
+        if self.activity.len()
+            > MAX_ACTIVITY
+        {
+            self.activity.pop_front();
+        }
+    }
+}
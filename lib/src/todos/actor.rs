@@ -0,0 +1,47 @@
+use super::TodoList;
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+
+const MAX_ACTOR_LEN: usize = 50;
+
+impl TodoList {
+    pub fn actor(
+        &self,
+    ) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Attributes every change from now on to `actor`, in
+    /// `last_modified_by` and the activity feed, until it is cleared.
+    /// Hosts set it around a single call.
+    pub fn set_actor(
+        &mut self,
+        actor: Option<&str>,
+    ) -> AppResult<()> {
+        let actor =
+            actor.map(str::trim);
+
+        if let Some(actor) = actor {
+            if actor.is_empty()
+                || actor.len()
+                    > MAX_ACTOR_LEN
+            {
+                bail!(
+                    AppError::InvalidActor(
+                        actor.into()
+                    )
+                )
+            }
+        }
+
+        self.actor =
+            actor.map(String::from);
+
+        Ok(())
+    }
+
+    pub fn clear_actor(&mut self) {
+        self.actor = None;
+    }
+}
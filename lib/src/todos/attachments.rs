@@ -0,0 +1,185 @@
+use super::{Todo, TodoList};
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use getset::Getters;
+use uuid::Uuid;
+
+const MAX_ATTACHMENTS_PER_ITEM: usize =
+    20;
+
+const MAX_URL_LEN: usize = 2048;
+
+/// A link to a document kept elsewhere; the list stores only the URL.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Getters,
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    )
+)]
+pub struct AttachmentRef {
+    #[getset(get = "pub")]
+    url: String,
+
+    #[getset(get = "pub")]
+    label: String,
+}
+
+impl TodoList {
+    /// Links `url` to the item; adding a URL it already has replaces
+    /// that attachment's label.
+    pub fn add_attachment(
+        &mut self,
+        id: Uuid,
+        url: &str,
+        label: &str,
+    ) -> AppResult<Todo> {
+        let attachment =
+            AttachmentRef {
+                url: validated_url(
+                    url,
+                )?,
+                label: label
+                    .trim()
+                    .into(),
+            };
+
+        self.change_item(id, |todo| {
+            if let Some(existing) = todo
+                .attachments
+                .iter_mut()
+                .find(|a| {
+                    a.url == attachment.url
+                })
+            {
+                let changed =
+                    *existing != attachment;
+                *existing = attachment;
+
+                return Ok(changed);
+            }
+
+            if todo.attachments.len()
+                >= MAX_ATTACHMENTS_PER_ITEM
+            {
+                bail!(
+                    AppError::TooManyAttachments {
+                        id,
+                        max: MAX_ATTACHMENTS_PER_ITEM,
+                    }
+                )
+            }
+
+            todo.attachments
+                .push(attachment);
+
+            Ok(true)
+        })
+    }
+
+    pub fn remove_attachment(
+        &mut self,
+        id: Uuid,
+        url: &str,
+    ) -> AppResult<Todo> {
+        let url = url.trim();
+
+        self.change_item(id, |todo| {
+            let before =
+                todo.attachments.len();
+
+            todo.attachments
+                .retain(|a| a.url != url);
+
+            if todo.attachments.len()
+                == before
+            {
+                bail!(
+                    AppError::AttachmentNotFound {
+                        id,
+                        url: url.into(),
+                    }
+                )
+            }
+
+            Ok(true)
+        })
+    }
+}
+
+// Only absolute http(s) URLs with a host; anything else is more likely
+// a typo than a document.
+fn validated_url(
+    url: &str,
+) -> AppResult<String> {
+    let url = url.trim();
+
+    let host = url
+        .split_once("://")
+        .filter(|(scheme, _)| {
+            scheme.eq_ignore_ascii_case(
+                "http",
+            ) || scheme
+                .eq_ignore_ascii_case(
+                    "https",
+                )
+        })
+        .and_then(|(_, rest)| {
+            rest.split(['/', '?', '#'])
+                .next()
+        })
+        .unwrap_or_default();
+
+    if host.is_empty()
+        || url.len() > MAX_URL_LEN
+        || url.chars().any(|c| {
+            c.is_whitespace()
+                || c.is_control()
+        })
+    {
+        bail!(
+            AppError::InvalidAttachmentUrl(
+                url.into()
+            )
+        )
+    }
+
+    Ok(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("https://example.com/spec.pdf" ; "https")]
+    #[test_case("HTTP://example.com" ; "upper-case scheme")]
+    fn validated_url_should_accept_web_links(
+        url: &str,
+    ) {
+        assert!(
+            validated_url(url).is_ok()
+        )
+    }
+
+    #[test_case("example.com/spec.pdf" ; "no scheme")]
+    #[test_case("ftp://example.com" ; "another scheme")]
+    #[test_case("https:///spec.pdf" ; "no host")]
+    #[test_case("https://exa mple.com" ; "a space")]
+    fn validated_url_should_reject_anything_else(
+        url: &str,
+    ) {
+        assert!(
+            validated_url(url).is_err()
+        )
+    }
+}
@@ -0,0 +1,341 @@
+use super::TodoList;
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use getset::{CopyGetters, Getters};
+
+const MIN_AUTH_TOKEN_LEN: usize = 16;
+
+// Who holds the shared secret, as named in permission errors.
+const OWNER: &str = "owner";
+
+/// What a principal may do; each role can do everything the ones
+/// before it can.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+)]
+pub enum Role {
+    /// Reads only.
+    Viewer,
+
+    /// Changes individual items.
+    Editor,
+
+    /// Bulk deletes and updates, imports and list settings.
+    Admin,
+}
+
+impl Role {
+    /// What a call applying several changes at once needs: one that
+    /// deletes any item, or rewrites every item a filter matches, can
+    /// undo many items' worth of work.
+    pub fn for_bulk_change(
+        destructive: bool,
+    ) -> Self {
+        if destructive {
+            Self::Admin
+        } else {
+            Self::Editor
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct Principal {
+    #[getset(get = "pub")]
+    name: String,
+
+    #[getset(get_copy = "pub")]
+    role: Role,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Registered {
+    principal: Principal,
+
+    token: String,
+}
+
+impl TodoList {
+    pub fn auth_required(
+        &self,
+    ) -> bool {
+        self.auth_token.is_some()
+    }
+
+    /// Sets the shared secret, which acts as an admin token, or drops it
+    /// with `None` along with every principal. Once a secret is set,
+    /// changing it takes an admin's `current` token.
+    pub fn configure_auth(
+        &mut self,
+        current: Option<&str>,
+        token: Option<&str>,
+    ) -> AppResult<()> {
+        self.authorize(
+            current,
+            Role::Admin,
+        )?;
+
+        if let Some(token) = token {
+            self.check_new_token(
+                token,
+            )?;
+        } else {
+            self.principals.clear();
+        }
+
+        self.auth_token =
+            token.map(String::from);
+
+        Ok(())
+    }
+
+    /// Lets `token` act as `name` with `role`. Needs a shared secret to
+    /// be set first, since without one nothing is checked.
+    pub fn register_principal(
+        &mut self,
+        name: &str,
+        token: &str,
+        role: Role,
+    ) -> AppResult<()> {
+        let name = name.trim();
+
+        if !self.auth_required() {
+            bail!(
+                AppError::AuthNotConfigured
+            )
+        }
+
+        if name.is_empty()
+            || name == OWNER
+            || self
+                .principal(name)
+                .is_some()
+        {
+            bail!(
+                AppError::DuplicatePrincipal(
+                    name.into()
+                )
+            )
+        }
+
+        self.check_new_token(token)?;
+
+        self.principals.push(
+            Registered {
+                principal: Principal {
+                    name: name.into(),
+                    role,
+                },
+                token: token.into(),
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn remove_principal(
+        &mut self,
+        name: &str,
+    ) -> AppResult<()> {
+        let before =
+            self.principals.len();
+
+        self.principals.retain(|r| {
+            r.principal.name
+                != name.trim()
+        });
+
+        if self.principals.len()
+            == before
+        {
+            bail!(
+                AppError::PrincipalNotFound(
+                    name.into()
+                )
+            )
+        }
+
+        Ok(())
+    }
+
+    pub fn list_principals(
+        &self,
+    ) -> Vec<Principal> {
+        let mut principals: Vec<_> =
+            self.principals
+                .iter()
+                .map(|r| {
+                    r.principal.clone()
+                })
+                .collect();
+
+        principals.sort_by(|a, b| {
+            a.name.cmp(&b.name)
+        });
+
+        principals
+    }
+
+    /// Passes when no secret is set, or when `token` is the secret or
+    /// belongs to a principal whose role is at least `required`.
+    pub fn authorize(
+        &self,
+        token: Option<&str>,
+        required: Role,
+    ) -> AppResult<()> {
+        let Some(secret) =
+            &self.auth_token
+        else {
+            return Ok(());
+        };
+
+        let Some(token) = token else {
+            bail!(
+                AppError::Unauthorized
+            )
+        };
+
+        if same_secret(secret, token) {
+            return Ok(());
+        }
+
+        let Some(principal) = self
+            .principals
+            .iter()
+            .find(|r| {
+                same_secret(
+                    &r.token, token,
+                )
+            })
+            .map(|r| &r.principal)
+        else {
+            bail!(
+                AppError::Unauthorized
+            )
+        };
+
+        if principal.role < required {
+            bail!(
+                AppError::PermissionDenied {
+                    principal: principal
+                        .name
+                        .clone(),
+                    role: principal.role,
+                    required,
+                }
+            )
+        }
+
+        Ok(())
+    }
+
+    fn principal(
+        &self,
+        name: &str,
+    ) -> Option<&Principal> {
+        self.principals
+            .iter()
+            .map(|r| &r.principal)
+            .find(|p| p.name == name)
+    }
+
+    // Tokens must be unique, or one could not tell principals apart.
+    fn check_new_token(
+        &self,
+        token: &str,
+    ) -> AppResult<()> {
+        if token.len()
+            < MIN_AUTH_TOKEN_LEN
+        {
+            bail!(
+                AppError::WeakAuthToken {
+                    min: MIN_AUTH_TOKEN_LEN
+                }
+            )
+        }
+
+        let taken = self
+            .auth_token
+            .iter()
+            .chain(
+                self.principals
+                    .iter()
+                    .map(|r| &r.token),
+            )
+            .any(|t| {
+                same_secret(t, token)
+            });
+
+        if taken {
+            bail!(AppError::AuthTokenInUse)
+        }
+
+        Ok(())
+    }
+}
+
+// Compares every byte, so the time taken does not reveal how much of a
+// guess was right.
+fn same_secret(
+    expected: &str,
+    actual: &str,
+) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0, |diff, (a, b)| {
+                diff | (a ^ b)
+            })
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("a-long-enough-secret", true ; "equal")]
+    #[test_case("a-long-enough-secreT", false ; "last byte differs")]
+    #[test_case("a-long-enough", false ; "prefix")]
+    fn same_secret_should_only_accept_an_exact_match(
+        actual: &str,
+        expected: bool,
+    ) {
+        assert_eq!(
+            same_secret(
+                "a-long-enough-secret",
+                actual
+            ),
+            expected
+        )
+    }
+
+    #[test_case(false, Role::Editor ; "not destructive")]
+    #[test_case(true, Role::Admin ; "destructive")]
+    fn for_bulk_change_should_need_an_admin_to_be_destructive(
+        destructive: bool,
+        expected: Role,
+    ) {
+        assert_eq!(
+            Role::for_bulk_change(
+                destructive
+            ),
+            expected
+        )
+    }
+}
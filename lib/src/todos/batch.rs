@@ -0,0 +1,201 @@
+use super::{
+    store::{UndoLog, UndoStore},
+    NewTodo, Todo, TodoList,
+    UpdateTodo,
+};
+use crate::app_error::{
+    AppError, AppResult,
+};
+use std::{any::Any, mem};
+use uuid::Uuid;
+
+pub enum BatchCommand {
+    Add(NewTodo),
+
+    Update(Uuid, UpdateTodo),
+
+    Delete(Uuid),
+}
+
+#[derive(
+    Clone, Debug, Eq, PartialEq,
+)]
+pub enum BatchResult {
+    Added(Todo),
+
+    Updated(Todo),
+
+    Deleted(Todo),
+}
+
+impl TodoList {
+    /// Runs the commands in order; the first failure undoes the ones
+    /// before it, so either every command is applied or none is.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(commands = commands.len()),
+            err
+        )
+    )]
+    pub fn apply_batch(
+        &mut self,
+        commands: Vec<BatchCommand>,
+    ) -> AppResult<Vec<BatchResult>>
+    {
+        self.ensure_writable()?;
+
+        let results =
+            self.atomically(|todos| {
+                commands
+                .into_iter()
+                .enumerate()
+                .map(|(index, command)| {
+                    todos.apply_command(command)
+                        .map_err(|report| {
+                            let reason = report
+                                .current_context()
+                                .to_string();
+
+                            report.change_context(
+                                AppError::BatchCommandFailed {
+                                    index,
+                                    reason,
+                                },
+                            )
+                        })
+                })
+                .collect::<AppResult<Vec<_>>>()
+            });
+
+        results
+    }
+
+    /// Runs `apply`, undoing what it did to the items if it fails,
+    /// along with the sequence numbers, short ids and item ids handed
+    /// out meanwhile. Only the items `apply` touches are copied. Nothing
+    /// is journaled while `apply` runs; afterwards the items it left
+    /// are, and a failed write undoes them too.
+    pub(super) fn atomically<T>(
+        &mut self,
+        apply: impl FnOnce(
+            &mut Self,
+        )
+            -> AppResult<T>,
+    ) -> AppResult<T> {
+        let snapshot = (
+            self.deleted.clone(),
+            self.activity.clone(),
+            self.change_seq,
+            self.change_seqs.clone(),
+            self.seq_floor,
+            self.seq_epoch,
+            self.last_short_id,
+            self.id_generator
+                .boxed_clone(),
+        );
+
+        self.items = Box::new(
+            UndoStore::new(mem::take(
+                &mut self.items,
+            )),
+        );
+
+        #[cfg(feature = "persistence")]
+        let persistence =
+            self.persistence.take();
+
+        let result = apply(self);
+
+        #[cfg(feature = "persistence")]
+        {
+            self.persistence =
+                persistence;
+        }
+
+        // Nothing swaps the store out while `apply` runs, so this is
+        // the `UndoStore` put in above.
+        let undo = match (self
+            .items
+            .as_mut()
+            as &mut dyn Any)
+            .downcast_mut::<UndoStore>()
+        {
+            Some(batch) => {
+                let (store, undo) =
+                    batch.take();
+                self.items = store;
+                undo
+            }
+            None => UndoLog::default(),
+        };
+
+        #[cfg(feature = "persistence")]
+        let (result, logged) =
+            match result {
+                Ok(value) => (
+                    self.journal_touched(
+                        &undo,
+                    )
+                    .map(|()| value),
+                    true,
+                ),
+                Err(e) => (Err(e), false),
+            };
+
+        if result.is_err() {
+            undo.roll_back(
+                self.items.as_mut(),
+            );
+
+            (
+                self.deleted,
+                self.activity,
+                self.change_seq,
+                self.change_seqs,
+                self.seq_floor,
+                self.seq_epoch,
+                self.last_short_id,
+                self.id_generator,
+            ) = snapshot;
+
+            // Entries written before the failed one would replay part
+            // of the batch, so the log is rewritten from what the list
+            // holds again. Should that fail as well, the first failure
+            // is still the one to report.
+            #[cfg(
+                feature = "persistence"
+            )]
+            if logged {
+                let _ =
+                    self.compact_log();
+            }
+        }
+
+        result
+    }
+
+    fn apply_command(
+        &mut self,
+        command: BatchCommand,
+    ) -> AppResult<BatchResult> {
+        match command {
+            BatchCommand::Add(item) => {
+                self.add(&item)
+                    .map(BatchResult::Added)
+            }
+            BatchCommand::Update(
+                id,
+                change,
+            ) => self
+                .update(id, &change)
+                .map(BatchResult::Updated),
+            BatchCommand::Delete(id) => {
+                self.delete_returning(id)
+                    .map(BatchResult::Deleted)
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Todo, TodoList};
+
+impl TodoList {
+    /// A hash of every live and archived item plus the default time
+    /// zone, as 16 hex digits. It ignores the order items are held in,
+    /// so two lists holding the same data agree whatever their history.
+    pub fn state_checksum(
+        &self,
+    ) -> String {
+        let checksum = sum(
+            "live",
+            self.items.values(),
+        )
+        .wrapping_add(sum(
+            "archived",
+            self.archived.values(),
+        ))
+        .wrapping_add(fnv1a(
+            format!(
+                "{:?}",
+                self.default_time_zone
+            )
+            .as_bytes(),
+        ));
+
+        format!("{checksum:016x}")
+    }
+}
+
+/// FNV-1a of `payload`'s bytes, as 16 hex digits, for a caller to
+/// check that a payload arrived whole.
+pub(super) fn payload_checksum(
+    payload: &str,
+) -> String {
+    format!(
+        "{:016x}",
+        fnv1a(payload.as_bytes())
+    )
+}
+
+// Adding the per-item hashes makes the total independent of order.
+fn sum<'a>(
+    tag: &str,
+    todos: impl Iterator<Item = &'a Todo>,
+) -> u64 {
+    todos.fold(0, |sum, t| {
+        // Debug output covers every field, and a todo holds only
+        // ordered collections.
+        sum.wrapping_add(fnv1a(
+            format!("{tag}{t:?}")
+                .as_bytes(),
+        ))
+    })
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(
+        0xcbf2_9ce4_8422_2325,
+        |hash, b| {
+            (hash ^ u64::from(*b))
+                .wrapping_mul(
+                    0x0100_0000_01b3,
+                )
+        },
+    )
+}
@@ -0,0 +1,150 @@
+use super::{
+    ActivityKind, Todo, TodoList,
+};
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    core::UnixTime,
+};
+use chrono::Utc;
+use getset::{CopyGetters, Getters};
+use uuid::Uuid;
+
+const MAX_COMMENT_LEN: usize = 1000;
+
+const MAX_AUTHOR_LEN: usize = 50;
+
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Getters,
+    CopyGetters,
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
+pub struct Comment {
+    #[getset(get_copy = "pub")]
+    id: Uuid,
+
+    #[getset(get = "pub")]
+    author: String,
+
+    #[getset(get = "pub")]
+    text: String,
+
+    #[getset(get_copy = "pub")]
+    created_timestamp: UnixTime,
+}
+
+impl TodoList {
+    pub fn add_comment(
+        &mut self,
+        id: Uuid,
+        author: &str,
+        text: &str,
+    ) -> AppResult<Comment> {
+        let author = author.trim();
+        let text = text.trim();
+
+        if author.is_empty()
+            || author.len()
+                > MAX_AUTHOR_LEN
+        {
+            bail!(
+                AppError::InvalidCommentAuthor(
+                    author.into()
+                )
+            )
+        }
+
+        if text.is_empty() {
+            bail!(
+                AppError::EmptyComment
+            )
+        }
+
+        if text.len() > MAX_COMMENT_LEN
+        {
+            bail!(
+                AppError::TooLongComment {
+                    len: text.len(),
+                    max: MAX_COMMENT_LEN,
+                }
+            )
+        }
+
+        let comment = Comment {
+            id: self
+                .id_generator
+                .next_id(),
+            author: author.into(),
+            text: text.into(),
+            created_timestamp: unix_time_now!(),
+        };
+
+        self.change_item(id, |todo| {
+            todo.comments
+                .push(comment.clone());
+
+            Ok(true)
+        })?;
+
+        self.record_activity(
+            id,
+            Some(author.into()),
+            ActivityKind::CommentAdded {
+                comment_id: comment.id,
+            },
+        );
+
+        Ok(comment)
+    }
+
+    /// Oldest first.
+    pub fn list_comments(
+        &self,
+        id: Uuid,
+    ) -> AppResult<Vec<Comment>> {
+        self.get(id)
+            .map(|todo| todo.comments)
+    }
+
+    pub fn delete_comment(
+        &mut self,
+        id: Uuid,
+        comment_id: Uuid,
+    ) -> AppResult<()> {
+        self.change_item(id, |todo| {
+            let before =
+                todo.comments.len();
+
+            todo.comments.retain(|c| {
+                c.id != comment_id
+            });
+
+            if todo.comments.len()
+                == before
+            {
+                bail!(
+                    AppError::CommentNotFound {
+                        id,
+                        comment_id,
+                    }
+                )
+            }
+
+            Ok(true)
+        })
+        .map(|_: Todo| ())
+    }
+}
@@ -0,0 +1,177 @@
+use super::{
+    OptionalDeadlineInput, TodoList,
+};
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    core::{UnixTime, SECONDS_PER_DAY},
+};
+use chrono::{
+    DateTime, Months, NaiveDateTime,
+    Utc,
+};
+use getset::CopyGetters;
+use typed_builder::TypedBuilder;
+
+const BOUND_FORMAT: &str = "%Y-%m-%d";
+
+/// How far from now a deadline may fall when bounds are enforced.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    CopyGetters,
+    TypedBuilder,
+)]
+pub struct DeadlineBounds {
+    #[builder(default = 1)]
+    #[getset(get_copy = "pub")]
+    max_days_past: u32,
+
+    #[builder(default = 10)]
+    #[getset(get_copy = "pub")]
+    max_years_ahead: u32,
+}
+
+impl Default for DeadlineBounds {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl DeadlineBounds {
+    fn range_at(
+        &self,
+        now: UnixTime,
+    ) -> Option<(
+        NaiveDateTime,
+        NaiveDateTime,
+    )> {
+        let now =
+            DateTime::from_timestamp(
+                now, 0,
+            )?
+            .naive_utc();
+
+        let earliest = now
+            .checked_sub_signed(
+            chrono::Duration::seconds(
+                UnixTime::from(
+                    self.max_days_past,
+                ) * SECONDS_PER_DAY,
+            ),
+        )?;
+
+        let latest = now
+            .checked_add_months(
+            Months::new(
+                self.max_years_ahead
+                    .saturating_mul(12),
+            ),
+        )?;
+
+        Some((earliest, latest))
+    }
+}
+
+impl TodoList {
+    pub fn deadline_bounds(
+        &self,
+    ) -> Option<DeadlineBounds> {
+        self.deadline_bounds
+    }
+
+    /// `None` turns the check off.
+    pub fn set_deadline_bounds(
+        &mut self,
+        bounds: Option<DeadlineBounds>,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.deadline_bounds = bounds;
+
+        Ok(())
+    }
+
+    pub(crate) fn check_deadline_bounds(
+        &self,
+        input: &OptionalDeadlineInput,
+        deadline: Option<UnixTime>,
+    ) -> AppResult<()> {
+        let (
+            Some(bounds),
+            Some(deadline),
+        ) = (
+            self.deadline_bounds,
+            deadline,
+        )
+        else {
+            return Ok(());
+        };
+
+        if input.ignores_bounds() {
+            return Ok(());
+        }
+
+        let Some((earliest, latest)) =
+            bounds.range_at(
+                unix_time_now!(),
+            )
+        else {
+            return Ok(());
+        };
+
+        if deadline
+            < earliest
+                .and_utc()
+                .timestamp()
+            || deadline
+                > latest
+                    .and_utc()
+                    .timestamp()
+        {
+            bail!(
+                AppError::DeadlineOutOfRange {
+                    input: input
+                        .input()
+                        .unwrap_or_default()
+                        .into(),
+                    earliest: earliest
+                        .format(BOUND_FORMAT)
+                        .to_string(),
+                    latest: latest
+                        .format(BOUND_FORMAT)
+                        .to_string(),
+                }
+            )
+        }
+
+        Ok(())
+    }
+}
+
+/// Refuses a start date later than the deadline.
+pub(crate) fn check_start_date(
+    start_date: Option<UnixTime>,
+    deadline: Option<UnixTime>,
+) -> AppResult<()> {
+    if let (
+        Some(start_date),
+        Some(deadline),
+    ) = (start_date, deadline)
+    {
+        if start_date > deadline {
+            bail!(
+                AppError::StartDateAfterDeadline {
+                    start_date,
+                    deadline,
+                }
+            )
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use super::{Priority, TodoList};
+use crate::{
+    app_error::AppResult,
+    core::{UnixTime, SECONDS_PER_DAY},
+    time,
+};
+use getset::CopyGetters;
+use typed_builder::TypedBuilder;
+
+/// What `add` fills in when a new item leaves a field out.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    CopyGetters,
+    TypedBuilder,
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
+pub struct CreationDefaults {
+    #[builder(default = Priority::Medium)]
+    #[getset(get_copy = "pub")]
+    priority: Priority,
+
+    /// Items without a deadline are due this many days after they are
+    /// added; `None` leaves them without one.
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    deadline_after_days: Option<u32>,
+}
+
+impl Default for CreationDefaults {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl CreationDefaults {
+    pub(super) fn deadline_from(
+        &self,
+        now: UnixTime,
+    ) -> Option<UnixTime> {
+        self.deadline_after_days.map(
+            |days| {
+                time::offset_clamped(
+                    now,
+                    UnixTime::from(
+                        days,
+                    ) * SECONDS_PER_DAY,
+                )
+            },
+        )
+    }
+}
+
+impl TodoList {
+    pub fn creation_defaults(
+        &self,
+    ) -> CreationDefaults {
+        self.creation_defaults
+    }
+
+    pub fn set_creation_defaults(
+        &mut self,
+        defaults: CreationDefaults,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        journal!(
+            self,
+            LogEntry::SetCreationDefaults(
+                defaults
+            )
+        );
+
+        self.creation_defaults =
+            defaults;
+
+        Ok(())
+    }
+}
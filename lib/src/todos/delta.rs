@@ -0,0 +1,849 @@
+use super::{
+    deadline_bounds, Color, Icon,
+    OptionalDeadlineInput, Status,
+    Title, Todo, TodoList,
+};
+use crate::app_error::{
+    bail, report, AppError, AppResult,
+};
+use std::collections::{
+    HashMap, HashSet,
+};
+use uuid::Uuid;
+
+/// What `export_delta` sends: the items changed after a sequence number
+/// and the ids deleted since, each in id order.
+#[derive(
+    serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+struct Delta {
+    /// The sender's epoch and latest sequence number, which make the
+    /// next `since` token.
+    epoch: Uuid,
+
+    seq: u64,
+
+    /// The sender could not tell what changed, so `items` is every live
+    /// item and the receiver drops the rest.
+    full: bool,
+
+    items: Vec<Todo>,
+
+    deleted: Vec<Uuid>,
+}
+
+// A `since` token is the sender's epoch and a sequence number.
+fn since_token(
+    epoch: Uuid,
+    seq: u64,
+) -> String {
+    format!("{}:{seq}", epoch.simple())
+}
+
+fn parse_since(
+    since: &str,
+) -> Option<(Uuid, u64)> {
+    let (epoch, seq) =
+        since.trim().split_once(':')?;
+
+    Some((
+        Uuid::parse_str(epoch).ok()?,
+        seq.parse().ok()?,
+    ))
+}
+
+impl TodoList {
+    /// The changes after the `since` token that `apply_delta` returned,
+    /// as JSON for another list's `apply_delta`; an empty token asks for
+    /// every item. Sequence numbers live in memory and each run of them
+    /// has its own epoch, so a delta after a restore, or asked for with
+    /// a token from before a restart, is a full one.
+    pub fn export_delta(
+        &self,
+        since: &str,
+    ) -> AppResult<Vec<u8>> {
+        let since_seq =
+            parse_since(since)
+                .filter(|(epoch, _)| {
+                    *epoch
+                        == self
+                            .seq_epoch
+                            .0
+                })
+                .map(|(_, seq)| seq)
+                .unwrap_or(0);
+
+        let full = since_seq
+            <= self.seq_floor
+            || since_seq
+                > self.change_seq;
+
+        let changed = |id: &Uuid| {
+            full || self
+                .change_seqs
+                .get(id)
+                .map(|seq| {
+                    *seq > since_seq
+                })
+                .unwrap_or(false)
+        };
+
+        let mut items: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| changed(&t.id))
+            .cloned()
+            .collect();
+        items.sort_by_key(|t| t.id);
+
+        let mut deleted: Vec<_> =
+            if full {
+                vec![]
+            } else {
+                self.change_seqs
+                    .keys()
+                    .filter(|id| {
+                        changed(id)
+                            && !self
+                                .items
+                                .contains_key(
+                                    id,
+                                )
+                    })
+                    .copied()
+                    .collect()
+            };
+        deleted.sort();
+
+        serde_json::to_vec(&Delta {
+            epoch: self.seq_epoch.0,
+            seq: self.change_seq,
+            full,
+            items,
+            deleted,
+        })
+        .map_err(|_| {
+            report!(AppError::Internal(
+                "delta-json"
+            ))
+        })
+    }
+
+    /// Brings the items in line with another list's `export_delta`,
+    /// keeping its ids and timestamps, and its short ids unless one here
+    /// already has them. Items are held to what `add` and `update`
+    /// enforce, and one that fails leaves everything as it was. Returns
+    /// the token to ask the sender for the next delta with.
+    pub fn apply_delta(
+        &mut self,
+        bytes: &[u8],
+    ) -> AppResult<String> {
+        self.ensure_writable()?;
+
+        let delta: Delta =
+            serde_json::from_slice(bytes)
+                .map_err(|e| {
+                    report!(
+                        AppError::InvalidDelta(
+                            e.to_string()
+                        )
+                    )
+                })?;
+
+        for todo in &delta.items {
+            self.check_incoming(todo)?;
+        }
+
+        let gone = if delta.full {
+            let kept: HashSet<_> =
+                delta
+                    .items
+                    .iter()
+                    .map(|t| t.id)
+                    .collect();
+
+            self.items
+                .keys()
+                .filter(|id| {
+                    !kept.contains(id)
+                })
+                .copied()
+                .collect()
+        } else {
+            delta.deleted
+        };
+
+        self.remove_all(&gone, false)?;
+
+        // Incoming numbers are noted first, so one handed out in place
+        // of a taken number is not also on an item still to come.
+        for todo in &delta.items {
+            self.note_short_id(
+                todo.short_id,
+            );
+        }
+
+        let mut short_ids: HashMap<
+            u64,
+            Uuid,
+        > = self
+            .items
+            .values()
+            .map(|t| (t.short_id, t.id))
+            .collect();
+
+        for mut todo in delta.items {
+            if let Some(held) =
+                self.items.get(&todo.id)
+            {
+                short_ids.remove(
+                    &held.short_id,
+                );
+            }
+
+            if todo.short_id != 0
+                && short_ids
+                    .contains_key(
+                        &todo.short_id,
+                    )
+            {
+                todo.short_id = self
+                    .next_short_id()?;
+            }
+            short_ids.insert(
+                todo.short_id,
+                todo.id,
+            );
+
+            journal!(
+                self,
+                LogEntry::Put(
+                    todo.clone()
+                )
+            );
+
+            self.deleted
+                .remove(&todo.id);
+            self.bump_seq(todo.id);
+            self.items.insert(todo);
+        }
+
+        Ok(since_token(
+            delta.epoch,
+            delta.seq,
+        ))
+    }
+
+    // A valid title, color and icon, a start date no later than the
+    // deadline and no completion time unless done; items done before
+    // completion times were kept have none. A deadline or
+    // start date that differs from the one held here must also fall
+    // within the list's bounds; an unchanged one may have been set
+    // before they moved.
+    fn check_incoming(
+        &self,
+        todo: &Todo,
+    ) -> AppResult<()> {
+        Title::new(todo.title.as_str())
+            .validated()?;
+
+        if let Some(color) = &todo.color
+        {
+            Color::new(color.as_str())
+                .validated()?;
+        }
+
+        if let Some(icon) = &todo.icon {
+            Icon::new(icon.as_str())
+                .validated()?;
+        }
+
+        deadline_bounds::check_start_date(
+            todo.start_date,
+            todo.deadline,
+        )?;
+
+        if todo.status != Status::Done
+            && todo
+                .completed_timestamp
+                .is_some()
+        {
+            bail!(AppError::InvalidDelta(
+                format!(
+                    "item {} has a completion time but is {:?}",
+                    todo.id, todo.status
+                )
+            ))
+        }
+
+        let held =
+            self.items.get(&todo.id);
+
+        for (date, held_date) in [
+            (
+                todo.deadline,
+                held.and_then(|t| {
+                    t.deadline
+                }),
+            ),
+            (
+                todo.start_date,
+                held.and_then(|t| {
+                    t.start_date
+                }),
+            ),
+        ] {
+            if date != held_date {
+                self.check_deadline_bounds(
+                    &OptionalDeadlineInput::from(
+                        date.map(|d| {
+                            d.to_string()
+                        }),
+                    ),
+                    date,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        sync::MAX_DELETED_SEQS,
+        BatchCommand, DeadlineBounds,
+        NewTodo, Priority, ReplicaList,
+        UpdateTodo,
+    };
+    use pretty_assertions::assert_eq;
+
+    // `primary`'s full delta with `edit` made to its first item.
+    fn tampered(
+        primary: &TodoList,
+        edit: impl FnOnce(
+            &mut serde_json::Value,
+        ),
+    ) -> Vec<u8> {
+        let mut sent: serde_json::Value =
+            serde_json::from_slice(
+                &primary.export_delta("").unwrap(),
+            )
+            .unwrap();
+
+        edit(&mut sent["items"][0]);
+
+        serde_json::to_vec(&sent)
+            .unwrap()
+    }
+
+    fn add(
+        todos: &mut TodoList,
+        title: &str,
+    ) -> Todo {
+        todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap()
+    }
+
+    fn all(
+        todos: &TodoList,
+    ) -> Vec<Todo> {
+        todos.snapshot().items().clone()
+    }
+
+    #[test]
+    fn apply_delta_should_catch_a_replica_up_in_steps(
+    ) {
+        let mut primary =
+            TodoList::new();
+        let a = add(&mut primary, "a");
+        let b = add(&mut primary, "b");
+
+        let mut replica =
+            TodoList::new();
+        let since = replica
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&primary)
+        );
+
+        primary
+            .update(
+                a.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+        primary.delete(b.id).unwrap();
+        add(&mut primary, "c");
+
+        let delta = primary
+            .export_delta(&since)
+            .unwrap();
+        let sent: serde_json::Value =
+            serde_json::from_slice(
+                &delta,
+            )
+            .unwrap();
+
+        assert_eq!(
+            sent["items"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            sent["deleted"],
+            serde_json::json!([b.id])
+        );
+
+        replica
+            .apply_delta(&delta)
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&primary)
+        );
+    }
+
+    #[test]
+    fn export_delta_should_send_everything_for_an_unknown_seq(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(&mut primary, "a");
+
+        let mut replica =
+            TodoList::new();
+        add(&mut replica, "stray");
+
+        replica
+            .apply_delta(
+                &primary.export_delta(
+                    &since_token(
+                        primary
+                            .seq_epoch
+                            .0,
+                        99,
+                    ),
+                ).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&primary)
+        );
+    }
+
+    #[test]
+    fn export_delta_should_send_everything_for_a_token_from_before_a_restart(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(&mut primary, "a");
+        add(&mut primary, "b");
+
+        let mut replica =
+            TodoList::new();
+        let since = replica
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let mut restarted =
+            TodoList::new();
+        restarted
+            .restore(primary.snapshot())
+            .unwrap();
+        for title in ["c", "d", "e"] {
+            add(&mut restarted, title);
+        }
+
+        let delta = restarted
+            .export_delta(&since)
+            .unwrap();
+        let sent: serde_json::Value =
+            serde_json::from_slice(
+                &delta,
+            )
+            .unwrap();
+
+        assert_eq!(
+            sent["full"],
+            serde_json::json!(true)
+        );
+
+        replica
+            .apply_delta(&delta)
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&restarted)
+        );
+    }
+
+    #[test]
+    fn export_delta_should_report_nothing_after_a_failed_batch(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(&mut primary, "a");
+
+        let since = TodoList::new()
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert!(primary
+            .apply_batch(vec![
+                BatchCommand::Add(
+                    NewTodo::builder()
+                        .title(Title::new(
+                            "b",
+                        ))
+                        .priority(
+                            Priority::Low,
+                        )
+                        .build(),
+                ),
+                BatchCommand::Delete(
+                    Uuid::nil(),
+                ),
+            ])
+            .is_err());
+
+        let sent: serde_json::Value =
+            serde_json::from_slice(
+                &primary
+                    .export_delta(
+                        &since,
+                    )
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            sent["full"],
+            serde_json::json!(false)
+        );
+        assert_eq!(
+            sent["items"],
+            serde_json::json!([])
+        );
+        assert_eq!(
+            sent["deleted"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn replica_list_should_follow_deltas(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(&mut primary, "a");
+
+        let mut replica =
+            ReplicaList::new();
+        replica
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&primary)
+        );
+    }
+
+    #[test]
+    fn apply_delta_should_reject_bytes_that_are_not_a_delta(
+    ) {
+        let result = TodoList::new()
+            .apply_delta(b"[]");
+
+        assert!(matches!(
+            result
+                .unwrap_err()
+                .current_context(),
+            AppError::InvalidDelta(_)
+        ));
+    }
+
+    #[test]
+    fn apply_delta_should_refuse_an_item_add_would_refuse(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(&mut primary, "a");
+
+        let mut replica =
+            TodoList::new();
+        replica
+            .set_deadline_bounds(Some(
+                DeadlineBounds::default(
+                ),
+            ))
+            .unwrap();
+
+        let cases = [
+            tampered(&primary, |t| {
+                t["title"] = serde_json::json!(
+                    " "
+                );
+            }),
+            tampered(&primary, |t| {
+                t["color"] = serde_json::json!(
+                    "not-a-color"
+                );
+            }),
+            tampered(&primary, |t| {
+                t["deadline"] = serde_json::json!(
+                    1
+                );
+            }),
+            tampered(&primary, |t| {
+                t["completed-timestamp"] =
+                    serde_json::json!(1);
+            }),
+        ];
+
+        for delta in cases {
+            assert!(replica
+                .apply_delta(&delta)
+                .is_err());
+        }
+
+        assert_eq!(
+            all(&replica),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn apply_delta_should_renumber_an_item_whose_short_id_is_taken(
+    ) {
+        let mut primary =
+            TodoList::new();
+        let a = add(&mut primary, "a");
+
+        let mut replica =
+            TodoList::new();
+        let since = replica
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let stray =
+            add(&mut replica, "stray");
+        let b = add(&mut primary, "b");
+
+        assert_eq!(
+            (
+                a.short_id,
+                stray.short_id,
+                b.short_id
+            ),
+            (1, 2, 2)
+        );
+
+        replica
+            .apply_delta(
+                &primary
+                    .export_delta(
+                        &since,
+                    )
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            replica
+                .get(stray.id)
+                .unwrap()
+                .short_id,
+            2
+        );
+        assert_eq!(
+            replica
+                .get(b.id)
+                .unwrap()
+                .short_id,
+            3
+        );
+        assert_eq!(
+            replica
+                .get(a.id)
+                .unwrap()
+                .short_id,
+            1
+        );
+    }
+
+    #[test]
+    fn bump_seq_should_forget_the_older_deleted_ids_past_the_limit(
+    ) {
+        let mut todos = TodoList::new();
+
+        for seq in
+            1..=MAX_DELETED_SEQS as u64
+        {
+            todos.change_seqs.insert(
+                Uuid::new_v4(),
+                seq,
+            );
+        }
+        todos.change_seq =
+            todos.change_seqs.len()
+                as u64;
+
+        todos.bump_seq(Uuid::new_v4());
+
+        assert_eq!(
+            todos.change_seqs.len(),
+            MAX_DELETED_SEQS / 2
+        );
+        assert_eq!(
+            todos.seq_floor,
+            MAX_DELETED_SEQS as u64 / 2
+                + 1
+        );
+
+        let sent: serde_json::Value =
+            serde_json::from_slice(
+                &todos.export_delta(
+                    &since_token(
+                        todos
+                            .seq_epoch
+                            .0,
+                        1,
+                    ),
+                ).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            sent["full"],
+            serde_json::json!(true)
+        );
+    }
+
+    #[test]
+    fn restore_should_forget_the_sequence_numbers_before_it(
+    ) {
+        let mut todos = TodoList::new();
+        let a = add(&mut todos, "a");
+        todos.delete(a.id).unwrap();
+        add(&mut todos, "b");
+
+        let snapshot = todos.snapshot();
+        todos
+            .restore(snapshot)
+            .unwrap();
+
+        assert!(todos
+            .change_seqs
+            .is_empty());
+    }
+
+    // An item with comments, an attachment, an external ref and a
+    // watcher, so every field has something to convert.
+    fn add_full(todos: &mut TodoList) {
+        let id = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Café ☕",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .id;
+
+        todos
+            .add_comment(
+                id, "ana", "soon",
+            )
+            .unwrap();
+        todos
+            .add_attachment(
+                id,
+                "https://example.com/a",
+                "spec",
+            )
+            .unwrap();
+        todos
+            .set_external_ref(
+                id,
+                "jira",
+                Some("TODO-1"),
+            )
+            .unwrap();
+        todos.watch(id, "bo").unwrap();
+    }
+
+    #[test]
+    fn export_delta_should_convert_an_item_with_every_field_set(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add_full(&mut primary);
+
+        let mut replica =
+            TodoList::new();
+        replica
+            .apply_delta(
+                &primary
+                    .export_delta("")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            all(&replica),
+            all(&primary)
+        );
+    }
+}
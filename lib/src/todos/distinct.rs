@@ -0,0 +1,57 @@
+use super::{
+    Priority, Status, TodoList,
+};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy)]
+pub enum DistinctField {
+    Status,
+    Priority,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+)]
+pub enum FieldValue {
+    Status(Status),
+    Priority(Priority),
+}
+
+impl TodoList {
+    /// Values of `field` currently in use, each with the number of items
+    /// carrying it.
+    pub fn distinct(
+        &self,
+        field: DistinctField,
+    ) -> Vec<(FieldValue, usize)> {
+        let mut counts =
+            BTreeMap::new();
+
+        for t in self.items.values() {
+            let value = match field {
+                DistinctField::Status => {
+                    FieldValue::Status(
+                        t.status,
+                    )
+                }
+                DistinctField::Priority => {
+                    FieldValue::Priority(
+                        t.priority,
+                    )
+                }
+            };
+
+            *counts
+                .entry(value)
+                .or_insert(0) += 1;
+        }
+
+        counts.into_iter().collect()
+    }
+}
@@ -0,0 +1,178 @@
+use super::{
+    Priority, Status, Todo, TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::{
+        UnixTime, SECONDS_PER_DAY,
+        SECONDS_PER_HOUR,
+    },
+    time,
+};
+use chrono::Utc;
+use getset::CopyGetters;
+use typed_builder::TypedBuilder;
+
+/// Both rules are off unless configured.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    CopyGetters,
+    TypedBuilder,
+)]
+#[builder(field_defaults(default))]
+pub struct EscalationPolicy {
+    /// Open items due within this many hours (or overdue) become High.
+    #[getset(get_copy = "pub")]
+    deadline_within_hours: Option<u32>,
+
+    /// Backlog items untouched for this many days go up one level.
+    #[getset(get_copy = "pub")]
+    backlog_after_days: Option<u32>,
+}
+
+impl Priority {
+    fn raised(self) -> Self {
+        match self {
+            Self::Low => Self::Medium,
+            Self::Medium
+            | Self::High => Self::High,
+        }
+    }
+}
+
+impl Todo {
+    fn escalated(
+        &self,
+        policy: &EscalationPolicy,
+        now: UnixTime,
+    ) -> Option<Priority> {
+        if self.status == Status::Done {
+            return None;
+        }
+
+        let deadline_is_near = policy
+            .deadline_within_hours
+            .zip(self.deadline)
+            .map(|(hours, deadline)| {
+                time::elapsed(
+                    now, deadline,
+                ) <= UnixTime::from(
+                    hours,
+                )
+                    * SECONDS_PER_HOUR
+            })
+            .unwrap_or(false);
+
+        let is_stale_in_backlog = policy
+            .backlog_after_days
+            .map(|days| {
+                self.status
+                    == Status::Backlog
+                    && time::elapsed(
+                        self.updated_timestamp,
+                        now,
+                    ) >= UnixTime::from(days)
+                            * SECONDS_PER_DAY
+            })
+            .unwrap_or(false);
+
+        let target = if deadline_is_near
+        {
+            Priority::High
+        } else if is_stale_in_backlog {
+            self.priority.raised()
+        } else {
+            self.priority
+        };
+
+        (target != self.priority)
+            .then_some(target)
+    }
+}
+
+impl TodoList {
+    pub fn escalation_policy(
+        &self,
+    ) -> EscalationPolicy {
+        self.escalation_policy
+    }
+
+    pub fn set_escalation_policy(
+        &mut self,
+        policy: EscalationPolicy,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.escalation_policy = policy;
+
+        Ok(())
+    }
+
+    pub fn escalate(
+        &mut self,
+    ) -> AppResult<Vec<Todo>> {
+        self.apply_escalation(
+            unix_time_now!(),
+        )
+    }
+
+    /// Raises priorities according to the configured policy and returns
+    /// the items that changed.
+    pub fn apply_escalation(
+        &mut self,
+        now: UnixTime,
+    ) -> AppResult<Vec<Todo>> {
+        self.ensure_writable()?;
+
+        let policy =
+            self.escalation_policy;
+
+        let mut changed: Vec<_> =
+            self.items
+                .values()
+                .filter_map(|todo| {
+                    todo.escalated(
+                        &policy, now,
+                    )
+                    .map(|priority| {
+                        Todo {
+                    priority,
+                    updated_timestamp:
+                        time::advanced(
+                            todo.updated_timestamp,
+                            now,
+                        ),
+                    last_modified_by: self
+                        .actor
+                        .clone(),
+                    ..todo.clone()
+                }
+                    })
+                })
+                .collect();
+
+        changed.sort_by(|a, b| {
+            a.title.cmp(&b.title)
+        });
+
+        for todo in &changed {
+            journal!(
+                self,
+                LogEntry::Put(
+                    todo.clone()
+                )
+            );
+
+            self.items
+                .insert(todo.clone());
+            self.bump_seq(todo.id);
+        }
+
+        Ok(changed)
+    }
+}
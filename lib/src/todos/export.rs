@@ -0,0 +1,20 @@
+use super::TodoList;
+use crate::importers::todotxt;
+
+impl TodoList {
+    /// The live items as todo.txt, one per line, oldest first and then
+    /// by id, so the same items always export the same way. Statuses
+    /// other than done, and start dates, have no place in the format
+    /// and are left out.
+    pub fn export_todo_txt(
+        &self,
+    ) -> String {
+        self.snapshot()
+            .items()
+            .iter()
+            .map(|t| {
+                todotxt::line(t) + "\n"
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,116 @@
+use super::{Todo, TodoList};
+use crate::app_error::{
+    bail, report, AppError, AppResult,
+};
+use uuid::Uuid;
+
+impl TodoList {
+    /// Records the item's id in another `system`, such as `jira`, or
+    /// forgets it with `None`. Within one system an external id may
+    /// belong to only one item.
+    pub fn set_external_ref(
+        &mut self,
+        id: Uuid,
+        system: &str,
+        external_id: Option<&str>,
+    ) -> AppResult<Todo> {
+        let system = system.trim();
+        let external_id =
+            external_id.map(str::trim);
+
+        if system.is_empty()
+            || external_id == Some("")
+        {
+            bail!(
+                AppError::EmptyExternalRef
+            )
+        }
+
+        if let Some(external_id) =
+            external_id
+        {
+            if let Some(holder) = self
+                .items
+                .values()
+                .find(|t| {
+                    t.id != id
+                        && t.has_external_ref(
+                            system,
+                            external_id,
+                        )
+                })
+            {
+                bail!(
+                    AppError::ExternalRefInUse {
+                        system: system.into(),
+                        external_id: external_id.into(),
+                        holder: holder.id,
+                    }
+                )
+            }
+        }
+
+        self.change_item(id, |todo| {
+            let previous =
+                match external_id {
+                    Some(
+                        external_id,
+                    ) => todo
+                        .external_refs
+                        .insert(
+                            system
+                                .into(),
+                            external_id
+                                .into(),
+                        ),
+                    None => todo
+                        .external_refs
+                        .remove(system),
+                };
+
+            Ok(previous.as_deref()
+                != external_id)
+        })
+    }
+
+    pub fn find_by_external_ref(
+        &self,
+        system: &str,
+        external_id: &str,
+    ) -> AppResult<Todo> {
+        let system = system.trim();
+        let external_id =
+            external_id.trim();
+
+        self.items
+            .values()
+            .find(|t| {
+                t.has_external_ref(
+                    system,
+                    external_id,
+                )
+            })
+            .cloned()
+            .ok_or_else(|| {
+                report!(
+                    AppError::ExternalRefNotFound {
+                        system: system.into(),
+                        external_id: external_id.into(),
+                    }
+                )
+            })
+    }
+}
+
+impl Todo {
+    fn has_external_ref(
+        &self,
+        system: &str,
+        external_id: &str,
+    ) -> bool {
+        self.external_refs
+            .get(system)
+            .map(|e| e == external_id)
+            .unwrap_or(false)
+    }
+}
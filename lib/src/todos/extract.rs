@@ -0,0 +1,96 @@
+use super::TodoList;
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    jmespath,
+};
+
+impl TodoList {
+    /// Evaluates a JMESPath expression over the list's snapshot as JSON
+    /// and returns the result as JSON; see the `jmespath` module for the
+    /// subset understood. Keys are kebab-case, as in `"default-time-zone"`.
+    pub fn extract(
+        &self,
+        expression: &str,
+    ) -> AppResult<String> {
+        let data = serde_json::to_value(
+            self.snapshot(),
+        )
+        .map_err(|_| {
+            report!(AppError::Internal(
+                "snapshot-json"
+            ))
+        })?;
+
+        let result = jmespath::search(
+            expression, &data,
+        )?;
+
+        Ok(result.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        NewTodo, Priority, Title,
+    };
+    use pretty_assertions::assert_eq;
+
+    // An item with comments, an attachment, an external ref and a
+    // watcher, so every field has something to convert.
+    fn add_full(todos: &mut TodoList) {
+        let id = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Café ☕",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .id;
+
+        todos
+            .add_comment(
+                id, "ana", "soon",
+            )
+            .unwrap();
+        todos
+            .add_attachment(
+                id,
+                "https://example.com/a",
+                "spec",
+            )
+            .unwrap();
+        todos
+            .set_external_ref(
+                id,
+                "jira",
+                Some("TODO-1"),
+            )
+            .unwrap();
+        todos.watch(id, "bo").unwrap();
+    }
+
+    #[test]
+    fn extract_should_convert_an_item_with_every_field_set(
+    ) {
+        let mut todos = TodoList::new();
+        add_full(&mut todos);
+
+        assert_eq!(
+            todos
+                .extract(
+                    "items[0].title"
+                )
+                .unwrap(),
+            "\"Café ☕\""
+        );
+    }
+}
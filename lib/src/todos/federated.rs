@@ -0,0 +1,397 @@
+use super::{
+    SearchResult, Todo, TodoList,
+};
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    query::Query,
+    sort_by::SortKey,
+};
+use std::collections::{
+    hash_map::Entry, HashMap,
+};
+use uuid::Uuid;
+
+impl TodoList {
+    /// `search_with_total` as JSON, for a gateway to hand to another
+    /// list's `merge_federated`.
+    pub fn search_federated(
+        &self,
+        query: &Query,
+    ) -> AppResult<String> {
+        let page = self
+            .search_with_total(query)?;
+
+        serde_json::to_string(&page)
+            .map_err(|_| {
+                report!(AppError::Internal(
+                    "search-page-json"
+                ))
+            })
+    }
+
+    /// Combines the pages other lists returned from `search_federated`
+    /// for the same query into one, sorted the way this list sorts. An
+    /// item found in several pages is kept once, as its most recently
+    /// updated copy. The cursor, when set, continues the search on every
+    /// list the pages came from.
+    pub fn merge_federated(
+        &self,
+        query: &Query,
+        result_sets: &[String],
+    ) -> AppResult<SearchResult> {
+        let limit = self.result_limit(
+            query.limit(),
+        )?;
+
+        let sort = SortKey::from(
+            query.sort(),
+            *query.direction(),
+            self.title_collation,
+        );
+
+        let mut merged: HashMap<
+            Uuid,
+            Todo,
+        > = HashMap::new();
+
+        let mut total_matched: usize =
+            0;
+
+        let mut more = false;
+
+        for (set, json) in result_sets
+            .iter()
+            .enumerate()
+        {
+            let page: SearchResult =
+                serde_json::from_str(json)
+                    .map_err(|e| {
+                        report!(AppError::InvalidResultSet {
+                            set,
+                            reason: e.to_string(),
+                        })
+                    })?;
+
+            total_matched +=
+                page.total_matched;
+
+            more |= page
+                .next_cursor
+                .is_some();
+
+            for todo in page.items {
+                match merged
+                    .entry(todo.id)
+                {
+                    Entry::Occupied(
+                        mut kept,
+                    ) => {
+                        total_matched =
+                            total_matched
+                                .saturating_sub(
+                                    1,
+                                );
+
+                        if todo.updated_timestamp
+                            > kept
+                                .get()
+                                .updated_timestamp
+                        {
+                            kept.insert(todo);
+                        }
+                    }
+                    Entry::Vacant(
+                        vacant,
+                    ) => {
+                        vacant.insert(
+                            todo,
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut items: Vec<_> = merged
+            .into_values()
+            .collect();
+
+        items.sort_by_cached_key(|t| {
+            sort(t)
+        });
+
+        more |= items.len() > limit;
+
+        items.truncate(limit);
+
+        let next_cursor = self
+            .next_cursor(
+                query, &items, more,
+            );
+
+        Ok(SearchResult {
+            items,
+            total_matched,
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        query::QuerySort,
+        result_limit::OptionalResultLimit,
+        todos::{
+            NewTodo, Priority, Title,
+        },
+    };
+    use pretty_assertions::assert_eq;
+
+    fn add(
+        todos: &mut TodoList,
+        title: &str,
+        priority: Priority,
+    ) -> Todo {
+        todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(priority)
+                    .build(),
+            )
+            .unwrap()
+    }
+
+    fn titles(
+        result: &SearchResult,
+    ) -> Vec<&str> {
+        result
+            .items()
+            .iter()
+            .map(|t| t.title().as_str())
+            .collect()
+    }
+
+    fn by_priority(
+        limit: u32,
+    ) -> Query {
+        Query::builder()
+            .limit(
+                OptionalResultLimit::some(
+                    limit,
+                ),
+            )
+            .sort(Some(
+                QuerySort::Priority,
+            ))
+            .build()
+    }
+
+    #[test]
+    fn merge_federated_should_sort_pages_into_one(
+    ) {
+        let mut east = TodoList::new();
+        add(
+            &mut east,
+            "a",
+            Priority::Low,
+        );
+        add(
+            &mut east,
+            "b",
+            Priority::High,
+        );
+
+        let mut west = TodoList::new();
+        add(
+            &mut west,
+            "c",
+            Priority::Medium,
+        );
+        add(
+            &mut west,
+            "d",
+            Priority::High,
+        );
+
+        let query = by_priority(3);
+
+        let merged = TodoList::new()
+            .merge_federated(
+                &query,
+                &[
+                    east.search_federated(&query)
+                        .unwrap(),
+                    west.search_federated(&query)
+                        .unwrap(),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            titles(&merged),
+            vec!["b", "d", "c"]
+        );
+        assert_eq!(
+            merged.total_matched(),
+            4
+        );
+        assert!(merged
+            .next_cursor()
+            .is_some());
+    }
+
+    #[test]
+    fn merge_federated_should_keep_the_newest_copy_of_an_item(
+    ) {
+        let mut primary =
+            TodoList::new();
+        add(
+            &mut primary,
+            "a",
+            Priority::Low,
+        );
+
+        let query = by_priority(10);
+
+        let stale = primary
+            .search_federated(&query)
+            .unwrap();
+
+        // A replica that saw a later rename of the same item.
+        let mut fresh: serde_json::Value =
+            serde_json::from_str(&stale)
+                .unwrap();
+        let item =
+            &mut fresh["items"][0];
+        item["title"] = "b".into();
+        item["updated-timestamp"] =
+            (item["updated-timestamp"]
+                .as_i64()
+                .unwrap()
+                + 1)
+            .into();
+
+        let merged = TodoList::new()
+            .merge_federated(
+                &query,
+                &[
+                    stale,
+                    fresh.to_string(),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            titles(&merged),
+            vec!["b"]
+        );
+        assert_eq!(
+            merged.total_matched(),
+            1
+        );
+        assert_eq!(
+            merged.next_cursor(),
+            &None
+        );
+    }
+
+    #[test]
+    fn merge_federated_should_reject_a_page_that_is_not_json(
+    ) {
+        let query = by_priority(10);
+
+        let page = TodoList::new()
+            .search_federated(&query)
+            .unwrap();
+
+        let result = TodoList::new()
+            .merge_federated(
+                &query,
+                &[
+                    page,
+                    "{".to_string(),
+                ],
+            );
+
+        assert!(matches!(
+            result
+                .unwrap_err()
+                .current_context(),
+            AppError::InvalidResultSet {
+                set: 1,
+                ..
+            }
+        ));
+    }
+
+    // An item with comments, an attachment, an external ref and a
+    // watcher, so every field has something to convert.
+    fn add_full(todos: &mut TodoList) {
+        let id = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Café ☕",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .id;
+
+        todos
+            .add_comment(
+                id, "ana", "soon",
+            )
+            .unwrap();
+        todos
+            .add_attachment(
+                id,
+                "https://example.com/a",
+                "spec",
+            )
+            .unwrap();
+        todos
+            .set_external_ref(
+                id,
+                "jira",
+                Some("TODO-1"),
+            )
+            .unwrap();
+        todos.watch(id, "bo").unwrap();
+    }
+
+    #[test]
+    fn search_federated_should_convert_an_item_with_every_field_set(
+    ) {
+        let mut todos = TodoList::new();
+        add_full(&mut todos);
+
+        let page = todos
+            .search_federated(
+                &Query::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            titles(
+                &todos
+                    .merge_federated(
+                        &Query::default(
+                        ),
+                        &[page],
+                    )
+                    .unwrap()
+            ),
+            vec!["Café ☕"]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use super::{
+    Activity, ActivityFilter, Comment,
+    OptionalResultLimit, Todo,
+    TodoList,
+};
+use crate::app_error::AppResult;
+use getset::Getters;
+use typed_builder::TypedBuilder;
+use uuid::Uuid;
+
+/// What `fetch` nests under the item; nothing by default.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    TypedBuilder,
+)]
+#[builder(field_defaults(default))]
+pub struct FetchInclude {
+    comments: bool,
+
+    watchers: bool,
+
+    /// The item's own feed, newest first, which doubles as its
+    /// revision history.
+    activity: bool,
+}
+
+/// An item with whatever its `FetchInclude` asked for; the rest is
+/// `None`.
+#[derive(
+    Clone, Debug, PartialEq, Getters,
+)]
+pub struct TodoDetail {
+    #[getset(get = "pub")]
+    todo: Todo,
+
+    #[getset(get = "pub")]
+    comments: Option<Vec<Comment>>,
+
+    #[getset(get = "pub")]
+    watchers: Option<Vec<String>>,
+
+    #[getset(get = "pub")]
+    activity: Option<Vec<Activity>>,
+}
+
+impl TodoList {
+    /// Reads an item and its related records in one call, so a detail
+    /// view needs no follow-up requests. The activity is capped at
+    /// `OptionalResultLimit::MAX` events.
+    pub fn fetch(
+        &self,
+        id: Uuid,
+        include: &FetchInclude,
+    ) -> AppResult<TodoDetail> {
+        let todo = self.get(id)?;
+
+        let activity = if include
+            .activity
+        {
+            Some(self.activity(
+                &Some(
+                    OptionalResultLimit::MAX,
+                )
+                .into(),
+                &ActivityFilter::builder()
+                    .todo_id(Some(id))
+                    .build(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(TodoDetail {
+            comments: include
+                .comments
+                .then(|| {
+                    todo.comments
+                        .clone()
+                }),
+            watchers: include
+                .watchers
+                .then(|| {
+                    todo.watchers
+                        .iter()
+                        .cloned()
+                        .collect()
+                }),
+            activity,
+            todo,
+        })
+    }
+}
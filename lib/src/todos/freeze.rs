@@ -0,0 +1,43 @@
+use super::TodoList;
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+
+impl TodoList {
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Makes the list read-only until `unfreeze`: every change to its
+    /// items, archive, settings, saved queries or templates fails with
+    /// `ListFrozen`. Principals and the current actor stay writable.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    pub(super) fn ensure_writable(
+        &self,
+    ) -> AppResult<()> {
+        if self.replica {
+            bail!(AppError::ReadOnlyReplica)
+        }
+
+        self.ensure_unfrozen()
+    }
+
+    // For settings, saved queries and templates, which a replica keeps
+    // for itself and so may still change.
+    pub(super) fn ensure_unfrozen(
+        &self,
+    ) -> AppResult<()> {
+        if self.frozen {
+            bail!(AppError::ListFrozen)
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Todo, TodoList};
+use crate::{core::UnixTime, time};
+use chrono::Utc;
+use getset::{CopyGetters, Getters};
+use std::{collections::BTreeMap, mem};
+
+/// Per-operation call counts since the list was created.
+pub struct OpsCounter {
+    started_at: UnixTime,
+
+    counts: BTreeMap<String, u64>,
+
+    last_snapshot: Option<UnixTime>,
+}
+
+impl Default for OpsCounter {
+    fn default() -> Self {
+        Self {
+            started_at: unix_time_now!(),
+            counts: BTreeMap::new(),
+            last_snapshot: None,
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct Health {
+    #[getset(get_copy = "pub")]
+    uptime_seconds: UnixTime,
+
+    /// Sorted by operation name.
+    #[getset(get = "pub")]
+    operation_counts:
+        Vec<(String, u64)>,
+
+    #[getset(get_copy = "pub")]
+    last_snapshot: Option<UnixTime>,
+
+    #[getset(get_copy = "pub")]
+    item_count: usize,
+
+    #[getset(get_copy = "pub")]
+    estimated_memory_bytes: usize,
+}
+
+impl TodoList {
+    pub fn record_op(
+        &mut self,
+        operation: &str,
+    ) {
+        let count = self
+            .ops
+            .counts
+            .entry(operation.into())
+            .or_insert(0);
+        *count =
+            count.saturating_add(1);
+    }
+
+    /// For hosts that persist the list, to report when they last did.
+    pub fn record_snapshot(
+        &mut self,
+        at: UnixTime,
+    ) {
+        self.ops.last_snapshot =
+            Some(at);
+    }
+
+    pub fn health(&self) -> Health {
+        Health {
+            // Never negative, should the clock go back.
+            uptime_seconds: time::elapsed(
+                self.ops.started_at,
+                unix_time_now!(),
+            )
+            .max(0),
+            operation_counts: self
+                .ops
+                .counts
+                .iter()
+                .map(|(op, n)| {
+                    (op.clone(), *n)
+                })
+                .collect(),
+            last_snapshot: self
+                .ops
+                .last_snapshot,
+            item_count: self.items.len(),
+            estimated_memory_bytes: self
+                .estimated_memory_bytes(),
+        }
+    }
+
+    // Counts the items and their titles only; good enough for trends.
+    fn estimated_memory_bytes(
+        &self,
+    ) -> usize {
+        self.items
+            .values()
+            .chain(
+                self.archived.values(),
+            )
+            .map(|t| {
+                mem::size_of::<Todo>()
+                    + t.title.capacity()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn record_op_should_stop_counting_at_the_largest_count(
+    ) {
+        let mut todos = TodoList::new();
+        todos.ops.counts.insert(
+            "add".into(),
+            u64::MAX,
+        );
+
+        todos.record_op("add");
+
+        assert_eq!(
+            todos
+                .health()
+                .operation_counts(),
+            &vec![(
+                "add".to_string(),
+                u64::MAX
+            )]
+        );
+    }
+
+    #[test]
+    fn health_should_report_no_uptime_when_the_clock_goes_back(
+    ) {
+        let mut todos = TodoList::new();
+
+        todos.ops.started_at =
+            UnixTime::MAX;
+        assert_eq!(
+            todos
+                .health()
+                .uptime_seconds(),
+            0
+        );
+
+        todos.ops.started_at =
+            UnixTime::MIN;
+        assert_eq!(
+            todos
+                .health()
+                .uptime_seconds(),
+            UnixTime::MAX
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Query, Todo, TodoList};
+use crate::app_error::AppResult;
+use getset::{CopyGetters, Getters};
+use std::borrow::Cow;
+use strum_macros::EnumIter;
+
+/// Where a query's keywords can match. Variants are in the same order
+/// as the WIT `search-field` enum.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    EnumIter,
+)]
+pub enum SearchField {
+    Title,
+
+    /// The text of any comment on the item.
+    Comments,
+}
+
+impl SearchField {
+    /// What a keyword match here adds to a hit's score; a title match
+    /// outweighs one in the comments.
+    pub fn weight(self) -> u32 {
+        match self {
+            Self::Title => 3,
+            Self::Comments => 1,
+        }
+    }
+
+    pub(crate) fn texts(
+        self,
+        todo: &Todo,
+    ) -> Box<
+        dyn Iterator<Item = &str> + '_,
+    > {
+        match self {
+            Self::Title => Box::new(
+                std::iter::once(
+                    todo.title.as_str(),
+                ),
+            ),
+            Self::Comments => Box::new(
+                todo.comments
+                    .iter()
+                    .map(|c| {
+                        c.text()
+                            .as_str()
+                    }),
+            ),
+        }
+    }
+
+    /// `texts` lowercased; a title's comes from the item's cache.
+    pub(crate) fn lowercase_texts(
+        self,
+        todo: &Todo,
+    ) -> Box<
+        dyn Iterator<
+                Item = Cow<'_, str>,
+            > + '_,
+    > {
+        match self {
+            Self::Title => Box::new(
+                std::iter::once(
+                    Cow::Borrowed(
+                        todo.title_lowercase(),
+                    ),
+                ),
+            ),
+            Self::Comments => Box::new(
+                self.texts(todo).map(
+                    |text| {
+                        Cow::Owned(
+                            text.to_lowercase(),
+                        )
+                    },
+                ),
+            ),
+        }
+    }
+}
+
+/// An item a search found, with where its keyword matched.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct SearchHit {
+    #[getset(get = "pub")]
+    todo: Todo,
+
+    /// The heaviest field the keyword matched; `None` when the query
+    /// has no keyword.
+    #[getset(get_copy = "pub")]
+    field: Option<SearchField>,
+
+    /// The weights of every field the keyword matched, added up.
+    #[getset(get_copy = "pub")]
+    score: u32,
+}
+
+impl TodoList {
+    /// Like `search`, but says where the keyword matched each item.
+    /// Hits keep the query's order; sort them by `score` to rank them.
+    pub fn search_hits(
+        &self,
+        query: &Query,
+    ) -> AppResult<Vec<SearchHit>> {
+        let found =
+            self.search(query)?;
+
+        Ok(found
+            .into_iter()
+            .map(|todo| {
+                let fields = query
+                    .keyword_fields(&todo);
+
+                SearchHit {
+                    field: fields
+                        .iter()
+                        .copied()
+                        .max_by_key(|f| {
+                            f.weight()
+                        }),
+                    score: fields
+                        .iter()
+                        .map(|f| f.weight())
+                        .sum(),
+                    todo,
+                }
+            })
+            .collect())
+    }
+}
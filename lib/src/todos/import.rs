@@ -0,0 +1,380 @@
+use super::{
+    checksum::payload_checksum,
+    DeadlineChange, Status, Todo,
+    TodoList, UpdateTodo,
+};
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    importers::{
+        self, ExternalTodo,
+        ImportFormat,
+    },
+};
+use getset::Getters;
+use typed_builder::TypedBuilder;
+use uuid::Uuid;
+
+/// What an import does with a row that matches a live item, by id,
+/// then by the external ref an earlier import of the same format
+/// recorded, then by exact title.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+)]
+pub enum MergeStrategy {
+    /// Deletes every live item first, so only the payload is left.
+    Replace,
+
+    /// Keeps the item and skips the row.
+    #[default]
+    SkipExisting,
+
+    /// Gives the item the row's title, priority, deadline and status.
+    OverwriteExisting,
+
+    /// Overwrites only when the row changed after the item did. A row
+    /// whose format carries no dates counts as older.
+    MergeNewest,
+}
+
+/// How `import_external` treats the payload as a whole.
+#[derive(
+    Clone, Debug, Default, TypedBuilder,
+)]
+#[builder(field_defaults(default))]
+pub struct ImportOptions {
+    /// The payload's FNV-1a checksum as 16 hex digits; a payload that
+    /// does not match is refused before anything is imported.
+    expected_checksum: Option<String>,
+
+    /// Any failed row aborts the import and leaves the list as it was.
+    strict: bool,
+
+    merge: MergeStrategy,
+}
+
+/// The outcome of every task in an import, keyed by its row.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Getters,
+)]
+pub struct ImportReport {
+    #[getset(get = "pub")]
+    imported: Vec<(usize, Todo)>,
+
+    /// Rows whose match was overwritten, with the item as it is now.
+    #[getset(get = "pub")]
+    updated: Vec<(usize, Todo)>,
+
+    /// Rows whose match was kept as it was, with its id.
+    #[getset(get = "pub")]
+    skipped: Vec<(usize, Uuid)>,
+
+    #[getset(get = "pub")]
+    failed: Vec<(usize, String)>,
+
+    /// The list's `state_checksum` once the import is done.
+    #[getset(get = "pub")]
+    checksum: String,
+}
+
+impl TodoList {
+    /// Adds each task in `payload` that passes the usual checks, merging
+    /// those already on the list per `options`. Unless `options` make
+    /// it strict, a bad row is reported and skipped. Fails when the
+    /// payload is not in `format` at all or does not match the expected
+    /// checksum.
+    pub fn import_external(
+        &mut self,
+        format: ImportFormat,
+        payload: &str,
+        options: &ImportOptions,
+    ) -> AppResult<ImportReport> {
+        self.ensure_writable()?;
+
+        if let Some(expected) =
+            &options.expected_checksum
+        {
+            let actual =
+                payload_checksum(
+                    payload,
+                );
+
+            if !expected
+                .trim()
+                .eq_ignore_ascii_case(
+                    &actual,
+                )
+            {
+                bail!(AppError::ImportChecksumMismatch {
+                    expected: expected.clone(),
+                    actual,
+                })
+            }
+        }
+
+        let rows = importers::parse(
+            format, payload,
+        )?;
+
+        let mut report = if options
+            .strict
+        {
+            let report = self
+                .atomically(|todos| {
+                    let report =
+                        todos.import_rows(
+                            format,
+                            rows,
+                            options.merge,
+                        )?;
+
+                    match report
+                        .failed
+                        .first()
+                    {
+                        Some((
+                            row,
+                            reason,
+                        )) => bail!(
+                            AppError::ImportRowFailed {
+                                row: *row,
+                                reason: reason.clone(),
+                            }
+                        ),
+                        None => Ok(report),
+                    }
+                })?;
+
+            report
+        } else {
+            self.import_rows(
+                format,
+                rows,
+                options.merge,
+            )?
+        };
+
+        report.checksum =
+            self.state_checksum();
+
+        Ok(report)
+    }
+
+    fn import_rows(
+        &mut self,
+        format: ImportFormat,
+        rows: Vec<(
+            usize,
+            ExternalTodo,
+        )>,
+        merge: MergeStrategy,
+    ) -> AppResult<ImportReport> {
+        let mut report =
+            ImportReport::default();
+
+        if merge
+            == MergeStrategy::Replace
+        {
+            self.delete_all()?;
+        }
+
+        for (row, external) in rows {
+            let (result, outcomes) = match self
+                .matching(format, &external)
+            {
+                Some(todo)
+                    if merge.overwrites(
+                        &todo, &external,
+                    ) =>
+                {
+                    (
+                        self.overwrite(
+                            &todo, &external,
+                        ),
+                        &mut report.updated,
+                    )
+                }
+                Some(todo) => {
+                    report
+                        .skipped
+                        .push((row, todo.id));
+
+                    continue;
+                }
+                None => (
+                    self.add_external(
+                        format, &external,
+                    ),
+                    &mut report.imported,
+                ),
+            };
+
+            match result {
+                Ok(todo) => {
+                    outcomes.push((row, todo))
+                }
+                Err(e) => {
+                    report.failed.push((
+                        row,
+                        e.current_context()
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn matching(
+        &self,
+        format: ImportFormat,
+        external: &ExternalTodo,
+    ) -> Option<Todo> {
+        let external_id = external
+            .external_id
+            .as_deref();
+
+        external_id
+            .and_then(|e| {
+                Uuid::parse_str(e).ok()
+            })
+            .and_then(|id| {
+                self.items.get(&id).cloned()
+            })
+            .or_else(|| {
+                external_id.and_then(|e| {
+                    self.find_by_external_ref(
+                        format.system(),
+                        e,
+                    )
+                    .ok()
+                })
+            })
+            .or_else(|| {
+                external
+                    .item
+                    .title
+                    .validated()
+                    .ok()
+                    .and_then(|title| {
+                        self.find_by_title(
+                            &title, true,
+                        )
+                        .into_iter()
+                        .next()
+                    })
+            })
+    }
+
+    // Records the row's own id as an external ref, so importing the
+    // same file again matches this item.
+    fn add_external(
+        &mut self,
+        format: ImportFormat,
+        external: &ExternalTodo,
+    ) -> AppResult<Todo> {
+        let todo =
+            self.add(&external.item)?;
+
+        let todo = if external.done {
+            self.update(
+                todo.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )?
+        } else {
+            todo
+        };
+
+        match &external.external_id {
+            Some(external_id) => self
+                .set_external_ref(
+                    todo.id,
+                    format.system(),
+                    Some(external_id),
+                ),
+            None => Ok(todo),
+        }
+    }
+
+    fn overwrite(
+        &mut self,
+        todo: &Todo,
+        external: &ExternalTodo,
+    ) -> AppResult<Todo> {
+        let item = &external.item;
+
+        let status = if external.done {
+            Some(Status::Done)
+        } else if todo.status
+            == Status::Done
+        {
+            Some(Status::Backlog)
+        } else {
+            None
+        };
+
+        let deadline = match item
+            .deadline
+            .input()
+        {
+            Some(_) => {
+                DeadlineChange::Set(
+                    item.deadline
+                        .clone(),
+                )
+            }
+            None => {
+                DeadlineChange::Clear
+            }
+        };
+
+        self.update(
+            todo.id,
+            &UpdateTodo::builder()
+                .title(Some(
+                    item.title.clone(),
+                ))
+                .priority(item.priority)
+                .status(status)
+                .deadline(deadline)
+                .build(),
+        )
+    }
+}
+
+impl MergeStrategy {
+    fn overwrites(
+        self,
+        todo: &Todo,
+        external: &ExternalTodo,
+    ) -> bool {
+        match self {
+            Self::OverwriteExisting => true,
+            Self::MergeNewest => external
+                .updated
+                .map(|updated| {
+                    updated
+                        > todo.updated_timestamp
+                })
+                .unwrap_or(false),
+            Self::Replace
+            | Self::SkipExisting => false,
+        }
+    }
+}
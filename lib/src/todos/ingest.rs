@@ -0,0 +1,228 @@
+use super::{NewTodo, Todo, TodoList};
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+        Report,
+    },
+    cursor::checksum,
+};
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+
+/// How `add_many_chunked` splits its work; by default a call adds at
+/// most 5,000 items.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    CopyGetters,
+    TypedBuilder,
+)]
+pub struct ChunkOptions {
+    #[builder(default = 500)]
+    #[getset(get_copy = "pub")]
+    chunk_size: usize,
+
+    /// Chunks one call adds before it hands back a continuation.
+    #[builder(default = 10)]
+    #[getset(get_copy = "pub")]
+    chunks_per_call: usize,
+
+    /// The continuation an earlier call returned for the same items.
+    #[builder(default)]
+    resume: Option<String>,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// What one call of `add_many_chunked` did, keyed by each item's
+/// position in the whole input.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Getters,
+)]
+#[getset(get = "pub")]
+pub struct ChunkedAddReport {
+    added: Vec<(usize, Todo)>,
+
+    failed: Vec<(usize, String)>,
+
+    /// Set while items remain; pass it as `resume` with the same items.
+    continuation: Option<String>,
+}
+
+impl TodoList {
+    /// Adds `items` a chunk at a time, stopping once the call's chunks
+    /// are used up so a large import fits an invocation's limits. An
+    /// item that fails is reported and skipped.
+    pub fn add_many_chunked(
+        &mut self,
+        items: &[NewTodo],
+        options: &ChunkOptions,
+    ) -> AppResult<ChunkedAddReport>
+    {
+        self.ensure_writable()?;
+
+        let start =
+            match &options.resume {
+                Some(token) => decode(
+                    token, items,
+                )?,
+                None => 0,
+            };
+
+        let chunk_size =
+            options.chunk_size.max(1);
+
+        let mut report =
+            ChunkedAddReport::default();
+
+        let mut next = start;
+
+        for chunk in items[start..]
+            .chunks(chunk_size)
+            .take(
+                options
+                    .chunks_per_call
+                    .max(1),
+            )
+        {
+            for item in chunk {
+                match self.add(item) {
+                    Ok(todo) => report
+                        .added
+                        .push((next, todo)),
+                    Err(e) => {
+                        report.failed.push((
+                            next,
+                            e.current_context()
+                                .to_string(),
+                        ))
+                    }
+                }
+
+                next += 1;
+            }
+
+            trace_debug!(
+                next,
+                total = items.len(),
+                "added a chunk"
+            );
+        }
+
+        report.continuation = (next
+            < items.len())
+        .then(|| encode(next, items));
+
+        Ok(report)
+    }
+}
+
+// The total and a digest of the items are kept so a token is refused
+// for a different input, even one of the same length.
+fn encode(
+    next: usize,
+    items: &[NewTodo],
+) -> String {
+    URL_SAFE_NO_PAD.encode(format!(
+        "{next}/{}/{}",
+        items.len(),
+        digest(items)
+    ))
+}
+
+fn decode(
+    token: &str,
+    items: &[NewTodo],
+) -> AppResult<usize> {
+    let total = items.len();
+
+    let text = URL_SAFE_NO_PAD
+        .decode(token)
+        .ok()
+        .and_then(|bytes| {
+            String::from_utf8(bytes)
+                .ok()
+        })
+        .ok_or_else(|| {
+            invalid(token)
+        })?;
+
+    let mut fields = text.split('/');
+
+    match (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) {
+        (
+            Some(next),
+            Some(given_total),
+            Some(given_digest),
+            None,
+        ) if given_total
+            == total.to_string()
+            && given_digest
+                == digest(items)
+                    .to_string() =>
+        {
+            next.parse()
+                .ok()
+                .filter(|next| {
+                    *next < total
+                })
+                .ok_or_else(|| {
+                    invalid(token)
+                })
+        }
+        _ => Err(invalid(token)),
+    }
+}
+
+// What each item asks for, with every field ended by a NUL so that
+// moving text between fields changes the digest.
+fn digest(items: &[NewTodo]) -> u32 {
+    let mut bytes = vec![];
+
+    for item in items {
+        for field in [
+            Some(item.title.as_str()),
+            item.deadline.input(),
+            item.start_date.input(),
+        ] {
+            bytes.extend(
+                field
+                    .unwrap_or_default()
+                    .as_bytes(),
+            );
+            bytes.push(0);
+        }
+    }
+
+    checksum(&bytes)
+}
+
+fn invalid(
+    token: &str,
+) -> Report<AppError> {
+    report!(
+        AppError::InvalidContinuation(
+            token.to_string()
+        )
+    )
+}
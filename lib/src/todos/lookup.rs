@@ -0,0 +1,87 @@
+use super::{Todo, TodoList};
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use uuid::Uuid;
+
+impl TodoList {
+    /// Live items titled `title`, oldest first. Exact means the whole
+    /// title, case included; otherwise any title containing it, case
+    /// ignored.
+    pub fn find_by_title(
+        &self,
+        title: &str,
+        exact: bool,
+    ) -> Vec<Todo> {
+        let title = title.trim();
+        let lowercase =
+            title.to_lowercase();
+
+        let mut found: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| {
+                if exact {
+                    t.title == title
+                } else {
+                    t.title_lowercase()
+                        .contains(
+                            &lowercase,
+                        )
+                }
+            })
+            .cloned()
+            .collect();
+
+        found.sort_by_key(|t| {
+            (t.created_timestamp, t.id)
+        });
+
+        found
+    }
+
+    /// The id that `input` names: an id, a short id such as `42` or
+    /// `#42`, or failing those the exact title of one live item.
+    pub fn resolve(
+        &self,
+        input: &str,
+    ) -> AppResult<Uuid> {
+        let input = input.trim();
+
+        if let Ok(short_id) = input
+            .strip_prefix('#')
+            .unwrap_or(input)
+            .parse()
+        {
+            return self
+                .get_by_short_id(
+                    short_id,
+                )
+                .map(|t| t.id);
+        }
+
+        if let Ok(id) =
+            Uuid::try_from(input)
+        {
+            return Ok(id);
+        }
+
+        match self
+            .find_by_title(input, true)
+            .as_slice()
+        {
+            [todo] => Ok(todo.id),
+            [] => bail!(
+                AppError::NoTodoMatches(
+                    input.into()
+                )
+            ),
+            found => bail!(
+                AppError::AmbiguousTitle {
+                    title: input.into(),
+                    count: found.len(),
+                }
+            ),
+        }
+    }
+}
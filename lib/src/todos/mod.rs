@@ -3,10 +3,14 @@ use crate::{
         bail, report, AppError,
         AppResult,
     },
+    appearance, collation,
     core::UnixTime,
-    deadline, query, result_limit,
-    sort_by::SortBy,
-    title,
+    cursor::Cursor,
+    deadline, filter_expr,
+    id_generator, importers, query,
+    result_limit,
+    sort_by::{SortBy, SortKey},
+    time, time_zone, title,
 };
 use binary_heap_plus::BinaryHeap;
 use chrono::Utc;
@@ -14,20 +18,150 @@ use getset::{CopyGetters, Getters};
 use nonempty_collections::{
     nes, NESet,
 };
-use std::collections::HashMap;
+use regex::Regex;
+use std::collections::{
+    BTreeMap, BTreeSet, HashMap,
+    VecDeque,
+};
 use strum_macros::EnumIter;
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
 
+pub type Color = appearance::Color;
+pub type Icon = appearance::Icon;
+pub type Activity = activity::Activity;
+pub type ActivityFilter =
+    activity::ActivityFilter;
+pub type ActivityKind =
+    activity::ActivityKind;
+pub type AddOutcome =
+    similar::AddOutcome;
+pub type ArenaStore = store::ArenaStore;
+pub type AttachmentRef =
+    attachments::AttachmentRef;
+pub type BatchCommand =
+    batch::BatchCommand;
+pub type BatchResult =
+    batch::BatchResult;
+pub type ChunkOptions =
+    ingest::ChunkOptions;
+pub type ChunkedAddReport =
+    ingest::ChunkedAddReport;
+pub type Comment = comments::Comment;
+pub type CreationDefaults =
+    defaults::CreationDefaults;
+pub type DeadlineChange =
+    deadline::DeadlineChange;
+pub type DeadlineBounds =
+    deadline_bounds::DeadlineBounds;
+pub type DistinctField =
+    distinct::DistinctField;
+pub type FetchInclude =
+    fetch::FetchInclude;
+pub type FieldValue =
+    distinct::FieldValue;
+pub type FieldPredicate =
+    filter_expr::FieldPredicate;
+pub type FilterExpr =
+    filter_expr::FilterExpr;
+pub type FilterNode =
+    filter_expr::FilterNode;
+pub type HashMapStore =
+    store::HashMapStore;
+pub type EscalationPolicy =
+    escalation::EscalationPolicy;
+pub type Health = health::Health;
+pub type ImportFormat =
+    importers::ImportFormat;
+pub type ImportOptions =
+    import::ImportOptions;
+pub type ImportReport =
+    import::ImportReport;
+pub type MergeStrategy =
+    import::MergeStrategy;
+pub type Lateness = overdue::Lateness;
+pub type OverdueReport =
+    overdue::OverdueReport;
+pub use id_generator::IdGenerator;
+pub type Principal = auth::Principal;
+pub type RandomIdGenerator =
+    id_generator::RandomIdGenerator;
+pub type SeededIdGenerator =
+    id_generator::SeededIdGenerator;
+pub type SequentialIdGenerator =
+    id_generator::SequentialIdGenerator;
+pub type RecentKind =
+    recent::RecentKind;
+pub type RetentionPolicy =
+    retention::RetentionPolicy;
+pub type RemoteChange =
+    sync::RemoteChange;
+pub type RemoteTodo = sync::RemoteTodo;
+pub type ReplicaList =
+    replica::ReplicaList;
+pub type ResultWithWarnings<T> =
+    warnings::ResultWithWarnings<T>;
+pub type Role = auth::Role;
+pub type SearchField =
+    hits::SearchField;
+pub type SearchHit = hits::SearchHit;
+pub type Snapshot = snapshot::Snapshot;
+pub use store::TodoStore;
+pub type ListSettings =
+    settings::ListSettings;
+pub type SettingsUpdate =
+    settings::SettingsUpdate;
+pub type ChangeSummary =
+    sync::ChangeSummary;
+pub type SyncReport = sync::SyncReport;
+pub type SyncStrategy =
+    sync::SyncStrategy;
+pub type TimeOrderedIdGenerator =
+    id_generator::TimeOrderedIdGenerator;
+#[cfg(feature = "persistence")]
+pub type FileLog = persistence::FileLog;
+#[cfg(feature = "persistence")]
+pub type LogEntry =
+    persistence::LogEntry;
+#[cfg(feature = "persistence")]
+pub use persistence::OperationLog;
+#[cfg(feature = "persistence")]
+pub type SnapshotEncoding =
+    persistence::SnapshotEncoding;
 pub type OptionalDeadlineInput =
     deadline::OptionalDeadlineInput;
+pub type Availability =
+    query::Availability;
 pub type Query = query::Query;
+pub type QueryCacheStats =
+    query_cache::QueryCacheStats;
 pub type QuerySort = query::QuerySort;
+pub type SortDirection =
+    query::SortDirection;
 pub type OptionalResultLimit =
     result_limit::OptionalResultLimit;
+pub type TemplateOverrides =
+    templates::TemplateOverrides;
+pub type TimeZone = time_zone::TimeZone;
 pub type Title = title::Title;
-
-mod tests;
+pub type TitleCollation =
+    collation::TitleCollation;
+pub type TodoDetail = fetch::TodoDetail;
+pub type TodoField =
+    projection::TodoField;
+pub type TodoSummary =
+    projection::TodoSummary;
+pub type TrendBucket =
+    trend::TrendBucket;
+pub type TrendPoint = trend::TrendPoint;
+pub type View = views::View;
+pub type Warning = warnings::Warning;
+
+// What an update does to a date: `None` keeps it and `Some(None)`
+// clears it.
+type DateUpdate = Option<
+    Option<(UnixTime, TimeZone)>,
+>;
 
 macro_rules! unix_time_now {
     () => {
@@ -35,6 +169,81 @@ macro_rules! unix_time_now {
     };
 }
 
+// Compiles to nothing unless the `tracing` feature is on.
+macro_rules! trace_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+// Compiles to nothing unless the `persistence` feature is on.
+macro_rules! journal {
+    ($list:expr, $entry:expr) => {
+        #[cfg(feature = "persistence")]
+        {
+            use crate::todos::LogEntry;
+
+            $list.journal($entry)?;
+        }
+    };
+}
+
+mod activity;
+mod actor;
+mod attachments;
+mod auth;
+mod batch;
+mod checksum;
+mod comments;
+mod deadline_bounds;
+mod defaults;
+#[cfg(feature = "delta")]
+mod delta;
+mod distinct;
+mod escalation;
+mod export;
+mod external_refs;
+#[cfg(feature = "extract")]
+mod extract;
+#[cfg(feature = "federated")]
+mod federated;
+mod fetch;
+mod freeze;
+mod health;
+mod hits;
+mod import;
+mod ingest;
+mod lookup;
+mod overdue;
+#[cfg(feature = "persistence")]
+mod persistence;
+mod pinned_views;
+mod projection;
+mod query_cache;
+mod recent;
+mod replica;
+mod retention;
+mod retitle;
+mod saved_queries;
+mod settings;
+mod shift;
+mod short_ids;
+mod similar;
+mod snapshot;
+mod snooze;
+mod stale;
+mod store;
+mod suggest;
+mod sync;
+mod templates;
+mod tests;
+mod trend;
+mod validate;
+mod views;
+mod warnings;
+mod watchers;
+
 #[derive(
     Clone,
     Copy,
@@ -46,6 +255,14 @@ macro_rules! unix_time_now {
     Ord,
     PartialOrd,
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
 pub enum Status {
     InProgress,
     Backlog,
@@ -63,6 +280,14 @@ pub enum Status {
     Ord,
     PartialOrd,
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
 pub enum Priority {
     Low,
     Medium,
@@ -73,10 +298,25 @@ pub enum Priority {
 pub struct NewTodo {
     title: Title,
 
-    priority: Priority,
+    /// Falls back to the list's creation defaults when left out.
+    #[builder(default, setter(into))]
+    priority: Option<Priority>,
 
     #[builder(default = OptionalDeadlineInput::default())]
     deadline: OptionalDeadlineInput,
+
+    #[builder(default = OptionalDeadlineInput::default())]
+    start_date: OptionalDeadlineInput,
+
+    // Past deadlines on new items are usually typos.
+    #[builder(default)]
+    allow_past_deadline: bool,
+
+    #[builder(default)]
+    color: Option<Color>,
+
+    #[builder(default)]
+    icon: Option<Icon>,
 }
 
 #[derive(TypedBuilder)]
@@ -88,7 +328,14 @@ pub struct UpdateTodo {
 
     status: Option<Status>,
 
-    deadline: OptionalDeadlineInput,
+    deadline: DeadlineChange,
+
+    start_date: DeadlineChange,
+
+    /// An empty color or icon clears the current one.
+    color: Option<Color>,
+
+    icon: Option<Icon>,
 }
 impl UpdateTodo {
     fn change_is_present(
@@ -97,7 +344,12 @@ impl UpdateTodo {
         self.title.is_some()
             || self.priority.is_some()
             || self.status.is_some()
-            || self.deadline.is_some()
+            || self.color.is_some()
+            || self.icon.is_some()
+            || !self.deadline.is_keep()
+            || !self
+                .start_date
+                .is_keep()
     }
 }
 
@@ -110,10 +362,27 @@ impl UpdateTodo {
     Getters,
     CopyGetters,
 )]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
 pub struct Todo {
     #[getset(get = "pub")]
     id: Uuid,
 
+    /// A number for people to type, such as `42` for `#42`; the id stays
+    /// canonical. Items saved before short ids existed have 0.
+    #[getset(get_copy = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    short_id: u64,
+
     #[getset(get = "pub")]
     title: String,
 
@@ -129,10 +398,123 @@ pub struct Todo {
     #[getset(get_copy = "pub")]
     updated_timestamp: UnixTime,
 
+    /// When the item last moved to `Done`; cleared if it moves back.
+    #[getset(get_copy = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    completed_timestamp:
+        Option<UnixTime>,
+
     #[getset(get_copy = "pub")]
     deadline: Option<UnixTime>,
+
+    #[getset(get_copy = "pub")]
+    time_zone: TimeZone,
+
+    #[getset(get_copy = "pub")]
+    start_date: Option<UnixTime>,
+
+    #[getset(get_copy = "pub")]
+    snoozed_until: Option<UnixTime>,
+
+    #[getset(get = "pub")]
+    color: Option<String>,
+
+    #[getset(get = "pub")]
+    icon: Option<String>,
+
+    #[getset(get = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    attachments: Vec<AttachmentRef>,
+
+    /// The item's id in other systems, keyed by system name.
+    #[getset(get = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    external_refs:
+        BTreeMap<String, String>,
+
+    // Read through `TodoList::list_comments` rather than with every item.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    comments: Vec<Comment>,
+
+    /// The actor behind the latest change, when one was given.
+    #[getset(get = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    last_modified_by: Option<String>,
+
+    // Read through `TodoList::list_watchers`, like comments.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    watchers: BTreeSet<String>,
+
+    // Filled as searches need it; `set_title` clears it.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip)
+    )]
+    normalized_title:
+        title::NormalizedTitle,
 }
 impl Todo {
+    pub fn deadline_local(
+        &self,
+    ) -> Option<String> {
+        self.deadline.and_then(|d| {
+            self.time_zone.format(d)
+        })
+    }
+
+    /// `title` lowercased, for matching that ignores case.
+    pub(crate) fn title_lowercase(
+        &self,
+    ) -> &str {
+        self.normalized_title
+            .lowercase(&self.title)
+    }
+
+    /// What sorting by title compares under `collation`.
+    pub(crate) fn title_key(
+        &self,
+        collation: TitleCollation,
+    ) -> &str {
+        match collation {
+            TitleCollation::Binary => {
+                &self.title
+            }
+            TitleCollation::Natural => {
+                self.normalized_title
+                    .natural(
+                        &self.title,
+                    )
+            }
+        }
+    }
+
+    fn set_title(
+        &mut self,
+        title: String,
+    ) {
+        self.title = title;
+        self.normalized_title =
+            title::NormalizedTitle::default();
+    }
+
     fn is_in_id_set(
         &self,
         ids: &NESet<Uuid>,
@@ -156,61 +538,508 @@ impl Todo {
     }
 }
 
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
+pub struct SearchResult {
+    #[getset(get = "pub")]
+    items: Vec<Todo>,
+
+    /// Matches counted past the cursor, if the query had one.
+    #[getset(get_copy = "pub")]
+    total_matched: usize,
+
+    /// Set when more matches follow; pass it as the next query's `after`.
+    #[getset(get = "pub")]
+    next_cursor: Option<String>,
+}
+
 #[derive(Default)]
-pub struct TodoList(
-    HashMap<Uuid, Todo>,
-);
+pub struct TodoList {
+    items: Box<dyn TodoStore>,
+
+    archived: HashMap<Uuid, Todo>,
+
+    saved_queries:
+        HashMap<String, Query>,
+
+    templates: HashMap<String, NewTodo>,
+
+    default_time_zone: TimeZone,
+
+    escalation_policy: EscalationPolicy,
+
+    retention_policy:
+        Option<RetentionPolicy>,
+
+    deadline_bounds:
+        Option<DeadlineBounds>,
+
+    strict_limits: bool,
+
+    track_views: bool,
+
+    warn_on_similar: bool,
+
+    title_collation: TitleCollation,
+
+    creation_defaults: CreationDefaults,
+
+    last_viewed:
+        HashMap<Uuid, UnixTime>,
+
+    query_cache:
+        query_cache::QueryCache,
+
+    pinned_views:
+        pinned_views::PinnedViews,
+
+    // When each deleted id went, for `diff_since`.
+    deleted: HashMap<Uuid, UnixTime>,
+
+    // The latest change's sequence number and each changed id's, for
+    // `export_delta`.
+    change_seq: u64,
+
+    change_seqs: HashMap<Uuid, u64>,
+
+    // Deltas since this sequence number or earlier carry every item.
+    seq_floor: u64,
+
+    // Deltas asked for with another epoch's sequence number carry every
+    // item.
+    seq_epoch: sync::SeqEpoch,
+
+    activity: VecDeque<Activity>,
+
+    actor: Option<String>,
+
+    frozen: bool,
+
+    // Set for the list inside a `ReplicaList`.
+    replica: bool,
+
+    auth_token: Option<String>,
+
+    principals: Vec<auth::Registered>,
+
+    ops: health::OpsCounter,
+
+    id_generator: Box<dyn IdGenerator>,
+
+    last_short_id: u64,
+
+    #[cfg(feature = "persistence")]
+    persistence: Option<
+        persistence::Persistence,
+    >,
+}
 impl TodoList {
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn new_with(
+        id_generator: impl IdGenerator
+            + 'static,
+    ) -> Self {
+        Self {
+            id_generator: Box::new(
+                id_generator,
+            ),
+            ..Self::default()
+        }
+    }
+
+    /// Keeps the items in `store` from now on, such as an `ArenaStore`
+    /// for a very large list; any already on the list move over.
+    pub fn with_store(
+        mut self,
+        mut store: impl TodoStore + 'static,
+    ) -> Self {
+        store.clear();
+        for todo in self.items.values()
+        {
+            store.insert(todo.clone());
+        }
+        self.items = Box::new(store);
+
+        self
+    }
+
+    pub fn default_time_zone(
+        &self,
+    ) -> TimeZone {
+        self.default_time_zone
+    }
+
+    pub fn set_default_time_zone(
+        &mut self,
+        time_zone: &str,
+    ) -> AppResult<()> {
+        self.ensure_writable()?;
+
+        let time_zone =
+            TimeZone::parse(time_zone)?;
+
+        journal!(
+            self,
+            LogEntry::SetDefaultTimeZone(
+                time_zone
+            )
+        );
+
+        self.default_time_zone =
+            time_zone;
+
+        Ok(())
+    }
+
+    pub fn strict_limits(
+        &self,
+    ) -> bool {
+        self.strict_limits
+    }
+
+    /// In strict mode an out-of-range query limit is an error rather than
+    /// being clamped.
+    pub fn set_strict_limits(
+        &mut self,
+        strict: bool,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.strict_limits = strict;
+
+        Ok(())
+    }
+
+    pub fn title_collation(
+        &self,
+    ) -> TitleCollation {
+        self.title_collation
+    }
+
+    /// Changes how searches order titles. Cursors issued before the
+    /// change are refused afterwards.
+    pub fn set_title_collation(
+        &mut self,
+        collation: TitleCollation,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.title_collation =
+            collation;
+
+        Ok(())
+    }
+
+    fn result_limit(
+        &self,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<usize> {
+        if self.strict_limits {
+            limit.strictly_validated()
+        } else {
+            limit.validated()
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            err
+        )
+    )]
     pub fn add(
         &mut self,
         item: &NewTodo,
     ) -> AppResult<Todo> {
-        let deadline = item
-            .deadline
-            .unix_time()?;
+        self.ensure_writable()?;
 
-        let title =
-            item.title.validated()?;
+        let mut todo = Todo {
+            id: self
+                .id_generator
+                .next_id(),
+            ..self.prepared(item)?
+        };
+        todo.short_id =
+            self.next_short_id()?;
+
+        let result = todo.clone();
+
+        journal!(
+            self,
+            LogEntry::Put(todo.clone())
+        );
+
+        self.items.insert(todo);
+        self.bump_seq(result.id);
+
+        self.record_changes(
+            None, &result,
+        );
 
-        let id = Uuid::new_v4();
+        trace_debug!(id = %result.id, "added");
+
+        Ok(result)
+    }
+
+    // Validates `item` and builds what `add` would store, with a nil id.
+    fn prepared(
+        &self,
+        item: &NewTodo,
+    ) -> AppResult<Todo> {
+        let (deadline, time_zone) =
+            self.new_deadline(item)?;
 
         let now = unix_time_now!();
 
-        let todo = Todo {
-            id,
+        let start_date = self
+            .new_start_date(
+                item, deadline,
+            )?;
+
+        let title =
+            item.title.validated()?;
+
+        let color = item
+            .color
+            .as_ref()
+            .map(Color::validated)
+            .transpose()?
+            .flatten();
+
+        let icon = item
+            .icon
+            .as_ref()
+            .map(Icon::validated)
+            .transpose()?
+            .flatten();
+
+        Ok(Todo {
+            id: Uuid::nil(),
+            short_id: 0,
             title,
-            priority: item.priority,
+            priority: item
+                .priority
+                .unwrap_or(
+                self.creation_defaults
+                    .priority(),
+            ),
             deadline,
+            time_zone,
+            start_date,
+            snoozed_until: None,
+            attachments: vec![],
+            external_refs:
+                BTreeMap::new(),
+            comments: vec![],
+            watchers: BTreeSet::new(),
+            last_modified_by: self
+                .actor
+                .clone(),
+            normalized_title:
+                Default::default(),
+            color,
+            icon,
             status: Status::Backlog,
             created_timestamp: now,
             updated_timestamp: now,
-        };
+            completed_timestamp: None,
+        })
+    }
 
-        let result = todo.clone();
+    // The deadline `item` resolves to and the zone it was read in.
+    fn new_deadline(
+        &self,
+        item: &NewTodo,
+    ) -> AppResult<(
+        Option<UnixTime>,
+        TimeZone,
+    )> {
+        let time_zone = item
+            .deadline
+            .time_zone_or(
+            self.default_time_zone,
+        )?;
 
-        self.0.insert(todo.id, todo);
+        let deadline = item
+            .deadline
+            .unix_time(time_zone)?
+            .or_else(|| {
+                self.creation_defaults
+                    .deadline_from(
+                        unix_time_now!(),
+                    )
+            });
+
+        self.check_deadline_bounds(
+            &item.deadline,
+            deadline,
+        )?;
 
-        Ok(result)
+        let now = unix_time_now!();
+
+        if !item.allow_past_deadline
+            && deadline
+                .map(|d| d < now)
+                .unwrap_or(false)
+        {
+            bail!(
+                AppError::DeadlineInPast(
+                    item.deadline
+                        .input()
+                        .unwrap_or_default()
+                        .into()
+                )
+            )
+        }
+
+        Ok((deadline, time_zone))
+    }
+
+    // The start date `item` resolves to, held to the same bounds as a
+    // deadline and to falling no later than `deadline`.
+    fn new_start_date(
+        &self,
+        item: &NewTodo,
+        deadline: Option<UnixTime>,
+    ) -> AppResult<Option<UnixTime>>
+    {
+        let start_date = item
+            .start_date
+            .unix_time(
+                item.start_date
+                    .time_zone_or(
+                    self.default_time_zone,
+                )?,
+            )?;
+
+        self.check_deadline_bounds(
+            &item.start_date,
+            start_date,
+        )?;
+
+        deadline_bounds::check_start_date(
+            start_date, deadline,
+        )?;
+
+        Ok(start_date)
     }
 
+    // What a deadline or start date change resolves to, checked
+    // against the list's deadline bounds.
+    fn date_update(
+        &self,
+        change: &DeadlineChange,
+    ) -> AppResult<DateUpdate> {
+        let date_update = change
+            .resolved(
+                self.default_time_zone,
+            )?;
+
+        if let DeadlineChange::Set(
+            input,
+        ) = change
+        {
+            self.check_deadline_bounds(
+                input,
+                date_update
+                    .flatten()
+                    .map(|(d, _)| d),
+            )?;
+        }
+
+        Ok(date_update)
+    }
+
+    // Checks that `todo` would start no later than it is due once the
+    // given date updates are made.
+    fn check_rescheduled(
+        todo: &Todo,
+        deadline_update: &DateUpdate,
+        start_date_update: &DateUpdate,
+    ) -> AppResult<()> {
+        if deadline_update.is_none()
+            && start_date_update
+                .is_none()
+        {
+            return Ok(());
+        }
+
+        let date =
+            |update: &DateUpdate,
+             current| {
+                update
+                    .map(|u| {
+                        u.map(
+                            |(d, _)| d,
+                        )
+                    })
+                    .unwrap_or(current)
+            };
+
+        deadline_bounds::check_start_date(
+            date(
+                start_date_update,
+                todo.start_date,
+            ),
+            date(
+                deadline_update,
+                todo.deadline,
+            ),
+        )
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%id), err)
+    )]
     pub fn update(
         &mut self,
         id: Uuid,
         change: &UpdateTodo,
     ) -> AppResult<Todo> {
-        if change.change_is_present() {
-            let deadline_update =
-                change
-                    .deadline
-                    .unix_time()?;
+        self.ensure_writable()?;
 
-            if let Some(todo) =
-                self.0.get_mut(&id)
+        if change.change_is_present() {
+            let deadline_update = self
+                .date_update(
+                    &change.deadline,
+                )?;
+
+            let start_date_update =
+                self.date_update(
+                    &change.start_date,
+                )?;
+
+            if let Some(mut todo) = self
+                .items
+                .get(&id)
+                .cloned()
             {
+                Self::check_rescheduled(
+                    &todo,
+                    &deadline_update,
+                    &start_date_update,
+                )?;
+
                 let mut modified =
                     false;
 
@@ -226,7 +1055,9 @@ impl TodoList {
                     if todo.title
                         != title_update
                     {
-                        todo.title = title_update;
+                        todo.set_title(
+                            title_update,
+                        );
                         modified = true;
                     }
                 }
@@ -249,23 +1080,132 @@ impl TodoList {
                         != status_update
                     {
                         todo.status = status_update;
+                        todo.completed_timestamp = (status_update
+                            == Status::Done)
+                            .then(|| {
+                                unix_time_now!()
+                            });
                         modified = true;
                     }
                 }
 
-                if todo.deadline
-                    != deadline_update
+                if let Some(
+                    deadline_update,
+                ) = deadline_update
                 {
-                    todo.deadline =
-                        deadline_update;
-                    modified = true;
+                    let deadline =
+                        deadline_update
+                            .map(|(d, _)| d);
+
+                    if todo.deadline
+                        != deadline
+                    {
+                        todo.deadline =
+                            deadline;
+                        modified = true;
+                    }
+
+                    if let Some((
+                        _,
+                        time_zone,
+                    )) =
+                        deadline_update
+                    {
+                        if todo
+                            .time_zone
+                            != time_zone
+                        {
+                            todo.time_zone = time_zone;
+                            modified =
+                                true;
+                        }
+                    }
+                }
+
+                if let Some(
+                    color_update,
+                ) = &change.color
+                {
+                    let color_update =
+                        color_update
+                            .validated(
+                            )?;
+
+                    if todo.color
+                        != color_update
+                    {
+                        todo.color =
+                            color_update;
+                        modified = true;
+                    }
+                }
+
+                if let Some(
+                    icon_update,
+                ) = &change.icon
+                {
+                    let icon_update =
+                        icon_update
+                            .validated(
+                            )?;
+
+                    if todo.icon
+                        != icon_update
+                    {
+                        todo.icon =
+                            icon_update;
+                        modified = true;
+                    }
+                }
+
+                if let Some(
+                    start_date_update,
+                ) = start_date_update
+                {
+                    let start_date =
+                        start_date_update
+                            .map(|(d, _)| d);
+
+                    if todo.start_date
+                        != start_date
+                    {
+                        todo.start_date =
+                            start_date;
+                        modified = true;
+                    }
                 }
 
                 if modified {
-                    todo.updated_timestamp = unix_time_now!();
+                    todo.updated_timestamp = time::advanced(
+                        todo.updated_timestamp,
+                        unix_time_now!(),
+                    );
+                    todo.last_modified_by =
+                        self.actor.clone();
+
+                    journal!(
+                        self,
+                        LogEntry::Put(
+                            todo.clone(
+                            )
+                        )
+                    );
+
+                    let before = self
+                        .items
+                        .insert(
+                            todo.clone(
+                            ),
+                        );
+                    self.bump_seq(id);
+
+                    self.record_changes(
+                        before.as_ref(),
+                        &todo,
+                    );
                 }
 
-                Ok(todo.clone())
+                Ok(todo)
             } else {
                 bail!(
                     AppError::TodoNotFound(id)
@@ -282,15 +1222,39 @@ impl TodoList {
         &'a self,
         query: &'a Query,
         deadline: &'a Option<UnixTime>,
-    ) -> impl Iterator<Item = &Todo>
+        title_regex: &'a Option<Regex>,
+    ) -> impl Iterator<Item = &'a Todo>
+    {
+        Self::filter_in(
+            self.items.as_ref(),
+            query,
+            deadline,
+            title_regex,
+        )
+    }
+
+    fn filter_in<'a>(
+        items: &'a dyn TodoStore,
+        query: &'a Query,
+        deadline: &'a Option<UnixTime>,
+        title_regex: &'a Option<Regex>,
+    ) -> impl Iterator<Item = &'a Todo>
     {
-        self.0
+        let now = unix_time_now!();
+
+        items
             .values()
             .filter(move |t| {
                 query.match_keyword(t) &&
+                Query::match_title_regex(title_regex, t) &&
                 query.match_priority(t) &&
                 query.match_status(t) &&
-                Query::match_deadline(deadline, t)
+                query.match_not_keyword(t) &&
+                query.match_exclusions(t) &&
+                query.match_expr(now, t) &&
+                Query::match_deadline(deadline, t) &&
+                query.match_availability(now, t) &&
+                query.match_snoozed(now, t)
             })
     }
 
@@ -298,16 +1262,76 @@ impl TodoList {
         &self,
         query: &Query,
     ) -> AppResult<Vec<Todo>> {
-        let deadline = query
-            .deadline()
-            .unix_time()?;
+        self.search_with_total(query)
+            .map(|result| result.items)
+    }
 
-        let top_n = query
-            .limit()
-            .validated()?;
+    /// Like `search`, but also counts every match beyond the limit.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(predicates = query.predicate_count()),
+            err
+        )
+    )]
+    pub fn search_with_total(
+        &self,
+        query: &Query,
+    ) -> AppResult<SearchResult> {
+        self.search_in(
+            self.items.as_ref(),
+            query,
+        )
+    }
 
-        let sort =
-            SortBy::from(query.sort());
+    // `search_with_total` over `items`, which a pinned view may have
+    // kept from before later changes.
+    fn search_in(
+        &self,
+        items: &dyn TodoStore,
+        query: &Query,
+    ) -> AppResult<SearchResult> {
+        let deadline = query
+            .deadline()
+            .unix_time(
+                self.default_time_zone,
+            )?;
+
+        let title_regex = query
+            .compiled_title_regex()?;
+
+        let top_n = self.result_limit(
+            query.limit(),
+        )?;
+
+        let direction =
+            *query.direction();
+
+        let sort = SortKey::from(
+            query.sort(),
+            direction,
+            self.title_collation,
+        );
+
+        let after = query
+            .after()
+            .as_ref()
+            .map(|cursor| {
+                Cursor::decode(
+                    cursor,
+                    query.sort(),
+                    direction,
+                    self.title_collation,
+                )
+                .map(|c| {
+                    SortKey::directed(
+                        c.key, direction,
+                    )
+                })
+            })
+            .transpose()?;
 
         let mut heap =
             BinaryHeap::with_capacity_by_key(
@@ -315,15 +1339,23 @@ impl TodoList {
                 &sort
             );
 
-        let mut count: usize = 0;
+        let mut total_matched: usize =
+            0;
 
-        for t in self
-            .filter_by(query, &deadline)
-        {
-            if count < top_n {
+        for t in Self::filter_in(
+            items,
+            query,
+            &deadline,
+            &title_regex,
+        )
+        .filter(|t| {
+            after
+                .as_ref()
+                .map(|a| sort(t) > *a)
+                .unwrap_or(true)
+        }) {
+            if total_matched < top_n {
                 heap.push(t.clone());
-
-                count += 1;
             } else if let Some(
                 mut todo,
             ) =
@@ -334,37 +1366,201 @@ impl TodoList {
                     *todo = t.clone();
                 }
             } else {
-                unreachable!("DEFECT: Heap in `TodoList::search` is empty.");
+                bail!(AppError::Internal(
+                    "search-heap-empty"
+                ))
             }
+
+            total_matched += 1;
         }
 
-        Ok(heap.into_sorted_vec())
+        trace_debug!(
+            total_matched,
+            returned = heap.len(),
+            "searched"
+        );
+
+        let items =
+            heap.into_sorted_vec();
+
+        let next_cursor = self
+            .next_cursor(
+                query,
+                &items,
+                total_matched
+                    > items.len(),
+            );
+
+        Ok(SearchResult {
+            items,
+            total_matched,
+            next_cursor,
+        })
+    }
+
+    // Points after the page's last item when more matches follow it.
+    fn next_cursor(
+        &self,
+        query: &Query,
+        items: &[Todo],
+        more: bool,
+    ) -> Option<String> {
+        items
+            .last()
+            .filter(|_| more)
+            .map(|t| {
+                Cursor {
+                    key: SortBy::from(
+                        query.sort(),
+                        self.title_collation,
+                    )(
+                        t
+                    ),
+                    id: t.id,
+                }
+                .encode(
+                    query.sort(),
+                    *query.direction(),
+                    self.title_collation,
+                )
+            })
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(predicates = query.predicate_count()),
+            err
+        )
+    )]
     pub fn count_by(
         &self,
         query: &Query,
     ) -> AppResult<usize> {
         let deadline = query
             .deadline()
-            .unix_time()?;
+            .unix_time(
+                self.default_time_zone,
+            )?;
+
+        let title_regex = query
+            .compiled_title_regex()?;
 
         let count = self
-            .filter_by(query, &deadline)
+            .filter_by(
+                query,
+                &deadline,
+                &title_regex,
+            )
             .count();
 
+        trace_debug!(count, "counted");
+
         Ok(count)
     }
 
     pub fn count_all(&self) -> usize {
-        self.0.len()
+        self.items.len()
+    }
+
+    /// One page of every item, ordered by title under the list's
+    /// collation and then id like a search, for clients that want
+    /// everything without building a `Query`. The limit is capped like a
+    /// query's.
+    pub fn list_page(
+        &self,
+        offset: usize,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Vec<Todo>> {
+        self.page_in(
+            self.items.as_ref(),
+            offset,
+            limit,
+        )
+    }
+
+    fn page_in(
+        &self,
+        items: &dyn TodoStore,
+        offset: usize,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Vec<Todo>> {
+        let limit =
+            self.result_limit(limit)?;
+
+        let mut all: Vec<_> =
+            items.values().collect();
+
+        let collation =
+            self.title_collation;
+        all.sort_by(|a, b| {
+            (
+                a.title_key(collation),
+                a.id,
+            )
+                .cmp(&(
+                    b.title_key(
+                        collation,
+                    ),
+                    b.id,
+                ))
+        });
+
+        Ok(all
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    // Applies `change` to a copy of the item, which is stored only when
+    // `change` succeeds and reports that something changed.
+    fn change_item(
+        &mut self,
+        id: Uuid,
+        change: impl FnOnce(
+            &mut Todo,
+        )
+            -> AppResult<
+            bool,
+        >,
+    ) -> AppResult<Todo> {
+        self.ensure_writable()?;
+
+        let mut todo = self.get(id)?;
+
+        if change(&mut todo)? {
+            todo.updated_timestamp =
+                time::advanced(
+                    todo.updated_timestamp,
+                    unix_time_now!(),
+                );
+            todo.last_modified_by =
+                self.actor.clone();
+
+            journal!(
+                self,
+                LogEntry::Put(
+                    todo.clone()
+                )
+            );
+
+            self.items
+                .insert(todo.clone());
+            self.bump_seq(id);
+        }
+
+        Ok(todo)
     }
 
     pub fn get(
         &self,
         id: Uuid,
     ) -> AppResult<Todo> {
-        self.0
+        self.items
             .get(&id)
             .cloned()
             .ok_or_else(|| {
@@ -378,16 +1574,43 @@ impl TodoList {
         &mut self,
         id: Uuid,
     ) -> AppResult<()> {
-        self.0
-            .remove(&id)
+        self.delete_returning(id)
             .map(|_| ())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(%id), err)
+    )]
+    pub fn delete_returning(
+        &mut self,
+        id: Uuid,
+    ) -> AppResult<Todo> {
+        self.ensure_writable()?;
+
+        if self.items.contains_key(&id)
+        {
+            journal!(
+                self,
+                LogEntry::Remove(id)
+            );
+        }
+
+        let removed = self
+            .items
+            .remove(&id)
             .ok_or_else(|| {
                 report!(
                     AppError::TodoNotFound(id)
                 )
-            })
+            })?;
+
+        self.record_deletions([id]);
+
+        Ok(removed)
     }
 
+    // Removed items come back in no particular order.
     fn delete_by<T>(
         &mut self,
         targets: &NESet<T>,
@@ -396,69 +1619,329 @@ impl TodoList {
             &NESet<T>,
         )
             -> bool,
-    ) -> usize {
-        let mut count = 0;
-
-        self.0.retain(|_, item| {
-            !{
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
+        let ids: Vec<_> = self
+            .items
+            .values()
+            .filter(|item| {
                 should_delete(
                     item, targets,
                 )
-            } || {
-                count += 1;
-                false
-            }
-        });
+            })
+            .map(|item| item.id)
+            .collect();
+
+        self.remove_all(&ids, dry_run)
+    }
+
+    // A dry run only looks the items up, so it works on a frozen list.
+    fn remove_all(
+        &mut self,
+        ids: &[Uuid],
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
+        if dry_run {
+            return Ok(ids
+                .iter()
+                .filter_map(|id| {
+                    self.items
+                        .get(id)
+                        .cloned()
+                })
+                .collect());
+        }
 
-        count
+        self.ensure_writable()?;
+
+        let mut removed = vec![];
+
+        // Items removed before an entry that cannot be logged stay
+        // removed, as their entries were logged.
+        let logged = ids.iter().try_for_each(
+            |id| -> AppResult<()> {
+                if self
+                    .items
+                    .contains_key(id)
+                {
+                    journal!(
+                        self,
+                        LogEntry::Remove(
+                            *id
+                        )
+                    );
+                }
+
+                removed.extend(
+                    self.items.remove(id),
+                );
+
+                Ok(())
+            },
+        );
+
+        self.record_deletions(
+            removed
+                .iter()
+                .map(|t| t.id),
+        );
+
+        logged.map(|()| removed)
     }
 
     pub fn delete_by_ids(
         &mut self,
         targets: &NESet<Uuid>,
-    ) -> usize {
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        self.delete_by_ids_returning(
+            targets, dry_run,
+        )
+        .map(|deleted| deleted.len())
+    }
+
+    pub fn delete_by_ids_returning(
+        &mut self,
+        targets: &NESet<Uuid>,
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
         self.delete_by(
             targets,
             Todo::is_in_id_set,
+            dry_run,
         )
     }
 
     pub fn delete_by_priorities(
         &mut self,
         targets: &NESet<Priority>,
-    ) -> usize {
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        self.delete_by_priorities_returning(
+            targets, dry_run,
+        )
+        .map(|deleted| deleted.len())
+    }
+
+    pub fn delete_by_priorities_returning(
+        &mut self,
+        targets: &NESet<Priority>,
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
         self.delete_by(
             targets,
             Todo::is_in_priority_set,
+            dry_run,
         )
     }
 
     pub fn delete_by_statuses(
         &mut self,
         targets: &NESet<Status>,
-    ) -> usize {
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        self.delete_by_statuses_returning(
+            targets, dry_run,
+        )
+        .map(|deleted| deleted.len())
+    }
+
+    pub fn delete_by_statuses_returning(
+        &mut self,
+        targets: &NESet<Status>,
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
         self.delete_by(
             targets,
             Todo::is_in_status_set,
+            dry_run,
         )
     }
 
     pub fn delete_by_status(
         &mut self,
         target: &Status,
-    ) -> usize {
-        self.delete_by_statuses(&nes![
-            *target
-        ])
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        self.delete_by_status_returning(
+            target, dry_run,
+        )
+        .map(|deleted| deleted.len())
     }
 
+    pub fn delete_by_status_returning(
+        &mut self,
+        target: &Status,
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
+        self.delete_by_statuses_returning(
+            &nes![*target],
+            dry_run,
+        )
+    }
+
+    /// Deletes every item matching the query's predicates; its sort and
+    /// limit are ignored.
+    pub fn delete_where(
+        &mut self,
+        query: &Query,
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        self.delete_where_returning(
+            query, dry_run,
+        )
+        .map(|deleted| deleted.len())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(predicates = query.predicate_count(), dry_run),
+            err
+        )
+    )]
+    pub fn delete_where_returning(
+        &mut self,
+        query: &Query,
+        dry_run: bool,
+    ) -> AppResult<Vec<Todo>> {
+        let ids =
+            self.ids_where(query)?;
+
+        trace_debug!(
+            matched = ids.len(),
+            "deleting"
+        );
+
+        self.remove_all(&ids, dry_run)
+    }
+
+    /// Applies the same change to every item matching the query's
+    /// predicates and returns how many matched.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(predicates = query.predicate_count(), dry_run),
+            err
+        )
+    )]
+    pub fn update_where(
+        &mut self,
+        query: &Query,
+        change: &UpdateTodo,
+        dry_run: bool,
+    ) -> AppResult<usize> {
+        if !dry_run {
+            self.ensure_writable()?;
+        }
+
+        if !change.change_is_present() {
+            bail!(
+                AppError::UpdateHasNoChanges
+            )
+        }
+
+        let ids =
+            self.ids_where(query)?;
+
+        trace_debug!(
+            matched = ids.len(),
+            "updating"
+        );
+
+        if !dry_run {
+            for id in &ids {
+                self.update(
+                    *id, change,
+                )?;
+            }
+        }
+
+        Ok(ids.len())
+    }
+
+    pub(super) fn ids_where(
+        &self,
+        query: &Query,
+    ) -> AppResult<Vec<Uuid>> {
+        let deadline = query
+            .deadline()
+            .unix_time(
+                self.default_time_zone,
+            )?;
+
+        let title_regex = query
+            .compiled_title_regex()?;
+
+        let ids = self
+            .filter_by(
+                query,
+                &deadline,
+                &title_regex,
+            )
+            .map(|t| t.id)
+            .collect();
+
+        Ok(ids)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all
+        )
+    )]
     pub fn delete_all(
         &mut self,
-    ) -> usize {
+    ) -> AppResult<usize> {
+        self.ensure_writable()?;
+
         let count = self.count_all();
 
-        self.0.clear();
+        journal!(self, LogEntry::Clear);
+
+        let ids: Vec<_> = self
+            .items
+            .keys()
+            .copied()
+            .collect();
+        self.record_deletions(ids);
+
+        self.items.clear();
+
+        trace_debug!(
+            count,
+            "deleted all"
+        );
+
+        Ok(count)
+    }
+
+    pub fn delete_all_returning(
+        &mut self,
+    ) -> AppResult<Vec<Todo>> {
+        self.ensure_writable()?;
+
+        journal!(self, LogEntry::Clear);
+
+        let removed: Vec<_> = self
+            .items
+            .values()
+            .cloned()
+            .collect();
+        self.items.clear();
+
+        self.record_deletions(
+            removed
+                .iter()
+                .map(|t| t.id),
+        );
 
-        count
+        Ok(removed)
     }
 }
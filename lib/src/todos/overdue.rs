@@ -0,0 +1,114 @@
+use super::{Priority, Todo, TodoList};
+use crate::{
+    core::{UnixTime, SECONDS_PER_DAY},
+    time,
+};
+use getset::Getters;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+const WORST_OFFENDERS: usize = 5;
+
+/// How long ago an overdue item's deadline passed.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    EnumIter,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+)]
+pub enum Lateness {
+    UpToADay,
+    UpToAWeek,
+    UpToAMonth,
+    OverAMonth,
+}
+
+impl Lateness {
+    fn of(late_by: UnixTime) -> Self {
+        match late_by / SECONDS_PER_DAY
+        {
+            0 => Self::UpToADay,
+            1..=6 => Self::UpToAWeek,
+            7..=29 => Self::UpToAMonth,
+            _ => Self::OverAMonth,
+        }
+    }
+}
+
+/// Overdue items counted by lateness and by priority, with every bucket
+/// present even when empty.
+#[derive(
+    Clone, Debug, Eq, PartialEq, Getters,
+)]
+#[getset(get = "pub")]
+pub struct OverdueReport {
+    by_lateness: Vec<(Lateness, usize)>,
+
+    by_priority: Vec<(Priority, usize)>,
+
+    /// The latest few, most overdue first.
+    worst: Vec<Todo>,
+}
+
+impl TodoList {
+    /// Summarizes the items in the overdue view as of `now`.
+    pub fn overdue_report(
+        &self,
+        now: UnixTime,
+    ) -> OverdueReport {
+        let overdue =
+            self.view_overdue(now);
+
+        let by_lateness = Lateness::iter()
+            .map(|lateness| {
+                (
+                    lateness,
+                    overdue
+                        .iter()
+                        .filter(|t| {
+                            t.deadline.map(
+                                |d| {
+                                    Lateness::of(
+                                        time::elapsed(
+                                            d, now,
+                                        ),
+                                    )
+                                },
+                            ) == Some(
+                                lateness,
+                            )
+                        })
+                        .count(),
+                )
+            })
+            .collect();
+
+        let by_priority = Priority::iter()
+            .map(|priority| {
+                (
+                    priority,
+                    overdue
+                        .iter()
+                        .filter(|t| {
+                            t.priority
+                                == priority
+                        })
+                        .count(),
+                )
+            })
+            .collect();
+
+        OverdueReport {
+            by_lateness,
+            by_priority,
+            worst: overdue
+                .into_iter()
+                .take(WORST_OFFENDERS)
+                .collect(),
+        }
+    }
+}
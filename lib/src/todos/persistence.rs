@@ -0,0 +1,1133 @@
+use super::{
+    store::UndoLog, CreationDefaults,
+    Snapshot, TimeZone, Todo, TodoList,
+};
+use crate::app_error::{
+    report, AppError, AppResult, Report,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{
+        self, BufRead, BufReader, Write,
+    },
+    path::PathBuf,
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use uuid::Uuid;
+
+const LOG_FILE: &str =
+    "operations.jsonl";
+
+/// How `FileLog` stores its snapshot. The binary encodings are much
+/// smaller for large lists; the log stays JSON lines either way, so a
+/// torn last line can still be told apart.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    EnumIter,
+)]
+pub enum SnapshotEncoding {
+    #[default]
+    Json,
+
+    Cbor,
+
+    MessagePack,
+}
+
+impl SnapshotEncoding {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Json => {
+                "snapshot.json"
+            }
+            Self::Cbor => {
+                "snapshot.cbor"
+            }
+            Self::MessagePack => {
+                "snapshot.msgpack"
+            }
+        }
+    }
+
+    fn encode(
+        self,
+        snapshot: &Snapshot,
+    ) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Json => {
+                Ok(serde_json::to_vec(
+                    snapshot,
+                )?)
+            }
+            Self::Cbor => {
+                let mut bytes =
+                    Vec::new();
+
+                ciborium::into_writer(
+                    snapshot,
+                    &mut bytes,
+                )
+                .map_err(
+                    invalid_data,
+                )?;
+
+                Ok(bytes)
+            }
+            // Named, as a field that defaults
+            // when missing cannot be positional.
+            Self::MessagePack => {
+                rmp_serde::to_vec_named(
+                    snapshot,
+                )
+                .map_err(invalid_data)
+            }
+        }
+    }
+
+    fn decode(
+        self,
+        bytes: &[u8],
+    ) -> io::Result<Snapshot> {
+        match self {
+            Self::Json => Ok(
+                serde_json::from_slice(
+                    bytes,
+                )?,
+            ),
+            Self::Cbor => {
+                ciborium::from_reader(
+                    bytes,
+                )
+                .map_err(invalid_data)
+            }
+            Self::MessagePack => {
+                rmp_serde::from_slice(
+                    bytes,
+                )
+                .map_err(invalid_data)
+            }
+        }
+    }
+}
+
+fn unwritable(
+    e: io::Error,
+) -> Report<AppError> {
+    report!(
+        AppError::OperationLogUnwritable(
+            e.to_string()
+        )
+    )
+}
+
+fn invalid_data(
+    e: impl ToString,
+) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        e.to_string(),
+    )
+}
+
+/// One change to a list, recorded before it is applied. Only what a
+/// `Snapshot` holds is recorded; see `TodoList::recover`.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogEntry {
+    Put(Todo),
+
+    Remove(Uuid),
+
+    Archive(Todo),
+
+    Clear,
+
+    SetDefaultTimeZone(TimeZone),
+
+    SetCreationDefaults(
+        CreationDefaults,
+    ),
+}
+
+/// Durable storage for a list: the latest snapshot plus every entry
+/// appended since it was taken.
+pub trait OperationLog: Send {
+    fn load(
+        &mut self,
+    ) -> io::Result<(
+        Option<Snapshot>,
+        Vec<LogEntry>,
+    )>;
+
+    fn append(
+        &mut self,
+        entry: &LogEntry,
+    ) -> io::Result<()>;
+
+    /// Replaces the stored snapshot and drops the entries it covers.
+    fn compact(
+        &mut self,
+        snapshot: &Snapshot,
+    ) -> io::Result<()>;
+}
+
+/// Keeps a snapshot and `operations.jsonl` in one directory.
+pub struct FileLog {
+    dir: PathBuf,
+
+    log: File,
+
+    encoding: SnapshotEncoding,
+}
+
+impl FileLog {
+    pub fn open(
+        dir: impl Into<PathBuf>,
+    ) -> io::Result<Self> {
+        Self::open_with_encoding(
+            dir,
+            SnapshotEncoding::default(),
+        )
+    }
+
+    /// Snapshots are written in `encoding`. One left in another
+    /// encoding is still read, and replaced at the next compaction.
+    pub fn open_with_encoding(
+        dir: impl Into<PathBuf>,
+        encoding: SnapshotEncoding,
+    ) -> io::Result<Self> {
+        let dir = dir.into();
+
+        fs::create_dir_all(&dir)?;
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE))?;
+
+        Ok(Self { dir, log, encoding })
+    }
+}
+
+impl OperationLog for FileLog {
+    fn load(
+        &mut self,
+    ) -> io::Result<(
+        Option<Snapshot>,
+        Vec<LogEntry>,
+    )> {
+        let mut snapshot = None;
+
+        for encoding in [self.encoding]
+            .into_iter()
+            .chain(
+                SnapshotEncoding::iter(
+                ),
+            )
+        {
+            match fs::read(
+                self.dir.join(
+                    encoding.file_name(),
+                ),
+            ) {
+                Ok(bytes) => {
+                    snapshot = Some(
+                        encoding.decode(
+                            &bytes,
+                        )?,
+                    );
+                    break;
+                }
+                Err(e)
+                    if e.kind()
+                        == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let lines: Vec<_> =
+            BufReader::new(File::open(
+                self.dir.join(LOG_FILE),
+            )?)
+            .lines()
+            .collect::<io::Result<_>>(
+            )?;
+
+        let mut entries = Vec::new();
+
+        for (i, line) in
+            lines.iter().enumerate()
+        {
+            match serde_json::from_str(
+                line,
+            ) {
+                Ok(entry) => {
+                    entries.push(entry)
+                }
+                // A crash mid-append can leave a torn last line.
+                Err(_)
+                    if i + 1
+                        == lines
+                            .len() => {}
+                Err(e) => {
+                    return Err(e.into())
+                }
+            }
+        }
+
+        Ok((snapshot, entries))
+    }
+
+    fn append(
+        &mut self,
+        entry: &LogEntry,
+    ) -> io::Result<()> {
+        let mut line =
+            serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        self.log.write_all(&line)?;
+        self.log.sync_data()
+    }
+
+    fn compact(
+        &mut self,
+        snapshot: &Snapshot,
+    ) -> io::Result<()> {
+        let name =
+            self.encoding.file_name();
+        let tmp = self.dir.join(
+            format!("{name}.tmp"),
+        );
+
+        let mut file =
+            File::create(&tmp)?;
+        file.write_all(
+            &self
+                .encoding
+                .encode(snapshot)?,
+        )?;
+        file.sync_all()?;
+
+        fs::rename(
+            tmp,
+            self.dir.join(name),
+        )?;
+
+        // Otherwise a later switch back
+        // could load this stale one.
+        for other in
+            SnapshotEncoding::iter()
+                .filter(|e| {
+                    *e != self.encoding
+                })
+        {
+            match fs::remove_file(
+                self.dir.join(
+                    other.file_name(),
+                ),
+            ) {
+                Err(e)
+                    if e.kind()
+                        != io::ErrorKind::NotFound =>
+                {
+                    return Err(e)
+                }
+                _ => {}
+            }
+        }
+
+        self.log.set_len(0)?;
+        self.log.sync_all()
+    }
+}
+
+pub(super) struct Persistence {
+    log: Box<dyn OperationLog>,
+
+    compact_every: usize,
+
+    since_compaction: usize,
+}
+
+impl TodoList {
+    /// Rebuilds the list from `log`'s snapshot and entries, then logs
+    /// every later change there, compacting after `compact_every`
+    /// entries. A change whose entry cannot be written fails before it
+    /// is applied, so the list and its log still agree.
+    ///
+    /// Only what a `Snapshot` holds is logged. The other settings,
+    /// saved queries and templates are the host's configuration rather
+    /// than list data, and change shape with `ListSettings::VERSION`
+    /// where the log has to stay readable across versions, so a host
+    /// applies them again after `recover`.
+    pub fn recover(
+        &mut self,
+        mut log: impl OperationLog + 'static,
+        compact_every: usize,
+    ) -> AppResult<()> {
+        let (snapshot, entries) =
+            log.load().map_err(|e| {
+                report!(
+                    AppError::OperationLogUnreadable(
+                        e.to_string()
+                    )
+                )
+            })?;
+
+        if let Some(snapshot) = snapshot
+        {
+            self.restore(snapshot)?;
+        }
+
+        for entry in entries {
+            self.replay(entry);
+        }
+
+        // Replayed changes take no sequence numbers to go stale by.
+        self.query_cache.clear();
+        self.pinned_views
+            .stop_sharing();
+
+        self.persistence = Some(
+            Persistence {
+                log: Box::new(log),
+                compact_every:
+                    compact_every.max(1),
+                since_compaction: 0,
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn compact_log(
+        &mut self,
+    ) -> AppResult<()> {
+        let snapshot = self.snapshot();
+
+        self.compact_log_to(&snapshot)
+    }
+
+    // Replaces the log with `snapshot`, which the list holds or is
+    // about to.
+    pub(super) fn compact_log_to(
+        &mut self,
+        snapshot: &Snapshot,
+    ) -> AppResult<()> {
+        if let Some(persistence) =
+            &mut self.persistence
+        {
+            persistence
+                .log
+                .compact(snapshot)
+                .map_err(unwritable)?;
+            persistence
+                .since_compaction = 0;
+
+            self.record_snapshot(
+                unix_time_now!(),
+            );
+        }
+
+        Ok(())
+    }
+
+    // Compacts first when due, so the snapshot holds every change
+    // logged so far but not this one.
+    pub(super) fn journal(
+        &mut self,
+        entry: LogEntry,
+    ) -> AppResult<()> {
+        let due = self
+            .persistence
+            .as_ref()
+            .map(|p| {
+                p.since_compaction
+                    >= p.compact_every
+            })
+            .unwrap_or(false);
+
+        if due {
+            self.compact_log()?;
+        }
+
+        if let Some(persistence) =
+            &mut self.persistence
+        {
+            persistence
+                .log
+                .append(&entry)
+                .map_err(unwritable)?;
+            persistence
+                .since_compaction += 1;
+        }
+
+        Ok(())
+    }
+
+    // What each item a batch touched ended up as, in id order.
+    pub(super) fn journal_touched(
+        &mut self,
+        undo: &UndoLog,
+    ) -> AppResult<()> {
+        for id in undo.touched() {
+            let entry = match self
+                .items
+                .get(&id)
+            {
+                Some(todo) => {
+                    LogEntry::Put(
+                        todo.clone(),
+                    )
+                }
+                None => {
+                    LogEntry::Remove(id)
+                }
+            };
+
+            self.journal(entry)?;
+        }
+
+        Ok(())
+    }
+
+    fn replay(
+        &mut self,
+        entry: LogEntry,
+    ) {
+        match entry {
+            LogEntry::Put(t) => {
+                self.note_short_id(
+                    t.short_id,
+                );
+                self.items.insert(t);
+            }
+            LogEntry::Remove(id) => {
+                self.items.remove(&id);
+            }
+            LogEntry::Archive(t) => {
+                self.note_short_id(
+                    t.short_id,
+                );
+                self.items.remove(&t.id);
+                self.archived
+                    .insert(t.id, t);
+            }
+            LogEntry::Clear => {
+                self.items.clear()
+            }
+            LogEntry::SetDefaultTimeZone(
+                time_zone,
+            ) => {
+                self.default_time_zone =
+                    time_zone
+            }
+            LogEntry::SetCreationDefaults(
+                defaults,
+            ) => {
+                self.creation_defaults =
+                    defaults
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        BatchCommand, CreationDefaults,
+        ImportFormat, ImportOptions,
+        MergeStrategy, NewTodo,
+        Priority, Status, Title,
+        UpdateTodo,
+    };
+    use pretty_assertions::assert_eq;
+    use std::sync::{Arc, Mutex};
+    use test_case::test_case;
+
+    #[derive(Clone, Default)]
+    struct MemoryLog(
+        Arc<
+            Mutex<(
+                Option<Snapshot>,
+                Vec<LogEntry>,
+            )>,
+        >,
+    );
+
+    impl OperationLog for MemoryLog {
+        fn load(
+            &mut self,
+        ) -> io::Result<(
+            Option<Snapshot>,
+            Vec<LogEntry>,
+        )> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .clone())
+        }
+
+        fn append(
+            &mut self,
+            entry: &LogEntry,
+        ) -> io::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .1
+                .push(entry.clone());
+
+            Ok(())
+        }
+
+        fn compact(
+            &mut self,
+            snapshot: &Snapshot,
+        ) -> io::Result<()> {
+            *self.0.lock().unwrap() = (
+                Some(snapshot.clone()),
+                vec![],
+            );
+
+            Ok(())
+        }
+    }
+
+    // A `MemoryLog` that fails once its writes run out, like a full
+    // disk.
+    #[derive(Clone)]
+    struct FullLog {
+        log: MemoryLog,
+
+        appends_left: usize,
+
+        compacts_left: usize,
+    }
+
+    impl FullLog {
+        fn new(
+            appends_left: usize,
+            compacts_left: usize,
+        ) -> Self {
+            Self {
+                log: MemoryLog::default(
+                ),
+                appends_left,
+                compacts_left,
+            }
+        }
+    }
+
+    fn use_up(
+        left: &mut usize,
+    ) -> io::Result<()> {
+        *left = left
+            .checked_sub(1)
+            .ok_or_else(|| {
+                io::Error::other(
+                    "no space left",
+                )
+            })?;
+
+        Ok(())
+    }
+
+    impl OperationLog for FullLog {
+        fn load(
+            &mut self,
+        ) -> io::Result<(
+            Option<Snapshot>,
+            Vec<LogEntry>,
+        )> {
+            self.log.load()
+        }
+
+        fn append(
+            &mut self,
+            entry: &LogEntry,
+        ) -> io::Result<()> {
+            use_up(
+                &mut self.appends_left,
+            )?;
+
+            self.log.append(entry)
+        }
+
+        fn compact(
+            &mut self,
+            snapshot: &Snapshot,
+        ) -> io::Result<()> {
+            use_up(
+                &mut self.compacts_left,
+            )?;
+
+            self.log.compact(snapshot)
+        }
+    }
+
+    fn unwritable<
+        T: std::fmt::Debug,
+    >(
+        result: AppResult<T>,
+    ) -> bool {
+        matches!(
+            result
+                .unwrap_err()
+                .current_context(),
+            AppError::OperationLogUnwritable(
+                _
+            )
+        )
+    }
+
+    fn add(
+        todos: &mut TodoList,
+        title: &str,
+    ) -> Todo {
+        todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap()
+    }
+
+    fn all(
+        todos: &TodoList,
+    ) -> Vec<Todo> {
+        todos.snapshot().items().clone()
+    }
+
+    #[test]
+    fn recover_should_replay_logged_changes(
+    ) {
+        let log = MemoryLog::default();
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 100)
+            .unwrap();
+
+        let a = add(&mut todos, "a");
+        let b = add(&mut todos, "b");
+        todos
+            .update(
+                a.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+        todos.delete(b.id).unwrap();
+
+        let mut recovered =
+            TodoList::new();
+        recovered
+            .recover(log, 100)
+            .unwrap();
+
+        assert_eq!(
+            all(&recovered),
+            all(&todos)
+        );
+    }
+
+    #[test]
+    fn journal_should_compact_into_a_snapshot_when_due(
+    ) {
+        let log = MemoryLog::default();
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 2)
+            .unwrap();
+
+        for title in ["a", "b", "c"] {
+            add(&mut todos, title);
+        }
+
+        let (snapshot, entries) = log
+            .0
+            .lock()
+            .unwrap()
+            .clone();
+
+        assert_eq!(
+            snapshot.map(|s| s
+                .items()
+                .len()),
+            Some(2)
+        );
+        assert_eq!(entries.len(), 1);
+
+        let mut recovered =
+            TodoList::new();
+        recovered
+            .recover(log, 2)
+            .unwrap();
+
+        assert_eq!(
+            all(&recovered),
+            all(&todos)
+        );
+    }
+
+    #[test]
+    fn apply_batch_should_log_nothing_when_it_fails(
+    ) {
+        let log = MemoryLog::default();
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 100)
+            .unwrap();
+
+        let _ =
+            todos.apply_batch(vec![
+            BatchCommand::Add(
+                NewTodo::builder()
+                    .title(Title::new(
+                        "a",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            ),
+            BatchCommand::Delete(
+                Uuid::nil(),
+            ),
+        ]);
+
+        assert_eq!(
+            log.0.lock().unwrap().1,
+            vec![]
+        );
+    }
+
+    #[test_case(MergeStrategy::Replace ; "replace")]
+    #[test_case(MergeStrategy::OverwriteExisting ; "overwrite existing")]
+    fn recover_should_replay_a_strict_import(
+        merge: MergeStrategy,
+    ) {
+        let log = MemoryLog::default();
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 100)
+            .unwrap();
+
+        add(&mut todos, "Read a book");
+        add(&mut todos, "Call mom");
+
+        todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "(A) Read a book\nWater plants\n",
+                &ImportOptions::builder()
+                    .strict(true)
+                    .merge(merge)
+                    .build(),
+            )
+            .unwrap();
+
+        let mut recovered =
+            TodoList::new();
+        recovered
+            .recover(log, 100)
+            .unwrap();
+
+        assert_eq!(
+            all(&recovered),
+            all(&todos)
+        );
+    }
+
+    #[test_case(SnapshotEncoding::Json ; "json")]
+    #[test_case(SnapshotEncoding::Cbor ; "cbor")]
+    #[test_case(SnapshotEncoding::MessagePack ; "message pack")]
+    fn snapshot_encoding_should_round_trip(
+        encoding: SnapshotEncoding,
+    ) {
+        let mut todos = TodoList::new();
+        add(&mut todos, "a");
+        add(&mut todos, "b");
+
+        let bytes = encoding
+            .encode(&todos.snapshot())
+            .unwrap();
+
+        let mut restored =
+            TodoList::new();
+        restored
+            .restore(
+                encoding
+                    .decode(&bytes)
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            all(&restored),
+            all(&todos)
+        );
+    }
+
+    #[test]
+    fn snapshot_should_keep_creation_defaults_and_read_older_ones_without(
+    ) {
+        let mut todos = TodoList::new();
+        todos.set_creation_defaults(
+            CreationDefaults::builder()
+                .priority(
+                    Priority::High,
+                )
+                .deadline_after_days(
+                    Some(3),
+                )
+                .build(),
+        ).unwrap();
+
+        let mut json =
+            serde_json::to_value(
+                todos.snapshot(),
+            )
+            .unwrap();
+
+        let mut restored =
+            TodoList::new();
+        restored
+            .restore(
+                serde_json::from_value(
+                    json.clone(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            restored
+                .creation_defaults(),
+            todos.creation_defaults()
+        );
+
+        json.as_object_mut()
+            .unwrap()
+            .remove(
+                "creation-defaults",
+            );
+
+        restored
+            .restore(
+                serde_json::from_value(
+                    json,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            restored
+                .creation_defaults(),
+            CreationDefaults::default()
+        );
+    }
+
+    #[test]
+    fn journal_should_refuse_a_change_it_cannot_log(
+    ) {
+        let mut todos = TodoList::new();
+        todos
+            .recover(
+                FullLog::new(1, 0),
+                100,
+            )
+            .unwrap();
+
+        let a = add(&mut todos, "a");
+
+        assert!(unwritable(
+            todos.add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "b",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+        ));
+        assert!(unwritable(
+            todos.delete(a.id)
+        ));
+        assert!(unwritable(
+            todos.compact_log()
+        ));
+
+        assert_eq!(
+            all(&todos),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn apply_batch_should_undo_a_batch_it_cannot_log_in_full(
+    ) {
+        let log = FullLog::new(1, 1);
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 100)
+            .unwrap();
+
+        let new_todo = |title| {
+            BatchCommand::Add(
+                NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+        };
+
+        assert!(unwritable(
+            todos.apply_batch(vec![
+                new_todo("a"),
+                new_todo("b"),
+            ])
+        ));
+        assert_eq!(all(&todos), vec![]);
+
+        let mut recovered =
+            TodoList::new();
+        recovered
+            .recover(log.log, 100)
+            .unwrap();
+
+        assert_eq!(
+            all(&recovered),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn restore_should_change_nothing_when_the_log_cannot_be_rewritten(
+    ) {
+        let mut other = TodoList::new();
+        add(&mut other, "b");
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(
+                FullLog::new(1, 0),
+                100,
+            )
+            .unwrap();
+        let a = add(&mut todos, "a");
+
+        assert!(unwritable(
+            todos.restore(
+                other.snapshot()
+            )
+        ));
+        assert_eq!(
+            all(&todos),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn recover_should_bring_back_creation_defaults_but_not_other_settings(
+    ) {
+        let log = MemoryLog::default();
+
+        let mut todos = TodoList::new();
+        todos
+            .recover(log.clone(), 100)
+            .unwrap();
+
+        let defaults =
+            CreationDefaults::builder()
+                .priority(
+                    Priority::High,
+                )
+                .build();
+        todos
+            .set_creation_defaults(
+                defaults,
+            )
+            .unwrap();
+        todos
+            .set_warn_on_similar(true)
+            .unwrap();
+        todos
+            .save_query(
+                "all",
+                crate::query::Query::empty(),
+            )
+            .unwrap();
+        todos
+            .save_template(
+                "chore",
+                NewTodo::builder()
+                    .title(Title::new(
+                        "chore",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let mut recovered =
+            TodoList::new();
+        recovered
+            .recover(log, 100)
+            .unwrap();
+
+        assert_eq!(
+            recovered
+                .get_settings()
+                .creation_defaults(),
+            defaults
+        );
+        assert!(!recovered
+            .warn_on_similar());
+        assert_eq!(
+            recovered.list_saved(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            recovered.list_templates(),
+            Vec::<String>::new()
+        );
+    }
+}
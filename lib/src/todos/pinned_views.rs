@@ -0,0 +1,280 @@
+use super::{
+    OptionalResultLimit, Query,
+    SearchResult, Todo, TodoList,
+    TodoStore,
+};
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    core::UnixTime,
+    time,
+};
+use chrono::Utc;
+use std::{
+    collections::BTreeMap, sync::Arc,
+};
+
+// Views a list keeps at once; pinning another releases the oldest.
+const MAX_PINNED_VIEWS: usize = 4;
+
+// How long a view stays readable after it is pinned.
+const PINNED_VIEW_SECONDS: UnixTime =
+    600;
+
+// The items as they stood when a view was pinned.
+struct PinnedView {
+    pinned_at: UnixTime,
+
+    items: Arc<dyn TodoStore>,
+}
+
+/// The views `pin_view` handed out, oldest first.
+#[derive(Default)]
+pub(super) struct PinnedViews {
+    next_pin: u64,
+
+    views: BTreeMap<u64, PinnedView>,
+
+    // The latest copy and the change sequence it was taken at, which
+    // a pin taken before the next change shares.
+    latest: Option<(
+        u64,
+        Arc<dyn TodoStore>,
+    )>,
+}
+
+impl PinnedViews {
+    /// Stops new pins sharing the latest copy, for changes made without
+    /// moving the change sequence on, such as a restore.
+    pub(super) fn stop_sharing(
+        &mut self,
+    ) {
+        self.latest = None;
+    }
+}
+
+impl TodoList {
+    /// Pins the items as they stand, so an export paged over several
+    /// calls with `list_page_pinned` or `search_pinned` reads one
+    /// consistent state while changes go on. Pins taken with no change
+    /// in between share a copy. A view lasts ten minutes, until
+    /// `unpin_view`, or until four newer ones push it out.
+    pub fn pin_view(&mut self) -> u64 {
+        let now = unix_time_now!();
+
+        let change_seq =
+            self.change_seq;
+
+        let items = match &self
+            .pinned_views
+            .latest
+        {
+            Some((seq, items))
+                if *seq
+                    == change_seq =>
+            {
+                items.clone()
+            }
+            _ => Arc::from(
+                self.items
+                    .boxed_clone(),
+            ),
+        };
+
+        let pins =
+            &mut self.pinned_views;
+
+        pins.views.retain(|_, v| {
+            !expired(v, now)
+        });
+
+        while pins.views.len()
+            >= MAX_PINNED_VIEWS
+        {
+            pins.views.pop_first();
+        }
+
+        pins.next_pin += 1;
+
+        let pin = pins.next_pin;
+
+        pins.latest = Some((
+            change_seq,
+            items.clone(),
+        ));
+        pins.views.insert(
+            pin,
+            PinnedView {
+                pinned_at: now,
+                items,
+            },
+        );
+
+        trace_debug!(
+            pin,
+            items = pins.views[&pin]
+                .items
+                .len(),
+            "pinned a view"
+        );
+
+        pin
+    }
+
+    /// Releases a view early; false when it was already gone.
+    pub fn unpin_view(
+        &mut self,
+        pin: u64,
+    ) -> bool {
+        self.pinned_views
+            .views
+            .remove(&pin)
+            .is_some()
+    }
+
+    /// `list_page` over a pinned view.
+    pub fn list_page_pinned(
+        &self,
+        pin: u64,
+        offset: usize,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Vec<Todo>> {
+        self.page_in(
+            self.pinned_items(pin)?,
+            offset,
+            limit,
+        )
+    }
+
+    /// `search_with_total` over a pinned view. Follow `next_cursor`
+    /// with the same pin to stream every match as of the pin.
+    pub fn search_pinned(
+        &self,
+        pin: u64,
+        query: &Query,
+    ) -> AppResult<SearchResult> {
+        self.search_in(
+            self.pinned_items(pin)?,
+            query,
+        )
+    }
+
+    fn pinned_items(
+        &self,
+        pin: u64,
+    ) -> AppResult<&dyn TodoStore> {
+        let now = unix_time_now!();
+
+        self.pinned_views
+            .views
+            .get(&pin)
+            .filter(|v| !expired(v, now))
+            .map(|v| v.items.as_ref())
+            .ok_or_else(|| {
+                report!(
+                    AppError::PinnedViewNotFound(
+                        pin
+                    )
+                )
+            })
+    }
+}
+
+fn expired(
+    view: &PinnedView,
+    now: UnixTime,
+) -> bool {
+    time::elapsed(view.pinned_at, now)
+        >= PINNED_VIEW_SECONDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        NewTodo, Priority, Title,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn add(
+        todos: &mut TodoList,
+        title: &str,
+    ) {
+        todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn pin_view_should_share_a_copy_until_an_item_changes(
+    ) {
+        let mut todos = TodoList::new();
+        add(&mut todos, "a");
+
+        let first = todos.pin_view();
+        let second = todos.pin_view();
+
+        add(&mut todos, "b");
+
+        let third = todos.pin_view();
+
+        let items = |pin| {
+            &todos.pinned_views.views
+                [&pin]
+                .items
+        };
+
+        assert!(Arc::ptr_eq(
+            items(first),
+            items(second)
+        ));
+        assert!(!Arc::ptr_eq(
+            items(second),
+            items(third)
+        ));
+    }
+
+    #[test]
+    fn pin_view_should_release_the_oldest_past_the_limit(
+    ) {
+        let mut todos = TodoList::new();
+        add(&mut todos, "a");
+
+        let pins: Vec<_> = (0
+            ..=MAX_PINNED_VIEWS)
+            .map(|_| todos.pin_view())
+            .collect();
+
+        let readable = |pin| {
+            todos
+                .list_page_pinned(
+                    pin,
+                    0,
+                    &OptionalResultLimit::default(),
+                )
+                .is_ok()
+        };
+
+        assert_eq!(
+            pins.iter()
+                .map(|pin| readable(
+                    *pin
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                false, true, true,
+                true, true
+            ]
+        );
+    }
+}
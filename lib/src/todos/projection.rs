@@ -0,0 +1,119 @@
+use super::{
+    Priority, Query, Status, Todo,
+    TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime, sql,
+};
+use getset::{CopyGetters, Getters};
+use uuid::Uuid;
+
+// Variants are in the same order as the WIT `todo-field` enum.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum TodoField {
+    Title,
+    Priority,
+    Status,
+    Deadline,
+}
+
+/// The id of an item plus whichever fields a query projected; the rest
+/// are `None`.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct TodoSummary {
+    #[getset(get_copy = "pub")]
+    id: Uuid,
+
+    #[getset(get = "pub")]
+    title: Option<String>,
+
+    #[getset(get_copy = "pub")]
+    priority: Option<Priority>,
+
+    #[getset(get_copy = "pub")]
+    status: Option<Status>,
+
+    #[getset(get_copy = "pub")]
+    deadline: Option<UnixTime>,
+}
+
+impl TodoSummary {
+    // An empty projection keeps every field.
+    fn project(
+        todo: Todo,
+        fields: &[TodoField],
+    ) -> Self {
+        let keep = |field| {
+            fields.is_empty()
+                || fields
+                    .contains(&field)
+        };
+
+        Self {
+            id: todo.id,
+            title: keep(TodoField::Title)
+                .then_some(todo.title),
+            priority: keep(
+                TodoField::Priority,
+            )
+            .then_some(todo.priority),
+            status: keep(
+                TodoField::Status,
+            )
+            .then_some(todo.status),
+            deadline: todo.deadline.filter(
+                |_| {
+                    keep(
+                        TodoField::Deadline,
+                    )
+                },
+            ),
+        }
+    }
+}
+
+impl TodoList {
+    /// Like `search`, but returns only the fields in the query's
+    /// projection.
+    pub fn search_projected(
+        &self,
+        query: &Query,
+    ) -> AppResult<Vec<TodoSummary>>
+    {
+        let found =
+            self.search(query)?;
+
+        Ok(found
+            .into_iter()
+            .map(|todo| {
+                TodoSummary::project(
+                    todo,
+                    query.projection(),
+                )
+            })
+            .collect())
+    }
+
+    /// Runs a read-only `SELECT` over the live items; see the `sql`
+    /// module for the dialect. Like any search, snoozed items are left
+    /// out and at most `OptionalResultLimit::MAX` rows come back.
+    pub fn query_sql(
+        &self,
+        text: &str,
+    ) -> AppResult<Vec<TodoSummary>>
+    {
+        self.search_projected(
+            &sql::parse(text)?,
+        )
+    }
+}
@@ -0,0 +1,332 @@
+use super::{SearchResult, TodoList};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime, query::Query,
+};
+use chrono::Utc;
+use getset::CopyGetters;
+use std::{
+    collections::VecDeque, iter::Sum,
+};
+
+const DEFAULT_QUERY_CACHE_SIZE: usize =
+    16;
+
+/// How well `search_cached` has done since the list was created.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    CopyGetters,
+)]
+#[getset(get_copy = "pub")]
+pub struct QueryCacheStats {
+    hits: u64,
+
+    misses: u64,
+
+    /// Results held right now.
+    entries: usize,
+
+    capacity: usize,
+}
+
+// Adds up the caches of several lists, for a host's metrics.
+impl Sum for QueryCacheStats {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.fold(
+            Self::default(),
+            |total, stats| Self {
+                hits: total
+                    .hits
+                    .saturating_add(
+                        stats.hits,
+                    ),
+                misses: total
+                    .misses
+                    .saturating_add(
+                        stats.misses,
+                    ),
+                entries: total
+                    .entries
+                    .saturating_add(
+                        stats.entries,
+                    ),
+                capacity: total
+                    .capacity
+                    .saturating_add(
+                        stats.capacity,
+                    ),
+            },
+        )
+    }
+}
+
+// A search's result, good while no item changes and the clock stays
+// short of `expires`.
+struct Entry {
+    fingerprint: String,
+
+    change_seq: u64,
+
+    cached_at: UnixTime,
+
+    expires: Option<UnixTime>,
+
+    result: SearchResult,
+}
+
+/// The latest results of `search_cached`, most recently used first.
+pub(super) struct QueryCache {
+    capacity: usize,
+
+    entries: VecDeque<Entry>,
+
+    hits: u64,
+
+    misses: u64,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self {
+            capacity:
+                DEFAULT_QUERY_CACHE_SIZE,
+            entries: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl QueryCache {
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl TodoList {
+    /// How many search results `search_cached` keeps; 0 turns caching
+    /// off.
+    pub fn query_cache_size(
+        &self,
+    ) -> usize {
+        self.query_cache.capacity
+    }
+
+    /// Shrinking drops the least recently used results.
+    pub fn set_query_cache_size(
+        &mut self,
+        size: usize,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.query_cache.capacity =
+            size;
+        self.query_cache
+            .entries
+            .truncate(size);
+
+        Ok(())
+    }
+
+    pub fn query_cache_stats(
+        &self,
+    ) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.query_cache.hits,
+            misses: self
+                .query_cache
+                .misses,
+            entries: self
+                .query_cache
+                .entries
+                .len(),
+            capacity: self
+                .query_cache
+                .capacity,
+        }
+    }
+
+    /// `search_with_total`, answered from the cache when the same query
+    /// ran since the last change to the items, so a dashboard polling
+    /// one search does not scan the list every time. A result also
+    /// expires once a snooze ends, a start date arrives or a deadline
+    /// passes, since any of those can change what matches.
+    pub fn search_cached(
+        &mut self,
+        query: &Query,
+    ) -> AppResult<SearchResult> {
+        let now = unix_time_now!();
+
+        let fingerprint =
+            self.fingerprint(query)?;
+
+        let change_seq =
+            self.change_seq;
+
+        let cache =
+            &mut self.query_cache;
+
+        let fresh = cache
+            .entries
+            .iter()
+            .position(|e| {
+                e.fingerprint
+                    == fingerprint
+                    && e.change_seq
+                        == change_seq
+                    && e.cached_at
+                        <= now
+                    && e.expires
+                        .map(|t| {
+                            now < t
+                        })
+                        .unwrap_or(true)
+            });
+
+        if let Some(entry) = fresh
+            .and_then(|i| {
+                cache.entries.remove(i)
+            })
+        {
+            cache.hits = cache
+                .hits
+                .saturating_add(1);
+
+            let result =
+                entry.result.clone();
+            cache
+                .entries
+                .push_front(entry);
+
+            return Ok(result);
+        }
+
+        cache.misses = cache
+            .misses
+            .saturating_add(1);
+
+        let result = self
+            .search_with_total(query)?;
+
+        let expires = self
+            .next_time_boundary(now);
+
+        let cache =
+            &mut self.query_cache;
+
+        cache.entries.retain(|e| {
+            e.fingerprint != fingerprint
+        });
+
+        if cache.capacity > 0 {
+            cache.entries.truncate(
+                cache.capacity - 1,
+            );
+            cache.entries.push_front(
+                Entry {
+                    fingerprint,
+                    change_seq,
+                    cached_at: now,
+                    expires,
+                    result: result
+                        .clone(),
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    // Everything besides the items and the clock that a search's result
+    // depends on. The deadline is resolved, so `+1d` differs by the
+    // second.
+    fn fingerprint(
+        &self,
+        query: &Query,
+    ) -> AppResult<String> {
+        let deadline = query
+            .deadline()
+            .unix_time(
+                self.default_time_zone,
+            )?;
+
+        Ok(format!(
+            "{query:?}|{deadline:?}|{:?}|{}",
+            self.title_collation,
+            self.strict_limits
+        ))
+    }
+
+    // The first moment after `now` at which an item's snooze ends, its
+    // start date arrives or its deadline passes.
+    fn next_time_boundary(
+        &self,
+        now: UnixTime,
+    ) -> Option<UnixTime> {
+        self.items
+            .values()
+            .flat_map(|t| {
+                [
+                    t.snoozed_until,
+                    t.start_date,
+                    t.deadline.map(|d| {
+                        d.saturating_add(1)
+                    }),
+                ]
+            })
+            .flatten()
+            .filter(|t| *t > now)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn search_cached_should_stop_counting_at_the_largest_count(
+    ) {
+        let mut todos = TodoList::new();
+        todos.query_cache.hits =
+            u64::MAX;
+        todos.query_cache.misses =
+            u64::MAX;
+
+        for _ in 0..2 {
+            todos
+                .search_cached(
+                    &Query::default(),
+                )
+                .unwrap();
+        }
+
+        let stats =
+            todos.query_cache_stats();
+
+        assert_eq!(
+            (
+                stats.hits(),
+                stats.misses()
+            ),
+            (u64::MAX, u64::MAX)
+        );
+        assert_eq!(
+            [stats, stats]
+                .into_iter()
+                .sum::<QueryCacheStats>(
+                )
+                .hits(),
+            u64::MAX
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use super::{
+    OptionalResultLimit, Todo, TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime,
+};
+use chrono::Utc;
+use std::cmp;
+use uuid::Uuid;
+
+// Only the latest views are kept, which is all `recent` can return.
+const MAX_TRACKED_VIEWS: usize =
+    OptionalResultLimit::MAX as usize;
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum RecentKind {
+    Viewed,
+    Modified,
+    Created,
+}
+
+impl TodoList {
+    pub fn track_views(&self) -> bool {
+        self.track_views
+    }
+
+    /// Turning tracking off forgets every recorded view.
+    pub fn set_track_views(
+        &mut self,
+        track: bool,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.track_views = track;
+
+        if !track {
+            self.last_viewed.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Like `get`, but records the view when tracking is on. A view is
+    /// not a change, so it leaves `updated_timestamp` alone.
+    pub fn get_and_record_view(
+        &mut self,
+        id: Uuid,
+    ) -> AppResult<Todo> {
+        let todo = self.get(id)?;
+
+        if self.track_views {
+            self.last_viewed.insert(
+                id,
+                unix_time_now!(),
+            );
+
+            if self.last_viewed.len()
+                > MAX_TRACKED_VIEWS
+            {
+                if let Some(oldest) = self
+                    .last_viewed
+                    .iter()
+                    .min_by_key(|(id, t)| {
+                        (**t, **id)
+                    })
+                    .map(|(id, _)| *id)
+                {
+                    self.last_viewed
+                        .remove(&oldest);
+                }
+            }
+        }
+
+        Ok(todo)
+    }
+
+    /// The most recently viewed, modified or created items, newest first.
+    /// Viewed items only appear while tracking is on.
+    pub fn recent(
+        &self,
+        kind: RecentKind,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Vec<Todo>> {
+        let limit =
+            self.result_limit(limit)?;
+
+        let mut found: Vec<(
+            UnixTime,
+            &Todo,
+        )> = match kind {
+            RecentKind::Viewed => self
+                .last_viewed
+                .iter()
+                .filter_map(|(id, t)| {
+                    self.items
+                        .get(id)
+                        .map(|todo| (*t, todo))
+                })
+                .collect(),
+            RecentKind::Modified => self
+                .items
+                .values()
+                .map(|t| {
+                    (t.updated_timestamp, t)
+                })
+                .collect(),
+            RecentKind::Created => self
+                .items
+                .values()
+                .map(|t| {
+                    (t.created_timestamp, t)
+                })
+                .collect(),
+        };
+
+        found.sort_by_key(
+            |(t, todo)| {
+                cmp::Reverse((
+                    *t, todo.id,
+                ))
+            },
+        );
+
+        Ok(found
+            .into_iter()
+            .take(limit)
+            .map(|(_, todo)| {
+                todo.clone()
+            })
+            .collect())
+    }
+}
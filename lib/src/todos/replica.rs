@@ -0,0 +1,124 @@
+use super::{
+    RemoteChange, SyncReport,
+    SyncStrategy, TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime, time,
+};
+use chrono::Utc;
+use std::ops::{Deref, DerefMut};
+
+/// A list that follows a primary one, so reads can be served away from
+/// it. It takes in the primary's deltas and changes and answers every
+/// read through `Deref`; any other change to its items fails with
+/// `ReadOnlyReplica`.
+pub struct ReplicaList {
+    list: TodoList,
+
+    // The second after the replica last took in changes; only items
+    // touched from then on can conflict with the next ones.
+    last_synced: UnixTime,
+}
+
+impl ReplicaList {
+    pub fn new() -> Self {
+        Self {
+            list: TodoList {
+                replica: true,
+                ..TodoList::default()
+            },
+            last_synced: 0,
+        }
+    }
+
+    /// Catches up with a delta from the primary's `export_delta`.
+    #[cfg(feature = "delta")]
+    pub fn apply_delta(
+        &mut self,
+        bytes: &[u8],
+    ) -> AppResult<String> {
+        self.ingest(|list| {
+            list.apply_delta(bytes)
+        })
+    }
+
+    /// Applies changes the primary made, which win over whatever the
+    /// replica holds. Only items changed since the last sync are
+    /// reported as conflicts.
+    pub fn apply_remote(
+        &mut self,
+        changes: Vec<RemoteChange>,
+    ) -> AppResult<SyncReport> {
+        let since = self.last_synced;
+
+        self.ingest(|list| {
+            list.apply_remote(
+                changes,
+                since,
+                SyncStrategy::RemoteWins,
+            )
+        })
+    }
+
+    /// The second after the replica last took in a delta or changes, or
+    /// 0 if it never has.
+    pub fn last_synced(
+        &self,
+    ) -> UnixTime {
+        self.last_synced
+    }
+
+    fn ingest<T>(
+        &mut self,
+        apply: impl FnOnce(
+            &mut TodoList,
+        )
+            -> AppResult<T>,
+    ) -> AppResult<T> {
+        self.list.replica = false;
+
+        let result =
+            apply(&mut self.list);
+
+        self.list.replica = true;
+
+        // Items the sync stamped carry this second at the latest, so
+        // they are not taken for local changes next time.
+        if result.is_ok() {
+            self.last_synced =
+                time::advanced(
+                    self.last_synced,
+                    unix_time_now!()
+                        .saturating_add(
+                            1,
+                        ),
+                );
+        }
+
+        result
+    }
+}
+
+impl Default for ReplicaList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for ReplicaList {
+    type Target = TodoList;
+
+    fn deref(&self) -> &TodoList {
+        &self.list
+    }
+}
+
+// Settings, auth and metrics stay usable; changes to the items do not.
+impl DerefMut for ReplicaList {
+    fn deref_mut(
+        &mut self,
+    ) -> &mut TodoList {
+        &mut self.list
+    }
+}
@@ -0,0 +1,83 @@
+use super::{Status, Todo, TodoList};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime,
+};
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+
+/// Which items `apply_retention` deletes, judged by when they were last
+/// updated.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    CopyGetters,
+    Getters,
+    TypedBuilder,
+)]
+pub struct RetentionPolicy {
+    #[getset(get_copy = "pub")]
+    older_than_days: u32,
+
+    /// Empty matches every status.
+    #[getset(get = "pub")]
+    #[builder(default)]
+    statuses: Vec<Status>,
+}
+
+impl TodoList {
+    pub fn retention_policy(
+        &self,
+    ) -> Option<&RetentionPolicy> {
+        self.retention_policy.as_ref()
+    }
+
+    /// `None` keeps everything.
+    pub fn set_retention_policy(
+        &mut self,
+        policy: Option<RetentionPolicy>,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.retention_policy = policy;
+
+        Ok(())
+    }
+
+    /// Deletes what the policy says has expired by `now` and returns it,
+    /// least recently updated first.
+    pub fn apply_retention(
+        &mut self,
+        now: UnixTime,
+    ) -> AppResult<Vec<Todo>> {
+        let Some(policy) =
+            &self.retention_policy
+        else {
+            return Ok(vec![]);
+        };
+
+        let ids: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| {
+                t.is_stale_at(
+                    now,
+                    policy.older_than_days,
+                    &policy.statuses,
+                )
+            })
+            .map(|t| t.id)
+            .collect();
+
+        let mut removed = self
+            .remove_all(&ids, false)?;
+
+        removed.sort_by_key(|t| {
+            t.updated_timestamp
+        });
+
+        Ok(removed)
+    }
+}
@@ -0,0 +1,48 @@
+use super::{Title, Todo, TodoList};
+use crate::app_error::AppResult;
+use uuid::Uuid;
+
+impl TodoList {
+    /// Adds `suffix` to the end of the item's title, which must still be
+    /// a valid title once combined.
+    pub fn append_to_title(
+        &mut self,
+        id: Uuid,
+        suffix: &str,
+    ) -> AppResult<Todo> {
+        self.retitle(id, |title| {
+            format!("{title}{suffix}")
+        })
+    }
+
+    /// Adds `prefix`, such as `"[BLOCKED] "`, to the start of the item's
+    /// title, which must still be a valid title once combined.
+    pub fn prefix_title(
+        &mut self,
+        id: Uuid,
+        prefix: &str,
+    ) -> AppResult<Todo> {
+        self.retitle(id, |title| {
+            format!("{prefix}{title}")
+        })
+    }
+
+    fn retitle(
+        &mut self,
+        id: Uuid,
+        combine: impl FnOnce(&str) -> String,
+    ) -> AppResult<Todo> {
+        self.change_item(id, |todo| {
+            let title = Title::new(
+                combine(&todo.title),
+            )
+            .validated()?;
+
+            let changed =
+                todo.title != title;
+            todo.set_title(title);
+
+            Ok(changed)
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use super::{Query, Todo, TodoList};
+use crate::app_error::{
+    bail, report, AppError, AppResult,
+};
+
+impl TodoList {
+    pub fn save_query(
+        &mut self,
+        name: &str,
+        query: Query,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        let name = name.trim();
+
+        if name.is_empty() {
+            bail!(
+                AppError::EmptySavedQueryName
+            )
+        }
+
+        // Reject a malformed query now rather than on every run.
+        query.deadline().unix_time(
+            self.default_time_zone,
+        )?;
+        self.result_limit(
+            query.limit(),
+        )?;
+
+        self.saved_queries
+            .insert(name.into(), query);
+
+        Ok(())
+    }
+
+    pub fn run_saved(
+        &self,
+        name: &str,
+    ) -> AppResult<Vec<Todo>> {
+        let query = self
+            .saved_queries
+            .get(name.trim())
+            .ok_or_else(|| {
+                report!(
+                    AppError::SavedQueryNotFound(
+                        name.into()
+                    )
+                )
+            })?;
+
+        self.search(query)
+    }
+
+    pub fn list_saved(
+        &self,
+    ) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .saved_queries
+            .keys()
+            .cloned()
+            .collect();
+
+        names.sort();
+
+        names
+    }
+
+    pub fn delete_saved(
+        &mut self,
+        name: &str,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.saved_queries
+            .remove(name.trim())
+            .map(|_| ())
+            .ok_or_else(|| {
+                report!(
+                    AppError::SavedQueryNotFound(
+                        name.into()
+                    )
+                )
+            })
+    }
+}
@@ -0,0 +1,240 @@
+use super::{
+    CreationDefaults, DeadlineBounds,
+    EscalationPolicy, RetentionPolicy,
+    TimeZone, TitleCollation, TodoList,
+};
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+
+/// Every per-list setting at once. Saved queries, templates and
+/// principals are data rather than settings and are left out.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Getters,
+    CopyGetters,
+)]
+pub struct ListSettings {
+    #[getset(get_copy = "pub")]
+    default_time_zone: TimeZone,
+
+    #[getset(get_copy = "pub")]
+    escalation_policy: EscalationPolicy,
+
+    #[getset(get = "pub")]
+    retention_policy:
+        Option<RetentionPolicy>,
+
+    #[getset(get_copy = "pub")]
+    deadline_bounds:
+        Option<DeadlineBounds>,
+
+    #[getset(get_copy = "pub")]
+    strict_limits: bool,
+
+    #[getset(get_copy = "pub")]
+    track_views: bool,
+
+    #[getset(get_copy = "pub")]
+    warn_on_similar: bool,
+
+    #[getset(get_copy = "pub")]
+    title_collation: TitleCollation,
+
+    #[getset(get_copy = "pub")]
+    creation_defaults: CreationDefaults,
+
+    #[getset(get_copy = "pub")]
+    query_cache_size: usize,
+}
+
+impl ListSettings {
+    /// Raised whenever a setting is added, renamed or removed.
+    pub const VERSION: u32 = 3;
+}
+
+/// The settings to change; `None` keeps the current value. The inner
+/// `None` of an optional setting turns it off.
+#[derive(
+    Clone, Default, TypedBuilder,
+)]
+#[builder(field_defaults(default))]
+pub struct SettingsUpdate {
+    /// The `ListSettings::VERSION` the caller was written against, if
+    /// it wants a mismatch refused rather than fields ignored.
+    version: Option<u32>,
+
+    default_time_zone: Option<String>,
+
+    escalation_policy:
+        Option<EscalationPolicy>,
+
+    retention_policy:
+        Option<Option<RetentionPolicy>>,
+
+    deadline_bounds:
+        Option<Option<DeadlineBounds>>,
+
+    strict_limits: Option<bool>,
+
+    track_views: Option<bool>,
+
+    warn_on_similar: Option<bool>,
+
+    title_collation:
+        Option<TitleCollation>,
+
+    creation_defaults:
+        Option<CreationDefaults>,
+
+    query_cache_size: Option<usize>,
+}
+
+impl TodoList {
+    pub fn get_settings(
+        &self,
+    ) -> ListSettings {
+        ListSettings {
+            default_time_zone: self
+                .default_time_zone,
+            escalation_policy: self
+                .escalation_policy,
+            retention_policy: self
+                .retention_policy
+                .clone(),
+            deadline_bounds: self
+                .deadline_bounds,
+            strict_limits: self
+                .strict_limits,
+            track_views: self
+                .track_views,
+            warn_on_similar: self
+                .warn_on_similar,
+            title_collation: self
+                .title_collation,
+            creation_defaults: self
+                .creation_defaults,
+            query_cache_size: self
+                .query_cache_size(),
+        }
+    }
+
+    /// Applies every change in `update` or, if any is invalid, none of
+    /// them, and returns the settings that result. A frozen list
+    /// refuses any update.
+    pub fn update_settings(
+        &mut self,
+        update: SettingsUpdate,
+    ) -> AppResult<ListSettings> {
+        if let Some(actual) =
+            update.version
+        {
+            if actual
+                != ListSettings::VERSION
+            {
+                bail!(AppError::SettingsVersionMismatch {
+                    expected: ListSettings::VERSION,
+                    actual,
+                })
+            }
+        }
+
+        self.ensure_unfrozen()?;
+
+        // The only setting that can fail on its own, so it is checked
+        // before anything changes.
+        if let Some(time_zone) =
+            &update.default_time_zone
+        {
+            TimeZone::parse(time_zone)?;
+            self.ensure_writable()?;
+        }
+
+        if let Some(time_zone) =
+            update.default_time_zone
+        {
+            self.set_default_time_zone(
+                &time_zone,
+            )?;
+        }
+
+        if let Some(policy) =
+            update.escalation_policy
+        {
+            self.set_escalation_policy(
+                policy,
+            )?;
+        }
+
+        if let Some(policy) =
+            update.retention_policy
+        {
+            self.set_retention_policy(
+                policy,
+            )?;
+        }
+
+        if let Some(bounds) =
+            update.deadline_bounds
+        {
+            self.set_deadline_bounds(
+                bounds,
+            )?;
+        }
+
+        if let Some(strict) =
+            update.strict_limits
+        {
+            self.set_strict_limits(
+                strict,
+            )?;
+        }
+
+        if let Some(track) =
+            update.track_views
+        {
+            self.set_track_views(
+                track,
+            )?;
+        }
+
+        if let Some(warn) =
+            update.warn_on_similar
+        {
+            self.set_warn_on_similar(
+                warn,
+            )?;
+        }
+
+        if let Some(collation) =
+            update.title_collation
+        {
+            self.set_title_collation(
+                collation,
+            )?;
+        }
+
+        if let Some(defaults) =
+            update.creation_defaults
+        {
+            self.set_creation_defaults(
+                defaults,
+            )?;
+        }
+
+        if let Some(size) =
+            update.query_cache_size
+        {
+            self.set_query_cache_size(
+                size,
+            )?;
+        }
+
+        Ok(self.get_settings())
+    }
+}
@@ -0,0 +1,82 @@
+use super::{Query, Todo, TodoList};
+use crate::{
+    app_error::AppResult,
+    core::SECONDS_PER_HOUR, time,
+};
+use chrono::Utc;
+
+impl TodoList {
+    /// Moves the deadline of every item matching the query's
+    /// predicates by `delta_hours`, which may be negative, and returns
+    /// how many moved. Items without a deadline are left alone, and
+    /// deadline bounds are not checked, since the whole set moves
+    /// together; a deadline pushed past the year 9999 stops there.
+    pub fn shift_deadlines(
+        &mut self,
+        query: &Query,
+        delta_hours: i64,
+    ) -> AppResult<usize> {
+        self.ensure_writable()?;
+
+        let delta = time::seconds(
+            delta_hours,
+            SECONDS_PER_HOUR,
+            "hours",
+        )?;
+
+        if delta == 0 {
+            return Ok(0);
+        }
+
+        let now = unix_time_now!();
+
+        let shifted: Vec<_> = self
+            .ids_where(query)?
+            .into_iter()
+            .filter_map(|id| {
+                let todo =
+                    self.items.get(&id)?;
+
+                todo.deadline.map(|d| {
+                    Todo {
+                        deadline: Some(
+                            time::offset_clamped(
+                                d, delta,
+                            ),
+                        ),
+                        updated_timestamp:
+                            time::advanced(
+                                todo.updated_timestamp,
+                                now,
+                            ),
+                        last_modified_by: self
+                            .actor
+                            .clone(),
+                        ..todo.clone()
+                    }
+                })
+            })
+            .collect();
+
+        for todo in &shifted {
+            journal!(
+                self,
+                LogEntry::Put(
+                    todo.clone()
+                )
+            );
+
+            let before = self
+                .items
+                .insert(todo.clone());
+            self.bump_seq(todo.id);
+
+            self.record_changes(
+                before.as_ref(),
+                todo,
+            );
+        }
+
+        Ok(shifted.len())
+    }
+}
@@ -0,0 +1,52 @@
+use super::{Todo, TodoList};
+use crate::app_error::{
+    report, AppError, AppResult,
+};
+
+impl TodoList {
+    pub fn get_by_short_id(
+        &self,
+        short_id: u64,
+    ) -> AppResult<Todo> {
+        self.items
+            .values()
+            .find(|t| {
+                t.short_id == short_id
+            })
+            .cloned()
+            .ok_or_else(|| {
+                report!(
+                    AppError::ShortIdNotFound(
+                        short_id
+                    )
+                )
+            })
+    }
+
+    // Only a restored or synced number near `u64::MAX` can run out.
+    pub(super) fn next_short_id(
+        &mut self,
+    ) -> AppResult<u64> {
+        self.last_short_id = self
+            .last_short_id
+            .checked_add(1)
+            .ok_or_else(|| {
+                report!(AppError::Internal(
+                    "short-id-overflow"
+                ))
+            })?;
+
+        Ok(self.last_short_id)
+    }
+
+    // Keeps numbers from being handed out again after items come back
+    // from a snapshot or log.
+    pub(super) fn note_short_id(
+        &mut self,
+        short_id: u64,
+    ) {
+        self.last_short_id = self
+            .last_short_id
+            .max(short_id);
+    }
+}
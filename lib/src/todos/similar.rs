@@ -0,0 +1,323 @@
+use super::{
+    NewTodo, Title, Todo, TodoList,
+};
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    core::UnixTime,
+};
+use std::collections::BTreeMap;
+
+// How alike a new title must be to an open item's to hold up `add`.
+const SIMILAR_ON_ADD: u8 = 85;
+
+// Titles are compared on this many characters at most, which keeps
+// the cost of each comparison bounded for items that were imported or
+// synced in without their title being checked.
+const MAX_COMPARED_CHARS: usize =
+    Title::MAX_LEN;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AddOutcome {
+    Added(Box<Todo>),
+
+    /// Nothing was added; these open items look like the same task.
+    Similar(Vec<Todo>),
+}
+
+impl TodoList {
+    pub fn warn_on_similar(
+        &self,
+    ) -> bool {
+        self.warn_on_similar
+    }
+
+    /// When on, `add_or_warn` holds back an item whose title closely
+    /// matches an open one.
+    pub fn set_warn_on_similar(
+        &mut self,
+        warn: bool,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.warn_on_similar = warn;
+
+        Ok(())
+    }
+
+    /// Like `add`, unless warnings are on and open items with a very
+    /// similar title exist, oldest first. Retitling the item enough
+    /// gets it through.
+    pub fn add_or_warn(
+        &mut self,
+        item: &NewTodo,
+    ) -> AppResult<AddOutcome> {
+        if self.warn_on_similar {
+            let candidates = self
+                .similar_open(
+                    &item
+                        .title
+                        .validated()?,
+                );
+
+            if !candidates.is_empty() {
+                return Ok(
+                    AddOutcome::Similar(
+                        candidates,
+                    ),
+                );
+            }
+        }
+
+        self.add(item).map(|t| {
+            AddOutcome::Added(Box::new(
+                t,
+            ))
+        })
+    }
+
+    // Open items whose title is close enough to `title` to hold up
+    // `add_or_warn`, oldest first.
+    pub(super) fn similar_open(
+        &self,
+        title: &str,
+    ) -> Vec<Todo> {
+        let title = normalized(title);
+
+        let mut candidates: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| {
+                t.is_open()
+                    && similarity(
+                        &title,
+                        &normalized(&t.title),
+                    ) >= SIMILAR_ON_ADD
+            })
+            .cloned()
+            .collect();
+
+        candidates.sort_by_key(|t| {
+            (t.created_timestamp, t.id)
+        });
+
+        candidates
+    }
+
+    /// Groups of live items that are probably the same task: their
+    /// titles are at least `threshold` percent alike once case,
+    /// punctuation and spacing are ignored, and their deadlines are
+    /// equal. Likeness carries over, so a group can hold two items
+    /// that are only alike through a third. Only items sharing a
+    /// deadline are compared, and only on the start of their titles.
+    /// Nothing is deleted.
+    pub fn find_similar(
+        &self,
+        threshold: u8,
+    ) -> AppResult<Vec<Vec<Todo>>> {
+        if threshold > 100 {
+            bail!(AppError::InvalidSimilarityThreshold(
+                threshold
+            ))
+        }
+
+        let mut items: Vec<_> = self
+            .items
+            .values()
+            .collect();
+
+        items.sort_by_key(|t| {
+            (t.created_timestamp, t.id)
+        });
+
+        let titles: Vec<_> = items
+            .iter()
+            .map(|t| {
+                normalized(&t.title)
+            })
+            .collect();
+
+        // Items are only ever alike to others due at the same time.
+        let mut by_deadline: BTreeMap<
+            Option<UnixTime>,
+            Vec<usize>,
+        > = BTreeMap::new();
+
+        for (i, t) in
+            items.iter().enumerate()
+        {
+            by_deadline
+                .entry(t.deadline)
+                .or_default()
+                .push(i);
+        }
+
+        // Each item points at an earlier one in its group, or itself.
+        let mut group: Vec<_> =
+            (0..items.len()).collect();
+
+        for bucket in
+            by_deadline.values()
+        {
+            for (n, &i) in bucket
+                .iter()
+                .enumerate()
+            {
+                for &j in &bucket[..n] {
+                    if similarity(
+                        &titles[i],
+                        &titles[j],
+                    ) >= threshold
+                    {
+                        let (a, b) = (
+                            root(
+                                &group,
+                                i,
+                            ),
+                            root(
+                                &group,
+                                j,
+                            ),
+                        );
+                        group[a
+                            .max(b)] =
+                            a.min(b);
+                    }
+                }
+            }
+        }
+
+        let mut groups: Vec<Vec<Todo>> =
+            vec![vec![]; items.len()];
+
+        for (i, t) in items
+            .into_iter()
+            .enumerate()
+        {
+            groups[root(&group, i)]
+                .push(t.clone());
+        }
+
+        Ok(groups
+            .into_iter()
+            .filter(|g| g.len() > 1)
+            .collect())
+    }
+}
+
+fn root(
+    group: &[usize],
+    mut i: usize,
+) -> usize {
+    while group[i] != i {
+        i = group[i];
+    }
+    i
+}
+
+/// Lowercase words with the punctuation taken out, one space apart.
+fn normalized(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| {
+            !c.is_alphanumeric()
+        })
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How alike two normalized titles are, from 0 to 100 percent: the
+/// share of characters an edit leaves untouched. Only the first
+/// `MAX_COMPARED_CHARS` of each count.
+fn similarity(a: &str, b: &str) -> u8 {
+    let a: Vec<_> = a
+        .chars()
+        .take(MAX_COMPARED_CHARS)
+        .collect();
+    let b: Vec<_> = b
+        .chars()
+        .take(MAX_COMPARED_CHARS)
+        .collect();
+
+    let longest = a.len().max(b.len());
+
+    if longest == 0 {
+        return 100;
+    }
+
+    // Levenshtein distance, one row at a time.
+    let mut row: Vec<_> =
+        (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate()
+    {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in
+            b.iter().enumerate()
+        {
+            let cost =
+                usize::from(ca != cb);
+
+            let next = (row[j + 1] + 1)
+                .min(row[j] + 1)
+                .min(diagonal + cost);
+
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    let same = longest - row[b.len()];
+
+    // At most 100, since no edit touches more characters than the
+    // longer title has.
+    u8::try_from(same * 100 / longest)
+        .unwrap_or(100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("", "", 100 ; "both empty")]
+    #[test_case("abc", "abc", 100 ; "equal")]
+    #[test_case("abcd", "abce", 75 ; "one edit")]
+    #[test_case("abc", "xyz", 0 ; "nothing alike")]
+    fn similarity_should_be_the_share_of_untouched_characters(
+        a: &str,
+        b: &str,
+        expected: u8,
+    ) {
+        assert_eq!(
+            similarity(a, b),
+            expected
+        )
+    }
+
+    #[test]
+    fn similarity_should_only_compare_the_start_of_long_titles(
+    ) {
+        let start = "a"
+            .repeat(MAX_COMPARED_CHARS);
+
+        assert_eq!(
+            similarity(
+                &format!(
+                    "{start}{}",
+                    "b".repeat(1_000)
+                ),
+                &format!(
+                    "{start}{}",
+                    "c".repeat(5_000)
+                ),
+            ),
+            100
+        )
+    }
+}
@@ -0,0 +1,182 @@
+use super::{
+    CreationDefaults, TimeZone, Todo,
+    TodoList,
+};
+use crate::app_error::AppResult;
+use chrono::Utc;
+use getset::{CopyGetters, Getters};
+
+/// The items of a list, live and archived, plus its default time zone
+/// and creation defaults; enough for a host to save the list and
+/// rebuild it later. Other settings and saved queries are not
+/// included.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Getters,
+    CopyGetters,
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(
+        serde::Serialize,
+        serde::Deserialize
+    ),
+    serde(rename_all = "kebab-case")
+)]
+pub struct Snapshot {
+    #[getset(get = "pub")]
+    items: Vec<Todo>,
+
+    #[getset(get = "pub")]
+    archived: Vec<Todo>,
+
+    #[getset(get_copy = "pub")]
+    default_time_zone: TimeZone,
+
+    /// The highest short id handed out so far, deleted items included.
+    #[getset(get_copy = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    last_short_id: u64,
+
+    /// Missing from snapshots saved before it was kept, which restore
+    /// the defaults' defaults.
+    #[getset(get_copy = "pub")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default)
+    )]
+    creation_defaults: CreationDefaults,
+}
+
+impl TodoList {
+    /// Items come out oldest first, so saved files diff cleanly.
+    pub fn snapshot(&self) -> Snapshot {
+        let sorted = |todos: Vec<
+            &Todo,
+        >| {
+            let mut todos: Vec<_> =
+                todos
+                    .into_iter()
+                    .cloned()
+                    .collect();
+
+            todos.sort_by_key(|t| {
+                (
+                    t.created_timestamp,
+                    t.id,
+                )
+            });
+
+            todos
+        };
+
+        Snapshot {
+            items: sorted(
+                self.items
+                    .values()
+                    .collect(),
+            ),
+            archived: sorted(
+                self.archived
+                    .values()
+                    .collect(),
+            ),
+            default_time_zone: self
+                .default_time_zone,
+            last_short_id: self
+                .last_short_id,
+            creation_defaults: self
+                .creation_defaults,
+        }
+    }
+
+    /// Replaces the items, default time zone and creation defaults
+    /// with those in `snapshot`; everything else but the record of
+    /// deletions is left as is. With an operation log, the snapshot is
+    /// written there first, and a failed write changes nothing.
+    pub fn restore(
+        &mut self,
+        mut snapshot: Snapshot,
+    ) -> AppResult<()> {
+        self.ensure_writable()?;
+
+        // Never lowered, so a number is not handed out twice.
+        snapshot.last_short_id =
+            snapshot
+                .items
+                .iter()
+                .chain(
+                    &snapshot.archived,
+                )
+                .map(|t| t.short_id)
+                .max()
+                .unwrap_or_default()
+                .max(
+                    snapshot
+                        .last_short_id,
+                )
+                .max(
+                    self.last_short_id,
+                );
+
+        #[cfg(feature = "persistence")]
+        self.compact_log_to(&snapshot)?;
+
+        let previous: Vec<_> = self
+            .items
+            .keys()
+            .copied()
+            .collect();
+        self.items.clear();
+        for todo in snapshot.items {
+            self.items.insert(todo);
+        }
+
+        // Items the snapshot brings back are live again, and those it
+        // drops count as deleted now.
+        let now = unix_time_now!();
+        self.deleted.retain(|id, _| {
+            !self.items.contains_key(id)
+        });
+        self.deleted.extend(
+            previous
+                .into_iter()
+                .filter(|id| {
+                    !self
+                        .items
+                        .contains_key(
+                            id,
+                        )
+                })
+                .map(|id| (id, now)),
+        );
+        self.archived = snapshot
+            .archived
+            .into_iter()
+            .map(|t| (t.id, t))
+            .collect();
+        self.default_time_zone =
+            snapshot.default_time_zone;
+        self.creation_defaults =
+            snapshot.creation_defaults;
+
+        self.note_short_id(
+            snapshot.last_short_id,
+        );
+
+        // Nothing says what a receiver already has of the old items.
+        self.raise_seq_floor(
+            self.change_seq,
+        );
+        self.query_cache.clear();
+        self.pinned_views
+            .stop_sharing();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use super::{
+    OptionalDeadlineInput, Todo,
+    TodoList,
+};
+use crate::{
+    app_error::{
+        report, AppError, AppResult,
+    },
+    core::UnixTime,
+    time,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+impl Todo {
+    pub(crate) fn is_snoozed_at(
+        &self,
+        now: UnixTime,
+    ) -> bool {
+        self.snoozed_until
+            .map(|until| until > now)
+            .unwrap_or(false)
+    }
+}
+
+impl TodoList {
+    /// Hides the item from searches and views until `until`, which accepts
+    /// the same formats as a deadline.
+    pub fn snooze(
+        &mut self,
+        id: Uuid,
+        until: &str,
+    ) -> AppResult<Todo> {
+        let until =
+            OptionalDeadlineInput::from(
+                Some(until.into()),
+            )
+            .unix_time(
+                self.default_time_zone,
+            )?;
+
+        self.set_snoozed_until(
+            id, until,
+        )
+    }
+
+    pub fn unsnooze(
+        &mut self,
+        id: Uuid,
+    ) -> AppResult<Todo> {
+        self.set_snoozed_until(id, None)
+    }
+
+    fn set_snoozed_until(
+        &mut self,
+        id: Uuid,
+        until: Option<UnixTime>,
+    ) -> AppResult<Todo> {
+        self.ensure_writable()?;
+
+        let mut todo = self
+            .items
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| {
+                report!(
+                    AppError::TodoNotFound(id)
+                )
+            })?;
+
+        if todo.snoozed_until != until {
+            todo.snoozed_until = until;
+            todo.updated_timestamp =
+                time::advanced(
+                    todo.updated_timestamp,
+                    unix_time_now!(),
+                );
+            todo.last_modified_by =
+                self.actor.clone();
+
+            journal!(
+                self,
+                LogEntry::Put(
+                    todo.clone()
+                )
+            );
+
+            self.items
+                .insert(todo.clone());
+            self.bump_seq(id);
+        }
+
+        Ok(todo)
+    }
+}
@@ -0,0 +1,136 @@
+use super::{Status, Todo, TodoList};
+use crate::{
+    app_error::AppResult,
+    core::{UnixTime, SECONDS_PER_DAY},
+    time,
+};
+use chrono::Utc;
+
+impl Todo {
+    pub(super) fn is_stale_at(
+        &self,
+        now: UnixTime,
+        older_than_days: u32,
+        statuses: &[Status],
+    ) -> bool {
+        (statuses.is_empty()
+            || statuses
+                .contains(&self.status))
+            && time::elapsed(
+                self.updated_timestamp,
+                now,
+            ) >= UnixTime::from(
+                older_than_days,
+            ) * SECONDS_PER_DAY
+    }
+}
+
+impl TodoList {
+    /// Items not updated within the last `older_than_days` days. An empty
+    /// `statuses` slice matches every status.
+    pub fn find_stale(
+        &self,
+        older_than_days: u32,
+        statuses: &[Status],
+    ) -> Vec<Todo> {
+        let now = unix_time_now!();
+
+        let mut stale: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| {
+                t.is_stale_at(
+                    now,
+                    older_than_days,
+                    statuses,
+                )
+            })
+            .cloned()
+            .collect();
+
+        stale.sort_by_key(|t| {
+            t.updated_timestamp
+        });
+
+        stale
+    }
+
+    pub fn delete_stale(
+        &mut self,
+        older_than_days: u32,
+        statuses: &[Status],
+    ) -> AppResult<usize> {
+        self.take_stale(
+            older_than_days,
+            statuses,
+        )
+        .map(|deleted| deleted.len())
+    }
+
+    /// Moves stale items out of the list into the archive.
+    pub fn archive_stale(
+        &mut self,
+        older_than_days: u32,
+        statuses: &[Status],
+    ) -> AppResult<usize> {
+        self.ensure_writable()?;
+
+        let stale = self.find_stale(
+            older_than_days,
+            statuses,
+        );
+
+        let count = stale.len();
+
+        for todo in stale {
+            journal!(
+                self,
+                LogEntry::Archive(
+                    todo.clone()
+                )
+            );
+
+            self.items.remove(&todo.id);
+            self.record_deletions([
+                todo.id,
+            ]);
+            self.archived
+                .insert(todo.id, todo);
+        }
+
+        Ok(count)
+    }
+
+    pub fn list_archived(
+        &self,
+    ) -> Vec<Todo> {
+        let mut archived: Vec<_> = self
+            .archived
+            .values()
+            .cloned()
+            .collect();
+
+        archived.sort_by_key(|t| {
+            t.updated_timestamp
+        });
+
+        archived
+    }
+
+    fn take_stale(
+        &mut self,
+        older_than_days: u32,
+        statuses: &[Status],
+    ) -> AppResult<Vec<Todo>> {
+        let ids: Vec<_> = self
+            .find_stale(
+                older_than_days,
+                statuses,
+            )
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+
+        self.remove_all(&ids, false)
+    }
+}
@@ -0,0 +1,530 @@
+use super::Todo;
+use std::{
+    any::Any, collections::HashMap,
+};
+use uuid::Uuid;
+
+/// Where a list keeps its live items, keyed by id. `HashMapStore` is
+/// the default; `ArenaStore` suits very large lists.
+pub trait TodoStore:
+    Any + Send + Sync
+{
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(
+        &self,
+        id: &Uuid,
+    ) -> Option<&Todo>;
+
+    fn contains_key(
+        &self,
+        id: &Uuid,
+    ) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Keeps `todo` under its id, returning the item it replaces.
+    fn insert(
+        &mut self,
+        todo: Todo,
+    ) -> Option<Todo>;
+
+    fn remove(
+        &mut self,
+        id: &Uuid,
+    ) -> Option<Todo>;
+
+    fn clear(&mut self);
+
+    /// Every item, in no particular order.
+    fn values(
+        &self,
+    ) -> Box<
+        dyn Iterator<Item = &Todo> + '_,
+    >;
+
+    fn keys(
+        &self,
+    ) -> Box<
+        dyn Iterator<Item = &Uuid> + '_,
+    > {
+        Box::new(
+            self.values()
+                .map(|t| t.id()),
+        )
+    }
+
+    /// A store of the same kind holding the same items, for pinned
+    /// views.
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn TodoStore>;
+}
+
+impl Default for Box<dyn TodoStore> {
+    fn default() -> Self {
+        Box::<HashMapStore>::default()
+    }
+}
+
+impl Clone for Box<dyn TodoStore> {
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+/// Each item allocated on its own behind a hash map.
+#[derive(Clone, Default)]
+pub struct HashMapStore {
+    items: HashMap<Uuid, Todo>,
+}
+
+impl TodoStore for HashMapStore {
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn get(
+        &self,
+        id: &Uuid,
+    ) -> Option<&Todo> {
+        self.items.get(id)
+    }
+
+    fn insert(
+        &mut self,
+        todo: Todo,
+    ) -> Option<Todo> {
+        self.items.insert(todo.id, todo)
+    }
+
+    fn remove(
+        &mut self,
+        id: &Uuid,
+    ) -> Option<Todo> {
+        self.items.remove(id)
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    fn values(
+        &self,
+    ) -> Box<
+        dyn Iterator<Item = &Todo> + '_,
+    > {
+        Box::new(self.items.values())
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn TodoStore> {
+        Box::new(self.clone())
+    }
+}
+
+/// Items side by side in one vector, found by id through a map of
+/// slot indices. Iterating walks contiguous memory, and the slot of a
+/// removed item goes to the next one added rather than being freed.
+#[derive(Clone, Default)]
+pub struct ArenaStore {
+    slots: Vec<Option<Todo>>,
+
+    // Slots emptied by `remove`, reused before the vector grows.
+    free: Vec<usize>,
+
+    index: HashMap<Uuid, usize>,
+}
+
+impl ArenaStore {
+    pub fn with_capacity(
+        capacity: usize,
+    ) -> Self {
+        Self {
+            slots: Vec::with_capacity(
+                capacity,
+            ),
+            free: vec![],
+            index:
+                HashMap::with_capacity(
+                    capacity,
+                ),
+        }
+    }
+}
+
+impl TodoStore for ArenaStore {
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn get(
+        &self,
+        id: &Uuid,
+    ) -> Option<&Todo> {
+        self.index.get(id).and_then(
+            |slot| {
+                self.slots[*slot]
+                    .as_ref()
+            },
+        )
+    }
+
+    fn insert(
+        &mut self,
+        todo: Todo,
+    ) -> Option<Todo> {
+        if let Some(slot) =
+            self.index.get(&todo.id)
+        {
+            return self.slots[*slot]
+                .replace(todo);
+        }
+
+        let id = todo.id;
+        let slot = match self.free.pop()
+        {
+            Some(slot) => {
+                self.slots[slot] =
+                    Some(todo);
+                slot
+            }
+            None => {
+                self.slots
+                    .push(Some(todo));
+                self.slots.len() - 1
+            }
+        };
+        self.index.insert(id, slot);
+
+        None
+    }
+
+    fn remove(
+        &mut self,
+        id: &Uuid,
+    ) -> Option<Todo> {
+        let slot =
+            self.index.remove(id)?;
+
+        self.free.push(slot);
+
+        self.slots[slot].take()
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.free.clear();
+        self.index.clear();
+    }
+
+    fn values(
+        &self,
+    ) -> Box<
+        dyn Iterator<Item = &Todo> + '_,
+    > {
+        Box::new(
+            self.slots.iter().flatten(),
+        )
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn TodoStore> {
+        Box::new(self.clone())
+    }
+}
+
+/// What each item was before a batch first touched it; `None` for an
+/// id that had no item.
+#[derive(Default)]
+pub(super) struct UndoLog(
+    HashMap<Uuid, Option<Todo>>,
+);
+
+impl UndoLog {
+    /// The ids the batch touched, in id order.
+    #[cfg(feature = "persistence")]
+    pub(super) fn touched(
+        &self,
+    ) -> Vec<Uuid> {
+        let mut ids: Vec<_> = self
+            .0
+            .keys()
+            .copied()
+            .collect();
+        ids.sort();
+
+        ids
+    }
+
+    /// Puts back what each touched item was.
+    pub(super) fn roll_back(
+        self,
+        store: &mut dyn TodoStore,
+    ) {
+        for (id, before) in self.0 {
+            match before {
+                Some(todo) => {
+                    store.insert(todo);
+                }
+                None => {
+                    store.remove(&id);
+                }
+            }
+        }
+    }
+
+    fn remember(
+        &mut self,
+        id: Uuid,
+        before: Option<&Todo>,
+    ) {
+        self.0
+            .entry(id)
+            .or_insert_with(|| {
+                before.cloned()
+            });
+    }
+}
+
+/// Wraps a list's store while a batch runs, keeping an `UndoLog` of
+/// what it changes, so a failed batch can be undone without copying
+/// the whole store up front.
+pub(super) struct UndoStore {
+    inner: Box<dyn TodoStore>,
+
+    log: UndoLog,
+}
+
+impl UndoStore {
+    pub(super) fn new(
+        inner: Box<dyn TodoStore>,
+    ) -> Self {
+        Self {
+            inner,
+            log: UndoLog::default(),
+        }
+    }
+
+    /// The wrapped store as the batch left it, and the log to undo the
+    /// batch with; an empty store is left behind.
+    pub(super) fn take(
+        &mut self,
+    ) -> (Box<dyn TodoStore>, UndoLog)
+    {
+        (
+            std::mem::take(
+                &mut self.inner,
+            ),
+            std::mem::take(
+                &mut self.log,
+            ),
+        )
+    }
+}
+
+impl TodoStore for UndoStore {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn get(
+        &self,
+        id: &Uuid,
+    ) -> Option<&Todo> {
+        self.inner.get(id)
+    }
+
+    fn insert(
+        &mut self,
+        todo: Todo,
+    ) -> Option<Todo> {
+        let id = todo.id;
+        let before =
+            self.inner.insert(todo);
+
+        self.log.remember(
+            id,
+            before.as_ref(),
+        );
+
+        before
+    }
+
+    fn remove(
+        &mut self,
+        id: &Uuid,
+    ) -> Option<Todo> {
+        let before =
+            self.inner.remove(id);
+
+        self.log.remember(
+            *id,
+            before.as_ref(),
+        );
+
+        before
+    }
+
+    fn clear(&mut self) {
+        for todo in self.inner.values()
+        {
+            self.log.remember(
+                todo.id,
+                Some(todo),
+            );
+        }
+
+        self.inner.clear();
+    }
+
+    fn values(
+        &self,
+    ) -> Box<
+        dyn Iterator<Item = &Todo> + '_,
+    > {
+        self.inner.values()
+    }
+
+    fn boxed_clone(
+        &self,
+    ) -> Box<dyn TodoStore> {
+        self.inner.boxed_clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todos::{
+        NewTodo, Priority, Title,
+        TodoList,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn todos(n: usize) -> Vec<Todo> {
+        let mut list = TodoList::new();
+
+        (0..n)
+            .map(|i| {
+                list.add(
+                    &NewTodo::builder()
+                        .title(Title::new(
+                            format!("{i}"),
+                        ))
+                        .priority(
+                            Priority::Low,
+                        )
+                        .build(),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arena_store_should_reuse_the_slot_of_a_removed_item(
+    ) {
+        let [a, b, c] =
+            <[Todo; 3]>::try_from(
+                todos(3),
+            )
+            .unwrap();
+
+        let mut store =
+            ArenaStore::default();
+        store.insert(a.clone());
+        store.insert(b.clone());
+
+        assert_eq!(
+            store.remove(&a.id),
+            Some(a.clone())
+        );
+        assert_eq!(
+            store.remove(&a.id),
+            None
+        );
+
+        store.insert(c.clone());
+
+        assert_eq!(
+            store.slots.len(),
+            2
+        );
+        assert_eq!(store.len(), 2);
+        assert_eq!(
+            store.get(&c.id),
+            Some(&c)
+        );
+        assert!(
+            !store.contains_key(&a.id)
+        );
+    }
+
+    #[test]
+    fn arena_store_should_replace_an_item_in_place(
+    ) {
+        let [a] =
+            <[Todo; 1]>::try_from(
+                todos(1),
+            )
+            .unwrap();
+
+        let mut store =
+            ArenaStore::default();
+        store.insert(a.clone());
+
+        let renamed = Todo {
+            title: "renamed".into(),
+            ..a.clone()
+        };
+
+        assert_eq!(
+            store.insert(
+                renamed.clone()
+            ),
+            Some(a)
+        );
+        assert_eq!(
+            store
+                .values()
+                .collect::<Vec<_>>(),
+            vec![&renamed]
+        );
+    }
+
+    #[test]
+    fn todo_list_with_store_should_move_its_items_over(
+    ) {
+        let mut list = TodoList::new();
+        let added = list
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "a",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let list = list.with_store(
+            ArenaStore::with_capacity(
+                8,
+            ),
+        );
+
+        assert_eq!(
+            list.get(added.id).unwrap(),
+            added
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use super::{
+    OptionalResultLimit, TodoList,
+};
+use crate::app_error::AppResult;
+use std::{cmp, collections::HashMap};
+
+impl TodoList {
+    /// Existing titles starting with `prefix`, ignoring case, for
+    /// autocomplete. Titles shared by more items come first, then in
+    /// alphabetical order; a blank prefix suggests nothing.
+    pub fn suggest_titles(
+        &self,
+        prefix: &str,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Vec<String>> {
+        let limit =
+            self.result_limit(limit)?;
+
+        let prefix = prefix
+            .trim()
+            .to_lowercase();
+
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut counts: HashMap<
+            &str,
+            usize,
+        > = HashMap::new();
+
+        for t in self
+            .items
+            .values()
+            .filter(|t| {
+                t.title_lowercase()
+                    .starts_with(
+                        &prefix,
+                    )
+            })
+        {
+            *counts
+                .entry(&t.title)
+                .or_default() += 1;
+        }
+
+        let mut titles: Vec<_> = counts
+            .into_iter()
+            .collect();
+
+        titles.sort_by_key(
+            |(title, count)| {
+                (
+                    cmp::Reverse(
+                        *count,
+                    ),
+                    *title,
+                )
+            },
+        );
+
+        Ok(titles
+            .into_iter()
+            .take(limit)
+            .map(|(title, _)| {
+                title.into()
+            })
+            .collect())
+    }
+}
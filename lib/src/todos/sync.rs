@@ -0,0 +1,429 @@
+use super::{
+    Priority, Status, Title, Todo,
+    TodoList,
+};
+use crate::{
+    app_error::AppResult,
+    core::UnixTime, time,
+};
+use chrono::Utc;
+use getset::Getters;
+use std::collections::{
+    BTreeMap, BTreeSet,
+};
+use typed_builder::TypedBuilder;
+use uuid::Uuid;
+
+/// How many deleted ids `export_delta` remembers; past that the older
+/// half are forgotten, and deltas from before them come out full.
+pub(super) const MAX_DELETED_SEQS:
+    usize = 10_000;
+
+/// Ids of the items created, updated and deleted since some moment, each
+/// sorted. An item created in that window is not also listed as updated.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Getters,
+)]
+#[getset(get = "pub")]
+pub struct ChangeSummary {
+    created: Vec<Uuid>,
+
+    updated: Vec<Uuid>,
+
+    deleted: Vec<Uuid>,
+}
+
+/// Names one run of a list's sequence numbers, picked at random when
+/// the list is made and again should the numbers run out, so a number
+/// from another run is never mistaken for one of this run's.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub(super) struct SeqEpoch(
+    pub(super) Uuid,
+);
+
+impl Default for SeqEpoch {
+    fn default() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+/// An item as another replica last saw it.
+#[derive(Clone, TypedBuilder)]
+pub struct RemoteTodo {
+    id: Uuid,
+
+    title: Title,
+
+    priority: Priority,
+
+    status: Status,
+
+    #[builder(default)]
+    deadline: Option<UnixTime>,
+
+    /// When the remote side last changed it.
+    updated_timestamp: UnixTime,
+}
+
+#[derive(Clone)]
+pub enum RemoteChange {
+    Upsert(RemoteTodo),
+
+    Delete(Uuid),
+}
+
+/// Which side wins when an item changed both here and remotely since
+/// the last sync.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum SyncStrategy {
+    LocalWins,
+
+    RemoteWins,
+
+    /// The newer change wins, and an edit wins over a delete.
+    Merge,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Getters,
+)]
+#[getset(get = "pub")]
+pub struct SyncReport {
+    /// Items whose remote change was applied.
+    applied: Vec<Uuid>,
+
+    /// Items changed on both sides, however the conflict was resolved.
+    conflicts: Vec<Uuid>,
+}
+
+impl TodoList {
+    /// Deletions are remembered only while the list is in memory, so a
+    /// recovered list reports none from before the restart.
+    pub fn diff_since(
+        &self,
+        since: UnixTime,
+    ) -> ChangeSummary {
+        let mut summary =
+            ChangeSummary::default();
+
+        for t in self.items.values() {
+            if t.created_timestamp
+                >= since
+            {
+                summary
+                    .created
+                    .push(t.id);
+            } else if t
+                .updated_timestamp
+                >= since
+            {
+                summary
+                    .updated
+                    .push(t.id);
+            }
+        }
+
+        summary.deleted = self
+            .deleted
+            .iter()
+            .filter(|(id, t)| {
+                **t >= since
+                    && !self
+                        .items
+                        .contains_key(
+                            id,
+                        )
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        summary.created.sort();
+        summary.updated.sort();
+        summary.deleted.sort();
+
+        summary
+    }
+
+    /// Reconciles changes made on another replica since `since`, the
+    /// last time the two synced. Every remote title is checked before
+    /// anything is applied, so a bad one changes nothing.
+    pub fn apply_remote(
+        &mut self,
+        changes: Vec<RemoteChange>,
+        since: UnixTime,
+        strategy: SyncStrategy,
+    ) -> AppResult<SyncReport> {
+        self.ensure_writable()?;
+
+        let mut titles =
+            BTreeMap::new();
+
+        for change in &changes {
+            if let RemoteChange::Upsert(
+                remote,
+            ) = change
+            {
+                titles.insert(
+                    remote.id,
+                    remote
+                        .title
+                        .validated()?,
+                );
+            }
+        }
+
+        let mut report =
+            SyncReport::default();
+
+        for change in changes {
+            let id = match &change {
+                RemoteChange::Upsert(r) => {
+                    r.id
+                }
+                RemoteChange::Delete(id) => {
+                    *id
+                }
+            };
+
+            let local =
+                self.items.get(&id);
+
+            let conflict = local
+                .map(|t| {
+                    t.updated_timestamp
+                        >= since
+                })
+                .unwrap_or(false);
+
+            let remote_wins = !conflict
+                || match strategy {
+                    SyncStrategy::LocalWins => false,
+                    SyncStrategy::RemoteWins => true,
+                    SyncStrategy::Merge => {
+                        match &change {
+                            RemoteChange::Upsert(r) => {
+                                local
+                                    .map(|t| {
+                                        r.updated_timestamp
+                                            > t.updated_timestamp
+                                    })
+                                    .unwrap_or(true)
+                            }
+                            RemoteChange::Delete(_) => false,
+                        }
+                    }
+                };
+
+            if conflict {
+                report
+                    .conflicts
+                    .push(id);
+            }
+
+            if !remote_wins {
+                continue;
+            }
+
+            let applied = match change {
+                RemoteChange::Upsert(
+                    remote,
+                ) => {
+                    let title = titles
+                        .remove(&id)
+                        .unwrap_or_default();
+
+                    self.put_remote(
+                        remote, title,
+                    )?;
+
+                    true
+                }
+                RemoteChange::Delete(
+                    id,
+                ) => self
+                    .remove_all(&[id], false)?
+                    .pop()
+                    .is_some(),
+            };
+
+            if applied {
+                report.applied.push(id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn put_remote(
+        &mut self,
+        remote: RemoteTodo,
+        title: String,
+    ) -> AppResult<()> {
+        let now = unix_time_now!();
+
+        let existing = self
+            .items
+            .get(&remote.id)
+            .cloned();
+
+        let mut todo = match existing {
+            Some(todo) => todo,
+            None => Todo {
+                id: remote.id,
+                short_id: self
+                    .next_short_id()?,
+                title: title.clone(),
+                priority: remote
+                    .priority,
+                status: Status::Backlog,
+                created_timestamp: now,
+                updated_timestamp: now,
+                completed_timestamp:
+                    None,
+                deadline: None,
+                time_zone: self
+                    .default_time_zone,
+                start_date: None,
+                snoozed_until: None,
+                color: None,
+                icon: None,
+                attachments: vec![],
+                external_refs:
+                    BTreeMap::new(),
+                comments: vec![],
+                watchers: BTreeSet::new(
+                ),
+                last_modified_by: None,
+                normalized_title:
+                    Default::default(),
+            },
+        };
+
+        if todo.status != remote.status
+        {
+            todo.completed_timestamp =
+                (remote.status
+                    == Status::Done)
+                    .then_some(now);
+        }
+
+        todo.set_title(title);
+        todo.priority = remote.priority;
+        todo.status = remote.status;
+        todo.deadline = remote.deadline;
+        todo.updated_timestamp =
+            time::advanced(
+                todo.updated_timestamp,
+                now,
+            );
+        todo.last_modified_by =
+            self.actor.clone();
+
+        journal!(
+            self,
+            LogEntry::Put(todo.clone())
+        );
+
+        self.deleted.remove(&todo.id);
+        self.bump_seq(todo.id);
+        self.items.insert(todo);
+
+        Ok(())
+    }
+
+    pub(super) fn record_deletions(
+        &mut self,
+        ids: impl IntoIterator<Item = Uuid>,
+    ) {
+        let now = unix_time_now!();
+
+        for id in ids {
+            self.deleted
+                .insert(id, now);
+            self.bump_seq(id);
+        }
+    }
+
+    /// Gives the item's latest change the next sequence number. Should
+    /// the numbers ever run out they start again in a new epoch, and
+    /// every delta asked for with an older number comes out full.
+    pub(super) fn bump_seq(
+        &mut self,
+        id: Uuid,
+    ) {
+        if self.change_seq == u64::MAX {
+            self.change_seqs.clear();
+            self.change_seq = 0;
+            self.seq_floor = 0;
+            self.seq_epoch =
+                SeqEpoch::default();
+            self.query_cache.clear();
+            self.pinned_views
+                .stop_sharing();
+        }
+
+        self.change_seq += 1;
+        self.change_seqs.insert(
+            id,
+            self.change_seq,
+        );
+
+        if self
+            .change_seqs
+            .len()
+            .saturating_sub(
+                self.items.len(),
+            )
+            > MAX_DELETED_SEQS
+        {
+            let mut deleted: Vec<_> = self
+                .change_seqs
+                .iter()
+                .filter(|(id, _)| {
+                    !self
+                        .items
+                        .contains_key(id)
+                })
+                .map(|(_, seq)| *seq)
+                .collect();
+            deleted.sort_unstable();
+
+            if let Some(seq) = deleted
+                .get(deleted.len() / 2)
+            {
+                self.raise_seq_floor(
+                    *seq,
+                );
+            }
+        }
+    }
+
+    /// Makes deltas since `floor` or earlier full ones, and forgets the
+    /// sequence numbers only those deltas would have looked at.
+    pub(super) fn raise_seq_floor(
+        &mut self,
+        floor: u64,
+    ) {
+        self.seq_floor =
+            self.seq_floor.max(floor);
+
+        let floor = self.seq_floor;
+        self.change_seqs.retain(
+            |_, seq| *seq > floor,
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use super::{
+    NewTodo, OptionalDeadlineInput,
+    Priority, Title, Todo, TodoList,
+};
+use crate::app_error::{
+    bail, report, AppError, AppResult,
+};
+use typed_builder::TypedBuilder;
+
+/// Fields that replace the template's own when it is instantiated.
+#[derive(Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct TemplateOverrides {
+    title: Option<Title>,
+
+    priority: Option<Priority>,
+
+    deadline:
+        Option<OptionalDeadlineInput>,
+
+    start_date:
+        Option<OptionalDeadlineInput>,
+}
+
+impl TodoList {
+    /// Stores `item` under `name`, replacing any template already there.
+    pub fn save_template(
+        &mut self,
+        name: &str,
+        item: NewTodo,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        let name = name.trim();
+
+        if name.is_empty() {
+            bail!(
+                AppError::EmptyTemplateName
+            )
+        }
+
+        // Deadlines are checked on use, as they may only pass later.
+        item.title.validated()?;
+
+        self.templates
+            .insert(name.into(), item);
+
+        Ok(())
+    }
+
+    /// Adds a new item from the template, with `overrides` applied.
+    pub fn instantiate_template(
+        &mut self,
+        name: &str,
+        overrides: TemplateOverrides,
+    ) -> AppResult<Todo> {
+        let template = self
+            .templates
+            .get(name.trim())
+            .ok_or_else(|| {
+                report!(
+                    AppError::TemplateNotFound(
+                        name.into()
+                    )
+                )
+            })?;
+
+        let item = NewTodo {
+            title: overrides
+                .title
+                .unwrap_or_else(|| {
+                    template
+                        .title
+                        .clone()
+                }),
+            priority: overrides
+                .priority
+                .or(template.priority),
+            deadline: overrides
+                .deadline
+                .unwrap_or_else(|| {
+                    template
+                        .deadline
+                        .clone()
+                }),
+            start_date: overrides
+                .start_date
+                .unwrap_or_else(|| {
+                    template
+                        .start_date
+                        .clone()
+                }),
+            ..template.clone()
+        };
+
+        self.add(&item)
+    }
+
+    pub fn list_templates(
+        &self,
+    ) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .templates
+            .keys()
+            .cloned()
+            .collect();
+
+        names.sort();
+
+        names
+    }
+
+    pub fn delete_template(
+        &mut self,
+        name: &str,
+    ) -> AppResult<()> {
+        self.ensure_unfrozen()?;
+
+        self.templates
+            .remove(name.trim())
+            .map(|_| ())
+            .ok_or_else(|| {
+                report!(
+                    AppError::TemplateNotFound(
+                        name.into()
+                    )
+                )
+            })
+    }
+}
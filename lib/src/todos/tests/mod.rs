@@ -1,12 +1,16 @@
 #[allow(unused_imports)]
 use super::*;
 
+#[cfg(test)]
+mod model;
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         assert_app_error,
-        deadline::USER_DATE_TIME_FORMAT,
+        core::SECONDS_PER_DAY,
+        deadline::ACCEPTED_DEADLINE_FORMATS,
     };
     use maplit::hashset;
     use memoize::memoize;
@@ -81,7 +85,11 @@ mod tests {
             self.update(
                 id,
                 &UpdateTodo::builder()
-                    .deadline(deadline)
+                    .deadline(
+                        DeadlineChange::Set(
+                            deadline,
+                        ),
+                    )
                     .build(),
             )
         }
@@ -153,8 +161,10 @@ mod tests {
         assert_eq!(
             new_todo_list!()
                 .delete_by_status(
-                    &Status::Done
-                ),
+                    &Status::Done,
+                    false
+                )
+                .unwrap(),
             0
         );
     }
@@ -164,7 +174,8 @@ mod tests {
     ) {
         assert_eq!(
             new_todo_list!()
-                .delete_all(),
+                .delete_all()
+                .unwrap(),
             0
         );
     }
@@ -180,8 +191,12 @@ mod tests {
 
         let item = NewTodo {
             title: Title::new(title),
-            priority,
+            priority: Some(priority),
             deadline: OptionalDeadlineInput::none(),
+            start_date: OptionalDeadlineInput::none(),
+            allow_past_deadline: false,
+            color: None,
+            icon: None,
         };
 
         let actual =
@@ -225,7 +240,7 @@ mod tests {
 
         let expected = AppError::DateTimeParseError {
                 input: invalid_date_time.into(),
-                expected_format: USER_DATE_TIME_FORMAT.into()
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
             };
 
         assert_app_error!(
@@ -304,7 +319,9 @@ mod tests {
                     Priority::High,
                 ))
                 .deadline(
-                    OptionalDeadlineInput::some("2022-01-01 19")
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some("2022-01-01 19")
+                    )
                 )
                 .build();
 
@@ -435,7 +452,9 @@ mod tests {
         let update =
             UpdateTodo::builder()
                 .deadline(
-                    OptionalDeadlineInput::some(invalid_date_time)
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some(invalid_date_time)
+                    )
                 )
                 .build();
 
@@ -444,7 +463,7 @@ mod tests {
 
         let expected = AppError::DateTimeParseError {
                 input: invalid_date_time.into(),
-                expected_format: USER_DATE_TIME_FORMAT.into()
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
             };
 
         assert_app_error!(
@@ -457,8 +476,12 @@ mod tests {
     ) -> AppResult<Vec<Todo>> {
         let low_todo = NewTodo {
             title: Title::new("a"),
-            priority: Priority::Low,
+            priority: Some(Priority::Low),
             deadline: OptionalDeadlineInput::none(),
+            start_date: OptionalDeadlineInput::none(),
+            allow_past_deadline: false,
+            color: None,
+            icon: None,
         };
 
         let todo_a = todos
@@ -474,7 +497,9 @@ mod tests {
         )?;
 
         let med_todo = NewTodo {
-            priority: Priority::Medium,
+            priority: Some(
+                Priority::Medium,
+            ),
             ..low_todo
         };
 
@@ -492,7 +517,9 @@ mod tests {
         )?;
 
         let high_todo = NewTodo {
-            priority: Priority::High,
+            priority: Some(
+                Priority::High,
+            ),
             ..med_todo
         };
 
@@ -552,7 +579,7 @@ mod tests {
         }
 
         assert_eq!(
-            todos.delete_all(),
+            todos.delete_all().unwrap(),
             count
         );
         assert_eq!(
@@ -615,6 +642,12 @@ mod tests {
                 updated,
                 Todo {
                     status: the_status,
+                    completed_timestamp: (the_status
+                        == Status::Done)
+                        .then_some(
+                            updated
+                                .updated_timestamp
+                        ),
                     ..item.clone()
                 }
             )
@@ -630,9 +663,12 @@ mod tests {
         );
 
         assert_eq!(
-            todos.delete_by_status(
-                &the_status
-            ),
+            todos
+                .delete_by_status(
+                    &the_status,
+                    false
+                )
+                .unwrap(),
             count
         );
         assert_eq!(
@@ -1148,7 +1184,7 @@ mod tests {
 
         let expected = AppError::DateTimeParseError {
                 input: invalid_date_time.into(),
-                expected_format: USER_DATE_TIME_FORMAT.into()
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
             };
 
         assert_app_error!(
@@ -1172,7 +1208,7 @@ mod tests {
 
         let expected = AppError::DateTimeParseError {
                 input: invalid_date_time.into(),
-                expected_format: USER_DATE_TIME_FORMAT.into()
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
             };
 
         assert_app_error!(
@@ -1255,10 +1291,14 @@ mod tests {
             .unwrap();
 
         let deleted_count = todos
-            .delete_by_statuses(&nes![
-                Status::Backlog,
-                Status::Done
-            ]);
+            .delete_by_statuses(
+                &nes![
+                    Status::Backlog,
+                    Status::Done
+                ],
+                false,
+            )
+            .unwrap();
 
         assert_eq!(deleted_count, 6);
 
@@ -1361,7 +1401,9 @@ mod tests {
                     Priority::Medium,
                     Priority::Low
                 ],
-            );
+                false,
+            )
+            .unwrap();
 
         assert_eq!(deleted_count, 6);
 
@@ -1459,10 +1501,16 @@ mod tests {
             .unwrap();
 
         let deleted_count = todos
-            .delete_by_ids(&nes![
-                todo_b.id, todo_d.id,
-                todo_f.id, todo_h.id
-            ]);
+            .delete_by_ids(
+                &nes![
+                    todo_b.id,
+                    todo_d.id,
+                    todo_f.id,
+                    todo_h.id
+                ],
+                false,
+            )
+            .unwrap();
 
         assert_eq!(deleted_count, 4);
 
@@ -1487,4 +1535,6589 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn todolist_run_saved_should_return_same_todos_as_the_saved_query(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let query = Query::builder()
+            .priority(Some(
+                Priority::High,
+            ))
+            .build();
+
+        todos
+            .save_query(
+                "  urgent  ",
+                query.clone(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .run_saved("urgent")
+            .unwrap();
+
+        let expected = todos
+            .search(&query)
+            .unwrap();
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual.len(), 3);
+    }
+
+    #[test]
+    fn todolist_save_query_should_fail_when_name_is_empty(
+    ) {
+        let actual = new_todo_list!()
+            .save_query(
+                "   ",
+                Query::empty(),
+            );
+
+        let expected =
+            AppError::EmptySavedQueryName;
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    #[test]
+    fn todolist_save_query_should_fail_when_deadline_is_invalid(
+    ) {
+        let invalid_date_time = "abc";
+
+        let query = Query::builder()
+            .deadline(
+                OptionalDeadlineInput::some(invalid_date_time)
+            )
+            .build();
+
+        let actual = new_todo_list!()
+            .save_query("bad", query);
+
+        let expected = AppError::DateTimeParseError {
+                input: invalid_date_time.into(),
+                expected_format: ACCEPTED_DEADLINE_FORMATS.clone()
+            };
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    #[test]
+    fn todolist_list_saved_and_delete_saved_should_work_as_expected(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for name in ["b", "a", "c"] {
+            todos
+                .save_query(
+                    name,
+                    Query::empty(),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            todos.list_saved(),
+            vec!["a", "b", "c"]
+        );
+
+        todos
+            .delete_saved("b")
+            .unwrap();
+
+        assert_eq!(
+            todos.list_saved(),
+            vec!["a", "c"]
+        );
+
+        let actual =
+            todos.run_saved("b");
+
+        let expected =
+            AppError::SavedQueryNotFound(
+                "b".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        let actual =
+            todos.delete_saved("b");
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    fn add_todos_for_views(
+        todos: &mut TodoList,
+    ) -> AppResult<Vec<Todo>> {
+        let item = NewTodo::builder()
+            .title(Title::new("x"))
+            .priority(Priority::Low)
+            .allow_past_deadline(true)
+            .build();
+
+        let overdue = todos.add(
+            &NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-01-09 00"),
+                ..item.cloned_with_title("overdue")
+            },
+        )?;
+        let today = todos.add(
+            &NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-01-10 18"),
+                ..item.cloned_with_title("today")
+            },
+        )?;
+        let this_week = todos.add(
+            &NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-01-14 00"),
+                ..item.cloned_with_title("this week")
+            },
+        )?;
+        let later = todos.add(
+            &NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-02-01 00"),
+                ..item.cloned_with_title("later")
+            },
+        )?;
+
+        let done = todos.add(
+            &NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-01-09 00"),
+                ..item.cloned_with_title("done")
+            },
+        )?;
+        let done = todos
+            .update_status(
+                done.id,
+                Status::Done,
+            )?;
+
+        let started = todos.add(
+            &item.cloned_with_title(
+                "started",
+            ),
+        )?;
+        let started = todos
+            .update_status(
+                started.id,
+                Status::InProgress,
+            )?;
+
+        let result = vec![
+            overdue, today, this_week,
+            later, done, started,
+        ];
+
+        Ok(result)
+    }
+
+    #[test]
+    fn todolist_views_should_return_open_todos_in_deadline_order(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items =
+            add_todos_for_views(
+                &mut todos,
+            )
+            .unwrap();
+        let [
+            overdue, today, this_week,
+            _later, _done, started
+        ] =
+            <[Todo; 6]>::try_from(items)
+                .expect(
+                    "`items` vec should contain 6 elements"
+                );
+
+        let now = OptionalDeadlineInput::some("2022-01-10 12")
+            .unix_time(TimeZone::default())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            todos.view_overdue(now),
+            vec![overdue.clone()]
+        );
+        assert_eq!(
+            todos.count_overdue(now),
+            1
+        );
+        assert_eq!(
+            todos.view_today(now),
+            vec![
+                overdue.clone(),
+                today.clone(),
+                started
+            ]
+        );
+        assert_eq!(
+            todos.view_week(now),
+            vec![
+                overdue, today,
+                this_week
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_interpret_deadline_in_the_list_default_time_zone(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .set_default_time_zone(
+                "Europe/Berlin",
+            )
+            .unwrap();
+
+        let actual = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .deadline(
+                        OptionalDeadlineInput::some("2022-01-01 10")
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.deadline(),
+            Some(1_641_027_600)
+        );
+        assert_eq!(
+            actual.deadline_local(),
+            Some(
+                "2022-01-01 10:00 CET"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_prefer_the_time_zone_given_with_the_deadline(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .set_default_time_zone(
+                "Europe/Berlin",
+            )
+            .unwrap();
+
+        let actual = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .deadline(
+                        OptionalDeadlineInput::new(
+                            Some("2022-01-01 14".into()),
+                            Some("+05:00".into()),
+                        )
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.deadline(),
+            Some(1_641_027_600)
+        );
+        assert_eq!(
+            actual.deadline_local(),
+            Some(
+                "2022-01-01 14:00 +05:00"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn todolist_set_default_time_zone_should_fail_when_time_zone_is_invalid(
+    ) {
+        let actual = new_todo_list!()
+            .set_default_time_zone(
+                "Nowhere",
+            );
+
+        let expected =
+            AppError::InvalidTimeZone(
+                "Nowhere".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    #[test]
+    fn todolist_update_should_keep_deadline_when_deadline_change_is_absent(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .deadline(
+                        OptionalDeadlineInput::some("2022-01-01 19")
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            ).unwrap();
+
+        let v2 = todos
+            .update_status(
+                v1.id,
+                Status::InProgress,
+            )
+            .unwrap();
+
+        assert_eq!(
+            v2.deadline,
+            v1.deadline
+        );
+        assert!(v2.deadline.is_some());
+    }
+
+    #[test]
+    fn todolist_update_should_remove_deadline_when_deadline_change_is_clear(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .deadline(
+                        OptionalDeadlineInput::some("2022-01-01 19")
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            ).unwrap();
+
+        let update =
+            UpdateTodo::builder()
+                .deadline(
+                    DeadlineChange::Clear,
+                )
+                .build();
+
+        let v2 = todos
+            .update(v1.id, &update)
+            .unwrap();
+
+        assert!(v2.deadline.is_none());
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v2
+        );
+    }
+
+    fn add_todos_with_start_dates(
+        todos: &mut TodoList,
+    ) -> AppResult<Vec<Todo>> {
+        let item = NewTodo::builder()
+            .title(Title::new("x"))
+            .priority(Priority::Low)
+            .build();
+
+        let unscheduled = todos.add(
+            &item.cloned_with_title(
+                "unscheduled",
+            ),
+        )?;
+        let started = todos.add(
+            &NewTodo {
+                start_date: OptionalDeadlineInput::some("2022-01-01 00"),
+                ..item.cloned_with_title("started")
+            },
+        )?;
+        let scheduled = todos.add(
+            &NewTodo {
+                start_date: OptionalDeadlineInput::some("2999-01-01 00"),
+                ..item.cloned_with_title("scheduled")
+            },
+        )?;
+
+        let result = vec![
+            unscheduled,
+            started,
+            scheduled,
+        ];
+
+        Ok(result)
+    }
+
+    #[test]
+    fn todolist_search_should_filter_todos_by_availability(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items =
+            add_todos_with_start_dates(
+                &mut todos,
+            )
+            .unwrap();
+        let [
+            unscheduled, started,
+            scheduled
+        ] =
+            <[Todo; 3]>::try_from(items)
+                .expect(
+                    "`items` vec should contain 3 elements"
+                );
+
+        let query = Query::builder()
+            .availability(Some(
+                Availability::AvailableNow,
+            ))
+            .build();
+
+        let actual: HashSet<_> = todos
+            .search(&query)
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            actual,
+            hashset![
+                unscheduled,
+                started
+            ]
+        );
+
+        let query = Query::builder()
+            .availability(Some(
+                Availability::ScheduledLater,
+            ))
+            .build();
+
+        assert_eq!(
+            todos
+                .search(&query)
+                .unwrap(),
+            vec![scheduled]
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_sort_todos_by_start_date_in_ascending_order(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items =
+            add_todos_with_start_dates(
+                &mut todos,
+            )
+            .unwrap();
+
+        let query = Query::builder()
+            .sort(Some(
+                QuerySort::StartDate,
+            ))
+            .build();
+
+        assert_eq!(
+            todos
+                .search(&query)
+                .unwrap(),
+            items
+        );
+    }
+
+    #[test]
+    fn todolist_update_should_set_and_clear_start_date(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .build()
+            ).unwrap();
+
+        let update =
+            UpdateTodo::builder()
+                .start_date(
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some("2022-01-01 00")
+                    )
+                )
+                .build();
+
+        let v2 = todos
+            .update(v1.id, &update)
+            .unwrap();
+
+        assert_eq!(
+            v2.start_date,
+            Some(1_640_995_200)
+        );
+
+        let update =
+            UpdateTodo::builder()
+                .start_date(
+                    DeadlineChange::Clear,
+                )
+                .build();
+
+        let v3 = todos
+            .update(v1.id, &update)
+            .unwrap();
+
+        assert!(v3
+            .start_date
+            .is_none());
+    }
+
+    #[test]
+    fn todolist_snooze_should_hide_todo_from_search_until_unsnoozed(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .build()
+            ).unwrap();
+
+        let v2 = todos
+            .snooze(v1.id, "+1d")
+            .unwrap();
+
+        assert!(v2
+            .snoozed_until
+            .is_some());
+        assert!(todos
+            .search(&Query::empty())
+            .unwrap()
+            .is_empty());
+
+        let query = Query::builder()
+            .include_snoozed(true)
+            .build();
+
+        assert_eq!(
+            todos
+                .search(&query)
+                .unwrap(),
+            vec![v2]
+        );
+
+        let v3 = todos
+            .unsnooze(v1.id)
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .search(&Query::empty())
+                .unwrap(),
+            vec![v3]
+        );
+    }
+
+    #[test]
+    fn todolist_snooze_should_not_hide_todo_when_time_has_passed(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Medium)
+                    .build()
+            ).unwrap();
+
+        let v2 = todos
+            .snooze(
+                v1.id,
+                "2022-01-01 00",
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .search(&Query::empty())
+                .unwrap(),
+            vec![v2]
+        );
+    }
+
+    #[test]
+    fn todolist_snooze_should_fail_when_todo_does_not_exist(
+    ) {
+        let actual = new_todo_list!()
+            .snooze(
+                NON_EXISTENT_ID,
+                "+1d",
+            );
+
+        let expected =
+            AppError::TodoNotFound(
+                NON_EXISTENT_ID,
+            );
+
+        assert_app_error!(
+            actual, expected
+        )
+    }
+
+    #[test]
+    fn todolist_apply_escalation_should_not_change_anything_without_a_policy(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        assert!(todos
+            .apply_escalation(
+                i64::MAX / 2
+            )
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_apply_escalation_should_raise_priority_of_todos_due_soon(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let due_soon = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("soon"))
+                    .priority(Priority::Low)
+                    .deadline(
+                        OptionalDeadlineInput::some("2022-01-01 12")
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            ).unwrap();
+        let _due_later = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("later"))
+                    .priority(Priority::Low)
+                    .deadline(
+                        OptionalDeadlineInput::some("2022-01-05 12")
+                    )
+                    .allow_past_deadline(true)
+                    .build()
+            ).unwrap();
+
+        todos.set_escalation_policy(
+            EscalationPolicy::builder()
+                .deadline_within_hours(
+                    Some(24),
+                )
+                .build(),
+        ).unwrap();
+
+        // 2022-01-01 09:00:00 UTC
+        let now = 1_641_027_600;
+
+        let actual = todos
+            .apply_escalation(now)
+            .unwrap();
+
+        // `now` is before the item was added, so its update time stays.
+        assert_eq!(
+            actual,
+            vec![Todo {
+                priority:
+                    Priority::High,
+                ..due_soon
+            }]
+        );
+        assert!(todos
+            .apply_escalation(now)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_apply_escalation_should_raise_priority_of_stale_backlog_todos_one_level(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        todos.set_escalation_policy(
+            EscalationPolicy::builder()
+                .backlog_after_days(
+                    Some(7),
+                )
+                .build(),
+        ).unwrap();
+
+        let week_later = v1
+            .updated_timestamp
+            + 7 * SECONDS_PER_DAY;
+
+        assert!(todos
+            .apply_escalation(
+                week_later - 1
+            )
+            .unwrap()
+            .is_empty());
+
+        let actual = todos
+            .apply_escalation(
+                week_later,
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual
+                .iter()
+                .map(|t| t.priority)
+                .collect::<Vec<_>>(),
+            vec![Priority::Medium]
+        );
+    }
+
+    #[test]
+    fn todolist_find_stale_should_return_items_in_the_given_statuses(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+        let v2 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "def",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+        let v2 = todos
+            .update_status(
+                v2.id,
+                Status::Done,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .find_stale(0, &[])
+                .len(),
+            2
+        );
+        assert_eq!(
+            todos.find_stale(
+                0,
+                &[Status::Done]
+            ),
+            vec![v2]
+        );
+        assert_eq!(
+            todos.find_stale(
+                0,
+                &[Status::Backlog]
+            ),
+            vec![v1]
+        );
+        assert!(todos
+            .find_stale(1, &[])
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_delete_stale_should_remove_only_stale_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        assert_eq!(
+            todos
+                .delete_stale(1, &[])
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            todos
+                .delete_stale(0, &[])
+                .unwrap(),
+            9
+        );
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+        assert!(todos
+            .list_archived()
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_archive_stale_should_move_items_to_the_archive(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .archive_stale(
+                    0,
+                    &[Status::Backlog]
+                )
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+        assert_eq!(
+            todos.list_archived(),
+            vec![v1]
+        );
+    }
+
+    #[test]
+    fn todolist_delete_where_should_delete_only_matching_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let query = Query::builder()
+            .priority(Some(
+                Priority::High,
+            ))
+            .build();
+
+        let actual = todos
+            .delete_where(&query, false)
+            .unwrap();
+
+        assert_eq!(actual, 3);
+        assert_eq!(
+            todos.count_all(),
+            6
+        );
+        assert_eq!(
+            todos
+                .count_by(&query)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_delete_where_should_fail_when_deadline_is_invalid(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let input = "invalid";
+
+        let actual = todos
+            .delete_where(
+                &Query::builder()
+                    .deadline(
+                        OptionalDeadlineInput::some(input),
+                    )
+                    .build(),
+                false,
+            );
+
+        let expected =
+            AppError::DateTimeParseError {
+                input: input.into(),
+                expected_format:
+                    ACCEPTED_DEADLINE_FORMATS
+                        .clone(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.count_all(),
+            9
+        );
+    }
+
+    #[test]
+    fn todolist_delete_returning_should_return_the_deleted_item(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .delete_returning(v1.id)
+            .unwrap();
+
+        assert_eq!(actual, v1);
+
+        let actual = todos
+            .delete_returning(v1.id);
+
+        let expected =
+            AppError::TodoNotFound(
+                v1.id,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_delete_by_priorities_returning_should_return_the_deleted_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let mut actual: Vec<_> = todos
+            .delete_by_priorities_returning(
+                &nes![Priority::Low],
+                false,
+            )
+.unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        actual.sort();
+
+        assert_eq!(
+            actual,
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            todos.count_all(),
+            6
+        );
+    }
+
+    #[test]
+    fn todolist_delete_all_returning_should_return_every_item(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let actual = todos
+            .delete_all_returning()
+            .unwrap();
+
+        assert_eq!(actual.len(), 9);
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_delete_where_should_not_delete_anything_on_a_dry_run(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let query = Query::builder()
+            .priority(Some(
+                Priority::High,
+            ))
+            .build();
+
+        let actual = todos
+            .delete_where(&query, true)
+            .unwrap();
+
+        assert_eq!(actual, 3);
+        assert_eq!(
+            todos.count_all(),
+            9
+        );
+    }
+
+    #[test]
+    fn todolist_delete_by_ids_returning_should_not_delete_anything_on_a_dry_run(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .delete_by_ids_returning(
+                &nes![v1.id],
+                true,
+            )
+            .unwrap();
+
+        assert_eq!(actual, vec![v1]);
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_update_where_should_update_all_matching_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let query = Query::builder()
+            .priority(Some(
+                Priority::Low,
+            ))
+            .build();
+
+        let change =
+            UpdateTodo::builder()
+                .status(Some(
+                    Status::Done,
+                ))
+                .build();
+
+        assert_eq!(
+            todos
+                .update_where(
+                    &query, &change,
+                    true
+                )
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            todos
+                .count_by(
+                    &Query::builder()
+                        .status(Some(Status::Done))
+                        .build()
+                )
+                .unwrap(),
+            0
+        );
+
+        assert_eq!(
+            todos
+                .update_where(
+                    &query, &change,
+                    false
+                )
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            todos
+                .count_by(
+                    &Query::builder()
+                        .status(Some(Status::Done))
+                        .build()
+                )
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn todolist_update_where_should_fail_when_there_are_no_changes(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos
+            .update_where(
+                &Query::empty(),
+                &UpdateTodo::empty(),
+                true,
+            );
+
+        let expected =
+            AppError::UpdateHasNoChanges;
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_apply_batch_should_apply_every_command_in_order(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .apply_batch(vec![
+                BatchCommand::Add(
+                    NewTodo::builder()
+                        .title(Title::new("def"))
+                        .priority(Priority::High)
+                        .build(),
+                ),
+                BatchCommand::Delete(
+                    v1.id,
+                ),
+            ])
+            .unwrap();
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(
+            actual[1],
+            BatchResult::Deleted(v1)
+        );
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_apply_batch_should_apply_nothing_when_any_command_fails(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .apply_batch(vec![
+                BatchCommand::Update(
+                    v1.id,
+                    UpdateTodo::builder()
+                        .status(Some(Status::Done))
+                        .build(),
+                ),
+                BatchCommand::Delete(
+                    v1.id,
+                ),
+                BatchCommand::Delete(
+                    v1.id,
+                ),
+            ]);
+
+        let expected =
+            AppError::BatchCommandFailed {
+                index: 2,
+                reason: AppError::TodoNotFound(v1.id)
+                    .to_string(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v1
+        );
+    }
+
+    #[test]
+    fn todolist_apply_batch_should_give_out_the_ids_of_a_failed_batch_again(
+    ) {
+        let mut todos =
+            TodoList::new_with(
+                SequentialIdGenerator::default(),
+            )
+            .with_store(
+                ArenaStore::default(),
+            );
+
+        let new_todo =
+            NewTodo::builder()
+                .title(Title::new(
+                    "abc",
+                ))
+                .priority(Priority::Low)
+                .build();
+
+        let v1 = todos
+            .add(&new_todo)
+            .unwrap();
+
+        let actual = todos
+            .apply_batch(vec![
+                BatchCommand::Add(
+                    new_todo.cloned_with_title("def"),
+                ),
+                BatchCommand::Delete(
+                    v1.id,
+                ),
+                BatchCommand::Delete(
+                    v1.id,
+                ),
+            ]);
+
+        assert!(actual.is_err());
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v1
+        );
+
+        let v2 = todos
+            .add(
+                &new_todo
+                    .cloned_with_title(
+                        "ghi",
+                    ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            v2.id,
+            uuid!("00000000-0000-0000-0000-000000000002")
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_take_ids_from_the_injected_generator(
+    ) {
+        let mut todos =
+            TodoList::new_with(
+                SequentialIdGenerator::default(),
+            );
+
+        let ids: Vec<_> =
+            add_todos(&mut todos)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.id)
+                .collect();
+
+        assert_eq!(
+            ids[..2],
+            [
+                uuid!("00000000-0000-0000-0000-000000000001"),
+                uuid!("00000000-0000-0000-0000-000000000002"),
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_sort_by_creation_order(
+    ) {
+        let mut todos =
+            TodoList::new_with(
+                SequentialIdGenerator::default(),
+            );
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        let actual = todos
+            .search(
+                &Query::builder()
+                    .sort(Some(
+                        QuerySort::CreatedAt,
+                    ))
+                    .limit(Some(100).into())
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(actual, added);
+    }
+
+    #[test]
+    fn todolist_search_should_sort_by_updated_timestamp_in_either_direction(
+    ) {
+        let mut todos =
+            TodoList::new_with(
+                SequentialIdGenerator::default(),
+            );
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        let search = |direction| {
+            todos
+                .search(
+                    &Query::builder()
+                        .sort(Some(
+                            QuerySort::UpdatedTimestamp,
+                        ))
+                        .direction(direction)
+                        .limit(Some(100).into())
+                        .build(),
+                )
+                .unwrap()
+        };
+
+        assert_eq!(
+            search(SortDirection::Ascending),
+            added
+        );
+        assert_eq!(
+            search(SortDirection::Descending),
+            added
+                .into_iter()
+                .rev()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn todolist_search_with_total_should_count_matches_beyond_the_limit(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let actual = todos
+            .search_with_total(
+                &Query::builder()
+                    .limit(
+                        Some(2).into(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.items().len(),
+            2
+        );
+        assert_eq!(
+            actual.total_matched(),
+            9
+        );
+    }
+
+    #[test]
+    fn todolist_distinct_should_count_values_in_use(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        todos
+            .update_status(
+                added[0].id,
+                Status::Done,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos.distinct(
+                DistinctField::Status
+            ),
+            vec![
+                (
+                    FieldValue::Status(
+                        Status::Backlog
+                    ),
+                    8
+                ),
+                (
+                    FieldValue::Status(
+                        Status::Done
+                    ),
+                    1
+                ),
+            ]
+        );
+        assert_eq!(
+            todos.distinct(
+                DistinctField::Priority
+            ),
+            vec![
+                (FieldValue::Priority(Priority::Low), 3),
+                (FieldValue::Priority(Priority::Medium), 3),
+                (FieldValue::Priority(Priority::High), 3),
+            ]
+        );
+        assert!(new_todo_list!()
+            .distinct(
+                DistinctField::Status
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_add_should_fail_when_deadline_is_out_of_bounds(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .set_deadline_bounds(Some(
+                DeadlineBounds::default(
+                ),
+            ))
+            .unwrap();
+
+        for input in
+            ["1900-01-01", "9999-01-01"]
+        {
+            let actual = todos.add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Low)
+                    .deadline(OptionalDeadlineInput::some(input))
+                    .build(),
+            );
+
+            assert!(actual
+                .unwrap_err()
+                .to_string()
+                .starts_with(&format!(
+                    "[DeadlineOutOfRange] Deadline '{}' must fall between ",
+                    input
+                )));
+        }
+
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_accept_out_of_bounds_deadline_when_bounds_are_ignored_or_unset(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let new_todo = NewTodo::builder()
+            .title(Title::new("abc"))
+            .priority(Priority::Low)
+            .deadline(
+                OptionalDeadlineInput::some("9999-01-01"),
+            )
+            .build();
+
+        todos.add(&new_todo).unwrap();
+
+        todos
+            .set_deadline_bounds(Some(
+                DeadlineBounds::default(
+                ),
+            ))
+            .unwrap();
+
+        todos
+            .add(&NewTodo {
+                deadline: OptionalDeadlineInput::some("9999-01-01")
+                    .ignoring_bounds(true),
+                ..new_todo
+            })
+            .unwrap();
+
+        assert_eq!(
+            todos.count_all(),
+            2
+        );
+    }
+
+    #[test]
+    fn todolist_update_should_fail_when_deadline_is_out_of_bounds(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .set_deadline_bounds(Some(
+            DeadlineBounds::builder()
+                .max_years_ahead(1)
+                .build(),
+        )).unwrap();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .update_deadline(
+            v1.id,
+            OptionalDeadlineInput::some(
+                "+800d",
+            ),
+        );
+
+        assert!(actual.is_err());
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v1
+        );
+    }
+
+    #[test]
+    fn todolist_add_and_update_should_hold_start_dates_to_deadline_bounds(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .set_deadline_bounds(Some(
+            DeadlineBounds::builder()
+                .max_years_ahead(1)
+                .build(),
+        )).unwrap();
+
+        let new_todo = NewTodo::builder()
+            .title(Title::new("abc"))
+            .priority(Priority::Low)
+            .start_date(
+                OptionalDeadlineInput::some("+800d"),
+            )
+            .build();
+
+        assert!(todos
+            .add(&new_todo)
+            .unwrap_err()
+            .to_string()
+            .starts_with(
+                "[DeadlineOutOfRange] Deadline '+800d' must fall between "
+            ));
+        assert!(!todos
+            .validate(&new_todo)
+            .is_empty());
+
+        let v1 = todos
+            .add(&NewTodo {
+                start_date:
+                    OptionalDeadlineInput::none(),
+                ..new_todo
+            })
+            .unwrap();
+
+        let update =
+            UpdateTodo::builder()
+                .start_date(
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some("+800d")
+                    )
+                )
+                .build();
+
+        assert!(todos
+            .update(v1.id, &update)
+            .is_err());
+        assert!(!todos
+            .validate_update(
+                v1.id, &update
+            )
+            .is_empty());
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v1
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_fail_when_start_date_is_after_deadline(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let new_todo = NewTodo::builder()
+            .title(Title::new("abc"))
+            .priority(Priority::Low)
+            .deadline(
+                OptionalDeadlineInput::some("+5d"),
+            )
+            .start_date(
+                OptionalDeadlineInput::some("+10d"),
+            )
+            .build();
+
+        assert!(todos
+            .add(&new_todo)
+            .unwrap_err()
+            .to_string()
+            .starts_with(
+                "[StartDateAfterDeadline]"
+            ));
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+
+        todos
+            .add(&NewTodo {
+                start_date:
+                    OptionalDeadlineInput::some("+5d"),
+                ..new_todo
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn todolist_update_should_fail_when_start_date_would_fall_after_deadline(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let v1 = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("abc"))
+                    .priority(Priority::Low)
+                    .deadline(
+                        OptionalDeadlineInput::some("+10d"),
+                    )
+                    .start_date(
+                        OptionalDeadlineInput::some("+5d"),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        for update in [
+            UpdateTodo::builder()
+                .start_date(
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some("+20d")
+                    )
+                )
+                .build(),
+            UpdateTodo::builder()
+                .deadline(
+                    DeadlineChange::Set(
+                        OptionalDeadlineInput::some("+1d")
+                    )
+                )
+                .build(),
+        ] {
+            let actual = todos
+                .update(v1.id, &update);
+
+            assert!(actual
+                .unwrap_err()
+                .to_string()
+                .starts_with(
+                    "[StartDateAfterDeadline]"
+                ));
+            assert_eq!(
+                todos
+                    .validate_update(
+                        v1.id, &update
+                    )
+                    .len(),
+                1
+            );
+        }
+
+        assert_eq!(
+            todos.get(v1.id).unwrap(),
+            v1
+        );
+
+        todos
+            .update(
+                v1.id,
+                &UpdateTodo::builder()
+                    .deadline(
+                        DeadlineChange::Clear,
+                    )
+                    .build(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn todolist_add_should_fail_when_deadline_is_in_the_past(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let input = "2022-01-01 10";
+
+        let new_todo = NewTodo::builder()
+            .title(Title::new("abc"))
+            .priority(Priority::Low)
+            .deadline(
+                OptionalDeadlineInput::some(input),
+            )
+            .build();
+
+        let actual =
+            todos.add(&new_todo);
+
+        let expected =
+            AppError::DeadlineInPast(
+                input.into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        todos
+            .add(&NewTodo {
+                allow_past_deadline:
+                    true,
+                ..new_todo
+            })
+            .unwrap();
+
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_fail_on_out_of_range_limit_in_strict_mode(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let query = Query::builder()
+            .limit(Some(0).into())
+            .build();
+
+        assert_eq!(
+            todos
+                .search(&query)
+                .unwrap()
+                .len(),
+            9
+        );
+
+        todos
+            .set_strict_limits(true)
+            .unwrap();
+
+        let actual =
+            todos.search(&query);
+
+        let expected =
+            AppError::InvalidResultLimit {
+                given: 0,
+                max: OptionalResultLimit::MAX,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_health_should_report_operation_counts_and_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        todos.record_op("add");
+        todos.record_op("search");
+        todos.record_op("add");
+        todos.record_snapshot(42);
+
+        let actual = todos.health();
+
+        assert_eq!(
+            actual.operation_counts(),
+            &vec![
+                ("add".to_string(), 2),
+                (
+                    "search"
+                        .to_string(),
+                    1
+                ),
+            ]
+        );
+        assert_eq!(
+            actual.last_snapshot(),
+            Some(42)
+        );
+        assert_eq!(
+            actual.item_count(),
+            9
+        );
+        assert!(
+            actual
+                .estimated_memory_bytes(
+                )
+                > 0
+        );
+    }
+
+    #[test]
+    fn todolist_restore_should_rebuild_the_items_of_a_snapshot(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+        todos
+            .set_default_time_zone(
+                "Europe/Berlin",
+            )
+            .unwrap();
+
+        let mut restored =
+            new_todo_list!();
+        restored
+            .restore(todos.snapshot())
+            .unwrap();
+
+        let all = Query::builder()
+            .limit(Some(100).into())
+            .build();
+
+        assert_eq!(
+            restored
+                .search(&all)
+                .unwrap(),
+            todos.search(&all).unwrap()
+        );
+        assert_eq!(
+            restored
+                .default_time_zone()
+                .to_string(),
+            "Europe/Berlin"
+        );
+    }
+
+    #[test]
+    fn todolist_restore_should_forget_deletions_it_undoes(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let snapshot = todos.snapshot();
+        let first =
+            snapshot.items()[0].clone();
+
+        todos.delete(first.id).unwrap();
+        let later = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "later",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        todos
+            .restore(snapshot)
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .diff_since(0)
+                .deleted()
+                .clone(),
+            vec![later.id]
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_report_each_row(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let payload = "TYPE,CONTENT,PRIORITY,DATE\n\
+            task,Buy milk,1,2023-06-18\n\
+            task,,2,\n\
+            task,Call mom,4,every day\n";
+
+        let actual = todos
+            .import_external(
+                ImportFormat::TodoistCsv,
+                payload,
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        let imported: Vec<_> = actual
+            .imported()
+            .iter()
+            .map(|(row, t)| {
+                (
+                    *row,
+                    t.title().as_str(),
+                    t.priority(),
+                )
+            })
+            .collect();
+
+        let failed: Vec<_> = actual
+            .failed()
+            .iter()
+            .map(|(row, _)| *row)
+            .collect();
+
+        assert_eq!(
+            imported,
+            vec![(
+                1,
+                "Buy milk",
+                Priority::High
+            )]
+        );
+        assert_eq!(failed, vec![2, 3]);
+    }
+
+    #[test]
+    fn todolist_import_external_should_fail_without_a_header(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos
+            .import_external(
+            ImportFormat::TickTickCsv,
+            "just,some,text\n",
+            &ImportOptions::default(),
+        );
+
+        let expected =
+            AppError::ImportHeaderMissing {
+                format: "TickTickCsv"
+                    .into(),
+                column: "title".into(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test]
+    fn todolist_import_external_should_read_things_to_dos_inside_projects(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let payload = r#"[
+            {"type": "project", "attributes": {"title": "Go Shopping", "items": [
+                {"type": "heading", "attributes": {"title": "Dairy"}},
+                {"type": "to-do", "attributes": {"title": "Milk", "tags": ["High"]}}
+            ]}},
+            {"type": "to-do", "attributes": {"title": "Call mom", "deadline": "2023-06-18", "tags": ["Low"]}}
+        ]"#;
+
+        let actual = todos
+            .import_external(
+                ImportFormat::ThingsJson,
+                payload,
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        let imported: Vec<_> = actual
+            .imported()
+            .iter()
+            .map(|(row, t)| {
+                (
+                    *row,
+                    t.title().as_str(),
+                    t.priority(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            imported,
+            vec![
+                (
+                    1,
+                    "Milk",
+                    Priority::High
+                ),
+                (
+                    2,
+                    "Call mom",
+                    Priority::Low
+                ),
+            ]
+        );
+    }
+
+    #[cfg(feature = "json-import")]
+    #[test]
+    fn todolist_import_external_should_fail_without_a_tasks_array(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos
+            .import_external(
+            ImportFormat::TickTickJson,
+            r#"{"project": {}}"#,
+            &ImportOptions::default(),
+        );
+
+        let expected =
+            AppError::ImportPayloadInvalid {
+                format: "TickTickJson"
+                    .into(),
+                reason: "expected an array, or an object with a 'tasks' array"
+                    .into(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_export_todo_txt_should_round_trip_through_import_external(
+    ) {
+        let payload = "(A) Pay rent +home due:2023-07-01\n\
+            x Call mom @phone pri:B\n\
+            (C) Read a book\n";
+
+        let mut todos =
+            TodoList::new_with(
+                SequentialIdGenerator::default(),
+            );
+
+        todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                payload,
+                &ImportOptions::default(
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos.export_todo_txt(),
+            payload
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_rebuild_exported_todo_txt_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Ship it +work",
+                    ))
+                    .priority(
+                        Priority::Medium,
+                    )
+                    .deadline(
+                        Some("+30d".into())
+                            .into(),
+                    )
+                    .build(),
+            )
+            .unwrap();
+        todos
+            .update(
+                added.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let mut imported =
+            new_todo_list!();
+
+        let actual = imported
+            .import_external(
+                ImportFormat::TodoTxt,
+                &todos
+                    .export_todo_txt(),
+                &ImportOptions::default(
+                ),
+            )
+            .unwrap();
+
+        let summary = |t: &Todo| {
+            (
+                t.title().clone(),
+                t.priority(),
+                t.status(),
+                t.deadline().and_then(
+                    |d| {
+                        t.time_zone()
+                            .local_date(
+                                d,
+                            )
+                    },
+                ),
+            )
+        };
+
+        assert_eq!(
+            actual
+                .imported()
+                .iter()
+                .map(|(_, t)| summary(
+                    t
+                ))
+                .collect::<Vec<_>>(),
+            vec![summary(
+                &todos
+                    .get(added.id)
+                    .unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_match_titles_against_title_regex(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for title in [
+            "Call mom",
+            "Call the bank",
+            "Recall the order",
+        ] {
+            todos
+                .add(
+                    &NewTodo::builder()
+                        .title(Title::new(
+                            title,
+                        ))
+                        .priority(
+                            Priority::Low,
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let query = Query::builder()
+            .title_regex(Some(
+                r"^Call\b".into(),
+            ))
+            .build();
+
+        let mut actual: Vec<_> = todos
+            .search(&query)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title().clone())
+            .collect();
+        actual.sort();
+
+        assert_eq!(
+            actual,
+            vec![
+                "Call mom",
+                "Call the bank"
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_reject_an_invalid_title_regex(
+    ) {
+        let todos = new_todo_list!();
+
+        let actual = todos.search(
+            &Query::builder()
+                .title_regex(Some(
+                    "(unclosed".into(),
+                ))
+                .build(),
+        );
+
+        assert!(matches!(
+            actual
+                .unwrap_err()
+                .current_context(),
+            AppError::InvalidRegex { .. }
+        ));
+    }
+
+    #[test]
+    fn todolist_search_should_reject_an_overlong_title_regex(
+    ) {
+        let todos = new_todo_list!();
+
+        let pattern = "a".repeat(257);
+
+        let actual = todos.count_by(
+            &Query::builder()
+                .title_regex(Some(
+                    pattern.clone(),
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::InvalidRegex {
+                pattern,
+                reason:
+                    "longer than 256 bytes"
+                        .into(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_leave_out_excluded_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for (title, priority) in [
+            (
+                "Pay rent",
+                Priority::High,
+            ),
+            (
+                "Chore: dishes",
+                Priority::High,
+            ),
+            (
+                "Read a book",
+                Priority::Low,
+            ),
+            (
+                "Call mom",
+                Priority::Medium,
+            ),
+        ] {
+            todos
+                .add(
+                    &NewTodo::builder()
+                        .title(
+                            Title::new(
+                                title,
+                            ),
+                        )
+                        .priority(
+                            priority,
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let done = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "File taxes",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+        todos
+            .update(
+                done.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let query = Query::builder()
+            .not_keyword(Some(
+                "Chore".into(),
+            ))
+            .exclude_priorities(vec![
+                Priority::Low,
+            ])
+            .exclude_statuses(vec![
+                Status::Done,
+            ])
+            .build();
+
+        let mut actual: Vec<_> = todos
+            .search(&query)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title().clone())
+            .collect();
+        actual.sort();
+
+        assert_eq!(
+            actual,
+            vec![
+                "Call mom", "Pay rent"
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_evaluate_a_filter_expression(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for (
+            title,
+            priority,
+            deadline,
+        ) in [
+            (
+                "Pay rent",
+                Priority::High,
+                Some("2020-01-01"),
+            ),
+            (
+                "Book flights",
+                Priority::High,
+                None,
+            ),
+            (
+                "urgent: call mom",
+                Priority::Low,
+                None,
+            ),
+            (
+                "Read",
+                Priority::Low,
+                None,
+            ),
+        ] {
+            todos
+                .add(
+                    &NewTodo::builder()
+                        .title(Title::new(
+                            title,
+                        ))
+                        .priority(priority)
+                        .deadline(
+                            deadline
+                                .map(String::from)
+                                .into(),
+                        )
+                        .allow_past_deadline(
+                            true,
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        // (High AND overdue) OR keyword:urgent
+        let expr = FilterExpr::Or(vec![
+            FilterExpr::And(vec![
+                FilterExpr::Field(
+                    FieldPredicate::Priority(
+                        Priority::High,
+                    ),
+                ),
+                FilterExpr::Field(
+                    FieldPredicate::Overdue,
+                ),
+            ]),
+            FilterExpr::Field(
+                FieldPredicate::Keyword(
+                    "urgent".into(),
+                ),
+            ),
+        ]);
+
+        let mut actual: Vec<_> = todos
+            .search(
+                &Query::builder()
+                    .expr(Some(expr))
+                    .build(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title().clone())
+            .collect();
+        actual.sort();
+
+        assert_eq!(
+            actual,
+            vec![
+                "Pay rent",
+                "urgent: call mom"
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_update_should_set_and_clear_color_and_icon(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Pay rent",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .color(Some(
+                        Color::new(
+                            "#1E90FF",
+                        ),
+                    ))
+                    .icon(Some(
+                        Icon::new(
+                            "home",
+                        ),
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            (
+                added.color(),
+                added.icon()
+            ),
+            (
+                &Some("#1e90ff".into()),
+                &Some("home".into())
+            )
+        );
+
+        let updated = todos
+            .update(
+                added.id,
+                &UpdateTodo::builder()
+                    .color(Some(
+                        Color::new(""),
+                    ))
+                    .icon(Some(
+                        Icon::new(
+                            "star",
+                        ),
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            (
+                updated.color(),
+                updated.icon()
+            ),
+            (
+                &None,
+                &Some("star".into())
+            )
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_reject_an_icon_outside_the_safelist(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos.add(
+            &NewTodo::builder()
+                .title(Title::new(
+                    "Pay rent",
+                ))
+                .priority(
+                    Priority::High,
+                )
+                .icon(Some(Icon::new(
+                    "skull",
+                )))
+                .build(),
+        );
+
+        let expected =
+            AppError::UnknownIcon {
+                input: "skull".into(),
+                allowed: Icon::SAFELIST
+                    .join(", "),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_add_attachment_should_cap_the_links_per_item(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Review spec",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        for n in 0..20 {
+            todos
+                .add_attachment(
+                    added.id,
+                    &format!("https://example.com/{n}"),
+                    "",
+                )
+                .unwrap();
+        }
+
+        let actual = todos.add_attachment(
+            added.id,
+            "https://example.com/one-more",
+            "",
+        );
+
+        let expected =
+            AppError::TooManyAttachments {
+                id: added.id,
+                max: 20,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_remove_attachment_should_drop_the_link(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Review spec",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let url = "https://example.com/spec.pdf";
+
+        let with_link = todos
+            .add_attachment(
+                added.id, url, "Spec",
+            )
+            .unwrap();
+        assert_eq!(
+            with_link
+                .attachments()
+                .len(),
+            1
+        );
+
+        let without = todos
+            .remove_attachment(
+                added.id, url,
+            )
+            .unwrap();
+        assert!(without
+            .attachments()
+            .is_empty());
+
+        let actual = todos
+            .remove_attachment(
+                added.id, url,
+            );
+
+        let expected =
+            AppError::AttachmentNotFound {
+                id: added.id,
+                url: url.into(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_comments_should_be_added_listed_and_deleted(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Review spec",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let first = todos
+            .add_comment(
+                added.id,
+                "ann",
+                "Looks good",
+            )
+            .unwrap();
+        let second = todos
+            .add_comment(
+                added.id,
+                "bob",
+                " Needs a diagram ",
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .list_comments(added.id)
+                .unwrap(),
+            vec![
+                first.clone(),
+                second.clone()
+            ]
+        );
+        assert_eq!(
+            second.text(),
+            "Needs a diagram"
+        );
+
+        todos
+            .delete_comment(
+                added.id,
+                first.id(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .list_comments(added.id)
+                .unwrap(),
+            vec![second]
+        );
+
+        let actual = todos
+            .delete_comment(
+                added.id,
+                first.id(),
+            );
+
+        let expected =
+            AppError::CommentNotFound {
+                id: added.id,
+                comment_id: first.id(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_add_comment_should_reject_an_overlong_text(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Review spec",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos.add_comment(
+            added.id,
+            "ann",
+            &"a".repeat(1001),
+        );
+
+        let expected =
+            AppError::TooLongComment {
+                len: 1001,
+                max: 1000,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_should_reject_changes_while_frozen(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Audit books",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        todos.freeze();
+
+        assert!(todos.is_frozen());
+        assert_eq!(
+            todos
+                .delete_by_ids(
+                    &nes![added.id],
+                    true,
+                )
+                .unwrap(),
+            1
+        );
+
+        let actual =
+            todos.delete(added.id);
+
+        let expected =
+            AppError::ListFrozen;
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        todos.unfreeze();
+
+        assert!(todos
+            .delete(added.id)
+            .is_ok());
+    }
+
+    #[test]
+    fn todolist_should_reject_setting_changes_while_frozen(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "Audit books",
+            ))
+            .build();
+
+        todos
+            .save_query(
+                "all",
+                Query::builder()
+                    .build(),
+            )
+            .unwrap();
+        todos
+            .save_template(
+                "audit",
+                item.clone(),
+            )
+            .unwrap();
+
+        todos.freeze();
+
+        let before =
+            todos.get_settings();
+
+        type Change = Box<
+            dyn Fn(
+                &mut TodoList,
+            )
+                -> AppResult<()>,
+        >;
+
+        let changes: Vec<Change> = vec![
+            Box::new(|t| {
+                t.save_query(
+                    "none",
+                    Query::builder()
+                        .build(),
+                )
+            }),
+            Box::new(|t| {
+                t.delete_saved("all")
+            }),
+            Box::new(move |t| {
+                t.save_template(
+                    "other",
+                    item.clone(),
+                )
+            }),
+            Box::new(|t| {
+                t.delete_template(
+                    "audit",
+                )
+            }),
+            Box::new(|t| {
+                t.set_warn_on_similar(
+                    true,
+                )
+            }),
+            Box::new(|t| {
+                t.set_escalation_policy(
+                    EscalationPolicy::default(),
+                )
+            }),
+            Box::new(|t| {
+                t.set_retention_policy(
+                    None,
+                )
+            }),
+            Box::new(|t| {
+                t.set_deadline_bounds(
+                    Some(
+                        DeadlineBounds::default(),
+                    ),
+                )
+            }),
+            Box::new(|t| {
+                t.set_strict_limits(
+                    true,
+                )
+            }),
+            Box::new(|t| {
+                t.set_track_views(false)
+            }),
+            Box::new(|t| {
+                t.set_title_collation(
+                    TitleCollation::Natural,
+                )
+            }),
+            Box::new(|t| {
+                t.set_creation_defaults(
+                    CreationDefaults::default(),
+                )
+            }),
+            Box::new(|t| {
+                t.set_query_cache_size(
+                    0,
+                )
+            }),
+            Box::new(|t| {
+                t.update_settings(
+                    SettingsUpdate::builder()
+                        .strict_limits(
+                            Some(true),
+                        )
+                        .build(),
+                )
+                .map(drop)
+            }),
+        ];
+
+        for change in changes {
+            let actual =
+                change(&mut todos);
+
+            let expected =
+                AppError::ListFrozen;
+
+            assert_app_error!(
+                actual, expected
+            );
+        }
+
+        assert_eq!(
+            todos.get_settings(),
+            before
+        );
+        assert_eq!(
+            todos.list_saved(),
+            vec!["all"]
+        );
+        assert_eq!(
+            todos.list_templates(),
+            vec!["audit"]
+        );
+    }
+
+    #[test]
+    fn todolist_authorize_should_require_the_configured_token(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        assert!(todos
+            .authorize(
+                None,
+                Role::Admin
+            )
+            .is_ok());
+
+        todos
+            .configure_auth(
+                None,
+                Some("correct-horse-battery"),
+            )
+            .unwrap();
+
+        assert!(todos
+            .authorize(
+                Some("correct-horse-battery"),
+                Role::Admin
+            )
+            .is_ok());
+
+        let actual = todos.authorize(
+            Some("guess"),
+            Role::Viewer,
+        );
+
+        let expected =
+            AppError::Unauthorized;
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        let actual = todos
+            .configure_auth(None, None);
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_configure_auth_should_reject_a_short_token(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos
+            .configure_auth(
+                None,
+                Some("hunter2"),
+            );
+
+        let expected =
+            AppError::WeakAuthToken {
+                min: 16,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert!(!todos.auth_required());
+    }
+
+    #[test]
+    fn todolist_authorize_should_check_the_principal_role(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .configure_auth(
+                None,
+                Some("correct-horse-battery"),
+            )
+            .unwrap();
+        todos
+            .register_principal(
+                "ann",
+                "ann-reads-everything",
+                Role::Viewer,
+            )
+            .unwrap();
+
+        assert!(todos
+            .authorize(
+                Some("ann-reads-everything"),
+                Role::Viewer
+            )
+            .is_ok());
+
+        let actual = todos.authorize(
+            Some(
+                "ann-reads-everything",
+            ),
+            Role::Editor,
+        );
+
+        let expected =
+            AppError::PermissionDenied {
+                principal: "ann".into(),
+                role: Role::Viewer,
+                required: Role::Editor,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        todos
+            .remove_principal("ann")
+            .unwrap();
+
+        let actual = todos.authorize(
+            Some(
+                "ann-reads-everything",
+            ),
+            Role::Viewer,
+        );
+
+        let expected =
+            AppError::Unauthorized;
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_instantiate_template_should_apply_overrides(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .save_template(
+                " onboarding ",
+                NewTodo::builder()
+                    .title(Title::new(
+                        "Set up laptop",
+                    ))
+                    .priority(Priority::Medium)
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos.list_templates(),
+            vec!["onboarding"]
+        );
+
+        let actual = todos
+            .instantiate_template(
+                "onboarding",
+                TemplateOverrides::builder()
+                    .priority(Some(
+                        Priority::High,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            (
+                actual.title(),
+                actual.priority()
+            ),
+            (
+                &"Set up laptop"
+                    .to_string(),
+                Priority::High
+            )
+        );
+
+        todos
+            .delete_template(
+                "onboarding",
+            )
+            .unwrap();
+
+        let actual = todos
+            .instantiate_template(
+            "onboarding",
+            TemplateOverrides::default(
+            ),
+        );
+
+        let expected =
+            AppError::TemplateNotFound(
+                "onboarding".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_apply_retention_should_delete_expired_items_only(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let now = add_todos(&mut todos)
+            .unwrap()
+            .iter()
+            .map(|t| {
+                t.updated_timestamp
+            })
+            .max()
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .apply_retention(now)
+                .unwrap(),
+            vec![]
+        );
+
+        todos
+            .set_retention_policy(Some(
+            RetentionPolicy::builder()
+                .older_than_days(30)
+                .statuses(vec![
+                    Status::Backlog,
+                ])
+                .build(),
+        )).unwrap();
+
+        assert_eq!(
+            todos
+                .apply_retention(now)
+                .unwrap(),
+            vec![]
+        );
+
+        let removed = todos
+            .apply_retention(
+                now + 31
+                    * SECONDS_PER_DAY,
+            )
+            .unwrap();
+
+        assert_eq!(removed.len(), 9);
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_stats_over_time_should_count_created_and_completed_items_per_bucket(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        for t in &added[..2] {
+            todos
+                .update(
+                    t.id,
+                    &UpdateTodo::builder()
+                        .status(Some(
+                            Status::Done,
+                        ))
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let from = added[0]
+            .created_timestamp
+            - 5;
+
+        let actual = todos
+            .stats_over_time(
+                TrendBucket::Day,
+                from,
+                from + 2
+                    * SECONDS_PER_DAY,
+            )
+            .unwrap()
+            .iter()
+            .map(|p| {
+                (
+                    p.start(),
+                    p.created(),
+                    p.completed(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            actual,
+            vec![
+                (from, 9, 2),
+                (
+                    from + SECONDS_PER_DAY,
+                    0,
+                    0
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_stats_over_time_should_reject_an_empty_range(
+    ) {
+        let todos = new_todo_list!();
+
+        let actual = todos
+            .stats_over_time(
+                TrendBucket::Week,
+                10,
+                10,
+            );
+
+        let expected =
+            AppError::InvalidTrendRange(
+                "10 is not after 10"
+                    .into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_overdue_report_should_group_open_overdue_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("x"))
+            .priority(Priority::High)
+            .allow_past_deadline(true)
+            .build();
+
+        let late_by_hours = todos
+            .add(&NewTodo {
+                deadline: OptionalDeadlineInput::some("2022-02-28 12"),
+                ..item.cloned_with_title("a")
+            })
+            .unwrap();
+        let late_by_days = todos
+            .add(&NewTodo {
+                priority: Some(Priority::Low),
+                deadline: OptionalDeadlineInput::some("2022-02-25 00"),
+                ..item.cloned_with_title("b")
+            })
+            .unwrap();
+        let late_by_months = todos
+            .add(&NewTodo {
+                priority: Some(Priority::Medium),
+                deadline: OptionalDeadlineInput::some("2021-12-01 00"),
+                ..item.cloned_with_title("c")
+            })
+            .unwrap();
+        let done = todos
+            .add(&NewTodo {
+                deadline: OptionalDeadlineInput::some("2021-12-01 00"),
+                ..item.cloned_with_title("d")
+            })
+            .unwrap();
+        todos
+            .update_status(
+                done.id,
+                Status::Done,
+            )
+            .unwrap();
+
+        let now = OptionalDeadlineInput::some("2022-03-01 00")
+            .unix_time(TimeZone::default())
+            .unwrap()
+            .unwrap();
+
+        let actual =
+            todos.overdue_report(now);
+
+        assert_eq!(
+            actual.by_lateness(),
+            &vec![
+                (Lateness::UpToADay, 1),
+                (Lateness::UpToAWeek, 1),
+                (Lateness::UpToAMonth, 0),
+                (Lateness::OverAMonth, 1),
+            ]
+        );
+        assert_eq!(
+            actual.by_priority(),
+            &vec![
+                (Priority::Low, 1),
+                (Priority::Medium, 1),
+                (Priority::High, 1),
+            ]
+        );
+        assert_eq!(
+            actual.worst(),
+            &vec![
+                late_by_months,
+                late_by_days,
+                late_by_hours
+            ]
+        );
+    }
+
+    #[test]
+    fn todolist_search_projected_should_keep_only_the_projected_fields(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "abc",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .search_projected(
+                &Query::builder()
+                    .projection(vec![
+                        TodoField::Title,
+                        TodoField::Status,
+                    ])
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(
+            actual[0].id(),
+            added.id
+        );
+        assert_eq!(
+            actual[0].title(),
+            &Some("abc".into())
+        );
+        assert_eq!(
+            actual[0].priority(),
+            None
+        );
+        assert_eq!(
+            actual[0].status(),
+            Some(Status::Backlog)
+        );
+
+        let actual = todos
+            .search_projected(
+                &Query::empty(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual[0].priority(),
+            Some(Priority::High)
+        );
+    }
+
+    #[test]
+    fn todolist_search_with_total_should_page_by_cursor_despite_inserts(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        let page = |todos: &TodoList,
+                    after: Option<String>| {
+            todos
+                .search_with_total(
+                    &Query::builder()
+                        .limit(
+                            OptionalResultLimit::some(4),
+                        )
+                        .sort(Some(
+                            QuerySort::Priority,
+                        ))
+                        .after(after)
+                        .build(),
+                )
+                .unwrap()
+        };
+
+        let first = page(&todos, None);
+
+        todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "new",
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let second = page(
+            &todos,
+            first.next_cursor().clone(),
+        );
+        let third = page(
+            &todos,
+            second
+                .next_cursor()
+                .clone(),
+        );
+
+        assert_eq!(
+            third.next_cursor(),
+            &None
+        );
+
+        let paged: HashSet<_> = first
+            .items()
+            .iter()
+            .chain(second.items())
+            .chain(third.items())
+            .map(|t| t.id)
+            .collect();
+
+        assert_eq!(
+            (
+                first.items().len()
+                    + second
+                        .items()
+                        .len()
+                    + third
+                        .items()
+                        .len(),
+                paged,
+            ),
+            (
+                9,
+                added
+                    .iter()
+                    .map(|t| t.id)
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn todolist_search_with_total_should_reject_a_cursor_for_another_sort(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let cursor = todos
+            .search_with_total(
+                &Query::builder()
+                    .limit(
+                        OptionalResultLimit::some(1),
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .next_cursor()
+            .clone()
+            .unwrap();
+
+        let actual = todos
+            .search_with_total(
+            &Query::builder()
+                .sort(Some(
+                    QuerySort::Deadline,
+                ))
+                .after(Some(
+                    cursor.clone(),
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::InvalidCursor(
+                cursor,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_break_ties_by_title_then_id(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("x"))
+            .priority(Priority::Low)
+            .build();
+
+        let mut same_title: Vec<_> = (0
+            ..5)
+            .map(|_| {
+                todos
+                    .add(&item)
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        same_title.sort();
+
+        let first = todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "a",
+                    ),
+            )
+            .unwrap()
+            .id;
+
+        let search = |limit: u32| {
+            todos
+                .search(
+                    &Query::builder()
+                        .sort(Some(
+                            QuerySort::Priority,
+                        ))
+                        .limit(
+                            OptionalResultLimit::some(limit),
+                        )
+                        .build(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|t| t.id)
+                .collect::<Vec<_>>()
+        };
+
+        let expected: Vec<_> = [first]
+            .into_iter()
+            .chain(same_title)
+            .collect();
+
+        for limit in 1..=6 {
+            assert_eq!(
+                search(limit),
+                expected
+                    [..limit as usize]
+            );
+        }
+    }
+
+    #[test]
+    fn todolist_search_should_break_timestamp_ties_by_title(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for title in ["c", "a", "b"] {
+            let added = todos
+                .add(
+                    &NewTodo::builder()
+                        .title(
+                            Title::new(
+                                title,
+                            ),
+                        )
+                        .build(),
+                )
+                .unwrap();
+
+            todos.items.insert(Todo {
+                created_timestamp: 100,
+                updated_timestamp: 100,
+                ..added
+            });
+        }
+
+        for sort in [
+            QuerySort::CreatedAt,
+            QuerySort::UpdatedTimestamp,
+        ] {
+            let titles = todos
+                .search(
+                    &Query::builder()
+                        .sort(Some(
+                            sort,
+                        ))
+                        .build(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                titles,
+                vec!["a", "b", "c"]
+            );
+        }
+    }
+
+    #[test]
+    fn todolist_list_page_should_page_through_every_item_by_title(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let titles =
+            |page: Vec<Todo>| {
+                page.into_iter()
+                    .map(|t| t.title)
+                    .collect::<Vec<_>>()
+            };
+
+        assert_eq!(
+            titles(
+                todos
+                    .list_page(
+                        0,
+                        &OptionalResultLimit::some(4)
+                    )
+                    .unwrap()
+            ),
+            vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            titles(
+                todos
+                    .list_page(
+                        8,
+                        &OptionalResultLimit::some(4)
+                    )
+                    .unwrap()
+            ),
+            vec!["i"]
+        );
+        assert_eq!(
+            todos
+                .list_page(
+                    0,
+                    &OptionalResultLimit::some(
+                        OptionalResultLimit::MAX + 1
+                    )
+                )
+                .unwrap()
+                .len(),
+            9
+        );
+    }
+
+    #[test]
+    fn todolist_suggest_titles_should_rank_common_titles_first(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("x"))
+            .priority(Priority::Low)
+            .build();
+
+        for title in [
+            "Pay rent",
+            "pay bills",
+            "Pay rent",
+            "Call mom",
+        ] {
+            todos
+                .add(
+                    &item.cloned_with_title(
+                        title,
+                    ),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            todos
+                .suggest_titles(
+                    " pay",
+                    &OptionalResultLimit::some(5)
+                )
+                .unwrap(),
+            vec!["Pay rent", "pay bills"]
+        );
+        assert_eq!(
+            todos
+                .suggest_titles(
+                    "",
+                    &OptionalResultLimit::some(5)
+                )
+                .unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn todolist_recent_should_list_viewed_items_only_while_tracking(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        let limit =
+            OptionalResultLimit::some(
+                5,
+            );
+
+        todos
+            .get_and_record_view(
+                added[0].id,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .recent(
+                    RecentKind::Viewed,
+                    &limit
+                )
+                .unwrap(),
+            vec![]
+        );
+
+        todos
+            .set_track_views(true)
+            .unwrap();
+
+        todos
+            .get_and_record_view(
+                added[3].id,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .recent(
+                    RecentKind::Viewed,
+                    &limit
+                )
+                .unwrap(),
+            vec![added[3].clone()]
+        );
+        assert_eq!(
+            todos
+                .recent(
+                    RecentKind::Created,
+                    &limit
+                )
+                .unwrap()
+                .len(),
+            5
+        );
+
+        todos
+            .set_track_views(false)
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .recent(
+                    RecentKind::Viewed,
+                    &limit
+                )
+                .unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn todolist_external_refs_should_be_unique_per_system(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+        let (a, b) =
+            (added[0].id, added[1].id);
+
+        todos
+            .set_external_ref(
+                a,
+                "jira",
+                Some("PROJ-1"),
+            )
+            .unwrap();
+        todos
+            .set_external_ref(
+                b,
+                "github",
+                Some("PROJ-1"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .find_by_external_ref(
+                    "jira", "PROJ-1"
+                )
+                .unwrap()
+                .id,
+            a
+        );
+
+        let actual = todos
+            .set_external_ref(
+                b,
+                "jira",
+                Some("PROJ-1"),
+            );
+
+        let expected =
+            AppError::ExternalRefInUse {
+                system: "jira".into(),
+                external_id: "PROJ-1"
+                    .into(),
+                holder: a,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+
+        todos
+            .set_external_ref(
+                a, "jira", None,
+            )
+            .unwrap();
+
+        let actual = todos
+            .find_by_external_ref(
+                "jira", "PROJ-1",
+            );
+
+        let expected =
+            AppError::ExternalRefNotFound {
+                system: "jira".into(),
+                external_id: "PROJ-1"
+                    .into(),
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_diff_since_and_apply_remote_should_reconcile_both_sides(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added =
+            add_todos(&mut todos)
+                .unwrap();
+
+        // Everything predates the last sync at 200.
+        for t in added.iter() {
+            todos.items.insert(Todo {
+                created_timestamp: 100,
+                updated_timestamp: 100,
+                ..t.clone()
+            });
+        }
+
+        let [a, b, c, d] = [0, 1, 2, 3]
+            .map(|i| added[i].id);
+
+        todos
+            .update_status(
+                a,
+                Status::Done,
+            )
+            .unwrap();
+        todos.delete(b).unwrap();
+        let j = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "j",
+                    ))
+                    .priority(
+                        Priority::Low,
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .id;
+
+        let summary =
+            todos.diff_since(200);
+
+        assert_eq!(
+            (
+                summary
+                    .created()
+                    .clone(),
+                summary
+                    .updated()
+                    .clone(),
+                summary
+                    .deleted()
+                    .clone()
+            ),
+            (vec![j], vec![a], vec![b])
+        );
+
+        let remote =
+            |id, title: &str| {
+                RemoteChange::Upsert(
+                RemoteTodo::builder()
+                    .id(id)
+                    .title(Title::new(title))
+                    .priority(Priority::High)
+                    .status(Status::Backlog)
+                    .updated_timestamp(150)
+                    .build(),
+            )
+            };
+
+        let k = Uuid::new_v4();
+
+        let report = todos
+            .apply_remote(
+                vec![
+                    remote(a, "a2"),
+                    remote(c, "c2"),
+                    remote(k, "k"),
+                    RemoteChange::Delete(d),
+                ],
+                200,
+                SyncStrategy::Merge,
+            )
+            .unwrap();
+
+        assert_eq!(
+            (
+                report
+                    .applied()
+                    .clone(),
+                report
+                    .conflicts()
+                    .clone()
+            ),
+            (vec![c, k, d], vec![a])
+        );
+        assert_eq!(
+            todos.get(a).unwrap().title,
+            "a"
+        );
+        assert_eq!(
+            todos.get(c).unwrap().title,
+            "c2"
+        );
+        assert_eq!(
+            todos
+                .get(k)
+                .unwrap()
+                .priority,
+            Priority::High
+        );
+        assert!(todos.get(d).is_err());
+
+        let actual = todos
+            .apply_remote(
+            vec![remote(c, "")],
+            200,
+            SyncStrategy::RemoteWins,
+        );
+
+        let expected =
+            AppError::EmptyTodoTitle;
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_state_checksum_should_ignore_order_but_not_content(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let snapshot = todos.snapshot();
+
+        // The restored map hashes with its own seed, so it holds the
+        // items in another order.
+        let mut restored =
+            new_todo_list!();
+        restored
+            .restore(snapshot.clone())
+            .unwrap();
+
+        assert_eq!(
+            restored.state_checksum(),
+            todos.state_checksum()
+        );
+
+        let id = snapshot.items()[0].id;
+        restored
+            .update_status(
+                id,
+                Status::Done,
+            )
+            .unwrap();
+
+        assert_ne!(
+            restored.state_checksum(),
+            todos.state_checksum()
+        );
+    }
+
+    #[test]
+    fn todolist_find_similar_should_group_near_identical_titles_with_equal_deadlines(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "Buy milk",
+            ))
+            .priority(Priority::Low)
+            .build();
+
+        let a =
+            todos.add(&item).unwrap();
+        let b = todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "buy  milk!",
+                    ),
+            )
+            .unwrap();
+        let c = todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "Buy silk",
+                    ),
+            )
+            .unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "Call the bank",
+                    ),
+            )
+            .unwrap();
+
+        let ids = |threshold| {
+            todos
+                .find_similar(threshold)
+                .unwrap()
+                .into_iter()
+                .map(|g| {
+                    g.into_iter()
+                        .map(|t| t.id)
+                        .collect::<HashSet<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(100),
+            vec![
+                hashset! { a.id, b.id }
+            ]
+        );
+        assert_eq!(
+            ids(80),
+            vec![
+                hashset! { a.id, b.id, c.id }
+            ]
+        );
+
+        let actual =
+            todos.find_similar(101);
+
+        let expected = AppError::InvalidSimilarityThreshold(101);
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_add_or_warn_should_hold_back_a_near_duplicate_when_warnings_are_on(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "Renew passport",
+            ))
+            .priority(Priority::Low)
+            .build();
+        let typo = item
+            .cloned_with_title(
+                "renew pasport",
+            );
+
+        let first =
+            todos.add(&item).unwrap();
+
+        assert!(matches!(
+            todos.add_or_warn(&typo),
+            Ok(AddOutcome::Added(_))
+        ));
+
+        todos
+            .set_warn_on_similar(true)
+            .unwrap();
+
+        let actual = todos
+            .add_or_warn(&item)
+            .unwrap();
+
+        let AddOutcome::Similar(found) =
+            actual
+        else {
+            panic!(
+                "{actual:?} was added"
+            )
+        };
+
+        // Both were added within the same second, so either may lead.
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&first));
+
+        todos
+            .update_status(
+                first.id,
+                Status::Done,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            todos.add_or_warn(
+                &item
+                    .cloned_with_title(
+                        "Book flights"
+                    )
+            ),
+            Ok(AddOutcome::Added(_))
+        ));
+        assert_eq!(
+            todos.count_all(),
+            3
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_order_titles_by_the_list_collation(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("zebra"))
+            .priority(Priority::Low)
+            .build();
+
+        for title in [
+            "zebra",
+            "Ångström",
+            "apple",
+            "Banana",
+        ] {
+            todos
+                .add(
+                    &item.cloned_with_title(
+                        title,
+                    ),
+                )
+                .unwrap();
+        }
+
+        let titles =
+            |todos: &TodoList| {
+                todos
+                    .search(
+                        &Query::empty(),
+                    )
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| t.title)
+                    .collect::<Vec<_>>()
+            };
+
+        assert_eq!(
+            titles(&todos),
+            vec![
+                "Banana",
+                "apple",
+                "zebra",
+                "Ångström"
+            ]
+        );
+
+        let cursor = todos
+            .search_with_total(
+                &Query::builder()
+                    .limit(
+                        OptionalResultLimit::some(1),
+                    )
+                    .build(),
+            )
+            .unwrap()
+            .next_cursor
+            .unwrap();
+
+        todos
+            .set_title_collation(
+                TitleCollation::Natural,
+            )
+            .unwrap();
+
+        assert_eq!(
+            titles(&todos),
+            vec![
+                "Ångström",
+                "apple",
+                "Banana",
+                "zebra"
+            ]
+        );
+        assert_eq!(
+            todos
+                .list_page(
+                    0,
+                    &OptionalResultLimit::some(10),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect::<Vec<_>>(),
+            titles(&todos)
+        );
+
+        let actual = todos.search(
+            &Query::builder()
+                .after(Some(
+                    cursor.clone(),
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::InvalidCursor(
+                cursor,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_match_keywords_as_plain_text_when_asked(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "🚀 **Release** v2",
+            ))
+            .priority(Priority::High)
+            .build();
+
+        let release =
+            todos.add(&item).unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "Write notes",
+                    ),
+            )
+            .unwrap();
+
+        let search =
+            |keyword: &str,
+             plain: bool| {
+                todos
+                .search(
+                    &Query::builder()
+                        .keyword(Some(
+                            keyword.into(),
+                        ))
+                        .plain_text_match(
+                            plain,
+                        )
+                        .build(),
+                )
+                .unwrap()
+            };
+
+        assert_eq!(
+            search("release", false),
+            vec![]
+        );
+        assert_eq!(
+            search("release", true),
+            vec![release.clone()]
+        );
+        assert_eq!(
+            search("release v2", true),
+            vec![release]
+        );
+    }
+
+    #[test]
+    fn todolist_update_settings_should_change_nothing_when_any_setting_is_invalid(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let before =
+            todos.get_settings();
+
+        let actual = todos
+            .update_settings(
+            SettingsUpdate::builder()
+                .default_time_zone(
+                    Some(
+                        "Mars/Olympus"
+                            .into(),
+                    ),
+                )
+                .strict_limits(Some(
+                    true,
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::InvalidTimeZone(
+                "Mars/Olympus".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.get_settings(),
+            before
+        );
+    }
+
+    #[test]
+    fn todolist_update_settings_should_refuse_another_settings_version(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let before =
+            todos.get_settings();
+
+        let actual = todos
+            .update_settings(
+            SettingsUpdate::builder()
+                .version(Some(
+                    ListSettings::VERSION
+                        - 1,
+                ))
+                .strict_limits(Some(
+                    true,
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::SettingsVersionMismatch {
+                expected:
+                    ListSettings::VERSION,
+                actual:
+                    ListSettings::VERSION
+                        - 1,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.get_settings(),
+            before
+        );
+        assert!(todos
+            .update_settings(
+                SettingsUpdate::builder()
+                    .version(Some(
+                        ListSettings::VERSION
+                    ))
+                    .strict_limits(Some(
+                        true
+                    ))
+                    .build(),
+            )
+            .unwrap()
+            .strict_limits());
+    }
+
+    #[test]
+    fn todolist_update_settings_should_apply_every_given_setting(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let bounds =
+            DeadlineBounds::builder()
+                .max_days_past(1)
+                .max_years_ahead(2)
+                .build();
+
+        todos
+            .set_deadline_bounds(Some(
+                bounds,
+            ))
+            .unwrap();
+
+        let actual = todos
+            .update_settings(
+                SettingsUpdate::builder()
+                    .default_time_zone(Some(
+                        "Europe/Paris".into(),
+                    ))
+                    .deadline_bounds(Some(
+                        None,
+                    ))
+                    .strict_limits(Some(true))
+                    .title_collation(Some(
+                        TitleCollation::Natural,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.default_time_zone(),
+            TimeZone::parse(
+                "Europe/Paris"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            actual.deadline_bounds(),
+            None
+        );
+        assert!(actual.strict_limits());
+        assert_eq!(
+            actual.title_collation(),
+            TitleCollation::Natural
+        );
+        assert!(!actual.track_views());
+        assert_eq!(
+            todos.get_settings(),
+            actual
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_fill_in_creation_defaults_for_omitted_fields(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos.set_creation_defaults(
+            CreationDefaults::builder()
+                .priority(
+                    Priority::High,
+                )
+                .deadline_after_days(
+                    Some(7),
+                )
+                .build(),
+        ).unwrap();
+
+        let before = unix_time_now!();
+
+        let defaulted = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "a",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let explicit = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new("b"))
+                    .priority(Priority::Low)
+                    .deadline(
+                        OptionalDeadlineInput::some(
+                            "+1d",
+                        ),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            defaulted.priority(),
+            Priority::High
+        );
+        assert!(defaulted
+            .deadline()
+            .is_some_and(|d| {
+                d >= before
+                    + 7 * SECONDS_PER_DAY
+            }));
+        assert_eq!(
+            explicit.priority(),
+            Priority::Low
+        );
+        assert!(explicit
+            .deadline()
+            .is_some_and(|d| {
+                d < before
+                    + 2 * SECONDS_PER_DAY
+            }));
+    }
+
+    #[test]
+    fn todolist_shift_deadlines_should_move_only_matching_items_with_a_deadline(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("a"))
+            .priority(Priority::High)
+            .deadline(
+                OptionalDeadlineInput::some(
+                    "+2d",
+                ),
+            )
+            .build();
+
+        let due =
+            todos.add(&item).unwrap();
+        let other = todos
+            .add(&NewTodo {
+                priority: Some(
+                    Priority::Low,
+                ),
+                ..item
+                    .cloned_with_title(
+                        "b",
+                    )
+            })
+            .unwrap();
+        let undated = todos
+            .add(&NewTodo {
+                deadline:
+                    OptionalDeadlineInput::none(),
+                ..item.cloned_with_title("c")
+            })
+            .unwrap();
+
+        let actual = todos
+            .shift_deadlines(
+                &Query::builder()
+                    .priority(Some(
+                        Priority::High,
+                    ))
+                    .build(),
+                -24,
+            )
+            .unwrap();
+
+        assert_eq!(actual, 1);
+        assert_eq!(
+            todos
+                .get(due.id)
+                .unwrap()
+                .deadline(),
+            due.deadline().map(|d| {
+                d - SECONDS_PER_DAY
+            })
+        );
+        assert_eq!(
+            todos
+                .get(other.id)
+                .unwrap()
+                .deadline(),
+            other.deadline()
+        );
+        assert_eq!(
+            todos
+                .get(undated.id)
+                .unwrap()
+                .deadline(),
+            None
+        );
+    }
+
+    #[test]
+    fn todolist_watch_should_keep_one_entry_per_watcher(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let todo =
+            add_todos(&mut todos)
+                .unwrap()[0]
+                .clone();
+
+        todos
+            .watch(todo.id, "alice")
+            .unwrap();
+        todos
+            .watch(todo.id, " alice ")
+            .unwrap();
+        todos
+            .watch(todo.id, "bob")
+            .unwrap();
+        todos
+            .unwatch(todo.id, "carol")
+            .unwrap();
+        todos
+            .unwatch(todo.id, "bob")
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .list_watchers(todo.id)
+                .unwrap(),
+            vec!["alice".to_string()]
+        );
+
+        let actual =
+            todos.watch(todo.id, " ");
+
+        let expected =
+            AppError::InvalidWatcher(
+                "".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_activity_should_list_recorded_changes_newest_first(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let todo =
+            add_todos(&mut todos)
+                .unwrap()[0]
+                .clone();
+
+        todos
+            .update(
+                todo.id,
+                &UpdateTodo::builder()
+                    .title(Some(
+                        Title::new(
+                            "renamed",
+                        ),
+                    ))
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let comment = todos
+            .add_comment(
+                todo.id,
+                "alice",
+                "done at last",
+            )
+            .unwrap();
+
+        let actual: Vec<_> = todos
+            .activity(
+                &OptionalResultLimit::default(),
+                &ActivityFilter::builder()
+                    .todo_id(Some(todo.id))
+                    .build(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|a| {
+                (a.actor().clone(), a.kind())
+            })
+            .collect();
+
+        let expected = vec![
+            (
+                Some("alice".to_string()),
+                ActivityKind::CommentAdded {
+                    comment_id: comment.id(),
+                },
+            ),
+            (
+                None,
+                ActivityKind::StatusChanged {
+                    before: Status::Backlog,
+                    after: Status::Done,
+                },
+            ),
+            (
+                None,
+                ActivityKind::Created,
+            ),
+        ];
+
+        assert_eq!(actual, expected);
+        assert_eq!(
+            todos
+                .activity(
+                    &OptionalResultLimit::some(
+                        2
+                    ),
+                    &ActivityFilter::default(),
+                )
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn todolist_set_actor_should_attribute_changes_until_cleared(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let todo =
+            add_todos(&mut todos)
+                .unwrap()[0]
+                .clone();
+
+        todos
+            .set_actor(Some(" alice "))
+            .unwrap();
+
+        let updated = todos
+            .update(
+                todo.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        todos.clear_actor();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "z",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            updated.last_modified_by(),
+            &Some("alice".to_string())
+        );
+        assert_eq!(
+            added.last_modified_by(),
+            &None
+        );
+        assert_eq!(
+            todos
+                .activity(
+                    &OptionalResultLimit::some(
+                        2
+                    ),
+                    &ActivityFilter::default(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|a| a.actor().clone())
+                .collect::<Vec<_>>(),
+            vec![
+                None,
+                Some("alice".to_string())
+            ]
+        );
+
+        let actual =
+            todos.set_actor(Some(""));
+
+        let expected =
+            AppError::InvalidActor(
+                "".into(),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_search_with_warnings_should_report_a_clamped_limit_and_default_time_zone(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let actual = todos
+            .search_with_warnings(
+                &Query::builder()
+                    .deadline(
+                        OptionalDeadlineInput::some(
+                            "2099-01-01 00",
+                        ),
+                    )
+                    .limit(
+                        OptionalResultLimit::some(
+                            500,
+                        ),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        let expected = vec![
+            Warning::LimitClamped {
+                given: 500,
+                used: OptionalResultLimit::MAX,
+            },
+            Warning::DefaultTimeZoneUsed(
+                todos.default_time_zone(),
+            ),
+        ];
+
+        assert_eq!(
+            actual.warnings(),
+            &expected
+        );
+        assert!(todos
+            .search_with_warnings(
+                &Query::empty()
+            )
+            .unwrap()
+            .warnings()
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_validate_new_todo_should_report_a_trimmed_title_without_adding(
+    ) {
+        let todos = new_todo_list!();
+
+        let actual = todos
+            .validate_new_todo(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "  Call mom ",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.value().title(),
+            "Call mom"
+        );
+        assert_eq!(
+            actual.warnings(),
+            &vec![
+                Warning::TitleTrimmed
+            ]
+        );
+        assert!(todos
+            .search(&Query::empty())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn todolist_validate_should_report_every_problem_without_adding(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(""))
+            .deadline(
+                OptionalDeadlineInput::some(
+                    "2000-01-01 00",
+                ),
+            )
+            .color(Some(Color::new("red")))
+            .build();
+
+        let actual =
+            todos.validate(&item);
+
+        let expected = vec![
+            AppError::EmptyTodoTitle
+                .to_string(),
+            AppError::DeadlineInPast(
+                "2000-01-01 00".into(),
+            )
+            .to_string(),
+            AppError::InvalidColor(
+                "red".into(),
+            )
+            .to_string(),
+        ];
+
+        assert_eq!(actual, expected);
+        assert!(todos
+            .search(&Query::empty())
+            .unwrap()
+            .is_empty());
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Call mom",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos.validate_update(
+                added.id,
+                &UpdateTodo::builder()
+                    .title(Some(
+                        Title::new(
+                            "Call dad"
+                        )
+                    ))
+                    .build(),
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn todolist_prefix_title_should_revalidate_the_combined_length(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Call mom",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .prefix_title(
+                added.id,
+                "[BLOCKED] ",
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.title(),
+            "[BLOCKED] Call mom"
+        );
+
+        let too_long = todos
+            .append_to_title(
+                added.id,
+                &"!".repeat(
+                    Title::MAX_LEN,
+                ),
+            );
+
+        assert!(too_long.is_err());
+        assert_eq!(
+            todos
+                .get(added.id)
+                .unwrap()
+                .title(),
+            "[BLOCKED] Call mom"
+        );
+    }
+
+    #[test]
+    fn todolist_short_ids_should_increase_and_resolve_like_ids(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("a"))
+            .build();
+
+        let first =
+            todos.add(&item).unwrap();
+        let second =
+            todos.add(&item).unwrap();
+
+        todos
+            .delete(second.id)
+            .unwrap();
+
+        let third =
+            todos.add(&item).unwrap();
+
+        assert_eq!(
+            [
+                first.short_id(),
+                second.short_id(),
+                third.short_id()
+            ],
+            [1, 2, 3]
+        );
+        assert_eq!(
+            todos
+                .resolve("#3")
+                .unwrap(),
+            third.id
+        );
+        assert_eq!(
+            todos
+                .resolve(
+                    &first
+                        .id
+                        .to_string()
+                )
+                .unwrap(),
+            first.id
+        );
+
+        let actual = todos.resolve("2");
+        let expected =
+            AppError::ShortIdNotFound(
+                2,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_resolve_should_accept_a_unique_exact_title(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for title in [
+            "Call mom", "Pay rent",
+            "Pay rent",
+        ] {
+            todos
+                .add(
+                    &NewTodo::builder()
+                        .title(
+                            Title::new(
+                                title,
+                            ),
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let call_mom = todos
+            .find_by_title(
+                "call", false,
+            );
+
+        assert_eq!(call_mom.len(), 1);
+        assert_eq!(
+            todos
+                .resolve("Call mom")
+                .unwrap(),
+            call_mom[0].id
+        );
+        assert!(todos
+            .find_by_title(
+                "call mom", true
+            )
+            .is_empty());
+
+        let actual =
+            todos.resolve("Pay rent");
+        let expected =
+            AppError::AmbiguousTitle {
+                title: "Pay rent"
+                    .into(),
+                count: 2,
+            };
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_query_sql_should_filter_order_and_project(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        for (title, priority) in [
+            (
+                "Call mom",
+                Priority::High,
+            ),
+            (
+                "Pay rent",
+                Priority::High,
+            ),
+            (
+                "Water plants",
+                Priority::Low,
+            ),
+        ] {
+            todos
+                .add(
+                    &NewTodo::builder()
+                        .title(
+                            Title::new(
+                                title,
+                            ),
+                        )
+                        .priority(
+                            priority,
+                        )
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let actual: Vec<_> = todos
+            .query_sql(
+                "SELECT title FROM todos \
+                 WHERE priority = 'high' AND NOT title LIKE '%rent%' \
+                 OR status != 'backlog' \
+                 ORDER BY created_timestamp DESC LIMIT 5;",
+            )
+            .unwrap()
+            .into_iter()
+            .map(|t| {
+                (
+                    t.title().clone(),
+                    t.priority(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![(
+                Some("Call mom".into()),
+                None
+            )]
+        );
+        assert!(todos
+            .query_sql(
+                "SELECT * FROM todos WHERE color = 'red'"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn todolist_fetch_should_nest_only_what_was_asked_for(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let todo = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "x",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+        todos
+            .watch(todo.id, "ann")
+            .unwrap();
+
+        let detail = todos
+            .fetch(
+                todo.id,
+                &FetchInclude::builder(
+                )
+                .watchers(true)
+                .activity(true)
+                .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            detail.todo(),
+            &todos
+                .get(todo.id)
+                .unwrap()
+        );
+        assert_eq!(
+            detail.comments(),
+            &None
+        );
+        assert_eq!(
+            detail.watchers(),
+            &Some(vec![
+                "ann".to_string()
+            ])
+        );
+        assert_eq!(
+            detail
+                .activity()
+                .as_ref()
+                .map(Vec::len),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_skip_titles_already_on_the_list(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let existing = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Read a book",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "Read a book\nWater plants\n",
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.skipped(),
+            &vec![(1, existing.id)]
+        );
+        assert_eq!(
+            actual.imported().len(),
+            1
+        );
+        assert_eq!(
+            actual.checksum(),
+            &todos.state_checksum()
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_refuse_a_payload_with_another_checksum(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos.import_external(
+            ImportFormat::TodoTxt,
+            "Water plants\n",
+            &ImportOptions::builder()
+                .expected_checksum(Some("0000000000000000".into()))
+                .build(),
+        );
+
+        assert!(actual.is_err());
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_import_nothing_when_strict_and_a_row_fails(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let payload = "TYPE,CONTENT,PRIORITY,DATE\n\
+            task,Buy milk,1,\n\
+            task,,2,\n";
+
+        let actual = todos
+            .import_external(
+            ImportFormat::TodoistCsv,
+            payload,
+            &ImportOptions::builder()
+                .strict(true)
+                .build(),
+        );
+
+        assert!(actual.is_err());
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_overwrite_rows_matched_by_external_ref(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let options = ImportOptions::builder()
+            .merge(MergeStrategy::OverwriteExisting)
+            .build();
+
+        todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "(C) Pay rent id:r1\n",
+                &options,
+            )
+            .unwrap();
+
+        let actual = todos
+            .import_external(ImportFormat::TodoTxt, "x Pay the rent id:r1 pri:A\n", &options)
+            .unwrap();
+
+        let updated: Vec<_> = actual
+            .updated()
+            .iter()
+            .map(|(_, t)| {
+                (
+                    t.title().as_str(),
+                    t.priority(),
+                    t.status(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            updated,
+            vec![(
+                "Pay the rent",
+                Priority::High,
+                Status::Done
+            )]
+        );
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_leave_only_the_payload_on_replace(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        add_todos(&mut todos).unwrap();
+
+        let actual = todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "Water plants\n",
+                &ImportOptions::builder()
+                    .merge(MergeStrategy::Replace)
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.imported().len(),
+            1
+        );
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_import_external_should_keep_items_newer_than_undated_rows(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let existing = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Water plants",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let actual = todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "(A) Water plants\n",
+                &ImportOptions::builder()
+                    .merge(MergeStrategy::MergeNewest)
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            actual.skipped(),
+            &vec![(1, existing.id)]
+        );
+        assert_eq!(
+            todos
+                .get(existing.id)
+                .unwrap(),
+            existing
+        );
+    }
+
+    #[test]
+    fn todolist_replica_should_reject_direct_changes(
+    ) {
+        let mut replica =
+            ReplicaList::new();
+
+        let actual = replica.add(
+            &NewTodo::builder()
+                .title(Title::new(
+                    "Buy milk",
+                ))
+                .build(),
+        );
+
+        let expected =
+            AppError::ReadOnlyReplica;
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_replica_should_serve_reads_of_what_it_ingested(
+    ) {
+        let mut replica =
+            ReplicaList::new();
+
+        let id = Uuid::new_v4();
+
+        replica
+            .apply_remote(vec![
+                RemoteChange::Upsert(
+                    RemoteTodo::builder()
+                        .id(id)
+                        .title(Title::new(
+                            "Buy milk",
+                        ))
+                        .priority(
+                            Priority::High,
+                        )
+                        .status(
+                            Status::Backlog,
+                        )
+                        .updated_timestamp(
+                            100,
+                        )
+                        .build(),
+                ),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            replica
+                .get(id)
+                .unwrap()
+                .title,
+            "Buy milk"
+        );
+        assert_eq!(
+            replica.count_all(),
+            1
+        );
+
+        let actual = replica.delete(id);
+
+        let expected =
+            AppError::ReadOnlyReplica;
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_replica_should_only_report_conflicts_since_its_last_sync(
+    ) {
+        let mut replica =
+            ReplicaList::new();
+
+        let id = Uuid::new_v4();
+
+        let upsert = |title: &str| {
+            vec![RemoteChange::Upsert(
+                RemoteTodo::builder()
+                    .id(id)
+                    .title(Title::new(
+                        title,
+                    ))
+                    .priority(
+                        Priority::High,
+                    )
+                    .status(
+                        Status::Backlog,
+                    )
+                    .updated_timestamp(
+                        100,
+                    )
+                    .build(),
+            )]
+        };
+
+        assert_eq!(
+            replica.last_synced(),
+            0
+        );
+
+        replica
+            .apply_remote(upsert(
+                "Buy milk",
+            ))
+            .unwrap();
+
+        assert!(
+            replica.last_synced() > 100
+        );
+
+        let report = replica
+            .apply_remote(upsert(
+                "Buy oat milk",
+            ))
+            .unwrap();
+
+        assert_eq!(
+            report.applied(),
+            &vec![id]
+        );
+        assert!(report
+            .conflicts()
+            .is_empty());
+        assert_eq!(
+            replica
+                .get(id)
+                .unwrap()
+                .title,
+            "Buy oat milk"
+        );
+    }
+
+    #[test]
+    fn todolist_add_many_chunked_should_resume_where_the_last_call_stopped(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items: Vec<_> = (0..7)
+            .map(|i| {
+                NewTodo::builder()
+                    .title(Title::new(
+                        format!(
+                            "Task {i}"
+                        ),
+                    ))
+                    .build()
+            })
+            .collect();
+
+        let options = |resume| {
+            ChunkOptions::builder()
+                .chunk_size(2)
+                .chunks_per_call(2)
+                .resume(resume)
+                .build()
+        };
+
+        let first = todos
+            .add_many_chunked(
+                &items,
+                &options(None),
+            )
+            .unwrap();
+
+        assert_eq!(
+            first.added().len(),
+            4
+        );
+
+        let second = todos
+            .add_many_chunked(
+                &items,
+                &options(
+                    first
+                        .continuation()
+                        .clone(),
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            second
+                .added()
+                .iter()
+                .map(|(i, _)| *i)
+                .collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+        assert_eq!(
+            second.continuation(),
+            &None
+        );
+        assert_eq!(
+            todos.count_all(),
+            7
+        );
+    }
+
+    #[test]
+    fn todolist_add_many_chunked_should_refuse_a_continuation_for_other_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items = vec![
+            NewTodo::builder()
+                .title(Title::new("a"))
+                .build();
+            3
+        ];
+
+        let first = todos
+            .add_many_chunked(
+                &items,
+                &ChunkOptions::builder(
+                )
+                .chunk_size(1)
+                .chunks_per_call(1)
+                .build(),
+            )
+            .unwrap();
+
+        let token = first
+            .continuation()
+            .clone()
+            .unwrap();
+
+        let actual = todos
+            .add_many_chunked(
+                &items[..2],
+                &ChunkOptions::builder(
+                )
+                .resume(Some(
+                    token.clone(),
+                ))
+                .build(),
+            );
+
+        let expected =
+            AppError::InvalidContinuation(
+                token,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_add_many_chunked_should_refuse_a_continuation_for_as_many_other_items(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let items = |title: &str| {
+            vec![
+                NewTodo::builder()
+                    .title(Title::new(
+                        title,
+                    ))
+                    .build();
+                3
+            ]
+        };
+
+        let token = todos
+            .add_many_chunked(
+                &items("a"),
+                &ChunkOptions::builder(
+                )
+                .chunk_size(1)
+                .chunks_per_call(1)
+                .build(),
+            )
+            .unwrap()
+            .continuation()
+            .clone()
+            .unwrap();
+
+        let actual = todos
+            .add_many_chunked(
+                &items("b"),
+                &ChunkOptions::builder(
+                )
+                .resume(Some(
+                    token.clone(),
+                ))
+                .build(),
+            );
+
+        let expected =
+            AppError::InvalidContinuation(
+                token,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.count_all(),
+            1
+        );
+    }
+
+    #[test]
+    fn todolist_add_should_fail_instead_of_overflowing_short_ids(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos.last_short_id = u64::MAX;
+
+        let actual = todos.add(
+            &NewTodo::builder()
+                .title(Title::new("a"))
+                .build(),
+        );
+
+        let expected =
+            AppError::Internal(
+                "short-id-overflow",
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+        assert_eq!(
+            todos.count_all(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_change_seqs_should_start_again_once_they_run_out(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos.change_seq = u64::MAX;
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "a",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(todos.change_seq, 1);
+        assert_eq!(
+            todos.change_seqs,
+            HashMap::from([(
+                added.id, 1
+            )])
+        );
+    }
+
+    #[test]
+    fn todolist_update_should_not_move_updated_timestamp_back_when_the_clock_does(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let added = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "a",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        // As if the item was last changed before the clock was set back.
+        let ahead = added
+            .updated_timestamp
+            + SECONDS_PER_DAY;
+        todos.items.insert(Todo {
+            updated_timestamp: ahead,
+            ..added.clone()
+        });
+
+        let updated = todos
+            .update(
+                added.id,
+                &UpdateTodo::builder()
+                    .status(Some(
+                        Status::Done,
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            updated.updated_timestamp,
+            ahead
+        );
+    }
+
+    #[test]
+    fn todolist_shift_deadlines_should_refuse_an_offset_that_overflows(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let actual = todos
+            .shift_deadlines(
+                &Query::default(),
+                i64::MAX,
+            );
+
+        let expected =
+            AppError::DurationOverflow(
+                format!(
+                    "{} hours",
+                    i64::MAX
+                ),
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
+
+    #[test]
+    fn todolist_search_should_look_in_comments_only_when_asked(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "Plan trip",
+            ))
+            .build();
+
+        let noted =
+            todos.add(&item).unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "Book flights",
+                    ),
+            )
+            .unwrap();
+        todos
+            .add_comment(
+                noted.id,
+                "ann",
+                "Remember the flights",
+            )
+            .unwrap();
+
+        let titles = |query: &Query| {
+            todos
+                .search(query)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect::<HashSet<_>>()
+        };
+
+        assert_eq!(
+            titles(
+                &Query::builder()
+                    .keyword(Some(
+                        "flights"
+                            .into()
+                    ))
+                    .build()
+            ),
+            hashset! {
+                "Book flights".to_string()
+            }
+        );
+        assert_eq!(
+            titles(
+                &Query::builder()
+                    .keyword(Some(
+                        "flights".into()
+                    ))
+                    .search_fields(vec![
+                        SearchField::Title,
+                        SearchField::Comments,
+                    ])
+                    .build()
+            ),
+            hashset! {
+                "Book flights".to_string(),
+                "Plan trip".to_string()
+            }
+        );
+        assert_eq!(
+            titles(
+                &Query::builder()
+                    .not_keyword(Some(
+                        "flights".into()
+                    ))
+                    .search_fields(vec![
+                        SearchField::Comments,
+                    ])
+                    .build()
+            ),
+            hashset! {
+                "Book flights".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn todolist_search_hits_should_say_where_the_keyword_matched(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let both = todos
+            .add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        "Call the bank",
+                    ))
+                    .build(),
+            )
+            .unwrap();
+        todos
+            .add_comment(
+                both.id,
+                "ann",
+                "The bank opens at 9",
+            )
+            .unwrap();
+
+        let hits = todos
+            .search_hits(
+                &Query::builder()
+                    .keyword(Some(
+                        "bank".into(),
+                    ))
+                    .search_fields(vec![
+                        SearchField::Comments,
+                        SearchField::Title,
+                    ])
+                    .build(),
+            )
+            .unwrap();
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].field(),
+            Some(SearchField::Title)
+        );
+        assert_eq!(
+            hits[0].score(),
+            SearchField::Title.weight()
+                + SearchField::Comments
+                    .weight()
+        );
+
+        let unkeyed = todos
+            .search_hits(
+                &Query::default(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            (
+                unkeyed[0].field(),
+                unkeyed[0].score()
+            ),
+            (None, 0)
+        );
+    }
+
+    #[test]
+    fn todolist_search_cached_should_reuse_a_result_until_an_item_changes(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("a"))
+            .build();
+
+        todos.add(&item).unwrap();
+
+        let query = Query::default();
+
+        let first = todos
+            .search_cached(&query)
+            .unwrap();
+        let second = todos
+            .search_cached(&query)
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(
+            (
+                todos
+                    .query_cache_stats()
+                    .hits(),
+                todos
+                    .query_cache_stats()
+                    .misses()
+            ),
+            (1, 1)
+        );
+
+        todos.add(&item).unwrap();
+
+        let third = todos
+            .search_cached(&query)
+            .unwrap();
+
+        assert_eq!(
+            third.total_matched(),
+            2
+        );
+        assert_eq!(
+            todos
+                .query_cache_stats()
+                .misses(),
+            2
+        );
+    }
+
+    #[test]
+    fn todolist_search_cached_should_keep_nothing_at_size_zero(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        todos
+            .search_cached(
+                &Query::default(),
+            )
+            .unwrap();
+
+        todos
+            .set_query_cache_size(0)
+            .unwrap();
+
+        todos
+            .search_cached(
+                &Query::default(),
+            )
+            .unwrap();
+
+        let stats =
+            todos.query_cache_stats();
+
+        assert_eq!(
+            (
+                stats.hits(),
+                stats.misses(),
+                stats.entries()
+            ),
+            (0, 2, 0)
+        );
+        assert_eq!(
+            todos
+                .get_settings()
+                .query_cache_size(),
+            0
+        );
+    }
+
+    #[test]
+    fn todolist_normalized_title_should_follow_updates(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new(
+                "Call MOM",
+            ))
+            .priority(Priority::Low)
+            .build();
+
+        let id = todos
+            .add(&item)
+            .unwrap()
+            .id;
+
+        let plain_search =
+            |todos: &TodoList,
+             keyword: &str| {
+                todos
+                    .search(
+                        &Query::builder()
+                            .keyword(Some(
+                                keyword.into(),
+                            ))
+                            .plain_text_match(
+                                true,
+                            )
+                            .build(),
+                    )
+                    .unwrap()
+                    .len()
+            };
+
+        assert_eq!(
+            plain_search(
+                &todos, "call mom"
+            ),
+            1
+        );
+
+        todos
+            .update(
+                id,
+                &UpdateTodo::builder()
+                    .title(Some(
+                        Title::new(
+                            "Émail Dad",
+                        ),
+                    ))
+                    .build(),
+            )
+            .unwrap();
+
+        let stored = todos
+            .items
+            .get(&id)
+            .unwrap();
+
+        assert_eq!(
+            stored.title_lowercase(),
+            "émail dad"
+        );
+        assert_eq!(
+            stored.title_key(
+                TitleCollation::Natural
+            ),
+            "email dad"
+        );
+        assert_eq!(
+            plain_search(
+                &todos, "call mom"
+            ),
+            0
+        );
+        assert_eq!(
+            plain_search(
+                &todos, "ÉMAIL"
+            ),
+            1
+        );
+
+        todos
+            .prefix_title(id, "Re: ")
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .items
+                .get(&id)
+                .unwrap()
+                .title_lowercase(),
+            "re: émail dad"
+        );
+    }
+
+    #[test]
+    fn todolist_normalized_title_should_follow_imports(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let options = ImportOptions::builder()
+            .merge(MergeStrategy::OverwriteExisting)
+            .build();
+
+        todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "(C) Pay RENT id:r1\n",
+                &options,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos
+                .find_by_title(
+                    "rent", false
+                )
+                .len(),
+            1
+        );
+
+        todos
+            .import_external(
+                ImportFormat::TodoTxt,
+                "Pay the Bills id:r1\n",
+                &options,
+            )
+            .unwrap();
+
+        assert_eq!(
+            todos.find_by_title(
+                "rent", false
+            ),
+            vec![]
+        );
+        assert_eq!(
+            todos
+                .find_by_title(
+                    "bills", false
+                )
+                .len(),
+            1
+        );
+        assert_eq!(
+            todos.suggest_titles(
+                "pay the b",
+                &OptionalResultLimit::default(),
+            )
+            .unwrap(),
+            vec!["Pay the Bills".to_string()]
+        );
+    }
+
+    #[test]
+    fn todolist_search_pinned_should_page_through_the_items_as_pinned(
+    ) {
+        let mut todos =
+            new_todo_list!();
+
+        let item = NewTodo::builder()
+            .title(Title::new("a"))
+            .priority(Priority::Low)
+            .build();
+
+        let a =
+            todos.add(&item).unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "b",
+                    ),
+            )
+            .unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "c",
+                    ),
+            )
+            .unwrap();
+
+        let pin = todos.pin_view();
+
+        let page = |todos: &TodoList,
+                    after: Option<String>| {
+            todos
+                .search_pinned(
+                    pin,
+                    &Query::builder()
+                        .limit(
+                            OptionalResultLimit::some(2),
+                        )
+                        .after(after)
+                        .build(),
+                )
+                .unwrap()
+        };
+
+        let first = page(&todos, None);
+
+        todos.delete(a.id).unwrap();
+        todos
+            .add(
+                &item
+                    .cloned_with_title(
+                        "aa",
+                    ),
+            )
+            .unwrap();
+
+        let second = page(
+            &todos,
+            first.next_cursor().clone(),
+        );
+
+        let titles: Vec<_> = first
+            .items()
+            .iter()
+            .chain(second.items())
+            .map(|t| t.title.as_str())
+            .collect();
+
+        assert_eq!(
+            titles,
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            todos
+                .list_page_pinned(
+                    pin,
+                    0,
+                    &OptionalResultLimit::default(),
+                )
+                .unwrap()
+                .len(),
+            3
+        );
+
+        assert!(todos.unpin_view(pin));
+
+        let actual = todos
+            .list_page_pinned(
+                pin,
+                0,
+                &OptionalResultLimit::default(),
+            );
+        let expected =
+            AppError::PinnedViewNotFound(
+                pin,
+            );
+
+        assert_app_error!(
+            actual, expected
+        );
+    }
 }
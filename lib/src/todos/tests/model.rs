@@ -0,0 +1,430 @@
+// State-machine tests: random command sequences run against both
+// `TodoList` and a naive reference model, which must agree after every
+// step.
+use crate::todos::*;
+use pretty_assertions::assert_eq;
+use proptest::prelude::{
+    any, prop, prop_oneof, proptest,
+    Just, Strategy,
+};
+use std::collections::{
+    HashMap, HashSet,
+};
+
+#[derive(Clone, Debug)]
+enum Command {
+    Add {
+        title: String,
+        priority: Priority,
+    },
+    UpdateTitle {
+        target: usize,
+        title: String,
+    },
+    UpdatePriority {
+        target: usize,
+        priority: Priority,
+    },
+    UpdateStatus {
+        target: usize,
+        status: Status,
+    },
+    Delete {
+        target: usize,
+    },
+    DeleteByStatus(Status),
+    Search {
+        keyword: Option<String>,
+        priority: Option<Priority>,
+        status: Option<Status>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ModelTodo {
+    title: String,
+    priority: Priority,
+    status: Status,
+}
+
+#[derive(Default)]
+struct Model {
+    items: HashMap<Uuid, ModelTodo>,
+
+    // Every ID ever issued, deleted ones included, so that commands
+    // can also target items that no longer exist.
+    known_ids: Vec<Uuid>,
+}
+impl Model {
+    fn target(
+        &self,
+        target: usize,
+    ) -> Option<Uuid> {
+        (!self.known_ids.is_empty())
+            .then(|| {
+                self.known_ids[target
+                    % self
+                        .known_ids
+                        .len()]
+            })
+    }
+
+    fn matching(
+        &self,
+        keyword: &Option<String>,
+        priority: Option<Priority>,
+        status: Option<Status>,
+    ) -> HashSet<Uuid> {
+        self.items
+            .iter()
+            .filter(|(_, t)| {
+                let keyword_matches = keyword
+                    .as_ref()
+                    .map(|k| t.title.contains(k.as_str()))
+                    .unwrap_or(true);
+
+                let priority_matches = priority
+                    .map(|p| t.priority == p)
+                    .unwrap_or(true);
+
+                let status_matches = status
+                    .map(|s| t.status == s)
+                    .unwrap_or(true);
+
+                keyword_matches && priority_matches && status_matches
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+fn priority_strategy(
+) -> impl Strategy<Value = Priority> {
+    prop_oneof![
+        Just(Priority::Low),
+        Just(Priority::Medium),
+        Just(Priority::High),
+    ]
+}
+
+fn status_strategy(
+) -> impl Strategy<Value = Status> {
+    prop_oneof![
+        Just(Status::Backlog),
+        Just(Status::InProgress),
+        Just(Status::Done),
+    ]
+}
+
+// A tiny alphabet keeps keyword hits likely; blank titles are
+// generated on purpose.
+fn title_strategy(
+) -> impl Strategy<Value = String> {
+    "[ab ]{0,4}"
+}
+
+fn command_strategy(
+) -> impl Strategy<Value = Command> {
+    prop_oneof![
+        3 => (title_strategy(), priority_strategy())
+            .prop_map(|(title, priority)| Command::Add { title, priority }),
+        1 => (any::<usize>(), title_strategy())
+            .prop_map(|(target, title)| Command::UpdateTitle { target, title }),
+        1 => (any::<usize>(), priority_strategy())
+            .prop_map(|(target, priority)| Command::UpdatePriority { target, priority }),
+        1 => (any::<usize>(), status_strategy())
+            .prop_map(|(target, status)| Command::UpdateStatus { target, status }),
+        1 => any::<usize>().prop_map(|target| Command::Delete { target }),
+        1 => status_strategy().prop_map(Command::DeleteByStatus),
+        1 => (
+            prop::option::of("[ab]{1,2}"),
+            prop::option::of(priority_strategy()),
+            prop::option::of(status_strategy()),
+        )
+            .prop_map(|(keyword, priority, status)| Command::Search {
+                keyword,
+                priority,
+                status,
+            }),
+    ]
+}
+
+fn run(
+    command: Command,
+    todos: &mut TodoList,
+    model: &mut Model,
+) {
+    match command {
+        Command::Add {
+            title,
+            priority,
+        } => {
+            let actual = todos.add(
+                &NewTodo::builder()
+                    .title(Title::new(
+                        &title,
+                    ))
+                    .priority(priority)
+                    .build(),
+            );
+
+            let title = title.trim();
+
+            if title.is_empty() {
+                assert!(actual.is_err());
+            } else {
+                let todo =
+                    actual.unwrap();
+
+                model
+                    .known_ids
+                    .push(todo.id);
+                model.items.insert(
+                    todo.id,
+                    ModelTodo {
+                        title: title.into(),
+                        priority,
+                        status: Status::Backlog,
+                    },
+                );
+            }
+        }
+        Command::UpdateTitle {
+            target,
+            title,
+        } => {
+            if let Some(id) =
+                model.target(target)
+            {
+                let actual = todos.update(
+                    id,
+                    &UpdateTodo::builder()
+                        .title(Some(Title::new(&title)))
+                        .build(),
+                );
+
+                let title =
+                    title.trim();
+
+                match model
+                    .items
+                    .get_mut(&id)
+                {
+                    Some(t)
+                        if !title
+                            .is_empty(
+                            ) =>
+                    {
+                        actual.unwrap();
+                        t.title = title
+                            .into();
+                    }
+                    _ => {
+                        assert!(actual
+                            .is_err())
+                    }
+                }
+            }
+        }
+        Command::UpdatePriority {
+            target,
+            priority,
+        } => {
+            if let Some(id) =
+                model.target(target)
+            {
+                let actual = todos.update(
+                    id,
+                    &UpdateTodo::builder()
+                        .priority(Some(priority))
+                        .build(),
+                );
+
+                match model
+                    .items
+                    .get_mut(&id)
+                {
+                    Some(t) => {
+                        actual.unwrap();
+                        t.priority =
+                            priority;
+                    }
+                    None => {
+                        assert!(actual
+                            .is_err())
+                    }
+                }
+            }
+        }
+        Command::UpdateStatus {
+            target,
+            status,
+        } => {
+            if let Some(id) =
+                model.target(target)
+            {
+                let actual = todos.update(
+                    id,
+                    &UpdateTodo::builder()
+                        .status(Some(status))
+                        .build(),
+                );
+
+                match model
+                    .items
+                    .get_mut(&id)
+                {
+                    Some(t) => {
+                        actual.unwrap();
+                        t.status =
+                            status;
+                    }
+                    None => {
+                        assert!(actual
+                            .is_err())
+                    }
+                }
+            }
+        }
+        Command::Delete { target } => {
+            if let Some(id) =
+                model.target(target)
+            {
+                let actual =
+                    todos.delete(id);
+
+                assert_eq!(
+                    actual.is_ok(),
+                    model
+                        .items
+                        .remove(&id)
+                        .is_some()
+                );
+            }
+        }
+        Command::DeleteByStatus(
+            status,
+        ) => {
+            let actual = todos
+                .delete_by_status(
+                    &status, false,
+                )
+                .unwrap();
+
+            let before =
+                model.items.len();
+            model.items.retain(
+                |_, t| {
+                    t.status != status
+                },
+            );
+
+            assert_eq!(
+                actual,
+                before
+                    - model.items.len()
+            );
+        }
+        Command::Search {
+            keyword,
+            priority,
+            status,
+        } => {
+            let query =
+                Query::builder()
+                    .keyword(
+                        keyword.clone(),
+                    )
+                    .priority(priority)
+                    .status(status)
+                    .limit(
+                        Some(100)
+                            .into(),
+                    )
+                    .build();
+
+            let actual: HashSet<_> =
+                todos
+                    .search(&query)
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| t.id)
+                    .collect();
+
+            let expected = model
+                .matching(
+                    &keyword, priority,
+                    status,
+                );
+
+            assert_eq!(
+                actual,
+                expected
+            );
+            assert_eq!(
+                todos
+                    .count_by(&query)
+                    .unwrap(),
+                expected.len()
+            );
+        }
+    }
+}
+
+fn assert_equivalent(
+    todos: &TodoList,
+    model: &Model,
+) {
+    assert_eq!(
+        todos.count_all(),
+        model.items.len()
+    );
+
+    for (id, expected) in &model.items {
+        let actual =
+            todos.get(*id).unwrap();
+
+        assert_eq!(
+            ModelTodo {
+                title: actual
+                    .title()
+                    .clone(),
+                priority: actual
+                    .priority(),
+                status: actual.status(),
+            },
+            *expected
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn todolist_should_behave_like_the_reference_model(
+        commands in prop::collection::vec(command_strategy(), 1..40)
+    ) {
+        let mut todos = TodoList::new();
+        let mut model = Model::default();
+
+        for command in commands {
+            run(command, &mut todos, &mut model);
+
+            assert_equivalent(&todos, &model);
+        }
+    }
+
+    #[test]
+    fn todolist_on_an_arena_store_should_behave_like_the_reference_model(
+        commands in prop::collection::vec(command_strategy(), 1..40)
+    ) {
+        let mut todos = TodoList::new()
+            .with_store(ArenaStore::default());
+        let mut model = Model::default();
+
+        for command in commands {
+            run(command, &mut todos, &mut model);
+
+            assert_equivalent(&todos, &model);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::TodoList;
+use crate::{
+    app_error::{
+        bail, AppError, AppResult,
+    },
+    core::{UnixTime, SECONDS_PER_DAY},
+    time,
+};
+use getset::CopyGetters;
+
+// Bounds the work a single request can ask for: a year of days.
+const MAX_TREND_BUCKETS: UnixTime = 366;
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum TrendBucket {
+    Day,
+    Week,
+}
+
+impl TrendBucket {
+    fn seconds(self) -> UnixTime {
+        match self {
+            Self::Day => {
+                SECONDS_PER_DAY
+            }
+            Self::Week => {
+                7 * SECONDS_PER_DAY
+            }
+        }
+    }
+}
+
+/// How many items were created and completed within one bucket.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    CopyGetters,
+)]
+#[getset(get_copy = "pub")]
+pub struct TrendPoint {
+    start: UnixTime,
+
+    created: usize,
+
+    completed: usize,
+}
+
+impl TodoList {
+    /// One point per bucket from `from` up to `to`, for burndown charts;
+    /// the last bucket ends at `to` even when that cuts it short.
+    /// Archived items are counted, but deleted ones leave no trace.
+    pub fn stats_over_time(
+        &self,
+        bucket: TrendBucket,
+        from: UnixTime,
+        to: UnixTime,
+    ) -> AppResult<Vec<TrendPoint>>
+    {
+        time::in_range(from)?;
+        time::in_range(to)?;
+
+        if to <= from {
+            bail!(
+                AppError::InvalidTrendRange(
+                    format!(
+                        "{} is not after {}",
+                        to, from
+                    )
+                )
+            )
+        }
+
+        let width = bucket.seconds();
+
+        let count =
+            (to - from - 1) / width + 1;
+
+        if count > MAX_TREND_BUCKETS {
+            bail!(
+                AppError::InvalidTrendRange(
+                    format!(
+                        "{} buckets exceed the limit of {}",
+                        count,
+                        MAX_TREND_BUCKETS
+                    )
+                )
+            )
+        }
+
+        let mut points: Vec<_> = (0
+            ..count)
+            .map(|i| TrendPoint {
+                start: from + i * width,
+                created: 0,
+                completed: 0,
+            })
+            .collect();
+
+        let index = |t: UnixTime| {
+            (from..to)
+                .contains(&t)
+                .then(|| {
+                    ((t - from) / width)
+                        as usize
+                })
+        };
+
+        for todo in
+            self.items.values().chain(
+                self.archived.values(),
+            )
+        {
+            if let Some(i) = index(
+                todo.created_timestamp,
+            ) {
+                points[i].created += 1;
+            }
+
+            if let Some(i) = todo
+                .completed_timestamp
+                .and_then(index)
+            {
+                points[i].completed +=
+                    1;
+            }
+        }
+
+        Ok(points)
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    NewTodo, TodoList, UpdateTodo,
+};
+use crate::app_error::{
+    report, AppError, AppResult,
+};
+use uuid::Uuid;
+
+impl TodoList {
+    /// Every reason `add_or_warn` would turn `item` down, as messages;
+    /// empty when it would be added. Nothing is changed, so a form can
+    /// call this as the user types.
+    pub fn validate(
+        &self,
+        item: &NewTodo,
+    ) -> Vec<String> {
+        let similar =
+            if self.warn_on_similar {
+                item.title
+                .validated()
+                .map(|title| {
+                    self.similar_open(
+                        &title,
+                    )
+                })
+                .unwrap_or_default()
+            } else {
+                vec![]
+            };
+
+        let deadline =
+            self.new_deadline(item);
+
+        let start_date = self
+            .new_start_date(
+                item,
+                deadline
+                    .as_ref()
+                    .map(|(d, _)| *d)
+                    .unwrap_or(None),
+            );
+
+        let checks = [
+            self.ensure_writable(),
+            item.title
+                .validated()
+                .map(drop),
+            deadline.map(drop),
+            start_date.map(drop),
+            item.color
+                .as_ref()
+                .map(|c| {
+                    c.validated()
+                        .map(drop)
+                })
+                .unwrap_or(Ok(())),
+            item.icon
+                .as_ref()
+                .map(|i| {
+                    i.validated()
+                        .map(drop)
+                })
+                .unwrap_or(Ok(())),
+        ];
+
+        problems(checks).chain(
+            similar.iter().map(|t| {
+                AppError::SimilarTodoExists(
+                    t.id,
+                )
+                .to_string()
+            }),
+        )
+        .collect()
+    }
+
+    /// Every reason `update` would turn `change` to item `id` down, as
+    /// messages; empty when it would go through. Nothing is changed.
+    pub fn validate_update(
+        &self,
+        id: Uuid,
+        change: &UpdateTodo,
+    ) -> Vec<String> {
+        if !change.change_is_present() {
+            return vec![
+                AppError::UpdateHasNoChanges
+                    .to_string(),
+            ];
+        }
+
+        let found = if self
+            .items
+            .contains_key(&id)
+        {
+            Ok(())
+        } else {
+            Err(report!(
+                AppError::TodoNotFound(
+                    id
+                )
+            ))
+        };
+
+        let deadline_update = self
+            .date_update(
+                &change.deadline,
+            );
+
+        let start_date_update = self
+            .date_update(
+                &change.start_date,
+            );
+
+        let rescheduled = match (
+            self.items.get(&id),
+            &deadline_update,
+            &start_date_update,
+        ) {
+            (
+                Some(todo),
+                Ok(deadline_update),
+                Ok(start_date_update),
+            ) => {
+                Self::check_rescheduled(
+                    todo,
+                    deadline_update,
+                    start_date_update,
+                )
+            }
+            _ => Ok(()),
+        };
+
+        let checks = [
+            self.ensure_writable(),
+            found,
+            change
+                .title
+                .as_ref()
+                .map(|t| {
+                    t.validated()
+                        .map(drop)
+                })
+                .unwrap_or(Ok(())),
+            deadline_update.map(drop),
+            start_date_update.map(drop),
+            rescheduled,
+            change
+                .color
+                .as_ref()
+                .map(|c| {
+                    c.validated()
+                        .map(drop)
+                })
+                .unwrap_or(Ok(())),
+            change
+                .icon
+                .as_ref()
+                .map(|i| {
+                    i.validated()
+                        .map(drop)
+                })
+                .unwrap_or(Ok(())),
+        ];
+
+        problems(checks).collect()
+    }
+}
+
+fn problems(
+    checks: impl IntoIterator<
+        Item = AppResult<()>,
+    >,
+) -> impl Iterator<Item = String> {
+    checks.into_iter().filter_map(|c| {
+        c.err().map(|e| {
+            e.current_context()
+                .to_string()
+        })
+    })
+}
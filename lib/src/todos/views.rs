@@ -0,0 +1,167 @@
+use super::{Status, Todo, TodoList};
+use crate::{
+    core::{UnixTime, SECONDS_PER_DAY},
+    time,
+};
+use chrono::Utc;
+use std::cmp;
+
+const DAYS_PER_WEEK: UnixTime = 7;
+
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum View {
+    Today,
+    ThisWeek,
+    Overdue,
+}
+
+impl Todo {
+    pub(crate) fn is_open(
+        &self,
+    ) -> bool {
+        self.status != Status::Done
+    }
+
+    pub(crate) fn is_due_before(
+        &self,
+        time: UnixTime,
+    ) -> bool {
+        self.deadline
+            .map(|d| d < time)
+            .unwrap_or(false)
+    }
+}
+
+impl TodoList {
+    pub fn view(
+        &self,
+        view: View,
+    ) -> Vec<Todo> {
+        let now = unix_time_now!();
+
+        match view {
+            View::Today => {
+                self.view_today(now)
+            }
+            View::ThisWeek => {
+                self.view_week(now)
+            }
+            View::Overdue => {
+                self.view_overdue(now)
+            }
+        }
+    }
+
+    /// Open items due by the end of `now`'s (UTC) day, including overdue
+    /// ones, plus anything already in progress.
+    pub fn view_today(
+        &self,
+        now: UnixTime,
+    ) -> Vec<Todo> {
+        let today = time::clamped(now);
+        let end_of_day = today
+            - today.rem_euclid(
+                SECONDS_PER_DAY,
+            )
+            + SECONDS_PER_DAY;
+
+        self.view_by(now, |t| {
+            t.status
+                == Status::InProgress
+                || t.is_due_before(
+                    end_of_day,
+                )
+        })
+    }
+
+    /// Open items due within the next seven days, including overdue ones.
+    pub fn view_week(
+        &self,
+        now: UnixTime,
+    ) -> Vec<Todo> {
+        let end_of_week =
+            time::offset_clamped(
+                now,
+                DAYS_PER_WEEK
+                    * SECONDS_PER_DAY,
+            );
+
+        self.view_by(now, |t| {
+            t.is_due_before(end_of_week)
+        })
+    }
+
+    /// Open items whose deadline has already passed.
+    pub fn view_overdue(
+        &self,
+        now: UnixTime,
+    ) -> Vec<Todo> {
+        self.view_by(now, |t| {
+            t.is_due_before(now)
+        })
+    }
+
+    /// How many items `view_overdue` would return, without cloning
+    /// or sorting them; cheap enough for a badge polled often.
+    pub fn count_overdue(
+        &self,
+        now: UnixTime,
+    ) -> usize {
+        self.items
+            .values()
+            .filter(|t| {
+                t.is_open()
+                    && !t.is_snoozed_at(
+                        now,
+                    )
+                    && t.is_due_before(
+                        now,
+                    )
+            })
+            .count()
+    }
+
+    // Most pressing first: earliest deadline, then highest priority.
+    fn view_by(
+        &self,
+        now: UnixTime,
+        include: impl Fn(&Todo) -> bool,
+    ) -> Vec<Todo> {
+        let mut result: Vec<_> = self
+            .items
+            .values()
+            .filter(|t| {
+                t.is_open()
+                    && !t.is_snoozed_at(
+                        now,
+                    )
+                    && include(t)
+            })
+            .cloned()
+            .collect();
+
+        result.sort_by(|a, b| {
+            (
+                a.deadline.is_none(),
+                a.deadline,
+                cmp::Reverse(
+                    a.priority,
+                ),
+                &a.title,
+            )
+                .cmp(&(
+                    b.deadline
+                        .is_none(),
+                    b.deadline,
+                    cmp::Reverse(
+                        b.priority,
+                    ),
+                    &b.title,
+                ))
+        });
+
+        result
+    }
+}
@@ -0,0 +1,212 @@
+use super::{
+    AddOutcome, DeadlineChange,
+    NewTodo, OptionalDeadlineInput,
+    OptionalResultLimit, Query,
+    TimeZone, Title, Todo, TodoList,
+    UpdateTodo,
+};
+use crate::app_error::AppResult;
+use getset::Getters;
+use uuid::Uuid;
+
+/// Input that was accepted, though not quite as given.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq,
+)]
+pub enum Warning {
+    /// The title had surrounding whitespace, which was dropped; the
+    /// stored title is in the result.
+    TitleTrimmed,
+
+    LimitClamped {
+        given: u32,
+        used: u32,
+    },
+
+    /// A deadline that named no time zone was read in this one.
+    DefaultTimeZoneUsed(TimeZone),
+}
+
+#[derive(Clone, Debug, Getters)]
+pub struct ResultWithWarnings<T> {
+    #[getset(get = "pub")]
+    value: T,
+
+    #[getset(get = "pub")]
+    warnings: Vec<Warning>,
+}
+
+impl<T> ResultWithWarnings<T> {
+    pub fn into_parts(
+        self,
+    ) -> (T, Vec<Warning>) {
+        (self.value, self.warnings)
+    }
+}
+
+impl TodoList {
+    /// `add_or_warn`, plus notices about how the item was read.
+    pub fn add_with_warnings(
+        &mut self,
+        item: &NewTodo,
+    ) -> AppResult<
+        ResultWithWarnings<AddOutcome>,
+    > {
+        let warnings = self
+            .new_item_warnings(item);
+
+        let value =
+            self.add_or_warn(item)?;
+
+        Ok(ResultWithWarnings {
+            value,
+            warnings,
+        })
+    }
+
+    /// What `add` would store for `item`, with a nil id, without adding
+    /// it; fails just as `add` would.
+    pub fn validate_new_todo(
+        &self,
+        item: &NewTodo,
+    ) -> AppResult<
+        ResultWithWarnings<Todo>,
+    > {
+        let value =
+            self.prepared(item)?;
+
+        Ok(ResultWithWarnings {
+            value,
+            warnings: self
+                .new_item_warnings(
+                    item,
+                ),
+        })
+    }
+
+    /// `update`, plus notices about how the change was read.
+    pub fn update_with_warnings(
+        &mut self,
+        id: Uuid,
+        change: &UpdateTodo,
+    ) -> AppResult<
+        ResultWithWarnings<Todo>,
+    > {
+        let deadline = match &change
+            .deadline
+        {
+            DeadlineChange::Set(
+                input,
+            ) => self.deadline_warning(
+                input,
+            ),
+            _ => None,
+        };
+
+        let warnings = [
+            change
+                .title
+                .as_ref()
+                .and_then(
+                    title_warning,
+                ),
+            deadline,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let value =
+            self.update(id, change)?;
+
+        Ok(ResultWithWarnings {
+            value,
+            warnings,
+        })
+    }
+
+    /// `search`, plus notices about how the query was read.
+    pub fn search_with_warnings(
+        &self,
+        query: &Query,
+    ) -> AppResult<
+        ResultWithWarnings<Vec<Todo>>,
+    > {
+        let value =
+            self.search(query)?;
+
+        let warnings = [
+            self.limit_warning(
+                query.limit(),
+            )?,
+            self.deadline_warning(
+                query.deadline(),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(ResultWithWarnings {
+            value,
+            warnings,
+        })
+    }
+
+    fn new_item_warnings(
+        &self,
+        item: &NewTodo,
+    ) -> Vec<Warning> {
+        [
+            title_warning(&item.title),
+            self.deadline_warning(
+                &item.deadline,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    fn limit_warning(
+        &self,
+        limit: &OptionalResultLimit,
+    ) -> AppResult<Option<Warning>>
+    {
+        let used =
+            self.result_limit(limit)?;
+
+        Ok(limit
+            .given()
+            .filter(|given| {
+                *given as usize != used
+            })
+            .map(|given| {
+                Warning::LimitClamped {
+                    given,
+                    used: used as u32,
+                }
+            }))
+    }
+
+    fn deadline_warning(
+        &self,
+        deadline: &OptionalDeadlineInput,
+    ) -> Option<Warning> {
+        (deadline.input().is_some()
+            && !deadline.names_time_zone())
+        .then_some(
+            Warning::DefaultTimeZoneUsed(
+                self.default_time_zone,
+            ),
+        )
+    }
+}
+
+fn title_warning(
+    title: &Title,
+) -> Option<Warning> {
+    title.was_trimmed().then_some(
+        Warning::TitleTrimmed,
+    )
+}
@@ -0,0 +1,72 @@
+use super::{Todo, TodoList};
+use crate::app_error::{
+    bail, AppError, AppResult,
+};
+use uuid::Uuid;
+
+const MAX_WATCHER_LEN: usize = 50;
+
+impl TodoList {
+    /// Subscribes `watcher`, such as a user name, to changes of the
+    /// item. A notifier can pair this with `diff_since` to tell each
+    /// watcher about the items they follow.
+    pub fn watch(
+        &mut self,
+        id: Uuid,
+        watcher: &str,
+    ) -> AppResult<()> {
+        let watcher = valid(watcher)?;
+
+        self.change_item(id, |todo| {
+            Ok(todo
+                .watchers
+                .insert(watcher.into()))
+        })
+        .map(|_: Todo| ())
+    }
+
+    /// Does nothing when `watcher` is not watching the item.
+    pub fn unwatch(
+        &mut self,
+        id: Uuid,
+        watcher: &str,
+    ) -> AppResult<()> {
+        let watcher = valid(watcher)?;
+
+        self.change_item(id, |todo| {
+            Ok(todo
+                .watchers
+                .remove(watcher))
+        })
+        .map(|_: Todo| ())
+    }
+
+    /// Sorted by name.
+    pub fn list_watchers(
+        &self,
+        id: Uuid,
+    ) -> AppResult<Vec<String>> {
+        self.get(id).map(|todo| {
+            todo.watchers
+                .into_iter()
+                .collect()
+        })
+    }
+}
+
+fn valid(
+    watcher: &str,
+) -> AppResult<&str> {
+    let watcher = watcher.trim();
+
+    if watcher.is_empty()
+        || watcher.len()
+            > MAX_WATCHER_LEN
+    {
+        bail!(AppError::InvalidWatcher(
+            watcher.into()
+        ))
+    }
+
+    Ok(watcher)
+}
@@ -0,0 +1,1457 @@
+// JSON mirrors of the records in `wasm/wit/todos.wit`, with the same
+// kebab-case names, so a request body works against either host.
+use lib::{
+    app_error::AppResultExt,
+    codec,
+    core::{u64_from, uuid_from, AppResult},
+    todos::{self, Color, Icon, OptionalDeadlineInput, Title},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+macro_rules! convert_enum_from_incoming {
+    (
+        $the_enum:ident { $($variant:ident),+ $(,)? }
+    ) => {
+        impl From<$the_enum> for todos::$the_enum {
+            fn from(the_enum: $the_enum) -> Self {
+                match the_enum {
+                    $($the_enum::$variant => Self::$variant,)+
+                }
+            }
+        }
+    };
+}
+
+macro_rules! convert_enum_both_ways {
+    (
+        $the_enum:ident { $($variant:ident),+ $(,)? }
+    ) => {
+        convert_enum_from_incoming!($the_enum { $($variant),+ });
+
+        impl From<todos::$the_enum> for $the_enum {
+            fn from(the_enum: todos::$the_enum) -> Self {
+                match the_enum {
+                    $(todos::$the_enum::$variant => Self::$variant,)+
+                }
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Backlog,
+    InProgress,
+    Done,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuerySort {
+    Priority,
+    Status,
+    Deadline,
+    StartDate,
+    CreatedAt,
+    UpdatedTimestamp,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Availability {
+    AvailableNow,
+    ScheduledLater,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum View {
+    Today,
+    ThisWeek,
+    Overdue,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DistinctField {
+    Status,
+    Priority,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecentKind {
+    Viewed,
+    Modified,
+    Created,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TodoField {
+    Title,
+    Priority,
+    Status,
+    Deadline,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchField {
+    Title,
+    Comments,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrendBucket {
+    Day,
+    Week,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStrategy {
+    LocalWins,
+    RemoteWins,
+    Merge,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TitleCollation {
+    Binary,
+    Natural,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+// Not a `todos` type, so `convert_enum_from_incoming!` does not apply.
+impl From<Compression> for codec::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None,
+            Compression::Gzip => Self::Gzip,
+            Compression::Zstd => Self::Zstd,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportFormat {
+    TodoistCsv,
+    TickTickCsv,
+    TodoTxt,
+    TodoistJson,
+    TickTickJson,
+    ThingsJson,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    Replace,
+    SkipExisting,
+    OverwriteExisting,
+    MergeNewest,
+}
+
+convert_enum_both_ways!(Status {
+    Backlog,
+    InProgress,
+    Done
+});
+convert_enum_both_ways!(Priority { Low, Medium, High });
+convert_enum_both_ways!(Role {
+    Viewer,
+    Editor,
+    Admin
+});
+convert_enum_both_ways!(SearchField { Title, Comments });
+convert_enum_both_ways!(TitleCollation { Binary, Natural });
+
+convert_enum_from_incoming!(Availability {
+    AvailableNow,
+    ScheduledLater
+});
+convert_enum_from_incoming!(DistinctField { Status, Priority });
+convert_enum_from_incoming!(TodoField {
+    Title,
+    Priority,
+    Status,
+    Deadline
+});
+convert_enum_from_incoming!(TrendBucket { Day, Week });
+convert_enum_from_incoming!(ImportFormat {
+    TodoistCsv,
+    TickTickCsv,
+    TodoTxt,
+    TodoistJson,
+    TickTickJson,
+    ThingsJson,
+});
+convert_enum_from_incoming!(MergeStrategy {
+    Replace,
+    SkipExisting,
+    OverwriteExisting,
+    MergeNewest,
+});
+convert_enum_from_incoming!(QuerySort {
+    Priority,
+    Status,
+    Deadline,
+    StartDate,
+    CreatedAt,
+    UpdatedTimestamp,
+});
+convert_enum_from_incoming!(RecentKind {
+    Viewed,
+    Modified,
+    Created
+});
+convert_enum_from_incoming!(SortDirection {
+    Ascending,
+    Descending
+});
+convert_enum_from_incoming!(SyncStrategy {
+    LocalWins,
+    RemoteWins,
+    Merge
+});
+convert_enum_from_incoming!(View {
+    Today,
+    ThisWeek,
+    Overdue
+});
+
+// An absent list excludes nothing.
+fn all_from_incoming<T: Into<U>, U>(values: Option<Vec<T>>) -> Vec<U> {
+    values
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldValue {
+    Status(Status),
+    Priority(Priority),
+}
+
+impl From<todos::FieldValue> for FieldValue {
+    fn from(value: todos::FieldValue) -> Self {
+        match value {
+            todos::FieldValue::Status(s) => Self::Status(s.into()),
+            todos::FieldValue::Priority(p) => Self::Priority(p.into()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DistinctValue {
+    pub value: FieldValue,
+    pub count: u64,
+}
+
+#[derive(Serialize)]
+pub struct TrendPoint {
+    pub start: i64,
+    pub created: u64,
+    pub completed: u64,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Lateness {
+    UpToADay,
+    UpToAWeek,
+    UpToAMonth,
+    OverAMonth,
+}
+
+impl From<todos::Lateness> for Lateness {
+    fn from(lateness: todos::Lateness) -> Self {
+        match lateness {
+            todos::Lateness::UpToADay => Self::UpToADay,
+            todos::Lateness::UpToAWeek => Self::UpToAWeek,
+            todos::Lateness::UpToAMonth => Self::UpToAMonth,
+            todos::Lateness::OverAMonth => Self::OverAMonth,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LatenessCount {
+    lateness: Lateness,
+    count: u64,
+}
+
+#[derive(Serialize)]
+pub struct PriorityCount {
+    priority: Priority,
+    count: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OverdueReport {
+    pub by_lateness: Vec<LatenessCount>,
+    pub by_priority: Vec<PriorityCount>,
+    pub worst: Vec<Todo>,
+}
+
+impl TryFrom<todos::OverdueReport> for OverdueReport {
+    type Error = String;
+
+    fn try_from(report: todos::OverdueReport) -> AppResult<Self> {
+        Ok(Self {
+            by_lateness: report
+                .by_lateness()
+                .iter()
+                .map(|(lateness, count)| {
+                    Ok(LatenessCount {
+                        lateness: (*lateness).into(),
+                        count: u64_from(*count)?,
+                    })
+                })
+                .collect::<AppResult<_>>()?,
+            by_priority: report
+                .by_priority()
+                .iter()
+                .map(|(priority, count)| {
+                    Ok(PriorityCount {
+                        priority: (*priority).into(),
+                        count: u64_from(*count)?,
+                    })
+                })
+                .collect::<AppResult<_>>()?,
+            worst: report.worst().iter().cloned().map(Into::into).collect(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NewTodo {
+    title: String,
+    priority: Option<Priority>,
+    deadline: Option<String>,
+    timezone: Option<String>,
+    start_date: Option<String>,
+    ignore_deadline_bounds: Option<bool>,
+    allow_past_deadline: Option<bool>,
+    color: Option<String>,
+    icon: Option<String>,
+}
+
+impl From<NewTodo> for todos::NewTodo {
+    fn from(item: NewTodo) -> Self {
+        todos::NewTodo::builder()
+            .title(item.title.into())
+            .priority(item.priority.map(todos::Priority::from))
+            .deadline(
+                OptionalDeadlineInput::new(item.deadline, item.timezone.clone())
+                    .ignoring_bounds(item.ignore_deadline_bounds.unwrap_or_default()),
+            )
+            .start_date(
+                OptionalDeadlineInput::new(item.start_date, item.timezone)
+                    .ignoring_bounds(item.ignore_deadline_bounds.unwrap_or_default()),
+            )
+            .allow_past_deadline(item.allow_past_deadline.unwrap_or_default())
+            .color(item.color.map(Color::new))
+            .icon(item.icon.map(Icon::new))
+            .build()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeadlineInput {
+    deadline: String,
+    timezone: Option<String>,
+    ignore_bounds: Option<bool>,
+}
+
+// Omitting a `deadline-change` field is read as `keep`.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeadlineChange {
+    #[default]
+    Keep,
+    Clear,
+    Set(DeadlineInput),
+}
+
+impl From<DeadlineChange> for todos::DeadlineChange {
+    fn from(change: DeadlineChange) -> Self {
+        match change {
+            DeadlineChange::Keep => Self::Keep,
+            DeadlineChange::Clear => Self::Clear,
+            DeadlineChange::Set(input) => Self::Set(
+                OptionalDeadlineInput::new(Some(input.deadline), input.timezone)
+                    .ignoring_bounds(input.ignore_bounds.unwrap_or_default()),
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UpdateTodo {
+    title: Option<String>,
+    priority: Option<Priority>,
+    status: Option<Status>,
+    #[serde(default)]
+    deadline: DeadlineChange,
+    #[serde(default)]
+    start_date: DeadlineChange,
+    color: Option<String>,
+    icon: Option<String>,
+}
+
+impl From<UpdateTodo> for todos::UpdateTodo {
+    fn from(item: UpdateTodo) -> Self {
+        todos::UpdateTodo::builder()
+            .title(item.title.map(Title::new))
+            .priority(item.priority.map(Into::into))
+            .status(item.status.map(Into::into))
+            .deadline(item.deadline.into())
+            .start_date(item.start_date.into())
+            .color(item.color.map(Color::new))
+            .icon(item.icon.map(Icon::new))
+            .build()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TemplateOverrides {
+    title: Option<String>,
+    priority: Option<Priority>,
+    deadline: Option<String>,
+    timezone: Option<String>,
+    start_date: Option<String>,
+}
+
+impl From<TemplateOverrides> for todos::TemplateOverrides {
+    fn from(overrides: TemplateOverrides) -> Self {
+        let TemplateOverrides {
+            title,
+            priority,
+            deadline,
+            timezone,
+            start_date,
+        } = overrides;
+
+        todos::TemplateOverrides::builder()
+            .title(title.map(Title::new))
+            .priority(priority.map(Into::into))
+            .deadline(deadline.map(|d| OptionalDeadlineInput::new(Some(d), timezone.clone())))
+            .start_date(start_date.map(|d| OptionalDeadlineInput::new(Some(d), timezone)))
+            .build()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BatchUpdate {
+    id: String,
+    change: UpdateTodo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchCommand {
+    Add(NewTodo),
+    Update(BatchUpdate),
+    Delete(String),
+}
+
+impl BatchCommand {
+    pub fn is_delete(&self) -> bool {
+        matches!(self, Self::Delete(_))
+    }
+
+    pub fn into_command(self) -> AppResult<todos::BatchCommand> {
+        let command = match self {
+            Self::Add(item) => todos::BatchCommand::Add(item.into()),
+            Self::Update(BatchUpdate { id, change }) => {
+                todos::BatchCommand::Update(uuid_from(&id)?, change.into())
+            }
+            Self::Delete(id) => todos::BatchCommand::Delete(uuid_from(&id)?),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Serialize)]
+pub struct AttachmentRef {
+    url: String,
+    label: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Todo {
+    id: String,
+    short_id: u64,
+    title: String,
+    priority: Priority,
+    status: Status,
+    created_timestamp: i64,
+    updated_timestamp: i64,
+    completed_timestamp: Option<i64>,
+    deadline: Option<i64>,
+    deadline_local: Option<String>,
+    start_date: Option<i64>,
+    snoozed_until: Option<i64>,
+    color: Option<String>,
+    icon: Option<String>,
+    attachments: Vec<AttachmentRef>,
+    external_refs: BTreeMap<String, String>,
+    last_modified_by: Option<String>,
+}
+
+impl From<todos::Todo> for Todo {
+    fn from(t: todos::Todo) -> Self {
+        Self {
+            id: t.id().to_string(),
+            short_id: t.short_id(),
+            title: t.title().into(),
+            priority: t.priority().into(),
+            deadline: t.deadline(),
+            deadline_local: t.deadline_local(),
+            start_date: t.start_date(),
+            snoozed_until: t.snoozed_until(),
+            status: t.status().into(),
+            created_timestamp: t.created_timestamp(),
+            updated_timestamp: t.updated_timestamp(),
+            completed_timestamp: t.completed_timestamp(),
+            color: t.color().clone(),
+            icon: t.icon().clone(),
+            attachments: t
+                .attachments()
+                .iter()
+                .map(|a| AttachmentRef {
+                    url: a.url().clone(),
+                    label: a.label().clone(),
+                })
+                .collect(),
+            external_refs: t.external_refs().clone(),
+            last_modified_by: t.last_modified_by().clone(),
+        }
+    }
+}
+
+fn ids_for_outgoing(ids: &[impl ToString]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+// Tells a missing field, which keeps a setting, from `null`, which
+// turns it off.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListSettings {
+    version: u32,
+    default_timezone: String,
+    escalation_policy: EscalationPolicy,
+    retention_policy: Option<RetentionPolicy>,
+    deadline_bounds: Option<DeadlineBounds>,
+    strict_limits: bool,
+    track_views: bool,
+    warn_on_similar: bool,
+    title_collation: TitleCollation,
+    creation_defaults: CreationDefaults,
+    query_cache_size: u32,
+}
+
+impl From<todos::ListSettings> for ListSettings {
+    fn from(settings: todos::ListSettings) -> Self {
+        Self {
+            version: todos::ListSettings::VERSION,
+            default_timezone: settings.default_time_zone().to_string(),
+            escalation_policy: settings.escalation_policy().into(),
+            retention_policy: settings.retention_policy().as_ref().map(Into::into),
+            deadline_bounds: settings.deadline_bounds().map(Into::into),
+            strict_limits: settings.strict_limits(),
+            track_views: settings.track_views(),
+            warn_on_similar: settings.warn_on_similar(),
+            title_collation: settings.title_collation().into(),
+            creation_defaults: settings.creation_defaults().into(),
+            query_cache_size: settings.query_cache_size() as u32,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SettingsUpdate {
+    version: Option<u32>,
+    default_timezone: Option<String>,
+    escalation_policy: Option<EscalationPolicy>,
+    #[serde(default, deserialize_with = "present")]
+    retention_policy: Option<Option<RetentionPolicy>>,
+    #[serde(default, deserialize_with = "present")]
+    deadline_bounds: Option<Option<DeadlineBounds>>,
+    strict_limits: Option<bool>,
+    track_views: Option<bool>,
+    warn_on_similar: Option<bool>,
+    title_collation: Option<TitleCollation>,
+    creation_defaults: Option<CreationDefaults>,
+    query_cache_size: Option<u32>,
+}
+
+impl From<SettingsUpdate> for todos::SettingsUpdate {
+    fn from(update: SettingsUpdate) -> Self {
+        todos::SettingsUpdate::builder()
+            .version(update.version)
+            .default_time_zone(update.default_timezone)
+            .escalation_policy(update.escalation_policy.map(Into::into))
+            .retention_policy(update.retention_policy.map(|p| p.map(Into::into)))
+            .deadline_bounds(update.deadline_bounds.map(|b| b.map(Into::into)))
+            .strict_limits(update.strict_limits)
+            .track_views(update.track_views)
+            .warn_on_similar(update.warn_on_similar)
+            .title_collation(update.title_collation.map(Into::into))
+            .creation_defaults(update.creation_defaults.map(Into::into))
+            .query_cache_size(update.query_cache_size.map(|size| size as usize))
+            .build()
+    }
+}
+
+#[derive(Serialize)]
+pub struct ChangeSummary {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl From<todos::ChangeSummary> for ChangeSummary {
+    fn from(summary: todos::ChangeSummary) -> Self {
+        Self {
+            created: ids_for_outgoing(summary.created()),
+            updated: ids_for_outgoing(summary.updated()),
+            deleted: ids_for_outgoing(summary.deleted()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteTodo {
+    id: String,
+    title: String,
+    priority: Priority,
+    status: Status,
+    deadline: Option<i64>,
+    updated_timestamp: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemoteChange {
+    Upsert(RemoteTodo),
+    Delete(String),
+}
+
+impl RemoteChange {
+    pub fn is_delete(&self) -> bool {
+        matches!(self, Self::Delete(_))
+    }
+
+    pub fn into_change(self) -> AppResult<todos::RemoteChange> {
+        let change = match self {
+            Self::Upsert(t) => todos::RemoteChange::Upsert(
+                todos::RemoteTodo::builder()
+                    .id(uuid_from(&t.id)?)
+                    .title(Title::new(t.title))
+                    .priority(t.priority.into())
+                    .status(t.status.into())
+                    .deadline(t.deadline)
+                    .updated_timestamp(t.updated_timestamp)
+                    .build(),
+            ),
+            Self::Delete(id) => todos::RemoteChange::Delete(uuid_from(&id)?),
+        };
+
+        Ok(change)
+    }
+}
+
+#[derive(Serialize)]
+pub struct SyncReport {
+    pub applied: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+impl From<todos::SyncReport> for SyncReport {
+    fn from(report: todos::SyncReport) -> Self {
+        Self {
+            applied: ids_for_outgoing(report.applied()),
+            conflicts: ids_for_outgoing(report.conflicts()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Principal {
+    name: String,
+    role: Role,
+}
+
+impl From<todos::Principal> for Principal {
+    fn from(p: todos::Principal) -> Self {
+        Self {
+            name: p.name().clone(),
+            role: p.role().into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Comment {
+    id: String,
+    author: String,
+    text: String,
+    created_timestamp: i64,
+}
+
+impl From<todos::Comment> for Comment {
+    fn from(c: todos::Comment) -> Self {
+        Self {
+            id: c.id().to_string(),
+            author: c.author().clone(),
+            text: c.text().clone(),
+            created_timestamp: c.created_timestamp(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StatusChange {
+    before: Status,
+    after: Status,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeadlineMove {
+    before: Option<i64>,
+    after: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ActivityKind {
+    Created,
+    StatusChanged(StatusChange),
+    CommentAdded(String),
+    DeadlineMoved(DeadlineMove),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Activity {
+    todo_id: String,
+    timestamp: i64,
+    actor: Option<String>,
+    kind: ActivityKind,
+}
+
+impl From<todos::Activity> for Activity {
+    fn from(a: todos::Activity) -> Self {
+        let kind = match a.kind() {
+            todos::ActivityKind::Created => ActivityKind::Created,
+            todos::ActivityKind::StatusChanged { before, after } => {
+                ActivityKind::StatusChanged(StatusChange {
+                    before: before.into(),
+                    after: after.into(),
+                })
+            }
+            todos::ActivityKind::CommentAdded { comment_id } => {
+                ActivityKind::CommentAdded(comment_id.to_string())
+            }
+            todos::ActivityKind::DeadlineMoved { before, after } => {
+                ActivityKind::DeadlineMoved(DeadlineMove { before, after })
+            }
+        };
+
+        Self {
+            todo_id: a.todo_id().to_string(),
+            timestamp: a.timestamp(),
+            actor: a.actor().clone(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub struct FetchInclude {
+    comments: bool,
+    watchers: bool,
+    activity: bool,
+}
+
+impl From<FetchInclude> for todos::FetchInclude {
+    fn from(include: FetchInclude) -> Self {
+        todos::FetchInclude::builder()
+            .comments(include.comments)
+            .watchers(include.watchers)
+            .activity(include.activity)
+            .build()
+    }
+}
+
+#[derive(Serialize)]
+pub struct TodoDetail {
+    todo: Todo,
+    comments: Option<Vec<Comment>>,
+    watchers: Option<Vec<String>>,
+    activity: Option<Vec<Activity>>,
+}
+
+impl From<todos::TodoDetail> for TodoDetail {
+    fn from(detail: todos::TodoDetail) -> Self {
+        Self {
+            todo: detail.todo().clone().into(),
+            comments: detail
+                .comments()
+                .clone()
+                .map(|found| found.into_iter().map(Into::into).collect()),
+            watchers: detail.watchers().clone(),
+            activity: detail
+                .activity()
+                .clone()
+                .map(|found| found.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ActivityFilter {
+    todo_id: Option<String>,
+    since: Option<i64>,
+}
+
+impl TryFrom<ActivityFilter> for todos::ActivityFilter {
+    type Error = String;
+
+    fn try_from(filter: ActivityFilter) -> AppResult<Self> {
+        Ok(todos::ActivityFilter::builder()
+            .todo_id(filter.todo_id.as_deref().map(uuid_from).transpose()?)
+            .since(filter.since)
+            .build())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LimitClamp {
+    given: u32,
+    used: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Warning {
+    TitleTrimmed,
+    LimitClamped(LimitClamp),
+    DefaultTimezoneUsed(String),
+}
+
+impl From<todos::Warning> for Warning {
+    fn from(w: todos::Warning) -> Self {
+        match w {
+            todos::Warning::TitleTrimmed => Self::TitleTrimmed,
+            todos::Warning::LimitClamped { given, used } => {
+                Self::LimitClamped(LimitClamp { given, used })
+            }
+            todos::Warning::DefaultTimeZoneUsed(time_zone) => {
+                Self::DefaultTimezoneUsed(time_zone.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CheckedAdd {
+    outcome: AddOutcome,
+    warnings: Vec<Warning>,
+}
+
+impl From<todos::ResultWithWarnings<todos::AddOutcome>> for CheckedAdd {
+    fn from(result: todos::ResultWithWarnings<todos::AddOutcome>) -> Self {
+        let (outcome, warnings) = result.into_parts();
+
+        Self {
+            outcome: outcome.into(),
+            warnings: warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CheckedTodo {
+    todo: Todo,
+    warnings: Vec<Warning>,
+}
+
+impl From<todos::ResultWithWarnings<todos::Todo>> for CheckedTodo {
+    fn from(result: todos::ResultWithWarnings<todos::Todo>) -> Self {
+        let (todo, warnings) = result.into_parts();
+
+        Self {
+            todo: todo.into(),
+            warnings: warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CheckedSearch {
+    pub items: Vec<Todo>,
+    warnings: Vec<Warning>,
+}
+
+impl From<todos::ResultWithWarnings<Vec<todos::Todo>>> for CheckedSearch {
+    fn from(result: todos::ResultWithWarnings<Vec<todos::Todo>>) -> Self {
+        let (found, warnings) = result.into_parts();
+
+        Self {
+            items: found.into_iter().map(Into::into).collect(),
+            warnings: warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AddOutcome {
+    Added(Box<Todo>),
+    Similar(Vec<Todo>),
+}
+
+impl From<todos::AddOutcome> for AddOutcome {
+    fn from(outcome: todos::AddOutcome) -> Self {
+        match outcome {
+            todos::AddOutcome::Added(t) => Self::Added(Box::new((*t).into())),
+            todos::AddOutcome::Similar(found) => {
+                Self::Similar(found.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchResult {
+    Added(Todo),
+    Updated(Todo),
+    Deleted(Todo),
+}
+
+impl From<todos::BatchResult> for BatchResult {
+    fn from(result: todos::BatchResult) -> Self {
+        match result {
+            todos::BatchResult::Added(t) => Self::Added(t.into()),
+            todos::BatchResult::Updated(t) => Self::Updated(t.into()),
+            todos::BatchResult::Deleted(t) => Self::Deleted(t.into()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldPredicate {
+    Keyword(String),
+    Priority(Priority),
+    Status(Status),
+    Overdue,
+    HasDeadline,
+}
+
+impl From<FieldPredicate> for todos::FieldPredicate {
+    fn from(predicate: FieldPredicate) -> Self {
+        match predicate {
+            FieldPredicate::Keyword(keyword) => Self::Keyword(keyword),
+            FieldPredicate::Priority(p) => Self::Priority(p.into()),
+            FieldPredicate::Status(s) => Self::Status(s.into()),
+            FieldPredicate::Overdue => Self::Overdue,
+            FieldPredicate::HasDeadline => Self::HasDeadline,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterNode {
+    And(Vec<u32>),
+    Or(Vec<u32>),
+    Not(u32),
+    Field(FieldPredicate),
+}
+
+#[derive(Deserialize)]
+pub struct FilterExpr {
+    nodes: Vec<FilterNode>,
+}
+
+impl TryFrom<FilterExpr> for todos::FilterExpr {
+    type Error = String;
+
+    fn try_from(expr: FilterExpr) -> AppResult<Self> {
+        let nodes = expr
+            .nodes
+            .into_iter()
+            .map(|node| match node {
+                FilterNode::And(children) => todos::FilterNode::And(children),
+                FilterNode::Or(children) => todos::FilterNode::Or(children),
+                FilterNode::Not(child) => todos::FilterNode::Not(child),
+                FilterNode::Field(predicate) => todos::FilterNode::Field(predicate.into()),
+            })
+            .collect();
+
+        todos::FilterExpr::from_nodes(nodes).err_as_string()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Query {
+    keyword: Option<String>,
+    title_regex: Option<String>,
+    not_keyword: Option<String>,
+    plain_text_match: Option<bool>,
+    search_fields: Option<Vec<SearchField>>,
+    priority: Option<Priority>,
+    status: Option<Status>,
+    exclude_priorities: Option<Vec<Priority>>,
+    exclude_statuses: Option<Vec<Status>>,
+    expr: Option<FilterExpr>,
+    deadline: Option<String>,
+    timezone: Option<String>,
+    availability: Option<Availability>,
+    include_snoozed: Option<bool>,
+    sort: Option<QuerySort>,
+    direction: Option<SortDirection>,
+    limit: Option<u32>,
+    projection: Option<Vec<TodoField>>,
+    after: Option<String>,
+}
+
+impl TryFrom<Query> for todos::Query {
+    type Error = String;
+
+    fn try_from(query: Query) -> AppResult<Self> {
+        let query = todos::Query::builder()
+            .keyword(query.keyword)
+            .title_regex(query.title_regex)
+            .priority(query.priority.map(Into::into))
+            .status(query.status.map(Into::into))
+            .not_keyword(query.not_keyword)
+            .plain_text_match(query.plain_text_match.unwrap_or_default())
+            .search_fields(all_from_incoming(query.search_fields))
+            .exclude_priorities(all_from_incoming(query.exclude_priorities))
+            .exclude_statuses(all_from_incoming(query.exclude_statuses))
+            .deadline(OptionalDeadlineInput::new(query.deadline, query.timezone))
+            .availability(query.availability.map(Into::into))
+            .include_snoozed(query.include_snoozed.unwrap_or_default())
+            .sort(query.sort.map(Into::into))
+            .direction(query.direction.map(Into::into).unwrap_or_default())
+            .expr(query.expr.map(TryInto::try_into).transpose()?)
+            .limit(query.limit.into())
+            .projection(all_from_incoming(query.projection))
+            .after(query.after)
+            .build();
+
+        Ok(query)
+    }
+}
+
+#[derive(Serialize)]
+pub struct TodoSummary {
+    id: String,
+    title: Option<String>,
+    priority: Option<Priority>,
+    status: Option<Status>,
+    deadline: Option<i64>,
+}
+
+impl From<todos::TodoSummary> for TodoSummary {
+    fn from(t: todos::TodoSummary) -> Self {
+        Self {
+            id: t.id().to_string(),
+            title: t.title().clone(),
+            priority: t.priority().map(Into::into),
+            status: t.status().map(Into::into),
+            deadline: t.deadline(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SearchResult {
+    pub items: Vec<Todo>,
+    pub total_matched: u64,
+    pub next_cursor: Option<String>,
+}
+
+impl TryFrom<todos::SearchResult> for SearchResult {
+    type Error = String;
+
+    fn try_from(found: todos::SearchResult) -> AppResult<Self> {
+        Ok(Self {
+            items: found.items().iter().cloned().map(Into::into).collect(),
+            total_matched: u64_from(found.total_matched())?,
+            next_cursor: found.next_cursor().clone(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    item: Todo,
+    field: Option<SearchField>,
+    score: u32,
+}
+
+impl From<todos::SearchHit> for SearchHit {
+    fn from(hit: todos::SearchHit) -> Self {
+        Self {
+            item: hit.todo().clone().into(),
+            field: hit.field().map(Into::into),
+            score: hit.score(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Filter {
+    keyword: Option<String>,
+    not_keyword: Option<String>,
+    priority: Option<Priority>,
+    status: Option<Status>,
+    exclude_priorities: Option<Vec<Priority>>,
+    exclude_statuses: Option<Vec<Status>>,
+    expr: Option<FilterExpr>,
+    deadline: Option<String>,
+    timezone: Option<String>,
+    availability: Option<Availability>,
+    include_snoozed: Option<bool>,
+}
+
+impl TryFrom<Filter> for todos::Query {
+    type Error = String;
+
+    fn try_from(filter: Filter) -> AppResult<Self> {
+        let filter = todos::Query::builder()
+            .keyword(filter.keyword)
+            .priority(filter.priority.map(Into::into))
+            .status(filter.status.map(Into::into))
+            .not_keyword(filter.not_keyword)
+            .exclude_priorities(all_from_incoming(filter.exclude_priorities))
+            .exclude_statuses(all_from_incoming(filter.exclude_statuses))
+            .deadline(OptionalDeadlineInput::new(filter.deadline, filter.timezone))
+            .availability(filter.availability.map(Into::into))
+            .include_snoozed(filter.include_snoozed.unwrap_or_default())
+            .expr(filter.expr.map(TryInto::try_into).transpose()?)
+            .build();
+
+        Ok(filter)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreationDefaults {
+    priority: Priority,
+    deadline_after_days: Option<u32>,
+}
+
+impl From<CreationDefaults> for todos::CreationDefaults {
+    fn from(defaults: CreationDefaults) -> Self {
+        todos::CreationDefaults::builder()
+            .priority(defaults.priority.into())
+            .deadline_after_days(defaults.deadline_after_days)
+            .build()
+    }
+}
+
+impl From<todos::CreationDefaults> for CreationDefaults {
+    fn from(defaults: todos::CreationDefaults) -> Self {
+        Self {
+            priority: defaults.priority().into(),
+            deadline_after_days: defaults.deadline_after_days(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EscalationPolicy {
+    deadline_within_hours: Option<u32>,
+    backlog_after_days: Option<u32>,
+}
+
+impl From<EscalationPolicy> for todos::EscalationPolicy {
+    fn from(policy: EscalationPolicy) -> Self {
+        todos::EscalationPolicy::builder()
+            .deadline_within_hours(policy.deadline_within_hours)
+            .backlog_after_days(policy.backlog_after_days)
+            .build()
+    }
+}
+
+impl From<todos::EscalationPolicy> for EscalationPolicy {
+    fn from(policy: todos::EscalationPolicy) -> Self {
+        Self {
+            deadline_within_hours: policy.deadline_within_hours(),
+            backlog_after_days: policy.backlog_after_days(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RetentionPolicy {
+    older_than_days: u32,
+    statuses: Vec<Status>,
+}
+
+impl From<RetentionPolicy> for todos::RetentionPolicy {
+    fn from(policy: RetentionPolicy) -> Self {
+        todos::RetentionPolicy::builder()
+            .older_than_days(policy.older_than_days)
+            .statuses(policy.statuses.into_iter().map(Into::into).collect())
+            .build()
+    }
+}
+
+impl From<&todos::RetentionPolicy> for RetentionPolicy {
+    fn from(policy: &todos::RetentionPolicy) -> Self {
+        Self {
+            older_than_days: policy.older_than_days(),
+            statuses: policy.statuses().iter().copied().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeadlineBounds {
+    max_days_past: u32,
+    max_years_ahead: u32,
+}
+
+impl From<DeadlineBounds> for todos::DeadlineBounds {
+    fn from(bounds: DeadlineBounds) -> Self {
+        todos::DeadlineBounds::builder()
+            .max_days_past(bounds.max_days_past)
+            .max_years_ahead(bounds.max_years_ahead)
+            .build()
+    }
+}
+
+impl From<todos::DeadlineBounds> for DeadlineBounds {
+    fn from(bounds: todos::DeadlineBounds) -> Self {
+        Self {
+            max_days_past: bounds.max_days_past(),
+            max_years_ahead: bounds.max_years_ahead(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct OperationCount {
+    operation: String,
+    count: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Health {
+    uptime_seconds: i64,
+    operation_counts: Vec<OperationCount>,
+    last_snapshot: Option<i64>,
+    item_count: u64,
+    estimated_memory_bytes: u64,
+}
+
+impl From<todos::Health> for Health {
+    fn from(health: todos::Health) -> Self {
+        Self {
+            uptime_seconds: health.uptime_seconds(),
+            operation_counts: health
+                .operation_counts()
+                .iter()
+                .map(|(operation, count)| OperationCount {
+                    operation: operation.clone(),
+                    count: *count,
+                })
+                .collect(),
+            last_snapshot: health.last_snapshot(),
+            item_count: health.item_count() as u64,
+            estimated_memory_bytes: health.estimated_memory_bytes() as u64,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportSuccess {
+    row: u32,
+    todo: Todo,
+}
+
+#[derive(Serialize)]
+pub struct ImportFailure {
+    row: u32,
+    reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImportSkip {
+    row: u32,
+    existing_id: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub imported: Vec<ImportSuccess>,
+    updated: Vec<ImportSuccess>,
+    skipped: Vec<ImportSkip>,
+    failed: Vec<ImportFailure>,
+    checksum: String,
+}
+
+impl From<todos::ImportReport> for ImportReport {
+    fn from(report: todos::ImportReport) -> Self {
+        Self {
+            imported: report
+                .imported()
+                .iter()
+                .map(|(row, todo)| ImportSuccess {
+                    row: *row as u32,
+                    todo: todo.clone().into(),
+                })
+                .collect(),
+            updated: report
+                .updated()
+                .iter()
+                .map(|(row, todo)| ImportSuccess {
+                    row: *row as u32,
+                    todo: todo.clone().into(),
+                })
+                .collect(),
+            skipped: report
+                .skipped()
+                .iter()
+                .map(|(row, id)| ImportSkip {
+                    row: *row as u32,
+                    existing_id: id.to_string(),
+                })
+                .collect(),
+            failed: report
+                .failed()
+                .iter()
+                .map(|(row, reason)| ImportFailure {
+                    row: *row as u32,
+                    reason: reason.clone(),
+                })
+                .collect(),
+            checksum: report.checksum().clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ChunkedAddReport {
+    pub added: Vec<ImportSuccess>,
+    failed: Vec<ImportFailure>,
+    continuation: Option<String>,
+}
+
+impl From<todos::ChunkedAddReport> for ChunkedAddReport {
+    fn from(report: todos::ChunkedAddReport) -> Self {
+        Self {
+            added: report
+                .added()
+                .iter()
+                .map(|(row, todo)| ImportSuccess {
+                    row: *row as u32,
+                    todo: todo.clone().into(),
+                })
+                .collect(),
+            failed: report
+                .failed()
+                .iter()
+                .map(|(row, reason)| ImportFailure {
+                    row: *row as u32,
+                    reason: reason.clone(),
+                })
+                .collect(),
+            continuation: report.continuation().clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetaData {
+    pub component_version: String,
+    pub schema_version: u64,
+    pub max_result_limit: u32,
+    pub max_title_length: u32,
+    pub accepted_deadline_formats: String,
+    pub capacity_limit: Option<u64>,
+    pub features: Vec<String>,
+    pub icons: Vec<String>,
+}
@@ -0,0 +1,2566 @@
+// Serves the wasm component's API over HTTP: every WIT function is a
+// `POST /api/<name>` whose body is the same JSON parameter array that
+// `golem instance invoke-and-await --parameters` takes.
+mod dto;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use dto::*;
+use lib::{
+    app_error::AppResultExt,
+    codec,
+    core::{enabled_features, u64_from, usize_from, uuid_from, AppResult},
+    metrics::Metrics,
+    todos::{self, OptionalDeadlineInput, Title, TodoList},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    env,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:3000";
+
+const SCHEMA_VERSION: u64 = 2;
+
+#[cfg(feature = "persistence")]
+const COMPACT_EVERY: usize = 1000;
+
+// The default list, which also holds the server's auth, and the
+// namespaced lists, made on first use. Only the default list is
+// persisted.
+struct AppState {
+    todos: TodoList,
+
+    namespaces: HashMap<String, TodoList>,
+
+    metrics: Metrics,
+}
+
+impl AppState {
+    fn list(&mut self, namespace: Option<&str>) -> &mut TodoList {
+        match namespace.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(namespace) => self
+                .namespaces
+                .entry(namespace.to_string())
+                .or_insert_with(new_todo_list),
+            None => &mut self.todos,
+        }
+    }
+}
+
+fn new_todo_list() -> TodoList {
+    TodoList::new_with(todos::TimeOrderedIdGenerator::default())
+}
+
+type SharedState = Arc<Mutex<AppState>>;
+
+// `operation` is the WIT function name, as in the wasm component.
+fn with_state<T: Observed>(
+    state: &SharedState,
+    operation: &str,
+    f: impl FnOnce(&mut AppState) -> T,
+) -> T {
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+
+    state.todos.record_op(operation);
+
+    let started = Instant::now();
+    let result = f(&mut state);
+
+    state.metrics.observe(
+        operation,
+        started.elapsed(),
+        result.result_size(),
+        result.failed(),
+    );
+
+    result
+}
+
+// Every call but meta, health and metrics runs through here, so a list
+// with a shared secret set rejects callers whose token lacks `required`.
+// The token is checked against the default list; `f` gets the
+// namespace's.
+fn with_authorized_todos<T>(
+    state: &SharedState,
+    operation: &str,
+    auth_token: Option<String>,
+    namespace: Option<String>,
+    required: todos::Role,
+    f: impl FnOnce(&mut TodoList) -> AppResult<T>,
+) -> AppResult<T>
+where
+    T: Observed,
+{
+    with_state(state, operation, |state| {
+        state
+            .todos
+            .authorize(auth_token.as_deref(), required)
+            .err_as_string()?;
+
+        f(state.list(namespace.as_deref()))
+    })
+}
+
+// Attributes the changes `f` makes to `actor`, when one is given.
+fn with_attributed_todos<T>(
+    state: &SharedState,
+    operation: &str,
+    auth_token: Option<String>,
+    namespace: Option<String>,
+    actor: Option<String>,
+    required: todos::Role,
+    f: impl FnOnce(&mut TodoList) -> AppResult<T>,
+) -> AppResult<T>
+where
+    T: Observed,
+{
+    with_authorized_todos(state, operation, auth_token, namespace, required, |todos| {
+        todos.set_actor(actor.as_deref()).err_as_string()?;
+
+        let result = f(todos);
+
+        todos.clear_actor();
+
+        result
+    })
+}
+
+// What the metrics need to know about an operation's return value.
+trait Observed {
+    fn result_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn failed(&self) -> bool {
+        false
+    }
+}
+
+impl<T: Observed> Observed for AppResult<T> {
+    fn result_size(&self) -> Option<usize> {
+        self.as_ref().ok().and_then(Observed::result_size)
+    }
+
+    fn failed(&self) -> bool {
+        self.is_err()
+    }
+}
+
+impl<T> Observed for Vec<T> {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl Observed for SearchResult {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<T> Observed for Option<T> {}
+impl Observed for () {}
+impl Observed for bool {}
+impl Observed for u32 {}
+impl Observed for u64 {}
+impl Observed for String {}
+impl Observed for Todo {}
+impl Observed for AddOutcome {}
+impl Observed for CheckedAdd {}
+impl Observed for CheckedTodo {}
+impl Observed for TodoDetail {}
+
+impl Observed for CheckedSearch {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+impl Observed for TitleCollation {}
+impl Observed for ListSettings {}
+impl Observed for Comment {}
+impl Observed for Principal {}
+impl Observed for EscalationPolicy {}
+impl Observed for Health {}
+
+impl Observed for OverdueReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.worst.len())
+    }
+}
+
+impl Observed for ChangeSummary {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.created.len() + self.updated.len() + self.deleted.len())
+    }
+}
+
+impl Observed for SyncReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.applied.len())
+    }
+}
+
+impl Observed for ChunkedAddReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.added.len())
+    }
+}
+
+impl Observed for ImportReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.imported.len())
+    }
+}
+
+// A WIT `result<T, string>`: the value on success, `{"error": ...}` with a
+// 400 otherwise.
+fn reply<T: Serialize>(result: AppResult<T>) -> Response {
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(error) => (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response(),
+    }
+}
+
+fn todos_for_outgoing(found: Vec<todos::Todo>) -> Vec<Todo> {
+    found.into_iter().map(Into::into).collect()
+}
+
+async fn meta() -> Json<MetaData> {
+    Json(MetaData {
+        component_version: env!("CARGO_PKG_VERSION").into(),
+        schema_version: SCHEMA_VERSION,
+        max_result_limit: todos::OptionalResultLimit::MAX,
+        max_title_length: Title::MAX_LEN as u32,
+        accepted_deadline_formats: OptionalDeadlineInput::accepted_formats(),
+        // The list has no capacity limit yet.
+        capacity_limit: None,
+        features: enabled_features().into_iter().map(String::from).collect(),
+        icons: todos::Icon::SAFELIST
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    })
+}
+
+async fn health(State(state): State<SharedState>) -> Json<Health> {
+    Json(with_state(&state, "health", |state| {
+        state.todos.health().into()
+    }))
+}
+
+async fn get_metrics(State(state): State<SharedState>) -> String {
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+
+    let query_cache = std::iter::once(&state.todos)
+        .chain(state.namespaces.values())
+        .map(TodoList::query_cache_stats)
+        .sum();
+    state.metrics.observe_query_cache(query_cache);
+
+    state.metrics.render()
+}
+
+type AddArgs = (Option<String>, Option<String>, Option<String>, NewTodo);
+
+async fn add(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, item)): Json<AddArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "add",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let result = todos.add_or_warn(&item.into()).err_as_string()?;
+
+            Ok(AddOutcome::from(result))
+        },
+    ))
+}
+
+type AddWithWarningsArgs = (Option<String>, Option<String>, Option<String>, NewTodo);
+
+async fn add_with_warnings(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, item)): Json<AddWithWarningsArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "add-with-warnings",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let result = todos.add_with_warnings(&item.into()).err_as_string()?;
+
+            Ok(CheckedAdd::from(result))
+        },
+    ))
+}
+
+async fn validate_new_todo(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, item)): Json<(Option<String>, Option<String>, NewTodo)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "validate-new-todo",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let result = todos.validate_new_todo(&item.into()).err_as_string()?;
+
+            Ok(CheckedTodo::from(result))
+        },
+    ))
+}
+
+async fn validate(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, item)): Json<(Option<String>, Option<String>, NewTodo)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "validate",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.validate(&item.into())),
+    ))
+}
+
+async fn validate_update(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id, change)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        UpdateTodo,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "validate-update",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            Ok(todos.validate_update(id, &change.into()))
+        },
+    ))
+}
+
+type UpdateArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    UpdateTodo,
+);
+
+async fn update(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, change)): Json<UpdateArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "update",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.update(id, &change.into()).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type UpdateWithWarningsArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    UpdateTodo,
+);
+
+async fn update_with_warnings(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, change)): Json<UpdateWithWarningsArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "update-with-warnings",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos
+                .update_with_warnings(id, &change.into())
+                .err_as_string()?;
+
+            Ok(CheckedTodo::from(result))
+        },
+    ))
+}
+
+type AppendToTitleArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn append_to_title(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, suffix)): Json<AppendToTitleArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "append-to-title",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.append_to_title(id, &suffix).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type PrefixTitleArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn prefix_title(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, prefix)): Json<PrefixTitleArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "prefix-title",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.prefix_title(id, &prefix).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type ShiftDeadlinesArgs = (Option<String>, Option<String>, Option<String>, Query, i64);
+
+async fn shift_deadlines(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, query, delta_hours)): Json<ShiftDeadlinesArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "shift-deadlines",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let count = todos
+                .shift_deadlines(&query.try_into()?, delta_hours)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+async fn search(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.search_cached(&query.try_into()?).err_as_string()?;
+
+            Ok(todos_for_outgoing(found.items().clone()))
+        },
+    ))
+}
+
+async fn search_with_warnings(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-with-warnings",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let result = todos
+                .search_with_warnings(&query.try_into()?)
+                .err_as_string()?;
+
+            Ok(CheckedSearch::from(result))
+        },
+    ))
+}
+
+async fn search_summaries(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-summaries",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.search_projected(&query.try_into()?).err_as_string()?;
+
+            Ok(found.into_iter().map(TodoSummary::from).collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn search_hits(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-hits",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.search_hits(&query.try_into()?).err_as_string()?;
+
+            Ok(found.into_iter().map(SearchHit::from).collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn query_sql(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, text)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "query-sql",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.query_sql(&text).err_as_string()?;
+
+            Ok(found.into_iter().map(TodoSummary::from).collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn search_with_total(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-with-total",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.search_cached(&query.try_into()?).err_as_string()?;
+
+            SearchResult::try_from(found)
+        },
+    ))
+}
+
+async fn search_federated(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query)): Json<(Option<String>, Option<String>, Query)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-federated",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| todos.search_federated(&query.try_into()?).err_as_string(),
+    ))
+}
+
+type MergeFederatedArgs = (Option<String>, Option<String>, Query, Vec<String>);
+
+async fn merge_federated(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, query, result_sets)): Json<MergeFederatedArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "merge-federated",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let merged = todos
+                .merge_federated(&query.try_into()?, &result_sets)
+                .err_as_string()?;
+
+            SearchResult::try_from(merged)
+        },
+    ))
+}
+
+async fn count_by(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, filter)): Json<(Option<String>, Option<String>, Filter)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "count-by",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let count = todos.count_by(&filter.try_into()?).err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+async fn count_all(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "count-all",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| u64_from(todos.count_all()),
+    ))
+}
+
+async fn count_overdue(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, now)): Json<(Option<String>, Option<String>, i64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "count-overdue",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| u64_from(todos.count_overdue(now)),
+    ))
+}
+
+type ListAllArgs = (Option<String>, Option<String>, u32, Option<u32>);
+
+async fn list_all(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, offset, limit)): Json<ListAllArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-all",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let page = todos
+                .list_page(usize_from(offset)?, &limit.into())
+                .err_as_string()?;
+
+            Ok(todos_for_outgoing(page))
+        },
+    ))
+}
+
+async fn pin_view(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "pin-view",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| Ok(todos.pin_view()),
+    ))
+}
+
+async fn unpin_view(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, pin)): Json<(Option<String>, Option<String>, u64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "unpin-view",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| Ok(todos.unpin_view(pin)),
+    ))
+}
+
+type ListAllPinnedArgs = (Option<String>, Option<String>, u64, u32, Option<u32>);
+
+async fn list_all_pinned(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, pin, offset, limit)): Json<ListAllPinnedArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-all-pinned",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let page = todos
+                .list_page_pinned(pin, usize_from(offset)?, &limit.into())
+                .err_as_string()?;
+
+            Ok(todos_for_outgoing(page))
+        },
+    ))
+}
+
+type SearchPinnedArgs = (Option<String>, Option<String>, u64, Query);
+
+async fn search_pinned(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, pin, query)): Json<SearchPinnedArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "search-pinned",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos
+                .search_pinned(pin, &query.try_into()?)
+                .err_as_string()?;
+
+            SearchResult::try_from(found)
+        },
+    ))
+}
+
+type SuggestArgs = (Option<String>, Option<String>, String, Option<u32>);
+
+async fn suggest(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, prefix, limit)): Json<SuggestArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "suggest",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| todos.suggest_titles(&prefix, &limit.into()).err_as_string(),
+    ))
+}
+
+async fn distinct_values(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, field)): Json<(Option<String>, Option<String>, DistinctField)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "distinct-values",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            todos
+                .distinct(field.into())
+                .into_iter()
+                .map(|(value, count)| {
+                    Ok(DistinctValue {
+                        value: value.into(),
+                        count: u64_from(count)?,
+                    })
+                })
+                .collect::<AppResult<Vec<_>>>()
+        },
+    ))
+}
+
+type GetTrendArgs = (Option<String>, Option<String>, TrendBucket, i64, i64);
+
+async fn get_trend(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, bucket, from, to)): Json<GetTrendArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-trend",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            todos
+                .stats_over_time(bucket.into(), from, to)
+                .err_as_string()?
+                .into_iter()
+                .map(|p| {
+                    Ok(TrendPoint {
+                        start: p.start(),
+                        created: u64_from(p.created())?,
+                        completed: u64_from(p.completed())?,
+                    })
+                })
+                .collect::<AppResult<Vec<_>>>()
+        },
+    ))
+}
+
+async fn find_duplicates(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, threshold)): Json<(Option<String>, Option<String>, u8)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "find-duplicates",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let groups = todos.find_similar(threshold).err_as_string()?;
+
+            Ok(groups
+                .into_iter()
+                .map(todos_for_outgoing)
+                .collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn overdue_report(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, now)): Json<(Option<String>, Option<String>, i64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "overdue-report",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| OverdueReport::try_from(todos.overdue_report(now)),
+    ))
+}
+
+async fn get(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.get_and_record_view(id).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn get_by_short_id(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, short_id)): Json<(Option<String>, Option<String>, u64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-by-short-id",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let result = todos.get_by_short_id(short_id).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn fetch(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id, include)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        FetchInclude,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "fetch",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.fetch(id, &include.into()).err_as_string()?;
+
+            Ok(TodoDetail::from(result))
+        },
+    ))
+}
+
+async fn find_by_title(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, title, exact)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        bool,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "find-by-title",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos_for_outgoing(todos.find_by_title(&title, exact))),
+    ))
+}
+
+type RecentArgs = (Option<String>, Option<String>, RecentKind, Option<u32>);
+
+async fn recent(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, kind, limit)): Json<RecentArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "recent",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.recent(kind.into(), &limit.into()).err_as_string()?;
+
+            Ok(todos_for_outgoing(found))
+        },
+    ))
+}
+
+async fn delete(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            todos.delete(id).err_as_string()
+        },
+    ))
+}
+
+async fn delete_returning(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-returning",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.delete_returning(id).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn delete_done_items(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, dry_run)): Json<(Option<String>, Option<String>, bool)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-done-items",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            let count = todos
+                .delete_by_status(&todos::Status::Done, dry_run)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+async fn delete_by_filter(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, filter, dry_run)): Json<(
+        Option<String>,
+        Option<String>,
+        Filter,
+        bool,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-by-filter",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            let count = todos
+                .delete_where(&filter.try_into()?, dry_run)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+type UpdateByFilterArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Filter,
+    UpdateTodo,
+    bool,
+);
+
+async fn update_by_filter(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, filter, change, dry_run)): Json<UpdateByFilterArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "update-by-filter",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::for_bulk_change(!dry_run),
+        |todos| {
+            let count = todos
+                .update_where(&filter.try_into()?, &change.into(), dry_run)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+async fn delete_all(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-all",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| u64_from(todos.delete_all().err_as_string()?),
+    ))
+}
+
+type BatchArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Vec<BatchCommand>,
+);
+
+async fn batch(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, commands)): Json<BatchArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "batch",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::for_bulk_change(commands.iter().any(BatchCommand::is_delete)),
+        |todos| {
+            let commands = commands
+                .into_iter()
+                .map(BatchCommand::into_command)
+                .collect::<AppResult<_>>()?;
+
+            let results = todos.apply_batch(commands).err_as_string()?;
+
+            Ok(results
+                .into_iter()
+                .map(BatchResult::from)
+                .collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn save_query(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, name, query)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        Query,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "save-query",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| todos.save_query(&name, query.try_into()?).err_as_string(),
+    ))
+}
+
+async fn run_saved(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, name)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "run-saved",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos.run_saved(&name).err_as_string()?;
+
+            Ok(todos_for_outgoing(found))
+        },
+    ))
+}
+
+async fn list_saved(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-saved",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.list_saved()),
+    ))
+}
+
+async fn delete_saved(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, name)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-saved",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| todos.delete_saved(&name).err_as_string(),
+    ))
+}
+
+async fn save_template(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, name, item)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        NewTodo,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "save-template",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| todos.save_template(&name, item.into()).err_as_string(),
+    ))
+}
+
+type InstantiateTemplateArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    TemplateOverrides,
+);
+
+async fn instantiate_template(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, name, overrides)): Json<InstantiateTemplateArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "instantiate-template",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let result = todos
+                .instantiate_template(&name, overrides.into())
+                .err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn list_templates(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-templates",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.list_templates()),
+    ))
+}
+
+async fn delete_template(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, name)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-template",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| todos.delete_template(&name).err_as_string(),
+    ))
+}
+
+async fn get_view(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, view)): Json<(Option<String>, Option<String>, View)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-view",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos_for_outgoing(todos.view(view.into()))),
+    ))
+}
+
+type SnoozeArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn snooze(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, until)): Json<SnoozeArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "snooze",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.snooze(id, &until).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type UnsnoozeArgs = (Option<String>, Option<String>, Option<String>, String);
+
+async fn unsnooze(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id)): Json<UnsnoozeArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "unsnooze",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.unsnooze(id).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type AddAttachmentArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    String,
+);
+
+async fn add_attachment(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, url, label)): Json<AddAttachmentArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "add-attachment",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.add_attachment(id, &url, &label).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type RemoveAttachmentArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn remove_attachment(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, url)): Json<RemoveAttachmentArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "remove-attachment",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.remove_attachment(id, &url).err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+type SetExternalRefArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+);
+
+async fn set_external_ref(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, system, external_id)): Json<SetExternalRefArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "set-external-ref",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos
+                .set_external_ref(id, &system, external_id.as_deref())
+                .err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn find_by_external_ref(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, system, external_id)): Json<(
+        Option<String>,
+        Option<String>,
+        String,
+        String,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "find-by-external-ref",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let result = todos
+                .find_by_external_ref(&system, &external_id)
+                .err_as_string()?;
+
+            Ok(Todo::from(result))
+        },
+    ))
+}
+
+async fn diff_since(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, since)): Json<(Option<String>, Option<String>, i64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "diff-since",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(ChangeSummary::from(todos.diff_since(since))),
+    ))
+}
+
+type ApplyRemoteArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Vec<RemoteChange>,
+    i64,
+    SyncStrategy,
+);
+
+async fn apply_remote(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, changes, since, strategy)): Json<ApplyRemoteArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "apply-remote",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::for_bulk_change(changes.iter().any(RemoteChange::is_delete)),
+        |todos| {
+            let changes = changes
+                .into_iter()
+                .map(RemoteChange::into_change)
+                .collect::<AppResult<_>>()?;
+
+            let report = todos
+                .apply_remote(changes, since, strategy.into())
+                .err_as_string()?;
+
+            Ok(SyncReport::from(report))
+        },
+    ))
+}
+
+async fn export_delta(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, since)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "export-delta",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| todos.export_delta(&since).err_as_string(),
+    ))
+}
+
+type ApplyDeltaArgs = (Option<String>, Option<String>, Option<String>, Vec<u8>);
+
+async fn apply_delta(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, delta)): Json<ApplyDeltaArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "apply-delta",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Admin,
+        |todos| todos.apply_delta(&delta).err_as_string(),
+    ))
+}
+
+type AddCommentArgs = (Option<String>, Option<String>, String, String, String);
+
+async fn add_comment(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id, author, text)): Json<AddCommentArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "add-comment",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.add_comment(id, &author, &text).err_as_string()?;
+
+            Ok(Comment::from(result))
+        },
+    ))
+}
+
+async fn list_comments(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-comments",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.list_comments(id).err_as_string()?;
+
+            Ok(result.into_iter().map(Comment::from).collect::<Vec<_>>())
+        },
+    ))
+}
+
+type DeleteCommentArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn delete_comment(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, comment_id)): Json<DeleteCommentArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "delete-comment",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+            let comment_id = uuid_from(&comment_id)?;
+
+            todos.delete_comment(id, comment_id).err_as_string()
+        },
+    ))
+}
+
+type GetActivityArgs = (Option<String>, Option<String>, Option<u32>, ActivityFilter);
+
+async fn get_activity(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, limit, filter)): Json<GetActivityArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-activity",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let found = todos
+                .activity(&limit.into(), &filter.try_into()?)
+                .err_as_string()?;
+
+            Ok(found.into_iter().map(Activity::from).collect::<Vec<_>>())
+        },
+    ))
+}
+
+type WatchArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn watch(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, watcher)): Json<WatchArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "watch",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            todos.watch(id, &watcher).err_as_string()
+        },
+    ))
+}
+
+type UnwatchArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+async fn unwatch(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, id, watcher)): Json<UnwatchArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "unwatch",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            todos.unwatch(id, &watcher).err_as_string()
+        },
+    ))
+}
+
+async fn list_watchers(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, id)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-watchers",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            todos.list_watchers(id).err_as_string()
+        },
+    ))
+}
+
+async fn get_default_timezone(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-default-timezone",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.default_time_zone().to_string()),
+    ))
+}
+
+async fn set_default_timezone(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, timezone)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-default-timezone",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_default_time_zone(&timezone).err_as_string(),
+    ))
+}
+
+async fn get_escalation_policy(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-escalation-policy",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(EscalationPolicy::from(todos.escalation_policy())),
+    ))
+}
+
+async fn set_escalation_policy(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, policy)): Json<(Option<String>, Option<String>, EscalationPolicy)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-escalation-policy",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_escalation_policy(policy.into()).err_as_string(),
+    ))
+}
+
+async fn run_escalation(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor)): Json<(Option<String>, Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "run-escalation",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Editor,
+        |todos| {
+            let changed = todos.escalate().err_as_string()?;
+
+            Ok(todos_for_outgoing(changed))
+        },
+    ))
+}
+
+async fn get_retention_policy(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-retention-policy",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.retention_policy().map(RetentionPolicy::from)),
+    ))
+}
+
+async fn set_retention_policy(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, policy)): Json<(
+        Option<String>,
+        Option<String>,
+        Option<RetentionPolicy>,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-retention-policy",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            todos
+                .set_retention_policy(policy.map(Into::into))
+                .err_as_string()
+        },
+    ))
+}
+
+async fn apply_retention(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, now)): Json<(Option<String>, Option<String>, i64)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "apply-retention",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            let removed = todos.apply_retention(now).err_as_string()?;
+
+            Ok(todos_for_outgoing(removed))
+        },
+    ))
+}
+
+async fn get_strict_limits(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-strict-limits",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.strict_limits()),
+    ))
+}
+
+async fn set_strict_limits(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, strict)): Json<(Option<String>, Option<String>, bool)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-strict-limits",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_strict_limits(strict).err_as_string(),
+    ))
+}
+
+async fn get_track_views(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-track-views",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.track_views()),
+    ))
+}
+
+async fn get_settings(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-settings",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(ListSettings::from(todos.get_settings())),
+    ))
+}
+
+async fn update_settings(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, update)): Json<(Option<String>, Option<String>, SettingsUpdate)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "update-settings",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            let settings = todos.update_settings(update.into()).err_as_string()?;
+
+            Ok(ListSettings::from(settings))
+        },
+    ))
+}
+
+async fn get_title_collation(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-title-collation",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(TitleCollation::from(todos.title_collation())),
+    ))
+}
+
+async fn set_title_collation(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, collation)): Json<(
+        Option<String>,
+        Option<String>,
+        TitleCollation,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-title-collation",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_title_collation(collation.into()).err_as_string(),
+    ))
+}
+
+async fn get_warn_on_similar(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-warn-on-similar",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.warn_on_similar()),
+    ))
+}
+
+async fn set_warn_on_similar(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, warn)): Json<(Option<String>, Option<String>, bool)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-warn-on-similar",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_warn_on_similar(warn).err_as_string(),
+    ))
+}
+
+async fn set_track_views(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, track)): Json<(Option<String>, Option<String>, bool)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-track-views",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| todos.set_track_views(track).err_as_string(),
+    ))
+}
+
+async fn get_query_cache_size(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-query-cache-size",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.query_cache_size() as u32),
+    ))
+}
+
+async fn set_query_cache_size(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, size)): Json<(Option<String>, Option<String>, u32)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-query-cache-size",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            todos
+                .set_query_cache_size(usize_from(size)?)
+                .err_as_string()
+        },
+    ))
+}
+
+async fn configure_auth(
+    State(state): State<SharedState>,
+    Json((current, token)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_state(&state, "configure-auth", |state| {
+        state
+            .todos
+            .configure_auth(current.as_deref(), token.as_deref())
+            .err_as_string()
+    }))
+}
+
+async fn register_principal(
+    State(state): State<SharedState>,
+    Json((auth_token, name, token, role)): Json<(Option<String>, String, String, Role)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "register-principal",
+        auth_token,
+        None,
+        todos::Role::Admin,
+        |todos| {
+            todos
+                .register_principal(&name, &token, role.into())
+                .err_as_string()
+        },
+    ))
+}
+
+async fn remove_principal(
+    State(state): State<SharedState>,
+    Json((auth_token, name)): Json<(Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "remove-principal",
+        auth_token,
+        None,
+        todos::Role::Admin,
+        |todos| todos.remove_principal(&name).err_as_string(),
+    ))
+}
+
+async fn list_principals(
+    State(state): State<SharedState>,
+    Json((auth_token,)): Json<(Option<String>,)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-principals",
+        auth_token,
+        None,
+        todos::Role::Admin,
+        |todos| {
+            Ok(todos
+                .list_principals()
+                .into_iter()
+                .map(Principal::from)
+                .collect::<Vec<_>>())
+        },
+    ))
+}
+
+async fn is_frozen(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "is-frozen",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.is_frozen()),
+    ))
+}
+
+async fn freeze(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "freeze",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            todos.freeze();
+
+            Ok(())
+        },
+    ))
+}
+
+async fn unfreeze(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "unfreeze",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            todos.unfreeze();
+
+            Ok(())
+        },
+    ))
+}
+
+async fn get_deadline_bounds(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-deadline-bounds",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.deadline_bounds().map(DeadlineBounds::from)),
+    ))
+}
+
+async fn set_deadline_bounds(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, bounds)): Json<(
+        Option<String>,
+        Option<String>,
+        Option<DeadlineBounds>,
+    )>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "set-deadline-bounds",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            todos
+                .set_deadline_bounds(bounds.map(Into::into))
+                .err_as_string()
+        },
+    ))
+}
+
+type FindStaleArgs = (Option<String>, Option<String>, u32, Vec<Status>);
+
+async fn find_stale(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, older_than_days, statuses)): Json<FindStaleArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "find-stale",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| {
+            let statuses: Vec<_> = statuses.into_iter().map(Into::into).collect();
+
+            Ok(todos_for_outgoing(
+                todos.find_stale(older_than_days, &statuses),
+            ))
+        },
+    ))
+}
+
+type DeleteStaleArgs = (Option<String>, Option<String>, u32, Vec<Status>);
+
+async fn delete_stale(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, older_than_days, statuses)): Json<DeleteStaleArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "delete-stale",
+        auth_token,
+        namespace,
+        todos::Role::Admin,
+        |todos| {
+            let statuses: Vec<_> = statuses.into_iter().map(Into::into).collect();
+
+            let count = todos
+                .delete_stale(older_than_days, &statuses)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+type ArchiveStaleArgs = (Option<String>, Option<String>, u32, Vec<Status>);
+
+async fn archive_stale(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, older_than_days, statuses)): Json<ArchiveStaleArgs>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "archive-stale",
+        auth_token,
+        namespace,
+        todos::Role::Editor,
+        |todos| {
+            let statuses: Vec<_> = statuses.into_iter().map(Into::into).collect();
+
+            let count = todos
+                .archive_stale(older_than_days, &statuses)
+                .err_as_string()?;
+
+            u64_from(count)
+        },
+    ))
+}
+
+async fn list_archived(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "list-archived",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos_for_outgoing(todos.list_archived())),
+    ))
+}
+
+type ImportExternalArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    ImportFormat,
+    String,
+    Compression,
+    Option<String>,
+    bool,
+    MergeStrategy,
+);
+
+async fn import_external(
+    State(state): State<SharedState>,
+    Json((
+        auth_token,
+        namespace,
+        actor,
+        format,
+        payload,
+        compression,
+        expected_checksum,
+        strict,
+        merge,
+    )): Json<ImportExternalArgs>,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "import-external",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Admin,
+        |todos| {
+            let payload = codec::decode(&payload, compression.into()).err_as_string()?;
+
+            let options = todos::ImportOptions::builder()
+                .expected_checksum(expected_checksum)
+                .strict(strict)
+                .merge(merge.into())
+                .build();
+
+            let report = todos
+                .import_external(format.into(), &payload, &options)
+                .err_as_string()?;
+
+            Ok(ImportReport::from(report))
+        },
+    ))
+}
+
+type AddManyChunkedArgs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Vec<NewTodo>,
+    Option<u32>,
+    Option<u32>,
+    Option<String>,
+);
+
+async fn add_many_chunked(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, actor, items, chunk_size, chunks_per_call, resume)): Json<
+        AddManyChunkedArgs,
+    >,
+) -> Response {
+    reply(with_attributed_todos(
+        &state,
+        "add-many-chunked",
+        auth_token,
+        namespace,
+        actor,
+        todos::Role::Admin,
+        |todos| {
+            let items: Vec<todos::NewTodo> = items.into_iter().map(Into::into).collect();
+
+            let defaults = todos::ChunkOptions::default();
+
+            let options = todos::ChunkOptions::builder()
+                .chunk_size(chunk_size.map_or(defaults.chunk_size(), |n| n as usize))
+                .chunks_per_call(chunks_per_call.map_or(defaults.chunks_per_call(), |n| n as usize))
+                .resume(resume)
+                .build();
+
+            let report = todos.add_many_chunked(&items, &options).err_as_string()?;
+
+            Ok(ChunkedAddReport::from(report))
+        },
+    ))
+}
+
+async fn export_todo_txt(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, compression)): Json<(Option<String>, Option<String>, Compression)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "export-todo-txt",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| codec::encode(&todos.export_todo_txt(), compression.into()).err_as_string(),
+    ))
+}
+
+async fn extract(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace, expression)): Json<(Option<String>, Option<String>, String)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "extract",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| todos.extract(&expression).err_as_string(),
+    ))
+}
+
+async fn get_checksum(
+    State(state): State<SharedState>,
+    Json((auth_token, namespace)): Json<(Option<String>, Option<String>)>,
+) -> Response {
+    reply(with_authorized_todos(
+        &state,
+        "get-checksum",
+        auth_token,
+        namespace,
+        todos::Role::Viewer,
+        |todos| Ok(todos.state_checksum()),
+    ))
+}
+
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/api/meta", post(meta))
+        .route("/api/health", post(health))
+        .route("/api/get-metrics", post(get_metrics))
+        .route("/api/add", post(add))
+        .route("/api/add-with-warnings", post(add_with_warnings))
+        .route("/api/validate-new-todo", post(validate_new_todo))
+        .route("/api/validate", post(validate))
+        .route("/api/validate-update", post(validate_update))
+        .route("/api/update", post(update))
+        .route("/api/update-with-warnings", post(update_with_warnings))
+        .route("/api/append-to-title", post(append_to_title))
+        .route("/api/prefix-title", post(prefix_title))
+        .route("/api/shift-deadlines", post(shift_deadlines))
+        .route("/api/search", post(search))
+        .route("/api/search-with-warnings", post(search_with_warnings))
+        .route("/api/search-summaries", post(search_summaries))
+        .route("/api/search-hits", post(search_hits))
+        .route("/api/query-sql", post(query_sql))
+        .route("/api/search-with-total", post(search_with_total))
+        .route("/api/search-federated", post(search_federated))
+        .route("/api/merge-federated", post(merge_federated))
+        .route("/api/count-by", post(count_by))
+        .route("/api/count-all", post(count_all))
+        .route("/api/count-overdue", post(count_overdue))
+        .route("/api/list-all", post(list_all))
+        .route("/api/pin-view", post(pin_view))
+        .route("/api/unpin-view", post(unpin_view))
+        .route("/api/list-all-pinned", post(list_all_pinned))
+        .route("/api/search-pinned", post(search_pinned))
+        .route("/api/suggest", post(suggest))
+        .route("/api/distinct-values", post(distinct_values))
+        .route("/api/get-trend", post(get_trend))
+        .route("/api/find-duplicates", post(find_duplicates))
+        .route("/api/overdue-report", post(overdue_report))
+        .route("/api/get", post(get))
+        .route("/api/get-by-short-id", post(get_by_short_id))
+        .route("/api/fetch", post(fetch))
+        .route("/api/find-by-title", post(find_by_title))
+        .route("/api/recent", post(recent))
+        .route("/api/delete", post(delete))
+        .route("/api/delete-returning", post(delete_returning))
+        .route("/api/delete-done-items", post(delete_done_items))
+        .route("/api/delete-by-filter", post(delete_by_filter))
+        .route("/api/update-by-filter", post(update_by_filter))
+        .route("/api/delete-all", post(delete_all))
+        .route("/api/batch", post(batch))
+        .route("/api/save-query", post(save_query))
+        .route("/api/run-saved", post(run_saved))
+        .route("/api/list-saved", post(list_saved))
+        .route("/api/delete-saved", post(delete_saved))
+        .route("/api/save-template", post(save_template))
+        .route("/api/instantiate-template", post(instantiate_template))
+        .route("/api/list-templates", post(list_templates))
+        .route("/api/delete-template", post(delete_template))
+        .route("/api/get-view", post(get_view))
+        .route("/api/snooze", post(snooze))
+        .route("/api/unsnooze", post(unsnooze))
+        .route("/api/add-attachment", post(add_attachment))
+        .route("/api/remove-attachment", post(remove_attachment))
+        .route("/api/set-external-ref", post(set_external_ref))
+        .route("/api/find-by-external-ref", post(find_by_external_ref))
+        .route("/api/diff-since", post(diff_since))
+        .route("/api/apply-remote", post(apply_remote))
+        .route("/api/export-delta", post(export_delta))
+        .route("/api/apply-delta", post(apply_delta))
+        .route("/api/add-comment", post(add_comment))
+        .route("/api/list-comments", post(list_comments))
+        .route("/api/delete-comment", post(delete_comment))
+        .route("/api/get-activity", post(get_activity))
+        .route("/api/watch", post(watch))
+        .route("/api/unwatch", post(unwatch))
+        .route("/api/list-watchers", post(list_watchers))
+        .route("/api/get-default-timezone", post(get_default_timezone))
+        .route("/api/set-default-timezone", post(set_default_timezone))
+        .route("/api/get-escalation-policy", post(get_escalation_policy))
+        .route("/api/set-escalation-policy", post(set_escalation_policy))
+        .route("/api/run-escalation", post(run_escalation))
+        .route("/api/get-retention-policy", post(get_retention_policy))
+        .route("/api/set-retention-policy", post(set_retention_policy))
+        .route("/api/apply-retention", post(apply_retention))
+        .route("/api/get-strict-limits", post(get_strict_limits))
+        .route("/api/set-strict-limits", post(set_strict_limits))
+        .route("/api/get-track-views", post(get_track_views))
+        .route("/api/set-track-views", post(set_track_views))
+        .route("/api/get-query-cache-size", post(get_query_cache_size))
+        .route("/api/set-query-cache-size", post(set_query_cache_size))
+        .route("/api/get-settings", post(get_settings))
+        .route("/api/update-settings", post(update_settings))
+        .route("/api/get-title-collation", post(get_title_collation))
+        .route("/api/set-title-collation", post(set_title_collation))
+        .route("/api/get-warn-on-similar", post(get_warn_on_similar))
+        .route("/api/set-warn-on-similar", post(set_warn_on_similar))
+        .route("/api/configure-auth", post(configure_auth))
+        .route("/api/register-principal", post(register_principal))
+        .route("/api/remove-principal", post(remove_principal))
+        .route("/api/list-principals", post(list_principals))
+        .route("/api/is-frozen", post(is_frozen))
+        .route("/api/freeze", post(freeze))
+        .route("/api/unfreeze", post(unfreeze))
+        .route("/api/get-deadline-bounds", post(get_deadline_bounds))
+        .route("/api/set-deadline-bounds", post(set_deadline_bounds))
+        .route("/api/find-stale", post(find_stale))
+        .route("/api/delete-stale", post(delete_stale))
+        .route("/api/archive-stale", post(archive_stale))
+        .route("/api/list-archived", post(list_archived))
+        .route("/api/import-external", post(import_external))
+        .route("/api/add-many-chunked", post(add_many_chunked))
+        .route("/api/export-todo-txt", post(export_todo_txt))
+        .route("/api/extract", post(extract))
+        .route("/api/get-checksum", post(get_checksum))
+        .with_state(state)
+}
+
+#[tokio::main]
+async fn main() {
+    let addr: SocketAddr = env::var("TODOS_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.into())
+        .parse()
+        .expect("TODOS_ADDR should be a socket address such as 127.0.0.1:3000");
+
+    #[allow(unused_mut)]
+    let mut todos = new_todo_list();
+
+    #[cfg(feature = "persistence")]
+    if let Ok(dir) = env::var("TODOS_DATA_DIR") {
+        let encoding = match env::var("TODOS_SNAPSHOT_ENCODING").as_deref() {
+            Ok("cbor") => todos::SnapshotEncoding::Cbor,
+            Ok("msgpack") => todos::SnapshotEncoding::MessagePack,
+            Ok("json") | Err(_) => todos::SnapshotEncoding::Json,
+            Ok(other) => {
+                panic!("TODOS_SNAPSHOT_ENCODING should be json, cbor or msgpack, not {other}")
+            }
+        };
+
+        let log = todos::FileLog::open_with_encoding(&dir, encoding)
+            .expect("TODOS_DATA_DIR should be a directory the server can write to");
+
+        todos
+            .recover(log, COMPACT_EVERY)
+            .expect("the operation log in TODOS_DATA_DIR should be readable");
+    }
+
+    let state = Arc::new(Mutex::new(AppState {
+        todos,
+        namespaces: HashMap::new(),
+        metrics: Metrics::default(),
+    }));
+
+    println!("Serving the todo list API on http://{addr}/api");
+
+    axum::Server::bind(&addr)
+        .serve(router(state).into_make_service())
+        .await
+        .expect("the server should keep running");
+}
@@ -1,16 +1,18 @@
 use bindings::{export, exports::golem::todos::api::*};
 use lib::{
     app_error::AppResultExt,
-    core::{u64_from, uuid_from, AppResult},
-    todos::{self, Title, TodoList},
+    codec,
+    core::{enabled_features, u64_from, usize_from, uuid_from, AppResult},
+    metrics::Metrics,
+    todos::{self, Color, Icon, OptionalDeadlineInput, Title, TodoList},
 };
 use once_cell::sync::Lazy;
 use paste::paste;
-use std::mem;
+use std::{collections::HashMap, mem, time::Instant};
 
 const COMPONENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const SCHEMA_VERSION: u64 = 1;
+const SCHEMA_VERSION: u64 = 2;
 
 /*
  Unfortunately, I cannot implement the `From` trait because I own neither the
@@ -62,147 +64,2835 @@ macro_rules! convert_enum_both_ways {
 
 convert_enum_both_ways!(Priority, todos);
 convert_enum_both_ways!(Status, todos);
+convert_enum_both_ways!(Role, todos);
+convert_enum_both_ways!(SearchField, todos);
+convert_enum_both_ways!(TitleCollation, todos);
 
+convert_enum_from_incoming!(Availability, todos);
+convert_enum_from_incoming!(Compression, codec);
+convert_enum_from_incoming!(DistinctField, todos);
+convert_enum_from_incoming!(ImportFormat, todos);
+convert_enum_from_incoming!(MergeStrategy, todos);
 convert_enum_from_incoming!(QuerySort, todos);
+convert_enum_from_incoming!(RecentKind, todos);
+convert_enum_from_incoming!(SortDirection, todos);
+convert_enum_from_incoming!(SyncStrategy, todos);
+convert_enum_from_incoming!(TodoField, todos);
+convert_enum_from_incoming!(TrendBucket, todos);
+convert_enum_from_incoming!(View, todos);
+
+convert_enum_for_outgoing!(Lateness, todos);
 
 fn new_todo_from_incoming(item: NewTodo) -> todos::NewTodo {
     todos::NewTodo::builder()
         .title(item.title.into())
-        .priority(priority_from_incoming(item.priority))
-        .deadline(item.deadline.into())
+        .priority(item.priority.map(priority_from_incoming))
+        .deadline(
+            OptionalDeadlineInput::new(item.deadline, item.timezone.clone())
+                .ignoring_bounds(item.ignore_deadline_bounds.unwrap_or_default()),
+        )
+        .start_date(
+            OptionalDeadlineInput::new(item.start_date, item.timezone)
+                .ignoring_bounds(item.ignore_deadline_bounds.unwrap_or_default()),
+        )
+        .allow_past_deadline(item.allow_past_deadline.unwrap_or_default())
+        .color(item.color.map(Color::new))
+        .icon(item.icon.map(Icon::new))
+        .build()
+}
+
+fn template_overrides_from_incoming(overrides: TemplateOverrides) -> todos::TemplateOverrides {
+    let TemplateOverrides {
+        title,
+        priority,
+        deadline,
+        timezone,
+        start_date,
+    } = overrides;
+
+    todos::TemplateOverrides::builder()
+        .title(title.map(Title::new))
+        .priority(priority.map(priority_from_incoming))
+        .deadline(deadline.map(|d| OptionalDeadlineInput::new(Some(d), timezone.clone())))
+        .start_date(start_date.map(|d| OptionalDeadlineInput::new(Some(d), timezone)))
         .build()
 }
 
+fn deadline_change_from_incoming(change: DeadlineChange) -> todos::DeadlineChange {
+    match change {
+        DeadlineChange::Keep => todos::DeadlineChange::Keep,
+        DeadlineChange::Clear => todos::DeadlineChange::Clear,
+        DeadlineChange::Set(input) => todos::DeadlineChange::Set(
+            OptionalDeadlineInput::new(Some(input.deadline), input.timezone)
+                .ignoring_bounds(input.ignore_bounds.unwrap_or_default()),
+        ),
+    }
+}
+
 fn update_todo_from_incoming(item: UpdateTodo) -> todos::UpdateTodo {
     todos::UpdateTodo::builder()
         .title(item.title.map(Title::new))
         .priority(item.priority.map(priority_from_incoming))
         .status(item.status.map(status_from_incoming))
-        .deadline(item.deadline.into())
+        .deadline(deadline_change_from_incoming(item.deadline))
+        .start_date(deadline_change_from_incoming(item.start_date))
+        .color(item.color.map(Color::new))
+        .icon(item.icon.map(Icon::new))
         .build()
 }
 
-fn query_from_incoming(query: Query) -> todos::Query {
-    todos::Query::builder()
+fn batch_command_from_incoming(command: BatchCommand) -> AppResult<todos::BatchCommand> {
+    let command = match command {
+        BatchCommand::Add(item) => todos::BatchCommand::Add(new_todo_from_incoming(item)),
+        BatchCommand::Update(BatchUpdate { id, change }) => {
+            todos::BatchCommand::Update(uuid_from(&id)?, update_todo_from_incoming(change))
+        }
+        BatchCommand::Delete(id) => todos::BatchCommand::Delete(uuid_from(&id)?),
+    };
+
+    Ok(command)
+}
+
+fn remote_change_from_incoming(change: RemoteChange) -> AppResult<todos::RemoteChange> {
+    let change = match change {
+        RemoteChange::Upsert(t) => todos::RemoteChange::Upsert(
+            todos::RemoteTodo::builder()
+                .id(uuid_from(&t.id)?)
+                .title(Title::new(t.title))
+                .priority(priority_from_incoming(t.priority))
+                .status(status_from_incoming(t.status))
+                .deadline(t.deadline)
+                .updated_timestamp(t.updated_timestamp)
+                .build(),
+        ),
+        RemoteChange::Delete(id) => todos::RemoteChange::Delete(uuid_from(&id)?),
+    };
+
+    Ok(change)
+}
+
+fn field_predicate_from_incoming(predicate: FieldPredicate) -> todos::FieldPredicate {
+    match predicate {
+        FieldPredicate::Keyword(keyword) => todos::FieldPredicate::Keyword(keyword),
+        FieldPredicate::Priority(p) => todos::FieldPredicate::Priority(priority_from_incoming(p)),
+        FieldPredicate::Status(s) => todos::FieldPredicate::Status(status_from_incoming(s)),
+        FieldPredicate::Overdue => todos::FieldPredicate::Overdue,
+        FieldPredicate::HasDeadline => todos::FieldPredicate::HasDeadline,
+    }
+}
+
+fn filter_expr_from_incoming(expr: FilterExpr) -> AppResult<todos::FilterExpr> {
+    let nodes = expr
+        .nodes
+        .into_iter()
+        .map(|node| match node {
+            FilterNode::And(children) => todos::FilterNode::And(children),
+            FilterNode::Or(children) => todos::FilterNode::Or(children),
+            FilterNode::Not(child) => todos::FilterNode::Not(child),
+            FilterNode::Field(predicate) => {
+                todos::FilterNode::Field(field_predicate_from_incoming(predicate))
+            }
+        })
+        .collect();
+
+    todos::FilterExpr::from_nodes(nodes).err_as_string()
+}
+
+fn query_from_incoming(query: Query) -> AppResult<todos::Query> {
+    let query = todos::Query::builder()
         .keyword(query.keyword)
+        .title_regex(query.title_regex)
         .priority(query.priority.map(priority_from_incoming))
         .status(query.status.map(status_from_incoming))
-        .deadline(query.deadline.into())
+        .not_keyword(query.not_keyword)
+        .plain_text_match(query.plain_text_match.unwrap_or_default())
+        .search_fields(
+            query
+                .search_fields
+                .unwrap_or_default()
+                .into_iter()
+                .map(searchfield_from_incoming)
+                .collect(),
+        )
+        .exclude_priorities(priorities_from_incoming(
+            query.exclude_priorities.unwrap_or_default(),
+        ))
+        .exclude_statuses(statuses_from_incoming(
+            query.exclude_statuses.unwrap_or_default(),
+        ))
+        .deadline(OptionalDeadlineInput::new(query.deadline, query.timezone))
+        .availability(query.availability.map(availability_from_incoming))
+        .include_snoozed(query.include_snoozed.unwrap_or_default())
         .sort(query.sort.map(querysort_from_incoming))
+        .direction(
+            query
+                .direction
+                .map(sortdirection_from_incoming)
+                .unwrap_or_default(),
+        )
+        .expr(query.expr.map(filter_expr_from_incoming).transpose()?)
         .limit(query.limit.into())
-        .build()
+        .projection(
+            query
+                .projection
+                .unwrap_or_default()
+                .into_iter()
+                .map(todofield_from_incoming)
+                .collect(),
+        )
+        .after(query.after)
+        .build();
+
+    Ok(query)
 }
 
-fn filter_from_incoming(filter: Filter) -> todos::Query {
-    todos::Query::builder()
+fn filter_from_incoming(filter: Filter) -> AppResult<todos::Query> {
+    let filter = todos::Query::builder()
         .keyword(filter.keyword)
         .priority(filter.priority.map(priority_from_incoming))
         .status(filter.status.map(status_from_incoming))
-        .deadline(filter.deadline.into())
-        .build()
+        .not_keyword(filter.not_keyword)
+        .exclude_priorities(priorities_from_incoming(
+            filter.exclude_priorities.unwrap_or_default(),
+        ))
+        .exclude_statuses(statuses_from_incoming(
+            filter.exclude_statuses.unwrap_or_default(),
+        ))
+        .deadline(OptionalDeadlineInput::new(filter.deadline, filter.timezone))
+        .availability(filter.availability.map(availability_from_incoming))
+        .include_snoozed(filter.include_snoozed.unwrap_or_default())
+        .expr(filter.expr.map(filter_expr_from_incoming).transpose()?)
+        .build();
+
+    Ok(filter)
+}
+
+fn batch_result_for_outgoing(result: todos::BatchResult) -> BatchResult {
+    match result {
+        todos::BatchResult::Added(t) => BatchResult::Added(todo_for_outgoing(t)),
+        todos::BatchResult::Updated(t) => BatchResult::Updated(todo_for_outgoing(t)),
+        todos::BatchResult::Deleted(t) => BatchResult::Deleted(todo_for_outgoing(t)),
+    }
+}
+
+fn field_value_for_outgoing(value: todos::FieldValue) -> FieldValue {
+    match value {
+        todos::FieldValue::Status(s) => FieldValue::Status(status_for_outgoing(s)),
+        todos::FieldValue::Priority(p) => FieldValue::Priority(priority_for_outgoing(p)),
+    }
+}
+
+fn statuses_from_incoming(statuses: Vec<Status>) -> Vec<todos::Status> {
+    statuses.into_iter().map(status_from_incoming).collect()
+}
+
+fn priorities_from_incoming(priorities: Vec<Priority>) -> Vec<todos::Priority> {
+    priorities.into_iter().map(priority_from_incoming).collect()
+}
+
+fn todo_summary_for_outgoing(t: todos::TodoSummary) -> TodoSummary {
+    TodoSummary {
+        id: t.id().to_string(),
+        title: t.title().clone(),
+        priority: t.priority().map(priority_for_outgoing),
+        status: t.status().map(status_for_outgoing),
+        deadline: t.deadline(),
+    }
 }
 
 fn todo_for_outgoing(t: todos::Todo) -> Todo {
     Todo {
         id: t.id().to_string(),
+        short_id: t.short_id(),
         title: t.title().into(),
         priority: priority_for_outgoing(t.priority()),
         deadline: t.deadline(),
+        deadline_local: t.deadline_local(),
+        start_date: t.start_date(),
+        snoozed_until: t.snoozed_until(),
         status: status_for_outgoing(t.status()),
         created_timestamp: t.created_timestamp(),
         updated_timestamp: t.updated_timestamp(),
+        completed_timestamp: t.completed_timestamp(),
+        color: t.color().clone(),
+        icon: t.icon().clone(),
+        attachments: t
+            .attachments()
+            .iter()
+            .map(|a| AttachmentRef {
+                url: a.url().clone(),
+                label: a.label().clone(),
+            })
+            .collect(),
+        external_refs: t
+            .external_refs()
+            .iter()
+            .map(|(system, external_id)| ExternalRef {
+                system: system.clone(),
+                external_id: external_id.clone(),
+            })
+            .collect(),
+        last_modified_by: t.last_modified_by().clone(),
+    }
+}
+
+fn search_result_for_outgoing(found: todos::SearchResult) -> AppResult<SearchResult> {
+    Ok(SearchResult {
+        items: found
+            .items()
+            .iter()
+            .cloned()
+            .map(todo_for_outgoing)
+            .collect(),
+        total_matched: u64_from(found.total_matched())?,
+        next_cursor: found.next_cursor().clone(),
+    })
+}
+
+fn search_hit_for_outgoing(hit: todos::SearchHit) -> SearchHit {
+    SearchHit {
+        item: todo_for_outgoing(hit.todo().clone()),
+        field: hit.field().map(searchfield_for_outgoing),
+        score: hit.score(),
+    }
+}
+
+fn escalation_policy_from_incoming(policy: EscalationPolicy) -> todos::EscalationPolicy {
+    todos::EscalationPolicy::builder()
+        .deadline_within_hours(policy.deadline_within_hours)
+        .backlog_after_days(policy.backlog_after_days)
+        .build()
+}
+
+fn escalation_policy_for_outgoing(policy: todos::EscalationPolicy) -> EscalationPolicy {
+    EscalationPolicy {
+        deadline_within_hours: policy.deadline_within_hours(),
+        backlog_after_days: policy.backlog_after_days(),
+    }
+}
+
+fn retention_policy_from_incoming(policy: RetentionPolicy) -> todos::RetentionPolicy {
+    todos::RetentionPolicy::builder()
+        .older_than_days(policy.older_than_days)
+        .statuses(statuses_from_incoming(policy.statuses))
+        .build()
+}
+
+fn retention_policy_for_outgoing(policy: &todos::RetentionPolicy) -> RetentionPolicy {
+    RetentionPolicy {
+        older_than_days: policy.older_than_days(),
+        statuses: policy
+            .statuses()
+            .iter()
+            .copied()
+            .map(status_for_outgoing)
+            .collect(),
     }
 }
 
-struct AppState(TodoList);
+fn deadline_bounds_from_incoming(bounds: DeadlineBounds) -> todos::DeadlineBounds {
+    todos::DeadlineBounds::builder()
+        .max_days_past(bounds.max_days_past)
+        .max_years_ahead(bounds.max_years_ahead)
+        .build()
+}
 
-static mut APP_STATE: Lazy<AppState> = Lazy::new(|| AppState(TodoList::new()));
+fn deadline_bounds_for_outgoing(bounds: todos::DeadlineBounds) -> DeadlineBounds {
+    DeadlineBounds {
+        max_days_past: bounds.max_days_past(),
+        max_years_ahead: bounds.max_years_ahead(),
+    }
+}
 
-fn with_app_state<T>(f: impl FnOnce(&mut AppState) -> T) -> T {
-    unsafe { f(&mut APP_STATE) }
+fn creation_defaults_from_incoming(defaults: CreationDefaults) -> todos::CreationDefaults {
+    todos::CreationDefaults::builder()
+        .priority(priority_from_incoming(defaults.priority))
+        .deadline_after_days(defaults.deadline_after_days)
+        .build()
+}
+
+fn creation_defaults_for_outgoing(defaults: todos::CreationDefaults) -> CreationDefaults {
+    CreationDefaults {
+        priority: priority_for_outgoing(defaults.priority()),
+        deadline_after_days: defaults.deadline_after_days(),
+    }
+}
+
+fn settings_update_from_incoming(update: SettingsUpdate) -> todos::SettingsUpdate {
+    todos::SettingsUpdate::builder()
+        .version(update.version)
+        .default_time_zone(update.default_timezone)
+        .escalation_policy(
+            update
+                .escalation_policy
+                .map(escalation_policy_from_incoming),
+        )
+        .retention_policy(
+            update
+                .retention_policy
+                .map(|p| p.map(retention_policy_from_incoming)),
+        )
+        .deadline_bounds(
+            update
+                .deadline_bounds
+                .map(|b| b.map(deadline_bounds_from_incoming)),
+        )
+        .strict_limits(update.strict_limits)
+        .track_views(update.track_views)
+        .warn_on_similar(update.warn_on_similar)
+        .title_collation(update.title_collation.map(titlecollation_from_incoming))
+        .creation_defaults(
+            update
+                .creation_defaults
+                .map(creation_defaults_from_incoming),
+        )
+        .query_cache_size(update.query_cache_size.map(|size| size as usize))
+        .build()
+}
+
+fn list_settings_for_outgoing(settings: todos::ListSettings) -> ListSettings {
+    ListSettings {
+        version: todos::ListSettings::VERSION,
+        default_timezone: settings.default_time_zone().to_string(),
+        escalation_policy: escalation_policy_for_outgoing(settings.escalation_policy()),
+        retention_policy: settings
+            .retention_policy()
+            .as_ref()
+            .map(retention_policy_for_outgoing),
+        deadline_bounds: settings.deadline_bounds().map(deadline_bounds_for_outgoing),
+        strict_limits: settings.strict_limits(),
+        track_views: settings.track_views(),
+        warn_on_similar: settings.warn_on_similar(),
+        title_collation: titlecollation_for_outgoing(settings.title_collation()),
+        creation_defaults: creation_defaults_for_outgoing(settings.creation_defaults()),
+        query_cache_size: settings.query_cache_size() as u32,
+    }
+}
+
+fn ids_for_outgoing(ids: &[impl ToString]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+fn comment_for_outgoing(c: todos::Comment) -> Comment {
+    Comment {
+        id: c.id().to_string(),
+        author: c.author().clone(),
+        text: c.text().clone(),
+        created_timestamp: c.created_timestamp(),
+    }
+}
+
+fn activity_for_outgoing(a: todos::Activity) -> Activity {
+    let kind = match a.kind() {
+        todos::ActivityKind::Created => ActivityKind::Created,
+        todos::ActivityKind::StatusChanged { before, after } => {
+            ActivityKind::StatusChanged(StatusChange {
+                before: status_for_outgoing(before),
+                after: status_for_outgoing(after),
+            })
+        }
+        todos::ActivityKind::CommentAdded { comment_id } => {
+            ActivityKind::CommentAdded(comment_id.to_string())
+        }
+        todos::ActivityKind::DeadlineMoved { before, after } => {
+            ActivityKind::DeadlineMoved(DeadlineMove { before, after })
+        }
+    };
+
+    Activity {
+        todo_id: a.todo_id().to_string(),
+        timestamp: a.timestamp(),
+        actor: a.actor().clone(),
+        kind,
+    }
+}
+
+fn todo_detail_for_outgoing(detail: todos::TodoDetail) -> TodoDetail {
+    TodoDetail {
+        todo: todo_for_outgoing(detail.todo().clone()),
+        comments: detail
+            .comments()
+            .clone()
+            .map(|found| found.into_iter().map(comment_for_outgoing).collect()),
+        watchers: detail.watchers().clone(),
+        activity: detail
+            .activity()
+            .clone()
+            .map(|found| found.into_iter().map(activity_for_outgoing).collect()),
+    }
+}
+
+fn activity_filter_from_incoming(filter: ActivityFilter) -> AppResult<todos::ActivityFilter> {
+    Ok(todos::ActivityFilter::builder()
+        .todo_id(filter.todo_id.as_deref().map(uuid_from).transpose()?)
+        .since(filter.since)
+        .build())
+}
+
+fn add_outcome_for_outgoing(outcome: todos::AddOutcome) -> AddOutcome {
+    match outcome {
+        todos::AddOutcome::Added(t) => AddOutcome::Added(todo_for_outgoing(*t)),
+        todos::AddOutcome::Similar(found) => {
+            AddOutcome::Similar(found.into_iter().map(todo_for_outgoing).collect())
+        }
+    }
+}
+
+fn warning_for_outgoing(w: todos::Warning) -> Warning {
+    match w {
+        todos::Warning::TitleTrimmed => Warning::TitleTrimmed,
+        todos::Warning::LimitClamped { given, used } => {
+            Warning::LimitClamped(LimitClamp { given, used })
+        }
+        todos::Warning::DefaultTimeZoneUsed(time_zone) => {
+            Warning::DefaultTimezoneUsed(time_zone.to_string())
+        }
+    }
+}
+
+// The default list, which also holds the worker's auth, and the
+// namespaced lists, made on first use.
+struct AppState {
+    todos: TodoList,
+
+    namespaces: HashMap<String, TodoList>,
+}
+
+impl AppState {
+    fn list(&mut self, namespace: Option<&str>) -> &mut TodoList {
+        match namespace.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(namespace) => self
+                .namespaces
+                .entry(namespace.to_string())
+                .or_insert_with(new_todo_list),
+            None => &mut self.todos,
+        }
+    }
+}
+
+fn new_todo_list() -> TodoList {
+    TodoList::new_with(todos::TimeOrderedIdGenerator::default())
+}
+
+static mut APP_STATE: Lazy<AppState> = Lazy::new(|| {
+    #[cfg(feature = "tracing")]
+    init_tracing();
+
+    AppState {
+        todos: new_todo_list(),
+        namespaces: HashMap::new(),
+    }
+});
+
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    // Timestamps are left to the host, which records them for stderr anyway.
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .without_time()
+        .try_init();
+}
+
+static mut METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+// `operation` is the WIT function name, counted for `health` and labelled in `get-metrics`.
+fn with_app_state<T: Observed>(operation: &str, f: impl FnOnce(&mut AppState) -> T) -> T {
+    unsafe {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("operation", name = operation).entered();
+
+        APP_STATE.todos.record_op(operation);
+
+        let started = Instant::now();
+        let result = f(&mut APP_STATE);
+
+        METRICS.observe(
+            operation,
+            started.elapsed(),
+            result.result_size(),
+            result.failed(),
+        );
+
+        result
+    }
+}
+
+// Every call but meta, health and metrics runs through here, so a list
+// with a shared secret set rejects callers whose token lacks `required`.
+// The token is checked against the default list; `f` gets the
+// namespace's.
+fn with_authorized_state<T: Observed>(
+    operation: &str,
+    auth_token: Option<String>,
+    namespace: Option<String>,
+    required: todos::Role,
+    f: impl FnOnce(&mut TodoList) -> AppResult<T>,
+) -> AppResult<T> {
+    with_app_state(operation, |state| {
+        state
+            .todos
+            .authorize(auth_token.as_deref(), required)
+            .err_as_string()?;
+
+        f(state.list(namespace.as_deref()))
+    })
+}
+
+// Attributes the changes `f` makes to `actor`, when one is given.
+fn with_attributed_state<T: Observed>(
+    operation: &str,
+    auth_token: Option<String>,
+    namespace: Option<String>,
+    actor: Option<String>,
+    required: todos::Role,
+    f: impl FnOnce(&mut TodoList) -> AppResult<T>,
+) -> AppResult<T> {
+    with_authorized_state(operation, auth_token, namespace, required, |todos| {
+        todos.set_actor(actor.as_deref()).err_as_string()?;
+
+        let result = f(todos);
+
+        todos.clear_actor();
+
+        result
+    })
+}
+
+// What the metrics need to know about an operation's return value.
+trait Observed {
+    fn result_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn failed(&self) -> bool {
+        false
+    }
+}
+
+impl<T: Observed> Observed for AppResult<T> {
+    fn result_size(&self) -> Option<usize> {
+        self.as_ref().ok().and_then(Observed::result_size)
+    }
+
+    fn failed(&self) -> bool {
+        self.is_err()
+    }
+}
+
+impl<T> Observed for Vec<T> {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl Observed for SearchResult {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<T> Observed for Option<T> {}
+impl Observed for () {}
+impl Observed for bool {}
+impl Observed for u32 {}
+impl Observed for u64 {}
+impl Observed for String {}
+impl Observed for Todo {}
+impl Observed for AddOutcome {}
+impl Observed for CheckedAdd {}
+impl Observed for CheckedTodo {}
+impl Observed for TodoDetail {}
+
+impl Observed for CheckedSearch {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+impl Observed for TitleCollation {}
+impl Observed for ListSettings {}
+impl Observed for Comment {}
+impl Observed for Principal {}
+impl Observed for EscalationPolicy {}
+impl Observed for Health {}
+
+impl Observed for OverdueReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.worst.len())
+    }
+}
+
+impl Observed for ChangeSummary {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.created.len() + self.updated.len() + self.deleted.len())
+    }
+}
+
+impl Observed for SyncReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.applied.len())
+    }
+}
+
+impl Observed for ChunkedAddReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.added.len())
+    }
+}
+
+impl Observed for ImportReport {
+    fn result_size(&self) -> Option<usize> {
+        Some(self.imported.len())
+    }
 }
 
 struct Todos;
 
 impl Api for Todos {
-    fn add(item: NewTodo) -> AppResult<Todo> {
-        with_app_state(|AppState(todos)| {
-            let result = todos.add(&new_todo_from_incoming(item)).err_as_string()?;
+    fn add(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        item: NewTodo,
+    ) -> AppResult<AddOutcome> {
+        with_attributed_state(
+            "add",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let result = todos
+                    .add_or_warn(&new_todo_from_incoming(item))
+                    .err_as_string()?;
 
-            Ok(todo_for_outgoing(result))
-        })
+                Ok(add_outcome_for_outgoing(result))
+            },
+        )
     }
 
-    fn update(id: String, change: UpdateTodo) -> AppResult<Todo> {
-        with_app_state(|AppState(todos)| {
-            let id = uuid_from(&id)?;
+    fn add_with_warnings(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        item: NewTodo,
+    ) -> AppResult<CheckedAdd> {
+        with_attributed_state(
+            "add-with-warnings",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let (outcome, warnings) = todos
+                    .add_with_warnings(&new_todo_from_incoming(item))
+                    .err_as_string()?
+                    .into_parts();
 
-            let result = todos
-                .update(id, &update_todo_from_incoming(change))
-                .err_as_string()?;
+                Ok(CheckedAdd {
+                    outcome: add_outcome_for_outgoing(outcome),
+                    warnings: warnings.into_iter().map(warning_for_outgoing).collect(),
+                })
+            },
+        )
+    }
 
-            Ok(todo_for_outgoing(result))
-        })
+    fn validate_new_todo(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        item: NewTodo,
+    ) -> AppResult<CheckedTodo> {
+        with_authorized_state(
+            "validate-new-todo",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let (todo, warnings) = todos
+                    .validate_new_todo(&new_todo_from_incoming(item))
+                    .err_as_string()?
+                    .into_parts();
+
+                Ok(CheckedTodo {
+                    todo: todo_for_outgoing(todo),
+                    warnings: warnings.into_iter().map(warning_for_outgoing).collect(),
+                })
+            },
+        )
     }
 
-    fn search(query: Query) -> AppResult<Vec<Todo>> {
-        with_app_state(|AppState(todos)| {
-            let found = todos.search(&query_from_incoming(query)).err_as_string()?;
+    fn validate(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        item: NewTodo,
+    ) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "validate",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.validate(&new_todo_from_incoming(item))),
+        )
+    }
 
-            let result = found.into_iter().map(todo_for_outgoing).collect();
+    fn validate_update(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+        change: UpdateTodo,
+    ) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "validate-update",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
 
-            Ok(result)
-        })
+                Ok(todos.validate_update(id, &update_todo_from_incoming(change)))
+            },
+        )
     }
 
-    fn count_by(filter: Filter) -> AppResult<u64> {
-        with_app_state(|AppState(todos)| {
-            let count = todos
-                .count_by(&filter_from_incoming(filter))
-                .err_as_string()?;
+    fn update(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        change: UpdateTodo,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "update",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
 
-            u64_from(count)
-        })
+                let result = todos
+                    .update(id, &update_todo_from_incoming(change))
+                    .err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn update_with_warnings(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        change: UpdateTodo,
+    ) -> AppResult<CheckedTodo> {
+        with_attributed_state(
+            "update-with-warnings",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let (todo, warnings) = todos
+                    .update_with_warnings(id, &update_todo_from_incoming(change))
+                    .err_as_string()?
+                    .into_parts();
+
+                Ok(CheckedTodo {
+                    todo: todo_for_outgoing(todo),
+                    warnings: warnings.into_iter().map(warning_for_outgoing).collect(),
+                })
+            },
+        )
+    }
+
+    fn append_to_title(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        suffix: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "append-to-title",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.append_to_title(id, &suffix).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn prefix_title(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        prefix: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "prefix-title",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.prefix_title(id, &prefix).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn shift_deadlines(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        query: Query,
+        delta_hours: i64,
+    ) -> AppResult<u64> {
+        with_attributed_state(
+            "shift-deadlines",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let count = todos
+                    .shift_deadlines(&query_from_incoming(query)?, delta_hours)
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn search(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "search",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .search_cached(&query_from_incoming(query)?)
+                    .err_as_string()?;
+
+                let result = found
+                    .items()
+                    .iter()
+                    .cloned()
+                    .map(todo_for_outgoing)
+                    .collect();
+
+                Ok(result)
+            },
+        )
+    }
+
+    fn search_with_warnings(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<CheckedSearch> {
+        with_authorized_state(
+            "search-with-warnings",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let (found, warnings) = todos
+                    .search_with_warnings(&query_from_incoming(query)?)
+                    .err_as_string()?
+                    .into_parts();
+
+                Ok(CheckedSearch {
+                    items: found.into_iter().map(todo_for_outgoing).collect(),
+                    warnings: warnings.into_iter().map(warning_for_outgoing).collect(),
+                })
+            },
+        )
+    }
+
+    fn search_summaries(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<Vec<TodoSummary>> {
+        with_authorized_state(
+            "search-summaries",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .search_projected(&query_from_incoming(query)?)
+                    .err_as_string()?;
+
+                let result = found.into_iter().map(todo_summary_for_outgoing).collect();
+
+                Ok(result)
+            },
+        )
+    }
+
+    fn search_hits(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<Vec<SearchHit>> {
+        with_authorized_state(
+            "search-hits",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .search_hits(&query_from_incoming(query)?)
+                    .err_as_string()?;
+
+                Ok(found.into_iter().map(search_hit_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn query_sql(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        text: String,
+    ) -> AppResult<Vec<TodoSummary>> {
+        with_authorized_state(
+            "query-sql",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos.query_sql(&text).err_as_string()?;
+
+                let result = found.into_iter().map(todo_summary_for_outgoing).collect();
+
+                Ok(result)
+            },
+        )
+    }
+
+    fn search_with_total(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<SearchResult> {
+        with_authorized_state(
+            "search-with-total",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .search_cached(&query_from_incoming(query)?)
+                    .err_as_string()?;
+
+                search_result_for_outgoing(found)
+            },
+        )
+    }
+
+    fn search_federated(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+    ) -> AppResult<String> {
+        with_authorized_state(
+            "search-federated",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                todos
+                    .search_federated(&query_from_incoming(query)?)
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn merge_federated(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        query: Query,
+        result_sets: Vec<String>,
+    ) -> AppResult<SearchResult> {
+        with_authorized_state(
+            "merge-federated",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let merged = todos
+                    .merge_federated(&query_from_incoming(query)?, &result_sets)
+                    .err_as_string()?;
+
+                search_result_for_outgoing(merged)
+            },
+        )
+    }
+
+    fn count_by(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        filter: Filter,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "count-by",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let count = todos
+                    .count_by(&filter_from_incoming(filter)?)
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn count_all(auth_token: Option<String>, namespace: Option<String>) -> AppResult<u64> {
+        with_authorized_state(
+            "count-all",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| u64_from(todos.count_all()),
+        )
+    }
+
+    fn count_overdue(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        now: i64,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "count-overdue",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| u64_from(todos.count_overdue(now)),
+        )
+    }
+
+    fn list_all(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "list-all",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let page = todos
+                    .list_page(usize_from(offset)?, &limit.into())
+                    .err_as_string()?;
+
+                Ok(page.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn pin_view(auth_token: Option<String>, namespace: Option<String>) -> AppResult<u64> {
+        with_authorized_state(
+            "pin-view",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| Ok(todos.pin_view()),
+        )
     }
 
-    fn count_all() -> AppResult<u64> {
-        with_app_state(|AppState(todos)| u64_from(todos.count_all()))
+    fn unpin_view(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        pin: u64,
+    ) -> AppResult<bool> {
+        with_authorized_state(
+            "unpin-view",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| Ok(todos.unpin_view(pin)),
+        )
     }
 
-    fn get(id: String) -> AppResult<Todo> {
-        with_app_state(|AppState(todos)| {
-            let id = uuid_from(&id)?;
+    fn list_all_pinned(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        pin: u64,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "list-all-pinned",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let page = todos
+                    .list_page_pinned(pin, usize_from(offset)?, &limit.into())
+                    .err_as_string()?;
+
+                Ok(page.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn search_pinned(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        pin: u64,
+        query: Query,
+    ) -> AppResult<SearchResult> {
+        with_authorized_state(
+            "search-pinned",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .search_pinned(pin, &query_from_incoming(query)?)
+                    .err_as_string()?;
+
+                search_result_for_outgoing(found)
+            },
+        )
+    }
+
+    fn suggest(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        prefix: String,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "suggest",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| todos.suggest_titles(&prefix, &limit.into()).err_as_string(),
+        )
+    }
+
+    fn distinct_values(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        field: DistinctField,
+    ) -> AppResult<Vec<DistinctValue>> {
+        with_authorized_state(
+            "distinct-values",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                todos
+                    .distinct(distinctfield_from_incoming(field))
+                    .into_iter()
+                    .map(|(value, count)| {
+                        Ok(DistinctValue {
+                            value: field_value_for_outgoing(value),
+                            count: u64_from(count)?,
+                        })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn get_trend(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        bucket: TrendBucket,
+        from: i64,
+        to: i64,
+    ) -> AppResult<Vec<TrendPoint>> {
+        with_authorized_state(
+            "get-trend",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                todos
+                    .stats_over_time(trendbucket_from_incoming(bucket), from, to)
+                    .err_as_string()?
+                    .into_iter()
+                    .map(|p| {
+                        Ok(TrendPoint {
+                            start: p.start(),
+                            created: u64_from(p.created())?,
+                            completed: u64_from(p.completed())?,
+                        })
+                    })
+                    .collect()
+            },
+        )
+    }
 
-            let result = todos.get(id).err_as_string()?;
+    fn find_duplicates(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        threshold: u8,
+    ) -> AppResult<Vec<Vec<Todo>>> {
+        with_authorized_state(
+            "find-duplicates",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let groups = todos.find_similar(threshold).err_as_string()?;
+
+                Ok(groups
+                    .into_iter()
+                    .map(|group| group.into_iter().map(todo_for_outgoing).collect())
+                    .collect())
+            },
+        )
+    }
+
+    fn overdue_report(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        now: i64,
+    ) -> AppResult<OverdueReport> {
+        with_authorized_state(
+            "overdue-report",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let report = todos.overdue_report(now);
+
+                Ok(OverdueReport {
+                    by_lateness: report
+                        .by_lateness()
+                        .iter()
+                        .map(|(lateness, count)| {
+                            Ok(LatenessCount {
+                                lateness: lateness_for_outgoing(*lateness),
+                                count: u64_from(*count)?,
+                            })
+                        })
+                        .collect::<AppResult<_>>()?,
+                    by_priority: report
+                        .by_priority()
+                        .iter()
+                        .map(|(priority, count)| {
+                            Ok(PriorityCount {
+                                priority: priority_for_outgoing(*priority),
+                                count: u64_from(*count)?,
+                            })
+                        })
+                        .collect::<AppResult<_>>()?,
+                    worst: report
+                        .worst()
+                        .iter()
+                        .cloned()
+                        .map(todo_for_outgoing)
+                        .collect(),
+                })
+            },
+        )
+    }
+
+    fn get(auth_token: Option<String>, namespace: Option<String>, id: String) -> AppResult<Todo> {
+        with_authorized_state("get", auth_token, namespace, todos::Role::Viewer, |todos| {
+            let id = todos.resolve(&id).err_as_string()?;
+
+            let result = todos.get_and_record_view(id).err_as_string()?;
 
             Ok(todo_for_outgoing(result))
         })
     }
 
-    fn delete(id: String) -> AppResult<()> {
-        with_app_state(|AppState(todos)| {
-            let id = uuid_from(&id)?;
+    fn get_by_short_id(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        short_id: u64,
+    ) -> AppResult<Todo> {
+        with_authorized_state(
+            "get-by-short-id",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let result = todos.get_by_short_id(short_id).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn fetch(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+        include: FetchInclude,
+    ) -> AppResult<TodoDetail> {
+        with_authorized_state(
+            "fetch",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let include = todos::FetchInclude::builder()
+                    .comments(include.comments)
+                    .watchers(include.watchers)
+                    .activity(include.activity)
+                    .build();
+
+                let result = todos.fetch(id, &include).err_as_string()?;
+
+                Ok(todo_detail_for_outgoing(result))
+            },
+        )
+    }
+
+    fn find_by_title(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        title: String,
+        exact: bool,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "find-by-title",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos.find_by_title(&title, exact);
+
+                Ok(found.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn recent(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        kind: RecentKind,
+        limit: Option<u32>,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "recent",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .recent(recentkind_from_incoming(kind), &limit.into())
+                    .err_as_string()?;
+
+                Ok(found.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn delete(auth_token: Option<String>, namespace: Option<String>, id: String) -> AppResult<()> {
+        with_authorized_state(
+            "delete",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                todos.delete(id).err_as_string()
+            },
+        )
+    }
+
+    fn delete_returning(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+    ) -> AppResult<Todo> {
+        with_authorized_state(
+            "delete-returning",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.delete_returning(id).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn delete_done_items(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        dry_run: bool,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "delete-done-items",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                let count = todos
+                    .delete_by_status(&todos::Status::Done, dry_run)
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn delete_by_filter(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        filter: Filter,
+        dry_run: bool,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "delete-by-filter",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                let count = todos
+                    .delete_where(&filter_from_incoming(filter)?, dry_run)
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn update_by_filter(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        filter: Filter,
+        change: UpdateTodo,
+        dry_run: bool,
+    ) -> AppResult<u64> {
+        with_attributed_state(
+            "update-by-filter",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::for_bulk_change(!dry_run),
+            |todos| {
+                let count = todos
+                    .update_where(
+                        &filter_from_incoming(filter)?,
+                        &update_todo_from_incoming(change),
+                        dry_run,
+                    )
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn delete_all(auth_token: Option<String>, namespace: Option<String>) -> AppResult<u64> {
+        with_authorized_state(
+            "delete-all",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| u64_from(todos.delete_all().err_as_string()?),
+        )
+    }
+
+    fn batch(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        commands: Vec<BatchCommand>,
+    ) -> AppResult<Vec<BatchResult>> {
+        with_attributed_state(
+            "batch",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::for_bulk_change(
+                commands
+                    .iter()
+                    .any(|c| matches!(c, BatchCommand::Delete(_))),
+            ),
+            |todos| {
+                let commands = commands
+                    .into_iter()
+                    .map(batch_command_from_incoming)
+                    .collect::<AppResult<_>>()?;
+
+                let results = todos.apply_batch(commands).err_as_string()?;
+
+                Ok(results.into_iter().map(batch_result_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn save_query(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        name: String,
+        query: Query,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "save-query",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                todos
+                    .save_query(&name, query_from_incoming(query)?)
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn run_saved(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        name: String,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "run-saved",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos.run_saved(&name).err_as_string()?;
+
+                let result = found.into_iter().map(todo_for_outgoing).collect();
+
+                Ok(result)
+            },
+        )
+    }
+
+    fn list_saved(auth_token: Option<String>, namespace: Option<String>) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "list-saved",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.list_saved()),
+        )
+    }
+
+    fn delete_saved(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        name: String,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "delete-saved",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| todos.delete_saved(&name).err_as_string(),
+        )
+    }
+
+    fn save_template(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        name: String,
+        item: NewTodo,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "save-template",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                todos
+                    .save_template(&name, new_todo_from_incoming(item))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn instantiate_template(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        name: String,
+        overrides: TemplateOverrides,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "instantiate-template",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let result = todos
+                    .instantiate_template(&name, template_overrides_from_incoming(overrides))
+                    .err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn list_templates(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "list-templates",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.list_templates()),
+        )
+    }
+
+    fn delete_template(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        name: String,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "delete-template",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| todos.delete_template(&name).err_as_string(),
+        )
+    }
+
+    fn get_view(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        view: View,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "get-view",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                Ok(todos
+                    .view(view_from_incoming(view))
+                    .into_iter()
+                    .map(todo_for_outgoing)
+                    .collect())
+            },
+        )
+    }
+
+    fn snooze(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        until: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "snooze",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.snooze(id, &until).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn unsnooze(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "unsnooze",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.unsnooze(id).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn add_attachment(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        url: String,
+        label: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "add-attachment",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.add_attachment(id, &url, &label).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn remove_attachment(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        url: String,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "remove-attachment",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.remove_attachment(id, &url).err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn set_external_ref(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        system: String,
+        external_id: Option<String>,
+    ) -> AppResult<Todo> {
+        with_attributed_state(
+            "set-external-ref",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos
+                    .set_external_ref(id, &system, external_id.as_deref())
+                    .err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn find_by_external_ref(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        system: String,
+        external_id: String,
+    ) -> AppResult<Todo> {
+        with_authorized_state(
+            "find-by-external-ref",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let result = todos
+                    .find_by_external_ref(&system, &external_id)
+                    .err_as_string()?;
+
+                Ok(todo_for_outgoing(result))
+            },
+        )
+    }
+
+    fn diff_since(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        since: i64,
+    ) -> AppResult<ChangeSummary> {
+        with_authorized_state(
+            "diff-since",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let summary = todos.diff_since(since);
+
+                Ok(ChangeSummary {
+                    created: ids_for_outgoing(summary.created()),
+                    updated: ids_for_outgoing(summary.updated()),
+                    deleted: ids_for_outgoing(summary.deleted()),
+                })
+            },
+        )
+    }
+
+    fn apply_remote(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        changes: Vec<RemoteChange>,
+        since: i64,
+        strategy: SyncStrategy,
+    ) -> AppResult<SyncReport> {
+        with_attributed_state(
+            "apply-remote",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::for_bulk_change(
+                changes.iter().any(|c| matches!(c, RemoteChange::Delete(_))),
+            ),
+            |todos| {
+                let changes = changes
+                    .into_iter()
+                    .map(remote_change_from_incoming)
+                    .collect::<AppResult<_>>()?;
+
+                let report = todos
+                    .apply_remote(changes, since, syncstrategy_from_incoming(strategy))
+                    .err_as_string()?;
+
+                Ok(SyncReport {
+                    applied: ids_for_outgoing(report.applied()),
+                    conflicts: ids_for_outgoing(report.conflicts()),
+                })
+            },
+        )
+    }
+
+    fn export_delta(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        since: String,
+    ) -> AppResult<Vec<u8>> {
+        with_authorized_state(
+            "export-delta",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| todos.export_delta(&since).err_as_string(),
+        )
+    }
+
+    fn apply_delta(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        delta: Vec<u8>,
+    ) -> AppResult<String> {
+        with_attributed_state(
+            "apply-delta",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Admin,
+            |todos| todos.apply_delta(&delta).err_as_string(),
+        )
+    }
+
+    fn add_comment(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+        author: String,
+        text: String,
+    ) -> AppResult<Comment> {
+        with_authorized_state(
+            "add-comment",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.add_comment(id, &author, &text).err_as_string()?;
 
-            todos.delete(id).err_as_string()
+                Ok(comment_for_outgoing(result))
+            },
+        )
+    }
+
+    fn list_comments(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+    ) -> AppResult<Vec<Comment>> {
+        with_authorized_state(
+            "list-comments",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                let result = todos.list_comments(id).err_as_string()?;
+
+                Ok(result.into_iter().map(comment_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn delete_comment(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        comment_id: String,
+    ) -> AppResult<()> {
+        with_attributed_state(
+            "delete-comment",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+                let comment_id = uuid_from(&comment_id)?;
+
+                todos.delete_comment(id, comment_id).err_as_string()
+            },
+        )
+    }
+
+    fn get_activity(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        limit: Option<u32>,
+        filter: ActivityFilter,
+    ) -> AppResult<Vec<Activity>> {
+        with_authorized_state(
+            "get-activity",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let found = todos
+                    .activity(&limit.into(), &activity_filter_from_incoming(filter)?)
+                    .err_as_string()?;
+
+                Ok(found.into_iter().map(activity_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn watch(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        watcher: String,
+    ) -> AppResult<()> {
+        with_attributed_state(
+            "watch",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                todos.watch(id, &watcher).err_as_string()
+            },
+        )
+    }
+
+    fn unwatch(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        id: String,
+        watcher: String,
+    ) -> AppResult<()> {
+        with_attributed_state(
+            "unwatch",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                todos.unwatch(id, &watcher).err_as_string()
+            },
+        )
+    }
+
+    fn list_watchers(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        id: String,
+    ) -> AppResult<Vec<String>> {
+        with_authorized_state(
+            "list-watchers",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                let id = todos.resolve(&id).err_as_string()?;
+
+                todos.list_watchers(id).err_as_string()
+            },
+        )
+    }
+
+    fn get_settings(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<ListSettings> {
+        with_authorized_state(
+            "get-settings",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(list_settings_for_outgoing(todos.get_settings())),
+        )
+    }
+
+    fn update_settings(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        update: SettingsUpdate,
+    ) -> AppResult<ListSettings> {
+        with_authorized_state(
+            "update-settings",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                let settings = todos
+                    .update_settings(settings_update_from_incoming(update))
+                    .err_as_string()?;
+
+                Ok(list_settings_for_outgoing(settings))
+            },
+        )
+    }
+
+    fn get_default_timezone(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<String> {
+        with_authorized_state(
+            "get-default-timezone",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.default_time_zone().to_string()),
+        )
+    }
+
+    fn set_default_timezone(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        timezone: String,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-default-timezone",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| todos.set_default_time_zone(&timezone).err_as_string(),
+        )
+    }
+
+    fn get_escalation_policy(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<EscalationPolicy> {
+        with_authorized_state(
+            "get-escalation-policy",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(escalation_policy_for_outgoing(todos.escalation_policy())),
+        )
+    }
+
+    fn set_escalation_policy(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        policy: EscalationPolicy,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-escalation-policy",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .set_escalation_policy(escalation_policy_from_incoming(policy))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn run_escalation(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+    ) -> AppResult<Vec<Todo>> {
+        with_attributed_state(
+            "run-escalation",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Editor,
+            |todos| {
+                let changed = todos.escalate().err_as_string()?;
+
+                Ok(changed.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn get_retention_policy(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<Option<RetentionPolicy>> {
+        with_authorized_state(
+            "get-retention-policy",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.retention_policy().map(retention_policy_for_outgoing)),
+        )
+    }
+
+    fn set_retention_policy(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        policy: Option<RetentionPolicy>,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-retention-policy",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .set_retention_policy(policy.map(retention_policy_from_incoming))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn apply_retention(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        now: i64,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "apply-retention",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                let removed = todos.apply_retention(now).err_as_string()?;
+
+                Ok(removed.into_iter().map(todo_for_outgoing).collect())
+            },
+        )
+    }
+
+    fn get_strict_limits(auth_token: Option<String>, namespace: Option<String>) -> AppResult<bool> {
+        with_authorized_state(
+            "get-strict-limits",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.strict_limits()),
+        )
+    }
+
+    fn set_strict_limits(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        strict: bool,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-strict-limits",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| todos.set_strict_limits(strict).err_as_string(),
+        )
+    }
+
+    fn get_track_views(auth_token: Option<String>, namespace: Option<String>) -> AppResult<bool> {
+        with_authorized_state(
+            "get-track-views",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.track_views()),
+        )
+    }
+
+    fn set_track_views(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        track: bool,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-track-views",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| todos.set_track_views(track).err_as_string(),
+        )
+    }
+
+    fn get_query_cache_size(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<u32> {
+        with_authorized_state(
+            "get-query-cache-size",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.query_cache_size() as u32),
+        )
+    }
+
+    fn set_query_cache_size(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        size: u32,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-query-cache-size",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .set_query_cache_size(usize_from(size)?)
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn get_title_collation(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<TitleCollation> {
+        with_authorized_state(
+            "get-title-collation",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(titlecollation_for_outgoing(todos.title_collation())),
+        )
+    }
+
+    fn set_title_collation(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        collation: TitleCollation,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-title-collation",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .set_title_collation(titlecollation_from_incoming(collation))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn get_warn_on_similar(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<bool> {
+        with_authorized_state(
+            "get-warn-on-similar",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.warn_on_similar()),
+        )
+    }
+
+    fn set_warn_on_similar(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        warn: bool,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-warn-on-similar",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| todos.set_warn_on_similar(warn).err_as_string(),
+        )
+    }
+
+    fn configure_auth(current: Option<String>, token: Option<String>) -> AppResult<()> {
+        with_app_state("configure-auth", |AppState { todos, .. }| {
+            todos
+                .configure_auth(current.as_deref(), token.as_deref())
+                .err_as_string()
         })
     }
 
-    fn delete_done_items() -> AppResult<u64> {
-        with_app_state(|AppState(todos)| {
-            let count = todos.delete_by_status(&todos::Status::Done);
+    fn register_principal(
+        auth_token: Option<String>,
+        name: String,
+        token: String,
+        role: Role,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "register-principal",
+            auth_token,
+            None,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .register_principal(&name, &token, role_from_incoming(role))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn remove_principal(auth_token: Option<String>, name: String) -> AppResult<()> {
+        with_authorized_state(
+            "remove-principal",
+            auth_token,
+            None,
+            todos::Role::Admin,
+            |todos| todos.remove_principal(&name).err_as_string(),
+        )
+    }
+
+    fn list_principals(auth_token: Option<String>) -> AppResult<Vec<Principal>> {
+        with_authorized_state(
+            "list-principals",
+            auth_token,
+            None,
+            todos::Role::Admin,
+            |todos| {
+                Ok(todos
+                    .list_principals()
+                    .into_iter()
+                    .map(|p| Principal {
+                        name: p.name().clone(),
+                        role: role_for_outgoing(p.role()),
+                    })
+                    .collect())
+            },
+        )
+    }
+
+    fn is_frozen(auth_token: Option<String>, namespace: Option<String>) -> AppResult<bool> {
+        with_authorized_state(
+            "is-frozen",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.is_frozen()),
+        )
+    }
+
+    fn freeze(auth_token: Option<String>, namespace: Option<String>) -> AppResult<()> {
+        with_authorized_state(
+            "freeze",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos.freeze();
+
+                Ok(())
+            },
+        )
+    }
+
+    fn unfreeze(auth_token: Option<String>, namespace: Option<String>) -> AppResult<()> {
+        with_authorized_state(
+            "unfreeze",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos.unfreeze();
+
+                Ok(())
+            },
+        )
+    }
+
+    fn get_deadline_bounds(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<Option<DeadlineBounds>> {
+        with_authorized_state(
+            "get-deadline-bounds",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.deadline_bounds().map(deadline_bounds_for_outgoing)),
+        )
+    }
+
+    fn set_deadline_bounds(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        bounds: Option<DeadlineBounds>,
+    ) -> AppResult<()> {
+        with_authorized_state(
+            "set-deadline-bounds",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                todos
+                    .set_deadline_bounds(bounds.map(deadline_bounds_from_incoming))
+                    .err_as_string()
+            },
+        )
+    }
+
+    fn find_stale(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        older_than_days: u32,
+        statuses: Vec<Status>,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "find-stale",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                Ok(todos
+                    .find_stale(older_than_days, &statuses_from_incoming(statuses))
+                    .into_iter()
+                    .map(todo_for_outgoing)
+                    .collect())
+            },
+        )
+    }
+
+    fn delete_stale(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        older_than_days: u32,
+        statuses: Vec<Status>,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "delete-stale",
+            auth_token,
+            namespace,
+            todos::Role::Admin,
+            |todos| {
+                let count = todos
+                    .delete_stale(older_than_days, &statuses_from_incoming(statuses))
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn archive_stale(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        older_than_days: u32,
+        statuses: Vec<Status>,
+    ) -> AppResult<u64> {
+        with_authorized_state(
+            "archive-stale",
+            auth_token,
+            namespace,
+            todos::Role::Editor,
+            |todos| {
+                let count = todos
+                    .archive_stale(older_than_days, &statuses_from_incoming(statuses))
+                    .err_as_string()?;
+
+                u64_from(count)
+            },
+        )
+    }
+
+    fn list_archived(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+    ) -> AppResult<Vec<Todo>> {
+        with_authorized_state(
+            "list-archived",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                Ok(todos
+                    .list_archived()
+                    .into_iter()
+                    .map(todo_for_outgoing)
+                    .collect())
+            },
+        )
+    }
+
+    fn import_external(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        format: ImportFormat,
+        payload: String,
+        compression: Compression,
+        expected_checksum: Option<String>,
+        strict: bool,
+        merge: MergeStrategy,
+    ) -> AppResult<ImportReport> {
+        with_attributed_state(
+            "import-external",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Admin,
+            |todos| {
+                let payload = codec::decode(&payload, compression_from_incoming(compression))
+                    .err_as_string()?;
+
+                let options = todos::ImportOptions::builder()
+                    .expected_checksum(expected_checksum)
+                    .strict(strict)
+                    .merge(mergestrategy_from_incoming(merge))
+                    .build();
+
+                let report = todos
+                    .import_external(importformat_from_incoming(format), &payload, &options)
+                    .err_as_string()?;
+
+                Ok(ImportReport {
+                    imported: report
+                        .imported()
+                        .iter()
+                        .map(|(row, todo)| ImportSuccess {
+                            row: *row as u32,
+                            todo: todo_for_outgoing(todo.clone()),
+                        })
+                        .collect(),
+                    updated: report
+                        .updated()
+                        .iter()
+                        .map(|(row, todo)| ImportSuccess {
+                            row: *row as u32,
+                            todo: todo_for_outgoing(todo.clone()),
+                        })
+                        .collect(),
+                    skipped: report
+                        .skipped()
+                        .iter()
+                        .map(|(row, id)| ImportSkip {
+                            row: *row as u32,
+                            existing_id: id.to_string(),
+                        })
+                        .collect(),
+                    failed: report
+                        .failed()
+                        .iter()
+                        .map(|(row, reason)| ImportFailure {
+                            row: *row as u32,
+                            reason: reason.clone(),
+                        })
+                        .collect(),
+                    checksum: report.checksum().clone(),
+                })
+            },
+        )
+    }
+
+    fn add_many_chunked(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        actor: Option<String>,
+        items: Vec<NewTodo>,
+        chunk_size: Option<u32>,
+        chunks_per_call: Option<u32>,
+        resume: Option<String>,
+    ) -> AppResult<ChunkedAddReport> {
+        with_attributed_state(
+            "add-many-chunked",
+            auth_token,
+            namespace,
+            actor,
+            todos::Role::Admin,
+            |todos| {
+                let items: Vec<_> = items.into_iter().map(new_todo_from_incoming).collect();
+
+                let defaults = todos::ChunkOptions::default();
+
+                let options = todos::ChunkOptions::builder()
+                    .chunk_size(chunk_size.map_or(defaults.chunk_size(), |n| n as usize))
+                    .chunks_per_call(
+                        chunks_per_call.map_or(defaults.chunks_per_call(), |n| n as usize),
+                    )
+                    .resume(resume)
+                    .build();
+
+                let report = todos.add_many_chunked(&items, &options).err_as_string()?;
+
+                Ok(ChunkedAddReport {
+                    added: report
+                        .added()
+                        .iter()
+                        .map(|(row, todo)| ImportSuccess {
+                            row: *row as u32,
+                            todo: todo_for_outgoing(todo.clone()),
+                        })
+                        .collect(),
+                    failed: report
+                        .failed()
+                        .iter()
+                        .map(|(row, reason)| ImportFailure {
+                            row: *row as u32,
+                            reason: reason.clone(),
+                        })
+                        .collect(),
+                    continuation: report.continuation().clone(),
+                })
+            },
+        )
+    }
+
+    fn export_todo_txt(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        compression: Compression,
+    ) -> AppResult<String> {
+        with_authorized_state(
+            "export-todo-txt",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| {
+                codec::encode(
+                    &todos.export_todo_txt(),
+                    compression_from_incoming(compression),
+                )
+                .err_as_string()
+            },
+        )
+    }
+
+    fn extract(
+        auth_token: Option<String>,
+        namespace: Option<String>,
+        expression: String,
+    ) -> AppResult<String> {
+        with_authorized_state(
+            "extract",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| todos.extract(&expression).err_as_string(),
+        )
+    }
+
+    fn get_checksum(auth_token: Option<String>, namespace: Option<String>) -> AppResult<String> {
+        with_authorized_state(
+            "get-checksum",
+            auth_token,
+            namespace,
+            todos::Role::Viewer,
+            |todos| Ok(todos.state_checksum()),
+        )
+    }
+
+    fn health() -> Health {
+        with_app_state("health", |AppState { todos, .. }| {
+            let health = todos.health();
 
-            u64_from(count)
+            Health {
+                uptime_seconds: health.uptime_seconds(),
+                operation_counts: health
+                    .operation_counts()
+                    .iter()
+                    .map(|(operation, count)| OperationCount {
+                        operation: operation.clone(),
+                        count: *count,
+                    })
+                    .collect(),
+                last_snapshot: health.last_snapshot(),
+                item_count: health.item_count() as u64,
+                estimated_memory_bytes: health.estimated_memory_bytes() as u64,
+            }
         })
     }
 
-    fn delete_all() -> AppResult<u64> {
-        with_app_state(|AppState(todos)| u64_from(todos.delete_all()))
+    fn get_metrics() -> String {
+        unsafe {
+            let AppState { todos, namespaces } = &mut *APP_STATE;
+
+            METRICS.observe_query_cache(
+                std::iter::once(&*todos)
+                    .chain(namespaces.values())
+                    .map(TodoList::query_cache_stats)
+                    .sum(),
+            );
+
+            METRICS.render()
+        }
     }
 
     fn meta() -> MetaData {
         MetaData {
             component_version: COMPONENT_VERSION.into(),
             schema_version: SCHEMA_VERSION,
+            max_result_limit: todos::OptionalResultLimit::MAX,
+            max_title_length: Title::MAX_LEN as u32,
+            accepted_deadline_formats: OptionalDeadlineInput::accepted_formats(),
+            // The list has no capacity limit yet.
+            capacity_limit: None,
+            features: enabled_features().into_iter().map(String::from).collect(),
+            icons: Icon::SAFELIST.iter().map(|s| s.to_string()).collect(),
         }
     }
 }